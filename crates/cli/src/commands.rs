@@ -1,6 +1,6 @@
 use crate::{
     CliPlugin, Plugin, PluginKind, WitOptions,
-    js_config::{ConfigSchema, JsConfig},
+    js_config::{ConfigSchema, JsConfig, JsConfigValue},
     option::OptionMeta,
     option_group,
     plugin::PLUGIN_MODULE,
@@ -11,10 +11,7 @@ use clap::{
     builder::{StringValueParser, TypedValueParser, ValueParserFactory},
     error::ErrorKind,
 };
-use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
-};
+use std::{collections::HashMap, path::PathBuf};
 
 use crate::option::{
     GroupDescriptor, GroupOption, GroupOptionBuilder, GroupOptionParser, OptionValue, fmt_help,
@@ -43,6 +40,14 @@ pub enum Command {
     /// Initializes a plugin binary.
     #[command(arg_required_else_help = true)]
     InitPlugin(InitPluginCommandOpts),
+    /// Remaps the stack frames of a stack trace to the original source code,
+    /// using the source map embedded in a WebAssembly module.
+    #[command(arg_required_else_help = true)]
+    Symbolicate(SymbolicateCommandOpts),
+    /// Runs a statically linked WebAssembly module with WASI, remapping the
+    /// stack traces it writes to stderr with its embedded source map.
+    #[command(arg_required_else_help = true)]
+    Run(RunCommandOpts),
     /// Profiling commands for Javy-generated WebAssembly modules.
     #[cfg(feature = "profiler")]
     #[command(arg_required_else_help = true, subcommand)]
@@ -95,6 +100,30 @@ pub struct InitPluginCommandOpts {
     pub deterministic: bool,
 }
 
+#[derive(Debug, Parser)]
+pub struct SymbolicateCommandOpts {
+    #[arg(value_name = "MODULE", required = true)]
+    /// Path of the WebAssembly module the stack trace comes from.
+    pub module: PathBuf,
+    #[arg(value_name = "TRACE")]
+    /// Path of the file containing the stack trace (default is stdin).
+    pub trace: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub struct RunCommandOpts {
+    #[arg(value_name = "MODULE", required = true)]
+    /// Path of the WebAssembly module to run.
+    pub module: PathBuf,
+    #[arg(
+        value_name = "ARGS",
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    /// Arguments passed to the module.
+    pub args: Vec<String>,
+}
+
 impl<T> ValueParserFactory for GroupOption<T>
 where
     T: GroupDescriptor,
@@ -162,6 +191,7 @@ pub struct CodegenOptionGroup {
     pub source: Source,
     pub plugin: Option<PathBuf>,
     pub deterministic: bool,
+    pub source_map: Option<PathBuf>,
 }
 
 impl Default for CodegenOptionGroup {
@@ -172,6 +202,7 @@ impl Default for CodegenOptionGroup {
             source: Source::Compressed,
             plugin: None,
             deterministic: false,
+            source_map: None,
         }
     }
 }
@@ -203,6 +234,11 @@ option_group! {
         /// always produces identical output. Security note: both
         /// secure_random and insecure_random become non-secure.
         Deterministic(bool),
+        /// Optional path to a source map of the JavaScript input, embedded in
+        /// the generated WebAssembly module to remap stack traces with the
+        /// `symbolicate` command. Defaults to the inline source map of the
+        /// input, if any.
+        SourceMap(PathBuf),
     }
 }
 
@@ -220,6 +256,7 @@ impl TryFrom<Vec<GroupOption<CodegenOption>>> for CodegenOptionGroup {
         let mut source_specified = false;
         let mut plugin_specified = false;
         let mut deterministic_specified = false;
+        let mut source_map_specified = false;
 
         for option in value.iter().flat_map(|i| i.0.iter()) {
             match option {
@@ -265,6 +302,13 @@ impl TryFrom<Vec<GroupOption<CodegenOption>>> for CodegenOptionGroup {
                     options.deterministic = *enabled;
                     deterministic_specified = true;
                 }
+                CodegenOption::SourceMap(path) => {
+                    if source_map_specified {
+                        bail!("source-map can only be specified once");
+                    }
+                    options.source_map = Some(path.clone());
+                    source_map_specified = true;
+                }
             }
         }
 
@@ -291,8 +335,8 @@ impl TryFrom<Vec<GroupOption<CodegenOption>>> for CodegenOptionGroup {
 pub(super) struct JsGroupOption {
     /// The property name used for the option.
     name: String,
-    /// Whether the config is enabled or not.
-    enabled: bool,
+    /// The value of the option, `y` or `n` for boolean properties, if any.
+    value: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -331,8 +375,11 @@ impl TypedValueParser for JsGroupOptionParser {
                 &supported_properties
                     .into_iter()
                     .map(|prop| OptionMeta {
+                        help: match &prop.values {
+                            Some(values) => format!("={}", values.join("|")),
+                            None => "[=y|n]".to_string(),
+                        },
                         name: prop.name,
-                        help: "[=y|n]".to_string(),
                         doc: prop.doc,
                     })
                     .collect::<Vec<_>>(),
@@ -340,17 +387,13 @@ impl TypedValueParser for JsGroupOptionParser {
             std::process::exit(0);
         }
 
+        // Values are checked against the config schema of the plugin once
+        // all the options are parsed.
         let mut splits = val.splitn(2, '=');
         let key = splits.next().unwrap();
-        let value = match splits.next() {
-            Some("y") => true,
-            Some("n") => false,
-            None => true,
-            _ => return Err(clap::Error::new(clap::error::ErrorKind::InvalidValue)),
-        };
         Ok(JsGroupOption {
             name: key.to_string(),
-            enabled: value,
+            value: splits.next().map(|value| value.to_string()),
         })
     }
 }
@@ -365,18 +408,34 @@ impl JsConfig {
         let supported_properties = ConfigSchema::from_cli_plugin(cli_plugin)?
             .map_or(Vec::new(), |schema| schema.supported_properties);
 
-        let mut supported_names = HashSet::new();
+        let mut supported = HashMap::new();
         for property in &supported_properties {
-            supported_names.insert(property.name.as_str());
+            supported.insert(property.name.as_str(), property.values.as_ref());
         }
 
         let mut config = HashMap::new();
-        for JsGroupOption { name, enabled } in group_values {
-            if supported_names.contains(name.as_str()) {
+        for JsGroupOption { name, value } in group_values {
+            if let Some(values) = supported.get(name.as_str()) {
                 if config.contains_key(&name) {
                     bail!("{name} can only be specified once");
                 }
-                config.insert(name, enabled);
+                let value = match (values, value.as_deref()) {
+                    (None, Some("y") | None) => JsConfigValue::Bool(true),
+                    (None, Some("n")) => JsConfigValue::Bool(false),
+                    (Some(values), Some(value)) if values.iter().any(|v| v == value) => {
+                        JsConfigValue::String(value.to_string())
+                    }
+                    (values, _) => {
+                        let expected = values.map_or("y|n".to_string(), |values| values.join("|"));
+                        Cli::command()
+                            .error(
+                                ErrorKind::InvalidValue,
+                                format!("Property {name} must be set to {expected}"),
+                            )
+                            .exit();
+                    }
+                };
+                config.insert(name, value);
             } else {
                 let msg = if matches!(cli_plugin.kind, PluginKind::User) {
                     "JavaScript runtime options (-J) are not supported when using a plugin (-C plugin=...)".into()
@@ -397,7 +456,7 @@ mod tests {
     use crate::{
         CliPlugin, Plugin, PluginKind,
         commands::{JsGroupOption, Source},
-        js_config::JsConfig,
+        js_config::{JsConfig, JsConfigValue},
         plugin::PLUGIN_MODULE,
     };
 
@@ -417,76 +476,103 @@ mod tests {
             &plugin,
             vec![JsGroupOption {
                 name: "javy-stream-io".to_string(),
-                enabled: false,
+                value: Some("n".to_string()),
             }],
         )?;
-        assert_eq!(group.get("javy-stream-io"), Some(false));
+        assert_eq!(
+            group.get("javy-stream-io"),
+            Some(JsConfigValue::Bool(false))
+        );
 
         let group = JsConfig::from_group_values(
             &plugin,
             vec![JsGroupOption {
                 name: "javy-stream-io".to_string(),
-                enabled: true,
+                value: Some("y".to_string()),
             }],
         )?;
-        assert_eq!(group.get("javy-stream-io"), Some(true));
+        assert_eq!(group.get("javy-stream-io"), Some(JsConfigValue::Bool(true)));
 
         let group = JsConfig::from_group_values(
             &plugin,
             vec![JsGroupOption {
                 name: "simd-json-builtins".to_string(),
-                enabled: false,
+                value: Some("n".to_string()),
             }],
         )?;
-        assert_eq!(group.get("simd-json-builtins"), Some(false));
+        assert_eq!(
+            group.get("simd-json-builtins"),
+            Some(JsConfigValue::Bool(false))
+        );
 
         let group = JsConfig::from_group_values(
             &plugin,
             vec![JsGroupOption {
                 name: "simd-json-builtins".to_string(),
-                enabled: true,
+                value: Some("y".to_string()),
             }],
         )?;
-        assert_eq!(group.get("simd-json-builtins"), Some(true));
+        assert_eq!(
+            group.get("simd-json-builtins"),
+            Some(JsConfigValue::Bool(true))
+        );
 
         let group = JsConfig::from_group_values(
             &plugin,
             vec![JsGroupOption {
                 name: "text-encoding".to_string(),
-                enabled: false,
+                value: Some("n".to_string()),
             }],
         )?;
-        assert_eq!(group.get("text-encoding"), Some(false));
+        assert_eq!(group.get("text-encoding"), Some(JsConfigValue::Bool(false)));
 
         let group = JsConfig::from_group_values(
             &plugin,
             vec![JsGroupOption {
                 name: "text-encoding".to_string(),
-                enabled: true,
+                value: Some("y".to_string()),
             }],
         )?;
-        assert_eq!(group.get("text-encoding"), Some(true));
+        assert_eq!(group.get("text-encoding"), Some(JsConfigValue::Bool(true)));
 
         let group = JsConfig::from_group_values(
             &plugin,
             vec![
                 JsGroupOption {
                     name: "javy-stream-io".to_string(),
-                    enabled: false,
+                    value: Some("n".to_string()),
                 },
                 JsGroupOption {
                     name: "simd-json-builtins".to_string(),
-                    enabled: false,
+                    value: Some("n".to_string()),
                 },
                 JsGroupOption {
                     name: "text-encoding".to_string(),
-                    enabled: false,
+                    value: Some("n".to_string()),
                 },
             ],
         )?;
-        assert_eq!(group.get("javy-stream-io"), Some(false));
-        assert_eq!(group.get("simd-json-builtins"), Some(false));
-        assert_eq!(group.get("text-encoding"), Some(false));
+        assert_eq!(
+            group.get("javy-stream-io"),
+            Some(JsConfigValue::Bool(false))
+        );
+        assert_eq!(
+            group.get("simd-json-builtins"),
+            Some(JsConfigValue::Bool(false))
+        );
+        assert_eq!(group.get("text-encoding"), Some(JsConfigValue::Bool(false)));
+
+        let group = JsConfig::from_group_values(
+            &plugin,
+            vec![JsGroupOption {
+                name: "unhandled-rejection".to_string(),
+                value: Some("warn".to_string()),
+            }],
+        )?;
+        assert_eq!(
+            group.get("unhandled-rejection"),
+            Some(JsConfigValue::String("warn".to_string()))
+        );
 
         Ok(())
     }
@@ -590,6 +676,16 @@ mod tests {
             result.err().unwrap().to_string(),
             "plugin can only be specified once"
         );
+
+        let raw = vec![GroupOption(vec![
+            CodegenOption::SourceMap(PathBuf::from("index.js.map")),
+            CodegenOption::SourceMap(PathBuf::from("index2.js.map")),
+        ])];
+        let result: Result<CodegenOptionGroup, Error> = raw.try_into();
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "source-map can only be specified once"
+        );
         Ok(())
     }
 
@@ -601,11 +697,11 @@ mod tests {
             vec![
                 JsGroupOption {
                     name: "javy-stream-io".to_string(),
-                    enabled: false,
+                    value: Some("n".to_string()),
                 },
                 JsGroupOption {
                     name: "javy-stream-io".to_string(),
-                    enabled: true,
+                    value: Some("y".to_string()),
                 },
             ],
         );
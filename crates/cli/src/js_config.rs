@@ -1,5 +1,5 @@
 use anyhow::{Result, anyhow};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, str};
 use wasmtime::{AsContext, AsContextMut, Engine, Linker};
 
@@ -42,6 +42,7 @@ impl ConfigSchema {
                     configs.push(JsConfigProperty {
                         name: config.name,
                         doc: config.doc,
+                        values: config.values,
                     });
                 }
 
@@ -61,15 +62,28 @@ pub(crate) struct JsConfigProperty {
     pub(crate) name: String,
     /// The documentation to display for the property.
     pub(crate) doc: String,
+    /// The values the property accepts, if it isn't a boolean property.
+    #[serde(default)]
+    pub(crate) values: Option<Vec<String>>,
 }
 
-/// A collection of property names to whether they are enabled.
+/// The value of a property.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub(crate) enum JsConfigValue {
+    /// Whether a boolean property is enabled.
+    Bool(bool),
+    /// One of the values of a property which isn't a boolean property.
+    String(String),
+}
+
+/// A collection of property names to their value.
 #[derive(Clone, Debug, Default)]
-pub(crate) struct JsConfig(HashMap<String, bool>);
+pub(crate) struct JsConfig(HashMap<String, JsConfigValue>);
 
 impl JsConfig {
     /// Create from a hash.
-    pub(crate) fn from_hash(configs: HashMap<String, bool>) -> Self {
+    pub(crate) fn from_hash(configs: HashMap<String, JsConfigValue>) -> Self {
         JsConfig(configs)
     }
 
@@ -80,7 +94,7 @@ impl JsConfig {
 
     #[cfg(test)]
     /// Retrieve a value for a property name.
-    pub(crate) fn get(&self, name: &str) -> Option<bool> {
-        self.0.get(name).copied()
+    pub(crate) fn get(&self, name: &str) -> Option<JsConfigValue> {
+        self.0.get(name).cloned()
     }
 }
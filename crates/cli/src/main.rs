@@ -5,17 +5,21 @@ mod plugin;
 #[cfg(feature = "profiler")]
 mod profiler;
 
-use crate::commands::{Cli, Command, EmitPluginCommandOpts};
-use anyhow::Result;
+use crate::commands::{
+    Cli, Command, EmitPluginCommandOpts, RunCommandOpts, SymbolicateCommandOpts,
+};
+use anyhow::{Context, Result, bail};
 use clap::Parser;
 
 use commands::CodegenOptionGroup;
-use javy_codegen::{Generator, JS, LinkingKind, Plugin, SourceEmbedding, WitOptions};
+use javy_codegen::{Generator, JS, LinkingKind, Plugin, SourceEmbedding, SourceMap, WitOptions};
 use js_config::JsConfig;
 use plugin::{CliPlugin, PLUGIN_MODULE, PluginKind, UninitializedPlugin};
 use std::fs;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Read, Write};
+use wasmtime::{Engine, Linker, Module, Store};
+use wasmtime_wasi::{I32Exit, WasiCtxBuilder, p1::WasiP1Ctx, p2::pipe::MemoryOutputPipe};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -49,6 +53,14 @@ async fn main() -> Result<()> {
             };
             generator.source_embedding(source_embedding);
 
+            if let Some(path) = &codegen_opts.source_map {
+                let source_map = fs::read(path)
+                    .with_context(|| format!("Failed to read source map {}", path.display()))?;
+                let source_map = SourceMap::from_slice(&source_map)
+                    .with_context(|| format!("Failed to parse source map {}", path.display()))?;
+                generator.source_map(source_map);
+            }
+
             set_producer_version(&mut generator);
             generator.deterministic(codegen_opts.deterministic);
 
@@ -63,6 +75,8 @@ async fn main() -> Result<()> {
             fs::write(&opts.output, wasm)?;
             Ok(())
         }
+        Command::Symbolicate(opts) => symbolicate(opts),
+        Command::Run(opts) => run(opts),
         #[cfg(feature = "profiler")]
         Command::Profile(cmd) => profiler::run(cmd).await,
         Command::InitPlugin(opts) => {
@@ -93,6 +107,71 @@ fn emit_plugin(opts: &EmitPluginCommandOpts) -> Result<()> {
     Ok(())
 }
 
+fn symbolicate(opts: &SymbolicateCommandOpts) -> Result<()> {
+    let wasm = fs::read(&opts.module)?;
+    let Some(source_map) = SourceMap::from_module(&wasm)? else {
+        bail!("{} doesn't contain a source map", opts.module.display());
+    };
+
+    let trace = match &opts.trace {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut trace = String::new();
+            std::io::stdin().read_to_string(&mut trace)?;
+            trace
+        }
+    };
+    std::io::stdout().write_all(source_map.symbolicate(&trace).as_bytes())?;
+    Ok(())
+}
+
+fn run(opts: &RunCommandOpts) -> Result<()> {
+    let wasm = fs::read(&opts.module)?;
+    let source_map = SourceMap::from_module(&wasm)?;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm)?;
+    let mut linker = Linker::<WasiP1Ctx>::new(&engine);
+    wasmtime_wasi::p1::add_to_linker_sync(&mut linker, |wasi| wasi)?;
+
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.inherit_stdin()
+        .inherit_stdout()
+        .inherit_env()
+        .args(&[opts.module.display().to_string()])
+        .args(&opts.args);
+    // Stack traces are remapped once the module exits, so stderr is buffered
+    // when there is a source map.
+    let stderr = MemoryOutputPipe::new(usize::MAX);
+    match &source_map {
+        Some(_) => wasi.stderr(stderr.clone()),
+        None => wasi.inherit_stderr(),
+    };
+
+    let mut store = Store::new(&engine, wasi.build_p1());
+    let result = linker
+        .instantiate(&mut store, &module)
+        .and_then(|instance| instance.get_typed_func::<(), ()>(&mut store, "_start"))
+        .and_then(|start| start.call(&mut store, ()));
+    drop(store);
+
+    if let Some(source_map) = &source_map {
+        let trace = stderr
+            .try_into_inner()
+            .expect("stderr to no longer be referenced by the store");
+        let trace = source_map.symbolicate(&String::from_utf8_lossy(&trace));
+        std::io::stderr().write_all(trace.as_bytes())?;
+    }
+
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => match e.downcast_ref::<I32Exit>() {
+            Some(I32Exit(code)) => std::process::exit(*code),
+            None => Err(e.into()),
+        },
+    }
+}
+
 fn set_producer_version(generator: &mut Generator) {
     generator.producer_version(env!("CARGO_PKG_VERSION").to_string());
 }
@@ -229,6 +229,218 @@ fn test_promise_top_level_await(builder: &mut Builder) -> Result<()> {
     Ok(())
 }
 
+#[javy_cli_test]
+fn test_timers_with_event_loop(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("timers.js").event_loop(true).build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+
+    assert_eq!("microtask,interval,interval,timeout\n", logs);
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_timers_without_event_loop(builder: &mut Builder) -> Result<()> {
+    use javy_runner::RunnerError;
+
+    let mut runner = builder.input("timers.js").build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(err.stderr.contains("'setTimeout' is not defined"));
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_url(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("url.js").url(true).build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+
+    assert_eq!("https://example.com/search?q=javy&page=2\n", logs);
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_url_disabled_by_default(builder: &mut Builder) -> Result<()> {
+    use javy_runner::RunnerError;
+
+    let mut runner = builder.input("url.js").build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(err.stderr.contains("'URL' is not defined"));
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_fetch_types(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder
+        .input("fetch-types.js")
+        .fetch_types(true)
+        .event_loop(true)
+        .build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+
+    assert_eq!("201 application/json {\"greeting\":\"hello\"}\n", logs);
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_fetch_types_disabled_by_default(builder: &mut Builder) -> Result<()> {
+    use javy_runner::RunnerError;
+
+    let mut runner = builder.input("fetch-types.js").event_loop(true).build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(err.stderr.contains("'Response' is not defined"));
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_event_target(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder
+        .input("event-target.js")
+        .event_target(true)
+        .build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+
+    assert_eq!("hello\nabort done\n", logs);
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_event_target_disabled_by_default(builder: &mut Builder) -> Result<()> {
+    use javy_runner::RunnerError;
+
+    let mut runner = builder.input("event-target.js").build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(err.stderr.contains("'AbortController' is not defined"));
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_streams(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder
+        .input("streams.js")
+        .event_loop(true)
+        .streams(true)
+        .build()?;
+    let (output, _, _) = run(&mut runner, "hello streams".into());
+
+    assert_eq!("HELLO STREAMS", str::from_utf8(&output)?);
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_streams_disabled_by_default(builder: &mut Builder) -> Result<()> {
+    use javy_runner::RunnerError;
+
+    let mut runner = builder.input("streams.js").event_loop(true).build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(err.stderr.contains("'TransformStream' is not defined"));
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_process(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("process.js").javy_process(true).build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    let exit = err.err.downcast_ref::<wasmtime_wasi::I32Exit>();
+    assert_eq!(Some(3), exit.map(|exit| exit.0));
+    assert_eq!("{\"args\":[],\"env\":{}}\n", err.stderr);
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_process_disabled_by_default(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("process.js").build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(err.stderr.contains("not a function"));
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_messagepack(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("messagepack.js").messagepack(true).build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+    assert_eq!("129 161 97 146 1 161 98\n{\"a\":[1,\"b\"]}\n", logs);
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_messagepack_disabled_by_default(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("messagepack.js").build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(
+        err.stderr
+            .contains("cannot read property 'encode' of undefined")
+    );
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_unhandled_rejections_are_ignored_by_default(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder
+        .input("unhandled-rejection.js")
+        .event_loop(true)
+        .build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+    assert_eq!("done\n", logs);
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_warn_on_unhandled_rejection(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder
+        .input("unhandled-rejection.js")
+        .event_loop(true)
+        .unhandled_rejection("warn")
+        .build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+    assert!(logs.starts_with("done\nUnhandled promise rejection: Error: rejected\n"));
+    assert!(!logs.contains("handled\n"));
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_fail_on_unhandled_rejection(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder
+        .input("unhandled-rejection.js")
+        .event_loop(true)
+        .unhandled_rejection("fail")
+        .build()?;
+    let res = runner.exec(vec![]);
+    let err = res.err().unwrap().downcast::<RunnerError>().unwrap();
+    assert!(
+        err.stderr
+            .starts_with("done\nUnhandled promise rejection: Error: rejected\n")
+    );
+
+    Ok(())
+}
+
+#[javy_cli_test]
+fn test_relative_imports(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("modules/index.js").build()?;
+    let (_, logs, _) = run(&mut runner, vec![]);
+
+    assert_eq!("Hello, Javy!\n", logs);
+    Ok(())
+}
+
 #[javy_cli_test]
 fn test_exported_functions(builder: &mut Builder) -> Result<()> {
     let mut runner = builder
@@ -288,6 +500,23 @@ fn test_error_handling(builder: &mut Builder) -> Result<()> {
     Ok(())
 }
 
+#[javy_cli_test]
+fn test_error_handling_as_json(builder: &mut Builder) -> Result<()> {
+    let mut runner = builder.input("error.js").json_errors(true).build()?;
+    let result = runner.exec(vec![]);
+    let err = result.err().unwrap().downcast::<RunnerError>().unwrap();
+
+    let expected_log_output = concat!(
+        r#"{"name":"Error","message":"error","stack":["#,
+        r#"{"function":"error","file":"function.mjs","line":2,"column":13},"#,
+        r#"{"function":"<anonymous>","file":"function.mjs","line":5,"column":1}]}"#,
+        "\n"
+    );
+
+    assert_eq!(expected_log_output, err.stderr);
+    Ok(())
+}
+
 #[javy_cli_test]
 fn test_same_module_outputs_different_random_result(builder: &mut Builder) -> Result<()> {
     let mut runner = builder.input("random.js").build()?;
@@ -557,6 +786,110 @@ fn test_init_plugin_deterministic() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_source_map() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let wasm = dir.path().join("index.wasm");
+    let source_map = sample_script("source-map.js.map");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_javy"))
+        .arg("build")
+        .arg(sample_script("source-map.js"))
+        .arg("-o")
+        .arg(&wasm)
+        .arg("-C")
+        .arg(format!("source-map={}", source_map.display()))
+        .output()?;
+    assert!(
+        output.status.success(),
+        "build -C source-map failed: {}",
+        str::from_utf8(&output.stderr)?
+    );
+
+    // `run` remaps the stack trace written by the module.
+    let output = Command::new(env!("CARGO_BIN_EXE_javy"))
+        .arg("run")
+        .arg(&wasm)
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = str::from_utf8(&output.stderr)?;
+    assert!(
+        stderr.starts_with(
+            "Error: failed\n    at fail (src/lib.ts:2:13)\n    at <anonymous> (src/index.ts:2:1)\n"
+        ),
+        "unexpected run output: {stderr}"
+    );
+
+    // `symbolicate` remaps a stack trace written when running the module
+    // elsewhere.
+    let trace = dir.path().join("trace.txt");
+    std::fs::write(
+        &trace,
+        "Error: failed\n    at fail (function.mjs:2:13)\n    at <anonymous> (function.mjs:4:1)\n",
+    )?;
+    let output = Command::new(env!("CARGO_BIN_EXE_javy"))
+        .arg("symbolicate")
+        .arg(&wasm)
+        .arg(&trace)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "symbolicate failed: {}",
+        str::from_utf8(&output.stderr)?
+    );
+    assert_eq!(
+        "Error: failed\n    at fail (src/lib.ts:2:13)\n    at <anonymous> (src/index.ts:2:1)\n",
+        str::from_utf8(&output.stdout)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_run_without_source_map() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let wasm = dir.path().join("index.wasm");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_javy"))
+        .arg("build")
+        .arg(sample_script("error.js"))
+        .arg("-o")
+        .arg(&wasm)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "build failed: {}",
+        str::from_utf8(&output.stderr)?
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_javy"))
+        .arg("run")
+        .arg(&wasm)
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = str::from_utf8(&output.stderr)?;
+    assert!(
+        stderr.starts_with(
+            "Error: error\n    at error (function.mjs:2:13)\n    at <anonymous> (function.mjs:5:1)\n"
+        ),
+        "unexpected run output: {stderr}"
+    );
+
+    // Modules without a source map can't be symbolicated.
+    let output = Command::new(env!("CARGO_BIN_EXE_javy"))
+        .arg("symbolicate")
+        .arg(&wasm)
+        .output()?;
+    assert!(!output.status.success());
+    Ok(())
+}
+
+fn sample_script(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("sample-scripts")
+        .join(name)
+}
+
 fn run_with_u8s(r: &mut Runner, stdin: u8) -> (u8, String, u64) {
     let (output, logs, fuel_consumed) = run(r, stdin.to_le_bytes().into());
     assert_eq!(1, output.len());
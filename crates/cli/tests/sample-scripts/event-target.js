@@ -0,0 +1,7 @@
+const controller = new AbortController();
+controller.signal.addEventListener("abort", (e) => console.log(`${e.type} ${controller.signal.reason}`));
+const target = new EventTarget();
+target.addEventListener("greet", (e) => console.log(e.detail), { signal: controller.signal });
+target.dispatchEvent(new CustomEvent("greet", { detail: "hello" }));
+controller.abort("done");
+target.dispatchEvent(new CustomEvent("greet", { detail: "unreachable" }));
@@ -0,0 +1,3 @@
+const response = Response.json({ greeting: "hello" }, { status: 201 });
+const body = await response.text();
+console.log(`${response.status} ${response.headers.get("content-type")} ${body}`);
@@ -0,0 +1,3 @@
+const encoded = Javy.MessagePack.encode({ a: [1, "b"] });
+console.log(Array.from(encoded).join(" "));
+console.log(JSON.stringify(Javy.MessagePack.decode(encoded)));
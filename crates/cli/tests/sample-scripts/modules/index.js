@@ -0,0 +1,4 @@
+import { greet } from "./lib/greet.js";
+import * as names from "./lib/names.js";
+
+console.log(greet(names.default));
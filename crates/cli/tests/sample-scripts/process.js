@@ -0,0 +1,3 @@
+console.log(JSON.stringify({ args: Javy.args, env: Javy.env }));
+Javy.exit(3);
+console.log("unreachable");
@@ -0,0 +1,4 @@
+function fail() {
+  throw new Error("failed");
+}
+fail();
@@ -0,0 +1,10 @@
+const upperCase = new TransformStream({
+  transform(chunk, controller) {
+    controller.enqueue(chunk.toUpperCase());
+  },
+});
+await Javy.IO.stdin()
+  .pipeThrough(new TextDecoderStream())
+  .pipeThrough(upperCase)
+  .pipeThrough(new TextEncoderStream())
+  .pipeTo(Javy.IO.stdout());
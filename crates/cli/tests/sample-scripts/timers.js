@@ -0,0 +1,11 @@
+const order = [];
+setTimeout(() => order.push("timeout"), 10);
+const interval = setInterval(() => {
+  order.push("interval");
+  if (order.filter((e) => e === "interval").length === 2) {
+    clearInterval(interval);
+  }
+}, 1);
+queueMicrotask(() => order.push("microtask"));
+await new Promise((resolve) => setTimeout(resolve, 20));
+console.log(order.join(","));
@@ -0,0 +1,3 @@
+Promise.reject(new Error("rejected"));
+Promise.reject(new Error("handled")).catch(() => {});
+console.log("done");
@@ -0,0 +1,3 @@
+const url = new URL("../search?q=javy", "https://example.com/docs/index.html");
+url.searchParams.append("page", "2");
+console.log(url.href);
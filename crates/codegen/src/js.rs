@@ -4,44 +4,63 @@
 /// code. For example, as a byte array, a string, QuickJS bytecode, compressed
 /// bytes, or attributes of the source code like what it exports.
 use std::{
-    collections::HashMap,
-    fs::File,
+    collections::{HashMap, HashSet},
+    fs::{self, File},
     io::{Cursor, Read},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::OnceLock,
 };
 
 use anyhow::{Context, Result, anyhow, bail};
 use brotli::enc::{self, BrotliEncoderParams};
+use javy::modules::ModuleBundle;
 use swc_core::{
     common::{FileName, SourceMap},
     ecma::{
         ast::{
-            Decl, EsVersion, ExportDecl, ExportSpecifier, Module, ModuleDecl, ModuleExportName,
-            ModuleItem, Stmt,
+            Decl, EsVersion, ExportAll, ExportDecl, ExportSpecifier, ImportDecl, Module,
+            ModuleDecl, ModuleExportName, ModuleItem, NamedExport, Stmt,
         },
         parser::{self, EsSyntax, Syntax},
     },
 };
 
+use crate::source_map::{self, DEFAULT_MODULE_NAME};
+
 /// JS source code.
 #[derive(Clone, Debug)]
 pub struct JS {
     source_code: String,
+    /// The path of the file containing the source code, used to resolve
+    /// relative imports.
+    path: Option<PathBuf>,
+    /// The modules imported by the source code, see [`JS::bundled_modules`].
+    bundled_modules: OnceLock<Option<Vec<(String, String)>>>,
 }
 
 impl JS {
     /// Create [`JS`] from a string containing JS source code.
+    ///
+    /// Relative imports can't be resolved for [`JS`] created from a string.
     pub fn from_string(source_code: String) -> JS {
-        JS { source_code }
+        JS {
+            source_code,
+            path: None,
+            bundled_modules: OnceLock::new(),
+        }
     }
 
     /// Create [`JS`] from a file containing JS.
+    ///
+    /// Modules imported through static relative imports (e.g.,
+    /// `import { foo } from "./foo.js"`), are resolved relative to the file
+    /// and compiled along with it.
     pub fn from_file(path: &Path) -> Result<JS> {
-        let mut input_file = File::open(path)
-            .with_context(|| format!("Failed to open input file {}", path.display()))?;
-        let mut contents: Vec<u8> = vec![];
-        input_file.read_to_end(&mut contents)?;
-        Ok(Self::from_string(String::from_utf8(contents)?))
+        Ok(JS {
+            source_code: read_file(path)?,
+            path: Some(path.to_path_buf()),
+            bundled_modules: OnceLock::new(),
+        })
     }
 
     /// Get source code as bytes.
@@ -49,6 +68,124 @@ impl JS {
         self.source_code.as_bytes()
     }
 
+    /// Get the source code to compile to bytecode.
+    ///
+    /// If the source code imports other modules through static relative
+    /// imports, the whole import graph is collected and returned as a module
+    /// bundle, where each module is named after its path relative to the
+    /// closest directory containing all the modules. Otherwise the source
+    /// code is returned as is.
+    pub(crate) fn compilation_input(&self) -> Result<Vec<u8>> {
+        match self.bundled_modules()? {
+            Some(modules) => {
+                let mut bundle = ModuleBundle::new();
+                for (name, source_code) in modules {
+                    bundle.push(name.as_str(), source_code.as_bytes());
+                }
+                Ok(bundle.to_bytes())
+            }
+            None => Ok(self.as_bytes().to_vec()),
+        }
+    }
+
+    /// Get the name of the module evaluated from the source code, as it
+    /// appears in stack traces.
+    pub(crate) fn module_name(&self) -> Result<String> {
+        Ok(match self.bundled_modules()? {
+            Some(modules) => modules[0].0.clone(),
+            None => DEFAULT_MODULE_NAME.to_string(),
+        })
+    }
+
+    /// Get the source map referenced by an inline
+    /// `//# sourceMappingURL=data:...` comment, if any.
+    ///
+    /// Source maps referenced by URL are ignored, they must be passed to the
+    /// [`Generator`](crate::Generator) explicitly.
+    pub(crate) fn inline_source_map(&self) -> Result<Option<source_map::SourceMap>> {
+        match swc_sourcemap::locate_sourcemap_reference_slice(self.as_bytes())? {
+            Some(reference) if reference.get_url().starts_with("data:") => {
+                source_map::SourceMap::from_data_url(reference.get_url())
+                    .map(Some)
+                    .context("Failed to parse the inline source map")
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Collects the modules imported through static relative imports, named
+    /// after their path relative to the closest directory containing all the
+    /// modules, the entry module first.
+    ///
+    /// Returns `None` when the source code doesn't import other modules. The
+    /// import graph is only read and parsed the first time.
+    fn bundled_modules(&self) -> Result<Option<&[(String, String)]>> {
+        if let Some(modules) = self.bundled_modules.get() {
+            return Ok(modules.as_deref());
+        }
+        let modules = self.collect_modules()?;
+        Ok(self.bundled_modules.get_or_init(|| modules).as_deref())
+    }
+
+    /// Reads and parses the import graph for [`JS::bundled_modules`].
+    fn collect_modules(&self) -> Result<Option<Vec<(String, String)>>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+
+        let entry = fs::canonicalize(path)
+            .with_context(|| format!("Failed to open input file {}", path.display()))?;
+        let mut seen = HashSet::from([entry.clone()]);
+        let mut modules = vec![(entry, self.source_code.clone())];
+        let mut index = 0;
+        while index < modules.len() {
+            let (path, source_code) = modules[index].clone();
+            let module = parse_module(&source_code)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            let dir = path.parent().unwrap_or(Path::new(""));
+            for specifier in relative_imports(&module) {
+                let import = fs::canonicalize(dir.join(&specifier)).with_context(|| {
+                    format!(
+                        "Failed to resolve import \"{specifier}\" in {}",
+                        path.display()
+                    )
+                })?;
+                if seen.insert(import.clone()) {
+                    let source_code = read_file(&import)?;
+                    modules.push((import, source_code));
+                }
+            }
+            index += 1;
+        }
+
+        if modules.len() == 1 {
+            return Ok(None);
+        }
+
+        let root = modules
+            .iter()
+            .filter_map(|(path, _)| path.parent())
+            .reduce(|root, dir| {
+                root.ancestors()
+                    .find(|ancestor| dir.starts_with(ancestor))
+                    .unwrap_or(root)
+            })
+            .unwrap_or(Path::new(""));
+        modules
+            .iter()
+            .map(|(path, source_code)| {
+                let name = path
+                    .strip_prefix(root)?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                Ok((name, source_code.clone()))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
+    }
+
     /// Get Brotli compressed JS source code as bytes.
     pub(crate) fn compress(&self) -> Result<Vec<u8>> {
         let mut compressed_source_code: Vec<u8> = vec![];
@@ -144,27 +281,59 @@ impl JS {
     }
 
     fn parse_module(&self) -> Result<Module> {
-        let source_map: SourceMap = Default::default();
-        // SWC's source file API works with BytesStr's and initializing a
-        // ByteStr requires either a static str or to own the string so we have
-        // to clone here.
-        let file = source_map.new_source_file(FileName::Anon.into(), self.source_code.to_owned());
-        let mut errors = vec![];
-        parser::parse_file_as_module(
-            &file,
-            Syntax::Es(EsSyntax::default()),
-            EsVersion::Es2020,
-            None,
-            &mut errors,
-        )
-        .map_err(|e| anyhow!(e.into_kind().msg()))
-        .with_context(|| "Invalid JavaScript")
+        parse_module(&self.source_code)
     }
 }
 
+fn read_file(path: &Path) -> Result<String> {
+    let mut input_file = File::open(path)
+        .with_context(|| format!("Failed to open input file {}", path.display()))?;
+    let mut contents: Vec<u8> = vec![];
+    input_file.read_to_end(&mut contents)?;
+    Ok(String::from_utf8(contents)?)
+}
+
+fn parse_module(source_code: &str) -> Result<Module> {
+    let source_map: SourceMap = Default::default();
+    // SWC's source file API works with BytesStr's and initializing a
+    // ByteStr requires either a static str or to own the string so we have
+    // to clone here.
+    let file = source_map.new_source_file(FileName::Anon.into(), source_code.to_owned());
+    let mut errors = vec![];
+    parser::parse_file_as_module(
+        &file,
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::Es2020,
+        None,
+        &mut errors,
+    )
+    .map_err(|e| anyhow!(e.into_kind().msg()))
+    .with_context(|| "Invalid JavaScript")
+}
+
+/// The specifiers of the static relative imports and re-exports of a module.
+fn relative_imports(module: &Module) -> Vec<String> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl { src, .. }))
+            | ModuleItem::ModuleDecl(ModuleDecl::ExportAll(ExportAll { src, .. }))
+            | ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+                src: Some(src), ..
+            })) => Some(src.value.to_atom_lossy().to_string()),
+            _ => None,
+        })
+        .filter(|specifier| specifier.starts_with("./") || specifier.starts_with("../"))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use anyhow::Result;
+    use javy::modules::ModuleBundle;
 
     use crate::js::JS;
 
@@ -311,6 +480,116 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn compilation_input_without_imports() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let entry = dir.path().join("index.js");
+        fs::write(&entry, "import 'javy:util'; export function foo() {}")?;
+
+        let input = JS::from_file(&entry)?.compilation_input()?;
+        assert_eq!(b"import 'javy:util'; export function foo() {}", &input[..]);
+        Ok(())
+    }
+
+    #[test]
+    fn compilation_input_with_relative_imports() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir_all(dir.path().join("src/lib"))?;
+        let entry = dir.path().join("src/index.js");
+        fs::write(&entry, "import { a } from './lib/a.js';")?;
+        fs::write(
+            dir.path().join("src/lib/a.js"),
+            "export { b as a } from './b.js'; export * from '../../shared.js';",
+        )?;
+        fs::write(dir.path().join("src/lib/b.js"), "export const b = 1;")?;
+        fs::write(dir.path().join("shared.js"), "import './src/lib/b.js';")?;
+
+        let input = JS::from_file(&entry)?.compilation_input()?;
+        let bundle = ModuleBundle::from_bytes(&input)?;
+        let expected = [
+            ("src/index.js", "import { a } from './lib/a.js';"),
+            (
+                "src/lib/a.js",
+                "export { b as a } from './b.js'; export * from '../../shared.js';",
+            ),
+            ("src/lib/b.js", "export const b = 1;"),
+            ("shared.js", "import './src/lib/b.js';"),
+        ];
+        assert_eq!(
+            expected
+                .iter()
+                .map(|(name, source_code)| (*name, source_code.as_bytes()))
+                .collect::<Vec<_>>(),
+            bundle.modules().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compilation_input_with_missing_import() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let entry = dir.path().join("index.js");
+        fs::write(&entry, "import './missing.js';")?;
+
+        let err = JS::from_file(&entry)?.compilation_input().unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Failed to resolve import \"./missing.js\"")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bundled_modules_are_read_once() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let entry = dir.path().join("index.js");
+        fs::write(&entry, "import './a.js';")?;
+        fs::write(dir.path().join("a.js"), "")?;
+
+        let js = JS::from_file(&entry)?;
+        let input = js.compilation_input()?;
+        fs::remove_file(dir.path().join("a.js"))?;
+        assert_eq!(input, js.compilation_input()?);
+        assert_eq!("index.js", js.module_name()?);
+        Ok(())
+    }
+
+    #[test]
+    fn module_name() -> Result<()> {
+        assert_eq!(
+            "function.mjs",
+            JS::from_string("import './a.js';".into()).module_name()?
+        );
+
+        let dir = tempfile::tempdir()?;
+        fs::create_dir_all(dir.path().join("src"))?;
+        let entry = dir.path().join("src/index.js");
+        fs::write(&entry, "import '../a.js';")?;
+        fs::write(dir.path().join("a.js"), "")?;
+        assert_eq!("src/index.js", JS::from_file(&entry)?.module_name()?);
+        fs::write(&entry, "")?;
+        assert_eq!("function.mjs", JS::from_file(&entry)?.module_name()?);
+        Ok(())
+    }
+
+    #[test]
+    fn inline_source_map() -> Result<()> {
+        let js = JS::from_string(
+            "foo();\n//# sourceMappingURL=data:application/json;base64,eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbImEudHMiXSwibmFtZXMiOltdLCJtYXBwaW5ncyI6IkFBQUEifQ==\n".into(),
+        );
+        let source_map = js.inline_source_map()?.unwrap();
+        assert_eq!("a.ts", source_map.lookup(1, 1).unwrap().file);
+
+        let js = JS::from_string("foo();\n//# sourceMappingURL=index.js.map\n".into());
+        assert!(js.inline_source_map()?.is_none());
+        assert!(
+            JS::from_string("foo();".into())
+                .inline_source_map()?
+                .is_none()
+        );
+        Ok(())
+    }
+
     fn parse(js: &str) -> Result<Vec<String>> {
         JS::from_string(js.to_string()).exports()
     }
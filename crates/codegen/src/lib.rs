@@ -65,6 +65,8 @@
 //! * [`Generator`] - The main entry point for generating Wasm modules.
 //! * [`Plugin`] - An initialized Javy plugin.
 //! * [`JS`] - JavaScript source code.
+//! * [`SourceMap`] - A source map of the JavaScript source code, used to remap
+//!   stack traces to the original source code.
 //!
 //! ## Features
 //!
@@ -81,13 +83,16 @@ pub(crate) mod transform;
 
 pub(crate) mod js;
 pub(crate) mod plugin;
+pub(crate) mod source_map;
 pub(crate) mod wit;
 
 use crate::exports::Exports;
 pub use crate::js::JS;
 pub use crate::plugin::Plugin;
+pub use crate::source_map::{OriginalLocation, SourceMap};
 pub use crate::wit::WitOptions;
 
+use source_map::SourceMapSection;
 use transform::SourceCodeSection;
 use walrus::{
     DataId, DataKind, ExportItem, FunctionBuilder, FunctionId, LocalId, MemoryId, Module, ValType,
@@ -178,6 +183,8 @@ pub struct Generator {
     producer_version: Option<String>,
     /// Whether to use fixed clocks for deterministic builds.
     deterministic: bool,
+    /// The source map of the JavaScript source code.
+    source_map: Option<SourceMap>,
 }
 
 impl Generator {
@@ -226,6 +233,15 @@ impl Generator {
         self.deterministic = deterministic;
         self
     }
+
+    /// Set the source map of the JavaScript source code, embedded in a custom
+    /// section of the generated module to remap stack traces with
+    /// [`SourceMap::symbolicate`] (default: the inline source map of the
+    /// source code, if any).
+    pub fn source_map(&mut self, source_map: SourceMap) -> &mut Self {
+        self.source_map = Some(source_map);
+        self
+    }
 }
 
 impl Generator {
@@ -330,7 +346,7 @@ impl Generator {
         js: &js::JS,
         imports: &Identifiers,
     ) -> Result<BytecodeMetadata> {
-        let bytecode = bytecode::compile_source(&self.plugin, js.as_bytes())?;
+        let bytecode = bytecode::compile_source(&self.plugin, &js.compilation_input()?)?;
         let bytecode_len: i32 = bytecode.len().try_into()?;
         let bytecode_data = module.data.add(DataKind::Passive, bytecode);
 
@@ -477,6 +493,14 @@ impl Generator {
                 module.customs.add(SourceCodeSection::compressed(js)?);
             }
         }
+        let source_map = match &self.source_map {
+            Some(source_map) => Some(source_map.clone()),
+            None => js.inline_source_map()?,
+        };
+        if let Some(mut source_map) = source_map {
+            source_map.set_module_name(js.module_name()?);
+            module.customs.add(SourceMapSection::new(&source_map)?);
+        }
 
         let wasm = self.postprocess(&mut module)?;
         Ok(wasm)
@@ -0,0 +1,359 @@
+//! Source maps, used to remap the stack traces of errors thrown by the
+//! JavaScript code to the original source code.
+
+use std::borrow::Cow;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use swc_sourcemap::DecodedMap;
+use walrus::{CustomSection, IdsToIndices};
+use wasmparser::{Parser, Payload};
+
+/// The name of the custom section containing the source map.
+const SECTION_NAME: &str = "javy_source_map";
+
+/// The name under which the plugin evaluates the JavaScript source code, which
+/// is the file name used in the stack traces.
+pub(crate) const DEFAULT_MODULE_NAME: &str = "function.mjs";
+
+/// A source map of the JavaScript source code.
+///
+/// The source map is embedded in a custom section of the generated module, from
+/// which it can be read with [`SourceMap::from_module`] to remap stack traces
+/// with [`SourceMap::symbolicate`].
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    map: swc_sourcemap::SourceMap,
+}
+
+/// A location in the original source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OriginalLocation {
+    /// The original source file.
+    pub file: String,
+    /// The line, starting at 1.
+    pub line: u32,
+    /// The column, starting at 1.
+    pub column: u32,
+    /// The original name of the identifier at the location, if any.
+    pub name: Option<String>,
+}
+
+impl SourceMap {
+    /// Parses a source map in the JSON format. Index source maps are
+    /// flattened.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        Self::from_decoded(swc_sourcemap::decode_slice(bytes)?)
+    }
+
+    /// Parses a source map from a `data:application/json;base64,...` URL, as
+    /// used in inline `//# sourceMappingURL=` comments.
+    pub fn from_data_url(url: &str) -> Result<Self> {
+        Self::from_decoded(swc_sourcemap::decode_data_url(url)?)
+    }
+
+    /// Reads the source map embedded in a module generated by Javy, if any.
+    pub fn from_module(wasm: &[u8]) -> Result<Option<Self>> {
+        for payload in Parser::new(0).parse_all(wasm) {
+            if let Payload::CustomSection(section) = payload?
+                && section.name() == SECTION_NAME
+            {
+                return Self::from_slice(section.data()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn from_decoded(decoded: DecodedMap) -> Result<Self> {
+        let map = match decoded {
+            DecodedMap::Regular(map) => map,
+            DecodedMap::Index(index) => index.flatten()?,
+            DecodedMap::Hermes(map) => (*map).clone(),
+        };
+        Ok(Self { map })
+    }
+
+    /// The name of the module the source map applies to in stack traces.
+    fn module_name(&self) -> &str {
+        self.map
+            .get_file()
+            .map(|file| file.as_str())
+            .unwrap_or(DEFAULT_MODULE_NAME)
+    }
+
+    /// Sets the name of the module the source map applies to in stack traces.
+    pub(crate) fn set_module_name(&mut self, name: String) {
+        self.map.set_file(Some(name));
+    }
+
+    /// Looks up the original location of a line and column of the generated
+    /// code, both starting at 1 like in stack traces.
+    pub fn lookup(&self, line: u32, column: u32) -> Option<OriginalLocation> {
+        let token = self
+            .map
+            .lookup_token(line.checked_sub(1)?, column.checked_sub(1)?)?;
+        Some(OriginalLocation {
+            file: token.get_source()?.to_string(),
+            line: token.get_src_line() + 1,
+            column: token.get_src_col() + 1,
+            name: token.get_name().map(|name| name.to_string()),
+        })
+    }
+
+    /// Remaps the frames of a stack trace to the original source code.
+    ///
+    /// Both the text stack traces written by Javy, with frames like
+    /// `    at foo (function.mjs:2:13)`, and the JSON errors written with the
+    /// `json-errors` runtime option are supported. Frames of other modules,
+    /// and frames without an original location, are left untouched.
+    pub fn symbolicate(&self, trace: &str) -> String {
+        trace
+            .split_inclusive('\n')
+            .map(|line| {
+                let (content, ending) = match line.strip_suffix('\n') {
+                    Some(content) => (content, "\n"),
+                    None => (line, ""),
+                };
+                let remapped = if content.trim_start().starts_with('{') {
+                    self.symbolicate_json(content)
+                } else {
+                    self.symbolicate_frame(content)
+                };
+                match remapped {
+                    Some(remapped) => format!("{remapped}{ending}"),
+                    None => line.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    /// Remaps a frame like `    at foo (file:line:column)` or
+    /// `    at file:line:column`.
+    fn symbolicate_frame(&self, frame: &str) -> Option<String> {
+        let rest = frame.trim_start().strip_prefix("at ")?;
+        let (location, suffix) = match rest.strip_suffix(')') {
+            Some(rest) => (rest.rsplit_once(" (")?.1, ")"),
+            None => (rest, ""),
+        };
+        let mut parts = location.rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        if parts.next()? != self.module_name() {
+            return None;
+        }
+
+        let original = self.lookup(line, column)?;
+        let prefix = &frame[..frame.len() - location.len() - suffix.len()];
+        Some(format!(
+            "{prefix}{}:{}:{}{suffix}",
+            original.file, original.line, original.column
+        ))
+    }
+
+    /// Remaps the stack frames of a JSON error, and of its `cause` and
+    /// `errors`.
+    fn symbolicate_json(&self, json: &str) -> Option<String> {
+        let mut error: JsonError = serde_json::from_str(json).ok()?;
+        self.symbolicate_json_error(&mut error);
+        serde_json::to_string(&error).ok()
+    }
+
+    fn symbolicate_json_error(&self, error: &mut JsonError) {
+        for frame in &mut error.stack {
+            if frame.file.as_deref() != Some(self.module_name()) {
+                continue;
+            }
+            let Some(original) = frame
+                .line
+                .zip(frame.column)
+                .and_then(|(line, column)| self.lookup(line, column))
+            else {
+                continue;
+            };
+            frame.file = Some(original.file);
+            frame.line = Some(original.line);
+            frame.column = Some(original.column);
+        }
+        if let Some(cause) = &mut error.cause {
+            self.symbolicate_json_error(cause);
+        }
+        for nested in &mut error.errors {
+            self.symbolicate_json_error(nested);
+        }
+    }
+
+    /// Serializes the source map to JSON.
+    fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        self.map
+            .to_writer(&mut bytes)
+            .map_err(|e| anyhow!("Failed to serialize the source map: {e}"))?;
+        Ok(bytes)
+    }
+}
+
+/// An error written by the plugin with the `json-errors` runtime option, see
+/// `javy::JsError`.
+#[derive(Debug, Deserialize, Serialize)]
+struct JsonError {
+    name: Option<String>,
+    message: String,
+    #[serde(default)]
+    stack: Vec<JsonStackFrame>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cause: Option<Box<JsonError>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<JsonError>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct JsonStackFrame {
+    function: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+/// The custom section containing the source map of the JavaScript source
+/// code.
+#[derive(Debug)]
+pub(crate) struct SourceMapSection {
+    source_map: Vec<u8>,
+}
+
+impl SourceMapSection {
+    pub fn new(source_map: &SourceMap) -> Result<SourceMapSection> {
+        Ok(SourceMapSection {
+            source_map: source_map.to_vec()?,
+        })
+    }
+}
+
+impl CustomSection for SourceMapSection {
+    fn name(&self) -> &str {
+        SECTION_NAME
+    }
+
+    fn data(&self, _ids_to_indices: &IdsToIndices) -> Cow<'_, [u8]> {
+        (&self.source_map).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OriginalLocation, SourceMap};
+    use anyhow::Result;
+
+    // Maps `function.mjs`, the output of bundling `src/lib.ts`:
+    //
+    // ```ts
+    // export function fail(): never {
+    //   throw new Error("failed");
+    // }
+    // ```
+    //
+    // And `src/index.ts`:
+    //
+    // ```ts
+    // import { fail } from "./lib";
+    // fail();
+    // ```
+    //
+    // Into:
+    //
+    // ```js
+    // function fail() {
+    //   throw new Error("failed");
+    // }
+    // fail();
+    // ```
+    const MAP: &str = r#"{
+        "version": 3,
+        "sources": ["src/lib.ts", "src/index.ts"],
+        "names": ["fail"],
+        "mappings": "AAAO,SAASA;EACd,UAAU;AACZ;ACDAA"
+    }"#;
+
+    fn location(file: &str, line: u32, column: u32) -> OriginalLocation {
+        OriginalLocation {
+            file: file.to_string(),
+            line,
+            column,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn lookup() -> Result<()> {
+        let map = SourceMap::from_slice(MAP.as_bytes())?;
+        assert_eq!(Some(location("src/lib.ts", 2, 3)), map.lookup(2, 3));
+        assert_eq!(Some(location("src/lib.ts", 2, 13)), map.lookup(2, 13));
+        assert_eq!(
+            Some(OriginalLocation {
+                name: Some("fail".to_string()),
+                ..location("src/index.ts", 2, 1)
+            }),
+            map.lookup(4, 1)
+        );
+        assert_eq!(None, map.lookup(0, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn from_data_url() -> Result<()> {
+        let map = SourceMap::from_data_url(
+            "data:application/json;base64,eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbImEudHMiXSwibmFtZXMiOltdLCJtYXBwaW5ncyI6IkFBQUEifQ==",
+        )?;
+        assert_eq!(Some(location("a.ts", 1, 1)), map.lookup(1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn symbolicate_text() -> Result<()> {
+        let map = SourceMap::from_slice(MAP.as_bytes())?;
+        let trace = "Error: failed\n    at fail (function.mjs:2:13)\n    at <anonymous> (function.mjs:4:1)\n    at map (native)\n    at other.js:1:1\n\n";
+        assert_eq!(
+            "Error: failed\n    at fail (src/lib.ts:2:13)\n    at <anonymous> (src/index.ts:2:1)\n    at map (native)\n    at other.js:1:1\n\n",
+            map.symbolicate(trace)
+        );
+        assert_eq!(
+            "    at src/index.ts:2:1",
+            map.symbolicate("    at function.mjs:4:1")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn symbolicate_json() -> Result<()> {
+        let map = SourceMap::from_slice(MAP.as_bytes())?;
+        let trace = concat!(
+            r#"{"name":"Error","message":"failed","stack":[{"function":"fail","file":"function.mjs","line":2,"column":13}],"#,
+            r#""cause":{"name":"Error","message":"cause","stack":[{"function":"<anonymous>","file":"function.mjs","line":4,"column":1}]}}"#,
+            "\n",
+        );
+        assert_eq!(
+            concat!(
+                r#"{"name":"Error","message":"failed","stack":[{"function":"fail","file":"src/lib.ts","line":2,"column":13}],"#,
+                r#""cause":{"name":"Error","message":"cause","stack":[{"function":"<anonymous>","file":"src/index.ts","line":2,"column":1}]}}"#,
+                "\n",
+            ),
+            map.symbolicate(trace)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn symbolicate_named_module() -> Result<()> {
+        let mut map = SourceMap::from_slice(MAP.as_bytes())?;
+        map.set_module_name("index.js".to_string());
+        assert_eq!(
+            "    at fail (function.mjs:2:13)\n    at fail (src/lib.ts:2:13)",
+            map.symbolicate("    at fail (function.mjs:2:13)\n    at fail (index.js:2:13)")
+        );
+
+        let map = SourceMap::from_slice(&map.to_vec()?)?;
+        assert_eq!("index.js", map.module_name());
+        Ok(())
+    }
+}
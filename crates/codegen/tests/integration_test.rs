@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
-use javy_codegen::{Generator, JS, LinkingKind, Plugin, WitOptions};
+use javy_codegen::{Generator, JS, LinkingKind, Plugin, SourceMap, WitOptions};
 
 #[tokio::test]
 async fn test_empty() -> Result<()> {
@@ -75,6 +75,34 @@ async fn test_deterministic_builds_produce_identical_output() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_source_map_is_embedded() -> Result<()> {
+    let js = JS::from_file(&sample_scripts_dir().join("empty.js"))?;
+    let source_map = SourceMap::from_slice(
+        br#"{"version":3,"sources":["empty.ts"],"names":[],"mappings":"AAAA"}"#,
+    )?;
+
+    let wasm = Generator::new(default_plugin()?)
+        .linking(LinkingKind::Static)
+        .source_map(source_map)
+        .generate(&js)
+        .await?;
+
+    let source_map = SourceMap::from_module(&wasm)?.expect("source map to be embedded");
+    assert_eq!(
+        "    at <anonymous> (empty.ts:1:1)",
+        source_map.symbolicate("    at <anonymous> (function.mjs:1:1)")
+    );
+
+    // Modules without a source map don't embed one.
+    let wasm = Generator::new(default_plugin()?)
+        .linking(LinkingKind::Static)
+        .generate(&js)
+        .await?;
+    assert!(SourceMap::from_module(&wasm)?.is_none());
+    Ok(())
+}
+
 fn cargo_manifest_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
 }
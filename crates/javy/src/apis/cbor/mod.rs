@@ -0,0 +1,140 @@
+use crate::{
+    Args, cbor, hold, hold_and_release,
+    quickjs::{Ctx, Error as JSError, Exception, Function, Object, TypedArray, Value},
+    to_js_error,
+};
+use anyhow::{Error, Result, anyhow};
+
+/// Register the `Javy.CBOR` namespace, with `encode` and `decode` functions.
+pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+    let globals = this.globals();
+    if globals.get::<_, Object>("Javy").is_err() {
+        globals.set("Javy", Object::new(this.clone())?)?
+    }
+
+    let cbor = Object::new(this.clone())?;
+    cbor.set(
+        "encode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            encode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    cbor.set(
+        "decode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            decode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+
+    globals.get::<_, Object>("Javy")?.set("CBOR", cbor)?;
+
+    Ok::<_, Error>(())
+}
+
+/// Encode a value as CBOR into a `Uint8Array`.
+fn encode(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    let value = args
+        .first()
+        .cloned()
+        .unwrap_or_else(|| Value::new_undefined(cx.clone()));
+    let encoded = cbor::transcode_output(value)?;
+    Ok(TypedArray::new(cx, encoded)?.into_value())
+}
+
+/// Decode the CBOR bytes of a `Uint8Array` into a value.
+fn decode(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    let array = args
+        .first()
+        .and_then(|value| value.as_object())
+        .and_then(|object| object.as_typed_array::<u8>())
+        .ok_or_else(|| anyhow!(Exception::throw_type(&cx, "Data needs to be an Uint8Array")))?;
+    let bytes = array.as_bytes().unwrap_or_default();
+
+    cbor::transcode_input(cx.clone(), bytes).map_err(|e| {
+        if e.is::<JSError>() {
+            return e;
+        }
+        anyhow!(Exception::throw_syntax(
+            &cx,
+            &format!("Invalid CBOR data: {e}")
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|this| {
+            let cbor: Value<'_> = this.eval("globalThis.Javy?.CBOR")?;
+            assert!(cbor.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let mut config = Config::default();
+        config.javy_cbor(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String =
+                this.eval("`${typeof Javy.CBOR.encode} ${typeof Javy.CBOR.decode}`")?;
+            assert_eq!("function function", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_and_decode() -> Result<()> {
+        let mut config = Config::default();
+        config.javy_cbor(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const value = { a: [1, "s"], d: new Date(0), b: new Uint8Array([7]) };
+                const decoded = Javy.CBOR.decode(Javy.CBOR.encode(value));
+                [
+                    Array.from(Javy.CBOR.encode([1, "a"])).join(" "),
+                    JSON.stringify(decoded.a),
+                    decoded.d instanceof Date && decoded.d.getTime(),
+                    decoded.b instanceof Uint8Array && decoded.b[0],
+                ].join()
+            "#,
+            )?;
+            assert_eq!(r#"130 1 97 97,[1,"s"],0,7"#, result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() -> Result<()> {
+        let mut config = Config::default();
+        config.javy_cbor(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const error = (f) => { try { f(); } catch (e) { return e.constructor.name; } };
+                [
+                    error(() => Javy.CBOR.encode(2n ** 64n)),
+                    error(() => Javy.CBOR.decode("not bytes")),
+                    error(() => Javy.CBOR.decode(new Uint8Array([0x82, 0x01]))),
+                    error(() => Javy.CBOR.decode(new Uint8Array([0xa1, 0xf5, 0x01]))),
+                ].join()
+            "#,
+            )?;
+            assert_eq!("RangeError,TypeError,SyntaxError,TypeError", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
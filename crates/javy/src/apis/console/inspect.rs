@@ -0,0 +1,990 @@
+//! Node-style inspection and formatting of JavaScript values, used by the
+//! `console` APIs.
+//!
+//! The output follows the format of Node's `util.inspect` and `util.format`
+//! closely, without colors and without the options which are not relevant to
+//! `console`.
+use crate::{
+    quickjs::{
+        Array, ArrayBuffer, Ctx, Function, Object, String as JSString, Type, Value,
+        convert::Coerced, function::This, promise::PromiseState,
+    },
+    to_string_lossy, val_to_string,
+};
+use anyhow::Result;
+
+/// The maximum number of array, set and map entries to display.
+const MAX_ARRAY_LENGTH: usize = 100;
+/// The maximum number of bytes of an `ArrayBuffer` to display.
+const MAX_BUFFER_LENGTH: usize = 50;
+/// The number of nesting levels which are combined on a single line.
+const COMPACT: usize = 3;
+
+/// Options controlling the inspection of values.
+#[derive(Clone, Copy)]
+pub(crate) struct InspectOptions {
+    /// The number of nesting levels to recurse into while inspecting objects.
+    /// `None` recurses indefinitely.
+    pub depth: Option<usize>,
+    /// The length at which entries are split across multiple lines.
+    pub break_length: usize,
+}
+
+impl Default for InspectOptions {
+    fn default() -> Self {
+        Self {
+            depth: Some(2),
+            break_length: 80,
+        }
+    }
+}
+
+/// Returns a string representation of a value, as `util.inspect` does.
+///
+/// Unlike [`format`], strings are quoted.
+pub(crate) fn inspect<'js>(
+    cx: &Ctx<'js>,
+    value: Value<'js>,
+    options: InspectOptions,
+) -> Result<String> {
+    Inspector::new(cx, options)?.format_value(value, 0)
+}
+
+/// Formats the arguments, as `util.format` does.
+///
+/// If the first argument is a string, it can contain the `%s`, `%d`, `%i`,
+/// `%f`, `%j`, `%o`, `%O` and `%c` format specifiers, which are replaced by
+/// the corresponding argument. The remaining arguments are appended,
+/// separated by spaces. Strings are appended as is, other values are
+/// inspected.
+pub(crate) fn format<'js>(cx: &Ctx<'js>, args: Vec<Value<'js>>) -> Result<String> {
+    let mut args = args.into_iter().peekable();
+    let mut out = String::new();
+
+    if let Some(first) = args.next_if(|arg| arg.is_string()) {
+        let format = string_to_string(cx, first.as_string().unwrap());
+        if args.peek().is_none() {
+            return Ok(format);
+        }
+
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.peek() {
+                Some('%') => {
+                    chars.next();
+                    out.push('%');
+                }
+                Some(&specifier @ ('s' | 'd' | 'i' | 'f' | 'j' | 'o' | 'O' | 'c')) => {
+                    match args.next() {
+                        Some(arg) => {
+                            chars.next();
+                            out.push_str(&format_specifier(cx, specifier, arg)?);
+                        }
+                        None => out.push('%'),
+                    }
+                }
+                _ => out.push('%'),
+            }
+        }
+    } else if let Some(first) = args.next() {
+        out.push_str(&inspect(cx, first, InspectOptions::default())?);
+    }
+
+    for arg in args {
+        out.push(' ');
+        match arg.as_string() {
+            Some(string) => out.push_str(&string_to_string(cx, string)),
+            None => out.push_str(&inspect(cx, arg, InspectOptions::default())?),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Formats a single argument according to a format specifier.
+fn format_specifier<'js>(cx: &Ctx<'js>, specifier: char, arg: Value<'js>) -> Result<String> {
+    let globals = cx.globals();
+    Ok(match specifier {
+        's' => match arg.type_of() {
+            Type::BigInt => format!("{}n", val_to_string(cx, arg)?),
+            Type::Object | Type::Array | Type::Exception | Type::Promise => inspect(
+                cx,
+                arg,
+                InspectOptions {
+                    depth: Some(0),
+                    ..Default::default()
+                },
+            )?,
+            _ => primitive_to_string(cx, arg)?,
+        },
+        'd' | 'i' | 'f' => match arg.type_of() {
+            Type::BigInt if specifier != 'f' => format!("{}n", val_to_string(cx, arg)?),
+            Type::Symbol => "NaN".to_string(),
+            _ => {
+                let number = match specifier {
+                    'd' => arg.get::<Coerced<f64>>()?.0,
+                    'i' => globals.get::<_, Function>("parseInt")?.call((arg,))?,
+                    _ => globals.get::<_, Function>("parseFloat")?.call((arg,))?,
+                };
+                number_to_string(cx, number)?
+            }
+        },
+        'j' => match cx.json_stringify(arg) {
+            Ok(Some(json)) => string_to_string(cx, &json),
+            Ok(None) => "undefined".to_string(),
+            Err(_) => {
+                // Discard the pending exception, the only expected error
+                // being a circular structure.
+                cx.catch();
+                "[Circular]".to_string()
+            }
+        },
+        'o' => inspect(
+            cx,
+            arg,
+            InspectOptions {
+                depth: Some(4),
+                ..Default::default()
+            },
+        )?,
+        'O' => inspect(cx, arg, InspectOptions::default())?,
+        // CSS styles are ignored.
+        _ => String::new(),
+    })
+}
+
+/// The kind of entries of an object, which determines how they are
+/// combined.
+#[derive(PartialEq)]
+enum Entries {
+    /// Array-like entries, which can be grouped in columns.
+    Array { numeric: bool },
+    /// Object properties, or any other kind of entries.
+    Object,
+}
+
+/// Formats the entries of an object, given the remaining recursion depth.
+type FormatEntries<'js> = Box<dyn FnOnce(&mut Inspector<'js>, usize) -> Result<Vec<String>> + 'js>;
+
+struct Inspector<'js> {
+    cx: Ctx<'js>,
+    options: InspectOptions,
+    /// The objects currently being inspected, used to detect cycles.
+    seen: Vec<Object<'js>>,
+    /// The reference numbers of the objects which are part of a cycle.
+    circular: Vec<Object<'js>>,
+    /// The current indentation.
+    indentation: usize,
+    /// The depth of the last inspected object.
+    current_depth: usize,
+    object_to_string: Function<'js>,
+    function_to_string: Function<'js>,
+    get_own_property_descriptor: Function<'js>,
+    own_keys: Function<'js>,
+    array_from: Function<'js>,
+}
+
+impl<'js> Inspector<'js> {
+    fn new(cx: &Ctx<'js>, options: InspectOptions) -> Result<Self> {
+        let globals = cx.globals();
+        let object: Object = globals.get("Object")?;
+        let function: Object = globals.get("Function")?;
+        let array: Object = globals.get("Array")?;
+        let reflect: Object = globals.get("Reflect")?;
+        Ok(Self {
+            cx: cx.clone(),
+            options,
+            seen: vec![],
+            circular: vec![],
+            indentation: 0,
+            current_depth: 0,
+            object_to_string: object.get::<_, Object>("prototype")?.get("toString")?,
+            function_to_string: function.get::<_, Object>("prototype")?.get("toString")?,
+            get_own_property_descriptor: object.get("getOwnPropertyDescriptor")?,
+            own_keys: reflect.get("ownKeys")?,
+            array_from: array.get("from")?,
+        })
+    }
+
+    fn format_value(&mut self, value: Value<'js>, recurse_times: usize) -> Result<String> {
+        match value.as_object() {
+            Some(object) => self.format_object(object.clone(), recurse_times),
+            None => match value.as_string() {
+                Some(string) => Ok(quote(&string_to_string(&self.cx, string))),
+                None => primitive_to_string(&self.cx, value),
+            },
+        }
+    }
+
+    fn format_object(&mut self, object: Object<'js>, recurse_times: usize) -> Result<String> {
+        if self.seen.contains(&object) {
+            let index = match self.circular.iter().position(|o| *o == object) {
+                Some(index) => index,
+                None => {
+                    self.circular.push(object.clone());
+                    self.circular.len() - 1
+                }
+            };
+            return Ok(format!("[Circular *{}]", index + 1));
+        }
+
+        let formatted = self.format_raw(&object, recurse_times)?;
+        Ok(match self.circular.iter().position(|o| *o == object) {
+            Some(index) => format!("<ref *{}> {formatted}", index + 1),
+            None => formatted,
+        })
+    }
+
+    fn format_raw(&mut self, object: &Object<'js>, recurse_times: usize) -> Result<String> {
+        let cx = self.cx.clone();
+        let value = object.as_value().clone();
+        let tag = self.tag(object)?;
+        let constructor = constructor_name(object)?;
+        let prefix = match &constructor {
+            Some(name) if *name != tag && !tag.is_empty() && !is_builtin_tag(&tag) => {
+                format!("{name} [{tag}]")
+            }
+            Some(name) => name.clone(),
+            None => "[Object: null prototype]".to_string(),
+        };
+        let mut keys = self.keys(object)?;
+
+        let mut base = String::new();
+        let mut entries = Entries::Object;
+        let mut braces: [String; 2];
+        let mut output_entries: Option<FormatEntries<'js>> = None;
+
+        if let Some(array) = value.as_array() {
+            let len = array.len();
+            keys.retain(|(key, _)| !is_index(key));
+            braces = [
+                if constructor.as_deref() == Some("Array") {
+                    "[".to_string()
+                } else {
+                    format!("{prefix}({len}) [")
+                },
+                "]".to_string(),
+            ];
+            if len == 0 && keys.is_empty() {
+                return Ok(format!("{}]", braces[0]));
+            }
+            let numeric = (0..len).all(|i| {
+                array
+                    .get::<Value>(i)
+                    .map(|v| v.is_number() || v.is_big_int())
+                    .unwrap_or(false)
+            });
+            entries = Entries::Array { numeric };
+            let array = array.clone();
+            output_entries = Some(Box::new(move |this, recurse_times| {
+                this.format_array(&array, recurse_times)
+            }));
+        } else if is_typed_array(&tag) {
+            let len: usize = object.get("length")?;
+            keys.retain(|(key, _)| !is_index(key));
+            braces = [format!("{prefix}({len}) ["), "]".to_string()];
+            if len == 0 && keys.is_empty() {
+                return Ok(format!("{}]", braces[0]));
+            }
+            entries = Entries::Array { numeric: true };
+            let object = object.clone();
+            output_entries = Some(Box::new(move |this, recurse_times| {
+                let mut output = vec![];
+                for i in 0..len.min(MAX_ARRAY_LENGTH) {
+                    output.push(this.format_value(object.get(i as u32)?, recurse_times)?);
+                }
+                more_items(&mut output, len);
+                Ok(output)
+            }));
+        } else if tag == "Map" || tag == "Set" {
+            let size: usize = object.get("size")?;
+            braces = [format!("{prefix}({size}) {{"), "}".to_string()];
+            if size == 0 && keys.is_empty() {
+                return Ok(format!("{}}}", braces[0]));
+            }
+            let items: Array = self.array_from.call((value.clone(),))?;
+            let is_map = tag == "Map";
+            output_entries = Some(Box::new(move |this, recurse_times| {
+                this.format_collection(&items, is_map, recurse_times)
+            }));
+        } else if tag == "WeakMap" || tag == "WeakSet" {
+            return Ok(format!("{prefix} {{ <items unknown> }}"));
+        } else if tag == "Promise" {
+            braces = [format!("{prefix} {{"), "}".to_string()];
+            let promise = value.as_promise().unwrap().clone();
+            output_entries = Some(Box::new(move |this, recurse_times| {
+                let state = match promise.state() {
+                    PromiseState::Pending => "<pending>".to_string(),
+                    PromiseState::Resolved => {
+                        let result: Value = promise.result().unwrap()?;
+                        this.indented(|this| this.format_value(result, recurse_times))?
+                    }
+                    PromiseState::Rejected => {
+                        // Reading the result of a rejected promise throws the
+                        // rejection reason.
+                        let _ = promise.result::<Value>();
+                        let reason = this.cx.catch();
+                        let reason =
+                            this.indented(|this| this.format_value(reason, recurse_times))?;
+                        format!("<rejected> {reason}")
+                    }
+                };
+                Ok(vec![state])
+            }));
+        } else if tag == "ArrayBuffer" || tag == "SharedArrayBuffer" {
+            braces = [format!("{prefix} {{"), "}".to_string()];
+            let bytes = ArrayBuffer::from_object(object.clone())
+                .and_then(|buffer| buffer.as_bytes().map(|b| b.to_vec()))
+                .unwrap_or_default();
+            output_entries = Some(Box::new(move |_, _| {
+                let mut contents = bytes
+                    .iter()
+                    .take(MAX_BUFFER_LENGTH)
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if bytes.len() > MAX_BUFFER_LENGTH {
+                    contents.push_str(&format!(
+                        " ... {} more byte{}",
+                        bytes.len() - MAX_BUFFER_LENGTH,
+                        if bytes.len() - MAX_BUFFER_LENGTH > 1 {
+                            "s"
+                        } else {
+                            ""
+                        }
+                    ));
+                }
+                Ok(vec![
+                    format!("[Uint8Contents]: <{contents}>"),
+                    format!("byteLength: {}", bytes.len()),
+                ])
+            }));
+        } else {
+            braces = ["{".to_string(), "}".to_string()];
+            if let Some(function) = value.as_function() {
+                base = self.function_base(function, constructor.as_deref())?;
+            } else if value.is_error() {
+                base = self.error_base(object)?;
+                // Own `name` and `message` properties are already part of
+                // the header.
+                for key in ["name", "message"] {
+                    if let Some(value) = self.own_property(object, key)?
+                        && let Some(value) = value.as_string()
+                        && base.contains(&string_to_string(&cx, value))
+                    {
+                        keys.retain(|(k, _)| {
+                            k.as_string().map(|k| string_to_string(&cx, k)).as_deref() != Some(key)
+                        });
+                    }
+                }
+                if let Some(cause) = self.own_property(object, "cause")? {
+                    keys.push((
+                        Value::from_string(JSString::from_str(cx.clone(), "[cause]")?),
+                        Some(cause),
+                    ));
+                }
+            } else if tag == "Date" {
+                let time: f64 = object
+                    .get::<_, Function>("getTime")?
+                    .call((This(object.clone()),))?;
+                base = if time.is_nan() {
+                    "Invalid Date".to_string()
+                } else {
+                    object
+                        .get::<_, Function>("toISOString")?
+                        .call((This(object.clone()),))?
+                };
+            } else if tag == "RegExp" {
+                base = object
+                    .get::<_, Function>("toString")?
+                    .call((This(object.clone()),))?;
+            } else if matches!(
+                tag.as_str(),
+                "Number" | "String" | "Boolean" | "BigInt" | "Symbol"
+            ) {
+                let primitive: Value = object
+                    .get::<_, Function>("valueOf")?
+                    .call((This(object.clone()),))?;
+                let primitive = self.format_value(primitive, recurse_times)?;
+                base = format!("[{tag}: {primitive}]");
+                if tag == "String" {
+                    keys.retain(|(key, _)| !is_index(key));
+                }
+            } else if constructor.as_deref() != Some("Object") || tag != "Object" {
+                braces[0] = format!("{prefix} {{");
+            }
+
+            if keys.is_empty() {
+                if base.is_empty() {
+                    return Ok(format!("{}}}", braces[0]));
+                }
+                return Ok(base);
+            }
+        }
+
+        if self
+            .options
+            .depth
+            .is_some_and(|depth| recurse_times > depth)
+        {
+            let name = match &constructor {
+                Some(name) => name.clone(),
+                None => format!("{tag}: null prototype"),
+            };
+            return Ok(format!("[{}]", if name.is_empty() { tag } else { name }));
+        }
+
+        let recurse_times = recurse_times + 1;
+        self.seen.push(object.clone());
+        self.current_depth = recurse_times;
+
+        let mut output = match output_entries {
+            Some(output_entries) => output_entries(self, recurse_times)?,
+            None => vec![],
+        };
+        for (key, value) in keys {
+            output.push(self.format_property(object, key, value, recurse_times)?);
+        }
+
+        self.seen.pop();
+
+        Ok(self.reduce_to_single_string(output, &base, &braces, entries, recurse_times))
+    }
+
+    /// Formats the elements of an array, including holes.
+    fn format_array(&mut self, array: &Array<'js>, recurse_times: usize) -> Result<Vec<String>> {
+        let mut output = vec![];
+        let len = array.len();
+        let mut i = 0;
+        while i < len && output.len() < MAX_ARRAY_LENGTH {
+            if !array.as_object().contains_key(i as u32)? {
+                let start = i;
+                while i < len && !array.as_object().contains_key(i as u32)? {
+                    i += 1;
+                }
+                let holes = i - start;
+                output.push(format!(
+                    "<{holes} empty item{}>",
+                    if holes > 1 { "s" } else { "" }
+                ));
+                continue;
+            }
+            let value = array.get(i)?;
+            output.push(self.indented(|this| this.format_value(value, recurse_times))?);
+            i += 1;
+        }
+        let total = len - i + output.len();
+        more_items(&mut output, total);
+        Ok(output)
+    }
+
+    /// Formats the entries of a `Map` or the values of a `Set`.
+    fn format_collection(
+        &mut self,
+        items: &Array<'js>,
+        is_map: bool,
+        recurse_times: usize,
+    ) -> Result<Vec<String>> {
+        let mut output = vec![];
+        for i in 0..items.len().min(MAX_ARRAY_LENGTH) {
+            let item: Value = items.get(i)?;
+            output.push(self.indented(|this| {
+                if is_map {
+                    let entry = item.as_array().unwrap();
+                    let key = this.format_value(entry.get(0)?, recurse_times)?;
+                    let value = this.format_value(entry.get(1)?, recurse_times)?;
+                    Ok(format!("{key} => {value}"))
+                } else {
+                    this.format_value(item, recurse_times)
+                }
+            })?);
+        }
+        more_items(&mut output, items.len());
+        Ok(output)
+    }
+
+    /// Formats a single property, without invoking getters.
+    fn format_property(
+        &mut self,
+        object: &Object<'js>,
+        key: Value<'js>,
+        value: Option<Value<'js>>,
+        recurse_times: usize,
+    ) -> Result<String> {
+        let name = match key.as_string() {
+            Some(key) if value.is_some() => string_to_string(&self.cx, key),
+            Some(key) => {
+                let key = string_to_string(&self.cx, key);
+                if is_identifier(&key) {
+                    key
+                } else {
+                    quote(&key)
+                }
+            }
+            None => format!("[{}]", val_to_string(&self.cx, key.clone())?),
+        };
+
+        let value = match value {
+            Some(value) => self.indented(|this| this.format_value(value, recurse_times))?,
+            None => {
+                let descriptor: Object = self
+                    .get_own_property_descriptor
+                    .call((object.clone(), key))?;
+                let getter = !descriptor.get::<_, Value>("get")?.is_undefined();
+                let setter = !descriptor.get::<_, Value>("set")?.is_undefined();
+                match (getter, setter) {
+                    (true, true) => "[Getter/Setter]".to_string(),
+                    (true, false) => "[Getter]".to_string(),
+                    (false, true) => "[Setter]".to_string(),
+                    (false, false) => {
+                        let value = descriptor.get("value")?;
+                        self.indented(|this| this.format_value(value, recurse_times))?
+                    }
+                }
+            }
+        };
+
+        Ok(format!("{name}: {value}"))
+    }
+
+    fn function_base(&self, function: &Function<'js>, constructor: Option<&str>) -> Result<String> {
+        let name = function
+            .get::<_, Value>("name")?
+            .as_string()
+            .map(|name| string_to_string(&self.cx, name))
+            .unwrap_or_default();
+        let source: String = self
+            .function_to_string
+            .call((This(function.clone()),))
+            .unwrap_or_default();
+
+        if source.starts_with("class") && source.ends_with('}') {
+            let mut base = if name.is_empty() {
+                "[class (anonymous)".to_string()
+            } else {
+                format!("[class {name}")
+            };
+            if let Some(parent) = function
+                .get_prototype()
+                .and_then(|p| p.as_function().cloned())
+            {
+                let parent: String = parent.get("name").unwrap_or_default();
+                if !parent.is_empty() {
+                    base.push_str(&format!(" extends {parent}"));
+                }
+            }
+            base.push(']');
+            return Ok(base);
+        }
+
+        let kind = match constructor {
+            Some(kind @ ("AsyncFunction" | "GeneratorFunction" | "AsyncGeneratorFunction")) => {
+                kind.to_string()
+            }
+            Some(_) => "Function".to_string(),
+            None => "Function (null prototype)".to_string(),
+        };
+        Ok(if name.is_empty() {
+            format!("[{kind} (anonymous)]")
+        } else {
+            format!("[{kind}: {name}]")
+        })
+    }
+
+    fn error_base(&self, error: &Object<'js>) -> Result<String> {
+        let to_string = |key: &str| -> Result<String> {
+            Ok(error
+                .get::<_, Value>(key)?
+                .as_string()
+                .map(|s| string_to_string(&self.cx, s))
+                .unwrap_or_default())
+        };
+        let name = to_string("name")?;
+        let message = to_string("message")?;
+        let stack = to_string("stack")?;
+
+        let mut header = if name.is_empty() {
+            "Error".to_string()
+        } else {
+            name
+        };
+        if !message.is_empty() {
+            header.push_str(&format!(": {message}"));
+        }
+        let stack = stack.trim_end();
+        let base = if stack.is_empty() {
+            format!("[{header}]")
+        } else if stack.starts_with(&header) {
+            stack.to_string()
+        } else {
+            format!("{header}\n{stack}")
+        };
+
+        // Align the stack frames with the current indentation.
+        Ok(if self.indentation > 0 {
+            base.replace('\n', &format!("\n{}", " ".repeat(self.indentation)))
+        } else {
+            base
+        })
+    }
+
+    /// Combines the formatted entries of an object, on a single line if they
+    /// fit, or across multiple lines otherwise.
+    fn reduce_to_single_string(
+        &self,
+        mut output: Vec<String>,
+        base: &str,
+        braces: &[String; 2],
+        entries: Entries,
+        recurse_times: usize,
+    ) -> String {
+        let count = output.len();
+        if let Entries::Array { numeric } = entries
+            && count > 6
+        {
+            output = self.group_array_elements(output, numeric);
+        }
+
+        let base = if base.is_empty() {
+            String::new()
+        } else {
+            format!("{base} ")
+        };
+        if self.current_depth - recurse_times < COMPACT && count == output.len() {
+            let start = output.len()
+                + self.indentation
+                + braces[0].chars().count()
+                + base.chars().count()
+                + 10;
+            if self.is_below_break_length(&output, start, &base) {
+                let joined = output.join(", ");
+                if !joined.contains('\n') {
+                    return format!("{base}{} {joined} {}", braces[0], braces[1]);
+                }
+            }
+        }
+
+        let indentation = format!("\n{}", " ".repeat(self.indentation));
+        format!(
+            "{base}{}{indentation}  {}{indentation}{}",
+            braces[0],
+            output.join(&format!(",{indentation}  ")),
+            braces[1]
+        )
+    }
+
+    fn is_below_break_length(&self, output: &[String], start: usize, base: &str) -> bool {
+        let mut total_length = output.len() + start;
+        if total_length + output.len() > self.options.break_length {
+            return false;
+        }
+        for entry in output {
+            total_length += entry.chars().count();
+            if total_length > self.options.break_length {
+                return false;
+            }
+        }
+        !base.contains('\n')
+    }
+
+    /// Groups the entries of arrays with more than six entries in columns.
+    fn group_array_elements(&self, output: Vec<String>, numeric: bool) -> Vec<String> {
+        let mut total_length = 0;
+        let mut max_length = 0;
+        let output_length = if output.len() > MAX_ARRAY_LENGTH
+            || output.last().is_some_and(|o| o.starts_with("... "))
+        {
+            // Don't take the "... more items" entry into account.
+            output.len() - 1
+        } else {
+            output.len()
+        };
+        // One for the space and one for the separator.
+        let separator_space = 2;
+        let data_len: Vec<usize> = output[..output_length]
+            .iter()
+            .map(|o| o.chars().count())
+            .collect();
+        for len in &data_len {
+            total_length += len + separator_space;
+            max_length = max_length.max(*len);
+        }
+        let actual_max = max_length + separator_space;
+
+        if actual_max * 3 + self.indentation < self.options.break_length
+            && (total_length as f64 / actual_max as f64 > 5.0 || max_length <= 6)
+        {
+            let approx_char_heights = 2.5;
+            let average_bias =
+                (actual_max as f64 - total_length as f64 / output.len() as f64).sqrt();
+            let biased_max = (actual_max as f64 - 3.0 - average_bias).max(1.0);
+            let columns = [
+                ((approx_char_heights * biased_max * output_length as f64).sqrt() / biased_max)
+                    .round() as usize,
+                (self.options.break_length - self.indentation) / actual_max,
+                COMPACT * 4,
+                15,
+            ]
+            .into_iter()
+            .min()
+            .unwrap();
+            if columns <= 1 {
+                return output;
+            }
+
+            let max_line_length: Vec<usize> = (0..columns)
+                .map(|i| {
+                    data_len
+                        .iter()
+                        .skip(i)
+                        .step_by(columns)
+                        .copied()
+                        .max()
+                        .unwrap_or(0)
+                        + separator_space
+                })
+                .collect();
+
+            let mut grouped = vec![];
+            for i in (0..output_length).step_by(columns) {
+                let max = (i + columns).min(output_length);
+                let mut line = String::new();
+                for j in i..max - 1 {
+                    let cell = format!("{}, ", output[j]);
+                    line.push_str(&pad(&cell, max_line_length[j - i], numeric));
+                }
+                let j = max - 1;
+                if numeric {
+                    line.push_str(&pad(
+                        &output[j],
+                        max_line_length[j - i] - separator_space,
+                        true,
+                    ));
+                } else {
+                    line.push_str(&output[j]);
+                }
+                grouped.push(line);
+            }
+            if output_length < output.len() {
+                grouped.push(output[output_length].clone());
+            }
+            return grouped;
+        }
+
+        output
+    }
+
+    /// Runs `f` with the indentation increased for nested entries.
+    fn indented<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.indentation += 2;
+        let result = f(self);
+        self.indentation -= 2;
+        result
+    }
+
+    /// The `Object.prototype.toString` tag of an object.
+    fn tag(&self, object: &Object<'js>) -> Result<String> {
+        let tag: String = self.object_to_string.call((This(object.clone()),))?;
+        Ok(tag
+            .strip_prefix("[object ")
+            .and_then(|t| t.strip_suffix(']'))
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// The own enumerable properties of an object, with string keys first.
+    fn keys(&self, object: &Object<'js>) -> Result<Vec<(Value<'js>, Option<Value<'js>>)>> {
+        let keys: Array = self.own_keys.call((object.clone(),))?;
+        let mut enumerable = vec![];
+        for key in keys.iter::<Value>() {
+            let key = key?;
+            let descriptor: Object = self
+                .get_own_property_descriptor
+                .call((object.clone(), key.clone()))?;
+            if descriptor.get("enumerable")? {
+                enumerable.push((key, None));
+            }
+        }
+        Ok(enumerable)
+    }
+
+    /// An own property, if it's present, without invoking getters.
+    fn own_property(&self, object: &Object<'js>, key: &str) -> Result<Option<Value<'js>>> {
+        let descriptor: Option<Object> = self
+            .get_own_property_descriptor
+            .call((object.clone(), key))?;
+        descriptor
+            .map(|d| d.get("value"))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+/// The name of the constructor of an object, `None` for objects with a null
+/// prototype.
+fn constructor_name(object: &Object<'_>) -> Result<Option<String>> {
+    let mut proto = object.get_prototype();
+    while let Some(current) = proto {
+        if let Some(constructor) = current
+            .get::<_, Value>("constructor")?
+            .as_function()
+            .cloned()
+        {
+            let name: Value = constructor.get("name")?;
+            if let Some(name) = name.as_string() {
+                let name = name.to_string()?;
+                if !name.is_empty() {
+                    return Ok(Some(name));
+                }
+            }
+        }
+        proto = current.get_prototype();
+    }
+    Ok(None)
+}
+
+/// Converts a primitive value to a string.
+fn primitive_to_string<'js>(cx: &Ctx<'js>, value: Value<'js>) -> Result<String> {
+    match value.type_of() {
+        Type::Float | Type::Int => number_to_string(cx, value.as_number().unwrap()),
+        Type::BigInt => Ok(format!("{}n", val_to_string(cx, value)?)),
+        _ => val_to_string(cx, value),
+    }
+}
+
+fn number_to_string(cx: &Ctx<'_>, number: f64) -> Result<String> {
+    if number == 0.0 && number.is_sign_negative() {
+        Ok("-0".to_string())
+    } else {
+        val_to_string(cx, Value::new_number(cx.clone(), number))
+    }
+}
+
+fn string_to_string<'js>(cx: &Ctx<'js>, string: &JSString<'js>) -> String {
+    string
+        .to_string()
+        .unwrap_or_else(|e| to_string_lossy(cx, string, e))
+}
+
+/// Quotes a string, picking single quotes unless the string contains any.
+fn quote(string: &str) -> String {
+    let quote = if !string.contains('\'') {
+        '\''
+    } else if !string.contains('"') {
+        '"'
+    } else if !string.contains('`') && !string.contains("${") {
+        '`'
+    } else {
+        '\''
+    };
+
+    let mut quoted = String::with_capacity(string.len() + 2);
+    quoted.push(quote);
+    for c in string.chars() {
+        match c {
+            c if c == quote => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\u{c}' => quoted.push_str("\\f"),
+            '\u{b}' => quoted.push_str("\\v"),
+            c if (c as u32) < 0x20 || c as u32 == 0x7f => {
+                quoted.push_str(&format!("\\x{:02X}", c as u32))
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push(quote);
+    quoted
+}
+
+fn pad(string: &str, width: usize, start: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(string.chars().count()));
+    if start {
+        format!("{padding}{string}")
+    } else {
+        format!("{string}{padding}")
+    }
+}
+
+fn more_items(output: &mut Vec<String>, len: usize) {
+    if len > output.len() {
+        let remaining = len - output.len();
+        output.push(format!(
+            "... {remaining} more item{}",
+            if remaining > 1 { "s" } else { "" }
+        ));
+    }
+}
+
+fn is_index(key: &Value<'_>) -> bool {
+    key.as_string()
+        .and_then(|key| key.to_string().ok())
+        .is_some_and(|key| key.parse::<u32>().is_ok_and(|i| i.to_string() == key))
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_typed_array(tag: &str) -> bool {
+    matches!(
+        tag,
+        "Int8Array"
+            | "Uint8Array"
+            | "Uint8ClampedArray"
+            | "Int16Array"
+            | "Uint16Array"
+            | "Int32Array"
+            | "Uint32Array"
+            | "Float16Array"
+            | "Float32Array"
+            | "Float64Array"
+            | "BigInt64Array"
+            | "BigUint64Array"
+    )
+}
+
+/// Whether the tag is one of the built-in tags, which are not displayed
+/// next to the constructor name.
+fn is_builtin_tag(tag: &str) -> bool {
+    is_typed_array(tag)
+        || matches!(
+            tag,
+            "Object"
+                | "Array"
+                | "Function"
+                | "AsyncFunction"
+                | "GeneratorFunction"
+                | "AsyncGeneratorFunction"
+                | "Error"
+                | "Boolean"
+                | "Number"
+                | "String"
+                | "Symbol"
+                | "BigInt"
+                | "Date"
+                | "RegExp"
+                | "Map"
+                | "Set"
+                | "WeakMap"
+                | "WeakSet"
+                | "Promise"
+                | "ArrayBuffer"
+                | "SharedArrayBuffer"
+                | "Arguments"
+        )
+}
@@ -1,73 +1,416 @@
-use std::io::Write;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, hash_map::Entry},
+    io::Write,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use crate::{
     Args, hold, hold_and_release,
-    quickjs::{Ctx, Function, Object, Value, prelude::MutFn},
-    to_js_error, val_to_string,
+    quickjs::{Ctx, Exception, Function, Object, Value, convert::Coerced, prelude::MutFn},
+    to_js_error,
 };
 use anyhow::Result;
 
-/// Register a `console` object on the global object with `.log` and `.error`
-/// streams.
-pub(crate) fn register<T, U>(this: Ctx<'_>, mut log_stream: T, mut error_stream: U) -> Result<()>
+mod inspect;
+mod table;
+
+use inspect::{InspectOptions, format, inspect};
+
+/// The state shared by the `console` methods.
+struct Console<T, U> {
+    /// The stream used by `log`, `info`, `debug`, `dir`, `table`, `count`,
+    /// `time` and `group`.
+    log_stream: T,
+    /// The stream used by `error`, `warn`, `trace` and `assert`.
+    error_stream: U,
+    /// The indentation of the current group.
+    group_indentation: usize,
+    /// The counters used by `count`.
+    counts: HashMap<String, usize>,
+    /// The timers used by `time`.
+    timers: HashMap<String, Instant>,
+}
+
+/// The stream a message is written to.
+#[derive(Clone, Copy)]
+enum Stream {
+    Log,
+    Error,
+}
+
+impl<T: Write, U: Write> Console<T, U> {
+    /// Writes a message, indenting each line according to the current group.
+    fn write(&mut self, stream: Stream, message: &str) -> Result<()> {
+        let indentation = " ".repeat(self.group_indentation);
+        let stream: &mut dyn Write = match stream {
+            Stream::Log => &mut self.log_stream,
+            Stream::Error => &mut self.error_stream,
+        };
+        for line in message.split('\n') {
+            writeln!(stream, "{indentation}{line}")?;
+        }
+        Ok(())
+    }
+}
+
+type SharedConsole<T, U> = Rc<RefCell<Console<T, U>>>;
+
+/// Register a `console` object on the global object.
+///
+/// `console.log`, `console.info`, `console.debug`, `console.dir`,
+/// `console.table`, `console.count`, `console.time` and `console.group`
+/// write to `log_stream`, while `console.error`, `console.warn`,
+/// `console.trace` and `console.assert` write to `error_stream`.
+pub(crate) fn register<T, U>(this: Ctx<'_>, log_stream: T, error_stream: U) -> Result<()>
 where
     T: Write + 'static,
     U: Write + 'static,
 {
     let globals = this.globals();
     let console = Object::new(this.clone())?;
+    let state = Rc::new(RefCell::new(Console {
+        log_stream,
+        error_stream,
+        group_indentation: 0,
+        counts: HashMap::new(),
+        timers: HashMap::new(),
+    }));
+
+    for (name, stream) in [
+        ("log", Stream::Log),
+        ("info", Stream::Log),
+        ("debug", Stream::Log),
+        ("error", Stream::Error),
+        ("warn", Stream::Error),
+    ] {
+        let state = state.clone();
+        set_method(&this, &console, name, move |args| log(args, &state, stream))?;
+    }
 
-    console.set(
-        "log",
-        Function::new(
-            this.clone(),
-            MutFn::new(move |cx, args| {
-                let (cx, args) = hold_and_release!(cx, args);
-                log(hold!(cx.clone(), args), &mut log_stream).map_err(|e| to_js_error(cx, e))
-            }),
-        )?,
-    )?;
+    let dir_state = state.clone();
+    set_method(&this, &console, "dir", move |args| dir(args, &dir_state))?;
+    let trace_state = state.clone();
+    set_method(&this, &console, "trace", move |args| {
+        trace(args, &trace_state)
+    })?;
+    let assert_state = state.clone();
+    set_method(&this, &console, "assert", move |args| {
+        assert(args, &assert_state)
+    })?;
+    let table_state = state.clone();
+    set_method(&this, &console, "table", move |args| {
+        table(args, &table_state)
+    })?;
+    let count_state = state.clone();
+    set_method(&this, &console, "count", move |args| {
+        count(args, &count_state, false)
+    })?;
+    let count_reset_state = state.clone();
+    set_method(&this, &console, "countReset", move |args| {
+        count(args, &count_reset_state, true)
+    })?;
+    let time_state = state.clone();
+    set_method(&this, &console, "time", move |args| time(args, &time_state))?;
+    let time_log_state = state.clone();
+    set_method(&this, &console, "timeLog", move |args| {
+        time_end(args, &time_log_state, false)
+    })?;
+    let time_end_state = state.clone();
+    set_method(&this, &console, "timeEnd", move |args| {
+        time_end(args, &time_end_state, true)
+    })?;
+    for name in ["group", "groupCollapsed"] {
+        let group_state = state.clone();
+        set_method(&this, &console, name, move |args| group(args, &group_state))?;
+    }
+    let group_end_state = state.clone();
+    set_method(&this, &console, "groupEnd", move |args| {
+        group_end(args, &group_end_state)
+    })?;
+
+    globals.set("console", console)?;
+    Ok(())
+}
 
+fn set_method<'js, F>(this: &Ctx<'js>, console: &Object<'js>, name: &str, mut f: F) -> Result<()>
+where
+    F: for<'a> FnMut(Args<'a>) -> Result<Value<'a>> + 'static,
+{
     console.set(
-        "error",
+        name,
         Function::new(
             this.clone(),
             MutFn::new(move |cx, args| {
                 let (cx, args) = hold_and_release!(cx, args);
-                log(hold!(cx.clone(), args), &mut error_stream).map_err(|e| to_js_error(cx, e))
+                f(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
             }),
         )?,
     )?;
-
-    globals.set("console", console)?;
     Ok(())
 }
 
-fn log<'js, T: Write>(args: Args<'js>, stream: &mut T) -> Result<Value<'js>> {
-    let (ctx, args) = args.release();
-    for (i, arg) in args.into_inner().into_iter().enumerate() {
-        if i != 0 {
-            write!(stream, " ")?;
+fn log<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+    stream: Stream,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    // Formatting may run arbitrary JavaScript, e.g. through `toString`, which
+    // can call back into `console`, so the state is only borrowed to write.
+    let message = format(&cx, args.into_inner())?;
+    state.borrow_mut().write(stream, &message)?;
+    Ok(Value::new_undefined(cx))
+}
+
+fn dir<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let mut args = args.into_inner().into_iter();
+    let value = args
+        .next()
+        .unwrap_or_else(|| Value::new_undefined(cx.clone()));
+
+    let mut options = InspectOptions::default();
+    if let Some(depth) = args
+        .next()
+        .and_then(|o| o.into_object())
+        .map(|o| o.get::<_, Value>("depth"))
+        .transpose()?
+    {
+        if depth.is_null() || depth.as_number().is_some_and(f64::is_infinite) {
+            options.depth = None;
+        } else if let Some(depth) = depth.as_number() {
+            options.depth = Some(depth.max(0.0) as usize);
         }
+    }
+
+    let message = inspect(&cx, value, options)?;
+    state.borrow_mut().write(Stream::Log, &message)?;
+    Ok(Value::new_undefined(cx))
+}
+
+fn trace<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let mut message = "Trace".to_string();
+    let formatted = format(&cx, args.into_inner())?;
+    if !formatted.is_empty() {
+        message.push_str(&format!(": {formatted}"));
+    }
+
+    let stack: Option<String> = Exception::from_message(cx.clone(), "")?.get("stack")?;
+    for frame in stack
+        .iter()
+        .flat_map(|stack| stack.lines())
+        // Skip the frame of `console.trace` itself.
+        .filter(|frame| frame.trim_start().starts_with("at ") && !frame.contains("(native)"))
+    {
+        message.push('\n');
+        message.push_str(frame);
+    }
+
+    state.borrow_mut().write(Stream::Error, &message)?;
+    Ok(Value::new_undefined(cx))
+}
 
-        let str = val_to_string(&ctx, arg)?;
-        write!(stream, "{str}")?;
+fn assert<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let mut args = args.into_inner();
+    let assertion = if args.is_empty() {
+        false
+    } else {
+        args.remove(0).get::<Coerced<bool>>()?.0
+    };
+    if assertion {
+        return Ok(Value::new_undefined(cx));
     }
-    writeln!(stream)?;
 
-    Ok(Value::new_undefined(ctx))
+    let message = match args.first().and_then(|a| a.as_string()) {
+        Some(_) => {
+            let formatted = format(&cx, args)?;
+            format!("Assertion failed: {formatted}")
+        }
+        None if args.is_empty() => "Assertion failed".to_string(),
+        None => format!("Assertion failed {}", format(&cx, args)?),
+    };
+    state.borrow_mut().write(Stream::Error, &message)?;
+    Ok(Value::new_undefined(cx))
+}
+
+fn table<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let args = args.into_inner();
+    let message = match table::render(&cx, &args)? {
+        Some(table) => table,
+        None => format(&cx, args)?,
+    };
+    state.borrow_mut().write(Stream::Log, &message)?;
+    Ok(Value::new_undefined(cx))
+}
+
+fn count<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+    reset: bool,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let label = label(&cx, args.into_inner())?;
+    let mut state = state.borrow_mut();
+    if reset {
+        match state.counts.get_mut(&label) {
+            Some(count) => *count = 0,
+            None => state.write(
+                Stream::Error,
+                &format!("Warning: Count for '{label}' does not exist"),
+            )?,
+        }
+    } else {
+        let count = state.counts.entry(label.clone()).or_default();
+        *count += 1;
+        let message = format!("{label}: {count}");
+        state.write(Stream::Log, &message)?;
+    }
+    Ok(Value::new_undefined(cx))
+}
+
+fn time<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let label = label(&cx, args.into_inner())?;
+    let mut state = state.borrow_mut();
+    let warning = match state.timers.entry(label) {
+        Entry::Occupied(entry) => Some(format!(
+            "Warning: Label '{}' already exists for console.time()",
+            entry.key()
+        )),
+        Entry::Vacant(entry) => {
+            entry.insert(Instant::now());
+            None
+        }
+    };
+    if let Some(warning) = warning {
+        state.write(Stream::Error, &warning)?;
+    }
+    Ok(Value::new_undefined(cx))
+}
+
+fn time_end<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+    end: bool,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let mut args = args.into_inner();
+    let data = if args.len() > 1 {
+        args.split_off(1)
+    } else {
+        vec![]
+    };
+    let label = label(&cx, args)?;
+
+    let start = if end {
+        state.borrow_mut().timers.remove(&label)
+    } else {
+        state.borrow().timers.get(&label).copied()
+    };
+    let Some(start) = start else {
+        let method = if end { "timeEnd" } else { "timeLog" };
+        state.borrow_mut().write(
+            Stream::Error,
+            &format!("Warning: No such label '{label}' for console.{method}()"),
+        )?;
+        return Ok(Value::new_undefined(cx));
+    };
+
+    let mut message = format!("{label}: {}", format_duration(start.elapsed()));
+    if !data.is_empty() {
+        message.push(' ');
+        message.push_str(&format(&cx, data)?);
+    }
+    state.borrow_mut().write(Stream::Log, &message)?;
+    Ok(Value::new_undefined(cx))
+}
+
+fn group<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let args = args.into_inner();
+    if !args.is_empty() {
+        let message = format(&cx, args)?;
+        state.borrow_mut().write(Stream::Log, &message)?;
+    }
+    state.borrow_mut().group_indentation += 2;
+    Ok(Value::new_undefined(cx))
+}
+
+fn group_end<'js, T: Write, U: Write>(
+    args: Args<'js>,
+    state: &SharedConsole<T, U>,
+) -> Result<Value<'js>> {
+    let (cx, _) = args.release();
+    let mut state = state.borrow_mut();
+    state.group_indentation = state.group_indentation.saturating_sub(2);
+    Ok(Value::new_undefined(cx))
+}
+
+/// The label passed to `count` and `time`, `"default"` if none is provided.
+fn label<'js>(cx: &Ctx<'js>, args: Vec<Value<'js>>) -> Result<String> {
+    match args.into_iter().next() {
+        Some(label) if !label.is_undefined() => crate::val_to_string(cx, label),
+        _ => Ok("default".to_string()),
+    }
+}
+
+/// Formats a duration as Node does for `console.timeEnd`.
+fn format_duration(duration: Duration) -> String {
+    let ms = duration.as_secs_f64() * 1000.0;
+    if ms >= 1000.0 {
+        let seconds = ms / 1000.0;
+        if seconds >= 60.0 {
+            let total = duration.as_millis();
+            let (hours, minutes) = (total / 3_600_000, total / 60_000 % 60);
+            let (seconds, millis) = (total / 1000 % 60, total % 1000);
+            return if hours > 0 {
+                format!("{hours}:{minutes:02}:{seconds:02}.{millis:03} (h:mm:ss.mmm)")
+            } else {
+                format!("{minutes}:{seconds:02}.{millis:03} (m:ss.mmm)")
+            };
+        }
+        return format!("{seconds:.3}s");
+    }
+
+    let formatted = format!("{ms:.3}");
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    format!("{formatted}ms")
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         Runtime,
-        apis::console::register,
+        apis::console::{format_duration, register},
         quickjs::{Object, Value},
     };
     use anyhow::{Error, Result};
     use std::cell::RefCell;
     use std::rc::Rc;
+    use std::time::Duration;
     use std::{cmp, io};
 
     #[test]
@@ -75,8 +418,27 @@ mod tests {
         let runtime = Runtime::default();
         runtime.context().with(|cx| {
             let console: Object<'_> = cx.globals().get("console")?;
-            assert!(console.get::<&str, Value<'_>>("log").is_ok());
-            assert!(console.get::<&str, Value<'_>>("error").is_ok());
+            for method in [
+                "log",
+                "error",
+                "info",
+                "debug",
+                "warn",
+                "dir",
+                "trace",
+                "assert",
+                "table",
+                "count",
+                "countReset",
+                "time",
+                "timeLog",
+                "timeEnd",
+                "group",
+                "groupCollapsed",
+                "groupEnd",
+            ] {
+                assert!(console.get::<&str, Value<'_>>(method)?.is_function());
+            }
 
             Ok::<_, Error>(())
         })?;
@@ -112,36 +474,33 @@ mod tests {
 
             test_console_log!(
                 "console.log(function(){ return 1 })",
-                "function(){ return 1 }\n"
+                "[Function (anonymous)]\n"
             );
 
             test_console_log!(
                 "console.log([1, \"two\", 3.42, null, 5])",
-                "1,two,3.42,,5\n"
+                "[ 1, 'two', 3.42, null, 5 ]\n"
             );
 
             test_console_log!(
                 "console.log(2.3, true, { foo: 'bar' }, null, undefined)",
-                "2.3 true [object Object] null undefined\n"
+                "2.3 true { foo: 'bar' } null undefined\n"
             );
 
+            test_console_log!("console.log(new Date(0))", "1970-01-01T00:00:00.000Z\n");
+
             test_console_log!(
-                "console.log(new Date(0))",
-                "Thu Jan 01 1970 00:00:00 GMT+0000\n"
+                "console.log(new ArrayBuffer())",
+                "ArrayBuffer { [Uint8Contents]: <>, byteLength: 0 }\n"
             );
 
-            test_console_log!("console.log(new ArrayBuffer())", "[object ArrayBuffer]\n");
-
             test_console_log!("console.log(NaN)", "NaN\n");
 
-            test_console_log!("console.log(new Set())", "[object Set]\n");
+            test_console_log!("console.log(new Set())", "Set(0) {}\n");
 
-            test_console_log!("console.log(new Map())", "[object Map]\n");
+            test_console_log!("console.log(new Map())", "Map(0) {}\n");
 
-            test_console_log!(
-                "function Foo(){}; console.log(new Foo())",
-                "[object Object]\n"
-            );
+            test_console_log!("function Foo(){}; console.log(new Foo())", "Foo {}\n");
 
             test_console_log!("console.log(Symbol())", "Symbol()\n");
 
@@ -158,6 +517,61 @@ mod tests {
             // Invalid UTF-16 surrogate pair
             test_console_log!("console.log(Symbol(\"\\uD800\"))", "Symbol(�)\n");
 
+            test_console_log!(
+                "const a = { x: 1 }; a.self = a; console.log(a)",
+                "<ref *1> { x: 1, self: [Circular *1] }\n"
+            );
+
+            test_console_log!(
+                "console.log({ a: { b: { c: { d: 1 } } } })",
+                "{ a: { b: { c: [Object] } } }\n"
+            );
+
+            test_console_log!(
+                "console.log(new Map([[1, 'a']]), new Set([1]), new Uint8Array([1, 2]))",
+                "Map(1) { 1 => 'a' } Set(1) { 1 } Uint8Array(2) [ 1, 2 ]\n"
+            );
+
+            test_console_log!(
+                "console.log({ [Symbol('s')]: 1, 'a-b': 2 })",
+                "{ 'a-b': 2, [Symbol(s)]: 1 }\n"
+            );
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_specifiers() -> Result<()> {
+        let mut stream = SharedStream::default();
+        let runtime = Runtime::default();
+
+        runtime.context().with(|this| {
+            register(this.clone(), stream.clone(), stream.clone()).unwrap();
+            this.eval::<(), _>(
+                "console.log('%s:%d:%i:%f:%j %O %%', 'a', 4.5, 4.5, '1.5x', { a: [1] }, [1])",
+            )?;
+            assert_eq!(
+                "a:4.5:4:1.5:{\"a\":[1]} [ 1 ] %\n",
+                std::str::from_utf8(stream.buffer.borrow().as_slice())?
+            );
+            stream.clear();
+
+            this.eval::<(), _>("console.log('%s %s', 'one')")?;
+            assert_eq!(
+                "one %s\n",
+                std::str::from_utf8(stream.buffer.borrow().as_slice())?
+            );
+            stream.clear();
+
+            this.eval::<(), _>("console.log('%c styled', 'color: red', 'extra')")?;
+            assert_eq!(
+                " styled extra\n",
+                std::str::from_utf8(stream.buffer.borrow().as_slice())?
+            );
+
             Ok::<_, Error>(())
         })?;
 
@@ -167,7 +581,7 @@ mod tests {
     #[test]
     fn test_console_streams() -> Result<()> {
         let mut log_stream = SharedStream::default();
-        let error_stream = SharedStream::default();
+        let mut error_stream = SharedStream::default();
 
         let runtime = Runtime::default();
         let ctx = runtime.context();
@@ -184,6 +598,207 @@ mod tests {
             assert_eq!(b"hello world\n", error_stream.buffer.borrow().as_slice());
             assert!(log_stream.buffer.borrow().is_empty());
 
+            error_stream.clear();
+
+            this.eval::<(), _>("console.info('info'); console.debug('debug');")?;
+            assert_eq!(b"info\ndebug\n", log_stream.buffer.borrow().as_slice());
+            assert!(error_stream.buffer.borrow().is_empty());
+
+            log_stream.clear();
+
+            this.eval::<(), _>("console.warn('warn'); console.trace('trace');")?;
+            let errors = String::from_utf8(error_stream.buffer.borrow().clone())?;
+            assert!(errors.starts_with("warn\nTrace: trace\n"));
+            assert!(log_stream.buffer.borrow().is_empty());
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() -> Result<()> {
+        let stream = SharedStream::default();
+        let runtime = Runtime::default();
+
+        runtime.context().with(|this| {
+            register(this.clone(), stream.clone(), stream.clone()).unwrap();
+            this.eval::<(), _>(
+                r#"
+                class MyError extends Error {
+                    constructor(message) {
+                        super(message, { cause: 'why' });
+                        this.name = 'MyError';
+                        this.code = 42;
+                    }
+                }
+                console.log(new MyError('boom'));
+                "#,
+            )?;
+            let output = String::from_utf8(stream.buffer.borrow().clone())?;
+            assert!(output.starts_with("MyError: boom\n    at "));
+            assert!(output.ends_with(" {\n  code: 42,\n  [cause]: 'why'\n}\n"));
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count() -> Result<()> {
+        let log_stream = SharedStream::default();
+        let error_stream = SharedStream::default();
+        let runtime = Runtime::default();
+
+        runtime.context().with(|this| {
+            register(this.clone(), log_stream.clone(), error_stream.clone()).unwrap();
+            this.eval::<(), _>(
+                "console.count(); console.count(); console.count('x'); \
+                 console.countReset(); console.count(); console.countReset('nope');",
+            )?;
+            assert_eq!(
+                "default: 1\ndefault: 2\nx: 1\ndefault: 1\n",
+                std::str::from_utf8(log_stream.buffer.borrow().as_slice())?
+            );
+            assert_eq!(
+                "Warning: Count for 'nope' does not exist\n",
+                std::str::from_utf8(error_stream.buffer.borrow().as_slice())?
+            );
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_time() -> Result<()> {
+        let log_stream = SharedStream::default();
+        let error_stream = SharedStream::default();
+        let runtime = Runtime::default();
+
+        runtime.context().with(|this| {
+            register(this.clone(), log_stream.clone(), error_stream.clone()).unwrap();
+            this.eval::<(), _>(
+                "console.time('t'); console.timeLog('t', 'extra'); console.timeEnd('t'); \
+                 console.timeEnd('t');",
+            )?;
+            let log = String::from_utf8(log_stream.buffer.borrow().clone())?;
+            let lines: Vec<_> = log.lines().collect();
+            assert_eq!(2, lines.len());
+            assert!(lines[0].starts_with("t: ") && lines[0].ends_with("ms extra"));
+            assert!(lines[1].starts_with("t: ") && lines[1].ends_with("ms"));
+            assert_eq!(
+                "Warning: No such label 't' for console.timeEnd()\n",
+                std::str::from_utf8(error_stream.buffer.borrow().as_slice())?
+            );
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!("0.5ms", format_duration(Duration::from_micros(500)));
+        assert_eq!(
+            "12.346ms",
+            format_duration(Duration::from_nanos(12_345_678))
+        );
+        assert_eq!("1.500s", format_duration(Duration::from_millis(1500)));
+        assert_eq!(
+            "1:01.005 (m:ss.mmm)",
+            format_duration(Duration::from_millis(61_005))
+        );
+        assert_eq!(
+            "1:00:01.000 (h:mm:ss.mmm)",
+            format_duration(Duration::from_secs(3601))
+        );
+    }
+
+    #[test]
+    fn test_group() -> Result<()> {
+        let stream = SharedStream::default();
+        let runtime = Runtime::default();
+
+        runtime.context().with(|this| {
+            register(this.clone(), stream.clone(), stream.clone()).unwrap();
+            this.eval::<(), _>(
+                "console.group('Group'); console.log('a\\nb'); console.groupCollapsed(); \
+                 console.error({ x: 1 }); console.groupEnd(); console.groupEnd(); \
+                 console.groupEnd(); console.log('c');",
+            )?;
+            assert_eq!(
+                "Group\n  a\n  b\n    { x: 1 }\nc\n",
+                std::str::from_utf8(stream.buffer.borrow().as_slice())?
+            );
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_assert() -> Result<()> {
+        let log_stream = SharedStream::default();
+        let error_stream = SharedStream::default();
+        let runtime = Runtime::default();
+
+        runtime.context().with(|this| {
+            register(this.clone(), log_stream.clone(), error_stream.clone()).unwrap();
+            this.eval::<(), _>(
+                "console.assert(true, 'nope'); console.assert(0, 'failed %s', 'here'); \
+                 console.assert(false);",
+            )?;
+            assert_eq!(
+                "Assertion failed: failed here\nAssertion failed\n",
+                std::str::from_utf8(error_stream.buffer.borrow().as_slice())?
+            );
+            assert!(log_stream.buffer.borrow().is_empty());
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dir_and_table() -> Result<()> {
+        let mut stream = SharedStream::default();
+        let runtime = Runtime::default();
+
+        runtime.context().with(|this| {
+            register(this.clone(), stream.clone(), stream.clone()).unwrap();
+            this.eval::<(), _>(
+                "console.dir({ a: { b: {} } }, { depth: 0 }); console.dir('str', { depth: null });",
+            )?;
+            assert_eq!(
+                "{ a: [Object] }\n'str'\n",
+                std::str::from_utf8(stream.buffer.borrow().as_slice())?
+            );
+            stream.clear();
+
+            this.eval::<(), _>("console.table([{ a: 1, b: 'Y' }, { a: 'Z', b: 2 }])")?;
+            assert_eq!(
+                concat!(
+                    "┌─────────┬─────┬─────┐\n",
+                    "│ (index) │ a   │ b   │\n",
+                    "├─────────┼─────┼─────┤\n",
+                    "│ 0       │ 1   │ 'Y' │\n",
+                    "│ 1       │ 'Z' │ 2   │\n",
+                    "└─────────┴─────┴─────┘\n",
+                ),
+                std::str::from_utf8(stream.buffer.borrow().as_slice())?
+            );
+            stream.clear();
+
+            this.eval::<(), _>("console.table(5)")?;
+            assert_eq!(b"5\n", stream.buffer.borrow().as_slice());
+
             Ok::<_, Error>(())
         })?;
 
@@ -0,0 +1,132 @@
+//! Rendering of `console.table`.
+use super::inspect::{InspectOptions, inspect};
+use crate::{
+    quickjs::{Ctx, Value, object::Filter},
+    val_to_string,
+};
+use anyhow::Result;
+
+const INDEX_HEADER: &str = "(index)";
+const VALUES_HEADER: &str = "Values";
+
+/// Renders the tabular data passed to `console.table`.
+///
+/// Each own enumerable property of the data is a row. The properties of
+/// object rows are displayed as columns, primitive rows are displayed in a
+/// `Values` column. The optional second argument restricts the columns to
+/// the given property names.
+///
+/// Returns `None` if the data can't be rendered as a table, in which case it
+/// is logged as is.
+pub(super) fn render<'js>(cx: &Ctx<'js>, args: &[Value<'js>]) -> Result<Option<String>> {
+    let Some(data) = args.first().and_then(|data| data.as_object()) else {
+        return Ok(None);
+    };
+    let properties = args
+        .get(1)
+        .and_then(|properties| properties.as_array())
+        .map(|properties| {
+            properties
+                .iter::<Value>()
+                .map(|property| val_to_string(cx, property?))
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?;
+
+    let mut columns: Vec<String> = properties.clone().unwrap_or_default();
+    let mut has_values = false;
+    // The index of each row, along with its cells, keyed by column.
+    let mut rows: Vec<(String, Vec<(String, String)>)> = vec![];
+    for index in data.own_keys::<String>(Filter::new().string().enum_only()) {
+        let index = index?;
+        let row: Value = data.get(&index)?;
+        let mut cells = vec![];
+        match row.as_object() {
+            Some(row) => {
+                for key in row.own_keys::<String>(Filter::new().string().enum_only()) {
+                    let key = key?;
+                    if properties.as_ref().is_some_and(|p| !p.contains(&key)) {
+                        continue;
+                    }
+                    if !columns.contains(&key) {
+                        columns.push(key.clone());
+                    }
+                    let cell = cell(cx, row.get(&key)?)?;
+                    cells.push((key, cell));
+                }
+            }
+            None => {
+                has_values = true;
+                cells.push((VALUES_HEADER.to_string(), cell(cx, row)?));
+            }
+        }
+        rows.push((index, cells));
+    }
+
+    let mut head = vec![INDEX_HEADER.to_string()];
+    head.extend(columns.iter().cloned());
+    if has_values {
+        head.push(VALUES_HEADER.to_string());
+    }
+
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|(index, mut cells)| {
+            let mut row = vec![index];
+            for column in &head[1..] {
+                let cell = cells
+                    .iter()
+                    .position(|(key, _)| key == column)
+                    .map(|i| cells.swap_remove(i).1)
+                    .unwrap_or_default();
+                row.push(cell);
+            }
+            row
+        })
+        .collect();
+
+    Ok(Some(draw(&head, &rows)))
+}
+
+/// Formats a single cell.
+fn cell<'js>(cx: &Ctx<'js>, value: Value<'js>) -> Result<String> {
+    inspect(
+        cx,
+        value,
+        InspectOptions {
+            depth: Some(0),
+            break_length: usize::MAX,
+        },
+    )
+}
+
+/// Draws the table with box-drawing characters.
+fn draw(head: &[String], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = (0..head.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([head[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let divider = |left: &str, middle: &str, right: &str| {
+        let cells: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{left}{}{right}", cells.join(middle))
+    };
+    let row = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - cell.chars().count())))
+            .collect();
+        format!("│ {} │", cells.join(" │ "))
+    };
+
+    let mut lines = vec![divider("┌", "┬", "┐"), row(head), divider("├", "┼", "┤")];
+    lines.extend(rows.iter().map(|cells| row(cells)));
+    lines.push(divider("└", "┴", "┘"));
+    lines.join("\n")
+}
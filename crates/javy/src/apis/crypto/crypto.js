@@ -0,0 +1,213 @@
+(function () {
+    const __javy_cryptoFillRandom = globalThis.__javy_cryptoFillRandom;
+    const __javy_cryptoRandomUUID = globalThis.__javy_cryptoRandomUUID;
+    const __javy_cryptoDigest = globalThis.__javy_cryptoDigest;
+    const __javy_cryptoHmacSign = globalThis.__javy_cryptoHmacSign;
+    const __javy_cryptoHmacVerify = globalThis.__javy_cryptoHmacVerify;
+
+    const integerArrays = [
+        "Int8Array", "Uint8Array", "Uint8ClampedArray", "Int16Array", "Uint16Array", "Int32Array",
+        "Uint32Array", "BigInt64Array", "BigUint64Array",
+    ];
+    const hashes = ["SHA-1", "SHA-256", "SHA-384", "SHA-512"];
+    const hmacUsages = ["sign", "verify"];
+
+    // Errors which would be `DOMException`s in browsers.
+    function domError(name, message) {
+        const error = new Error(message);
+        error.name = name;
+        return error;
+    }
+
+    function toBytes(data, name) {
+        if (ArrayBuffer.isView(data)) {
+            return new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+        }
+        if (data instanceof ArrayBuffer) {
+            return new Uint8Array(data);
+        }
+        throw new TypeError(`The provided value for ${name} is not of type '(ArrayBuffer or ArrayBufferView)'`);
+    }
+
+    function algorithmName(algorithm) {
+        const name = typeof algorithm === "string" ? algorithm : algorithm?.name;
+        if (typeof name !== "string") {
+            throw new TypeError("The algorithm must be a string or an object with a name");
+        }
+        return name.toUpperCase();
+    }
+
+    function normalizeHash(algorithm) {
+        const name = algorithmName(algorithm);
+        if (!hashes.includes(name)) {
+            throw domError("NotSupportedError", `Unrecognized algorithm name: ${name}`);
+        }
+        return name;
+    }
+
+    function normalizeHmac(algorithm) {
+        if (algorithmName(algorithm) !== "HMAC") {
+            throw domError("NotSupportedError", `Unrecognized algorithm name: ${algorithmName(algorithm)}`);
+        }
+        if (algorithm.hash === undefined) {
+            throw new TypeError("The HMAC algorithm requires a hash");
+        }
+        const hash = normalizeHash(algorithm.hash);
+        let length;
+        if (algorithm.length !== undefined) {
+            length = Number(algorithm.length);
+            if (!Number.isInteger(length) || length <= 0) {
+                throw domError("OperationError", "The HMAC key length must be a positive integer");
+            }
+        }
+        return { hash, length };
+    }
+
+    function normalizeUsages(usages) {
+        usages = [...usages];
+        for (const usage of usages) {
+            if (!hmacUsages.includes(usage)) {
+                throw domError("SyntaxError", `Unsupported key usage: ${usage}`);
+            }
+        }
+        if (usages.length === 0) {
+            throw domError("SyntaxError", "Key usages must not be empty");
+        }
+        return [...new Set(usages)];
+    }
+
+    const keyMaterial = new WeakMap();
+    const constructing = Symbol("constructing");
+
+    class CryptoKey {
+        constructor(token, data, hash, extractable, usages) {
+            if (token !== constructing) {
+                throw new TypeError("Illegal constructor");
+            }
+            keyMaterial.set(this, data);
+            Object.defineProperties(this, {
+                type: { value: "secret", enumerable: true },
+                extractable: { value: extractable, enumerable: true },
+                algorithm: {
+                    value: Object.freeze({ name: "HMAC", hash: Object.freeze({ name: hash }), length: data.length * 8 }),
+                    enumerable: true,
+                },
+                usages: { value: Object.freeze(usages), enumerable: true },
+            });
+        }
+    }
+
+    function checkKey(key, usage) {
+        if (!keyMaterial.has(key)) {
+            throw new TypeError("The provided value is not of type 'CryptoKey'");
+        }
+        if (!key.usages.includes(usage)) {
+            throw domError("InvalidAccessError", `The key does not support the '${usage}' operation`);
+        }
+        return keyMaterial.get(key);
+    }
+
+    class SubtleCrypto {
+        async digest(algorithm, data) {
+            const hash = normalizeHash(algorithm);
+            return __javy_cryptoDigest(hash, toBytes(data, "data"));
+        }
+
+        async importKey(format, keyData, algorithm, extractable, keyUsages) {
+            const { hash, length } = normalizeHmac(algorithm);
+            const usages = normalizeUsages(keyUsages);
+            if (format !== "raw") {
+                throw domError("NotSupportedError", `Unsupported key format: ${format}`);
+            }
+            const data = toBytes(keyData, "keyData").slice();
+            if (data.length === 0) {
+                throw domError("DataError", "The key data must not be empty");
+            }
+            if (length !== undefined && (length > data.length * 8 || length <= (data.length - 1) * 8)) {
+                throw domError("DataError", "The key length doesn't match the key data");
+            }
+            return new CryptoKey(constructing, data, hash, !!extractable, usages);
+        }
+
+        async generateKey(algorithm, extractable, keyUsages) {
+            let { hash, length } = normalizeHmac(algorithm);
+            const usages = normalizeUsages(keyUsages);
+            // Default to the block size of the hash function.
+            length ??= hash === "SHA-384" || hash === "SHA-512" ? 1024 : 512;
+            const data = new Uint8Array(Math.ceil(length / 8));
+            __javy_cryptoFillRandom(data);
+            return new CryptoKey(constructing, data, hash, !!extractable, usages);
+        }
+
+        async exportKey(format, key) {
+            if (!keyMaterial.has(key)) {
+                throw new TypeError("The provided value is not of type 'CryptoKey'");
+            }
+            if (format !== "raw") {
+                throw domError("NotSupportedError", `Unsupported key format: ${format}`);
+            }
+            if (!key.extractable) {
+                throw domError("InvalidAccessError", "The key is not extractable");
+            }
+            return keyMaterial.get(key).slice().buffer;
+        }
+
+        async sign(algorithm, key, data) {
+            if (algorithmName(algorithm) !== "HMAC") {
+                throw domError("NotSupportedError", `Unrecognized algorithm name: ${algorithmName(algorithm)}`);
+            }
+            const bytes = checkKey(key, "sign");
+            return __javy_cryptoHmacSign(key.algorithm.hash.name, bytes, toBytes(data, "data"));
+        }
+
+        async verify(algorithm, key, signature, data) {
+            if (algorithmName(algorithm) !== "HMAC") {
+                throw domError("NotSupportedError", `Unrecognized algorithm name: ${algorithmName(algorithm)}`);
+            }
+            const bytes = checkKey(key, "verify");
+            return __javy_cryptoHmacVerify(
+                key.algorithm.hash.name,
+                bytes,
+                toBytes(signature, "signature"),
+                toBytes(data, "data"),
+            );
+        }
+    }
+
+    const subtle = new SubtleCrypto();
+
+    class Crypto {
+        get subtle() {
+            return subtle;
+        }
+
+        getRandomValues(array) {
+            if (!ArrayBuffer.isView(array) || !integerArrays.includes(array[Symbol.toStringTag])) {
+                throw domError("TypeMismatchError", "The provided value is not an integer-type TypedArray");
+            }
+            if (array.byteLength > 65536) {
+                throw domError(
+                    "QuotaExceededError",
+                    `The ArrayBufferView's byte length (${array.byteLength}) exceeds the number of bytes of entropy available via this API (65536)`,
+                );
+            }
+            __javy_cryptoFillRandom(new Uint8Array(array.buffer, array.byteOffset, array.byteLength));
+            return array;
+        }
+
+        randomUUID() {
+            return __javy_cryptoRandomUUID();
+        }
+    }
+
+    globalThis.crypto = new Crypto();
+    globalThis.Crypto = Crypto;
+    globalThis.CryptoKey = CryptoKey;
+    globalThis.SubtleCrypto = SubtleCrypto;
+
+    Reflect.deleteProperty(globalThis, "__javy_cryptoFillRandom");
+    Reflect.deleteProperty(globalThis, "__javy_cryptoRandomUUID");
+    Reflect.deleteProperty(globalThis, "__javy_cryptoDigest");
+    Reflect.deleteProperty(globalThis, "__javy_cryptoHmacSign");
+    Reflect.deleteProperty(globalThis, "__javy_cryptoHmacVerify");
+})();
@@ -0,0 +1,80 @@
+//! The hash functions supported by `crypto.subtle`, and HMAC on top of them.
+use hmac::{Hmac, Mac, digest::KeyInit};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// A hash algorithm supported by `crypto.subtle.digest` and HMAC keys.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Hash {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Hash {
+    /// The algorithm for a normalized algorithm name, e.g. `SHA-256`.
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "SHA-1" => Some(Self::Sha1),
+            "SHA-256" => Some(Self::Sha256),
+            "SHA-384" => Some(Self::Sha384),
+            "SHA-512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    /// Computes the digest of the concatenation of `parts`.
+    pub(super) fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        fn digest<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+
+        match self {
+            Self::Sha1 => digest::<Sha1>(parts),
+            Self::Sha256 => digest::<Sha256>(parts),
+            Self::Sha384 => digest::<Sha384>(parts),
+            Self::Sha512 => digest::<Sha512>(parts),
+        }
+    }
+
+    /// Computes the HMAC of `data` with `key`, as defined in RFC 2104.
+    pub(super) fn hmac(&self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        fn hmac<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+            mac::<M>(key, data).finalize().into_bytes().to_vec()
+        }
+
+        match self {
+            Self::Sha1 => hmac::<Hmac<Sha1>>(key, data),
+            Self::Sha256 => hmac::<Hmac<Sha256>>(key, data),
+            Self::Sha384 => hmac::<Hmac<Sha384>>(key, data),
+            Self::Sha512 => hmac::<Hmac<Sha512>>(key, data),
+        }
+    }
+
+    /// Whether `signature` is the HMAC of `data` with `key`, compared in
+    /// constant time.
+    pub(super) fn verify_hmac(&self, key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+        fn verify<M: Mac + KeyInit>(key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+            mac::<M>(key, data).verify_slice(signature).is_ok()
+        }
+
+        match self {
+            Self::Sha1 => verify::<Hmac<Sha1>>(key, data, signature),
+            Self::Sha256 => verify::<Hmac<Sha256>>(key, data, signature),
+            Self::Sha384 => verify::<Hmac<Sha384>>(key, data, signature),
+            Self::Sha512 => verify::<Hmac<Sha512>>(key, data, signature),
+        }
+    }
+}
+
+/// An HMAC of `data` keyed with `key`.
+fn mac<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> M {
+    let mut mac = <M as KeyInit>::new_from_slice(key).expect("HMAC to accept keys of any length");
+    mac.update(data);
+    mac
+}
@@ -0,0 +1,369 @@
+use crate::{
+    Args, check_arg_count, hold, hold_and_release,
+    quickjs::{ArrayBuffer, Ctx, Function, String as JSString, Value, context::EvalOptions},
+    to_js_error,
+};
+use anyhow::{Error, Result, anyhow};
+
+mod hash;
+
+use hash::Hash;
+
+/// Register a `crypto` object on the global object.
+///
+/// Random values are read from the host's source of randomness on each call,
+/// so they're never part of a snapshot of the runtime.
+pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+    let globals = this.globals();
+    globals.set(
+        "__javy_cryptoFillRandom",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            fill_random(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    globals.set(
+        "__javy_cryptoRandomUUID",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            random_uuid(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    globals.set(
+        "__javy_cryptoDigest",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            digest(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    globals.set(
+        "__javy_cryptoHmacSign",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            hmac_sign(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    globals.set(
+        "__javy_cryptoHmacVerify",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            hmac_verify(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    let mut opts = EvalOptions::default();
+    opts.strict = false;
+    this.eval_with_options::<(), _>(include_str!("./crypto.js"), opts)?;
+
+    Ok::<_, Error>(())
+}
+
+/// The bytes viewed by a `Uint8Array`, empty if its buffer is detached.
+fn bytes<'a>(value: &'a Value<'_>, name: &str) -> Result<&'a [u8]> {
+    let array = value
+        .as_object()
+        .and_then(|object| object.as_typed_array::<u8>())
+        .ok_or_else(|| anyhow!("{name} must be a Uint8Array"))?;
+    Ok(array.as_bytes().unwrap_or_default())
+}
+
+/// The hash algorithm for a normalized algorithm name.
+fn hash(value: &Value<'_>) -> Result<Hash> {
+    let name = value
+        .as_string()
+        .ok_or_else(|| anyhow!("algorithm must be a string"))?
+        .to_string()?;
+    Hash::from_name(&name).ok_or_else(|| anyhow!("Unsupported algorithm: {name}"))
+}
+
+/// Fill a `Uint8Array` with cryptographically secure random bytes.
+fn fill_random(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    check_arg_count(&args, 1)?;
+
+    let array = args[0]
+        .as_object()
+        .and_then(|object| object.as_typed_array::<u8>())
+        .ok_or_else(|| anyhow!("array must be a Uint8Array"))?;
+    if let Some(raw) = array.as_raw() {
+        // SAFETY: The array is kept alive by the arguments and isn't otherwise
+        // accessed while the slice is in use.
+        let bytes = unsafe { std::slice::from_raw_parts_mut(raw.ptr.as_ptr(), raw.len) };
+        getrandom::fill(bytes).map_err(|e| anyhow!("Failed to generate random values: {e}"))?;
+    }
+
+    Ok(Value::new_undefined(cx))
+}
+
+/// Generate a random version 4 UUID, as defined in RFC 9562.
+fn random_uuid(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    check_arg_count(&args, 0)?;
+
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow!("Failed to generate random values: {e}"))?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    let uuid = format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    );
+    Ok(Value::from_string(JSString::from_str(cx, &uuid)?))
+}
+
+/// Compute the digest of a `Uint8Array` into an `ArrayBuffer`.
+fn digest(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    check_arg_count(&args, 2)?;
+
+    let hash = hash(&args[0])?;
+    let digest = hash.digest(&[bytes(&args[1], "data")?]);
+    Ok(ArrayBuffer::new(cx, digest)?.into_value())
+}
+
+/// Compute the HMAC signature of a `Uint8Array` into an `ArrayBuffer`.
+fn hmac_sign(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    check_arg_count(&args, 3)?;
+
+    let hash = hash(&args[0])?;
+    let signature = hash.hmac(bytes(&args[1], "key")?, bytes(&args[2], "data")?);
+    Ok(ArrayBuffer::new(cx, signature)?.into_value())
+}
+
+/// Verify the HMAC signature of a `Uint8Array`.
+fn hmac_verify(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    check_arg_count(&args, 4)?;
+
+    let hash = hash(&args[0])?;
+    let valid = hash.verify_hmac(
+        bytes(&args[1], "key")?,
+        bytes(&args[3], "data")?,
+        bytes(&args[2], "signature")?,
+    );
+    Ok(Value::new_bool(cx, valid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hash::Hash;
+    use crate::{Config, Runtime, apis::test_utils::eval_async, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    fn runtime() -> Result<Runtime> {
+        let mut config = Config::default();
+        config.crypto(true);
+        Runtime::new(config)
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let default = Runtime::default();
+        default.context().with(|this| {
+            assert!(this.globals().get::<_, Value<'_>>("crypto")?.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let names: Vec<String> = this.eval(
+                "[typeof crypto.getRandomValues, typeof crypto.randomUUID, typeof crypto.subtle.digest, typeof __javy_cryptoDigest]",
+            )?;
+            assert_eq!(vec!["function", "function", "function", "undefined"], names);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_hashes() {
+        let long_key = [0xaa; 131];
+        let long_data = b"Test Using Larger Than Block-Size Key - Hash Key First";
+        for (hash, digest, hmac, long_hmac) in [
+            (
+                Hash::Sha1,
+                "a9993e364706816aba3e25717850c26c9cd0d89d",
+                "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+                "90d0dace1c1bdc957339307803160335bde6df2b",
+            ),
+            (
+                Hash::Sha256,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+            (
+                Hash::Sha384,
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+                "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+                "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+            ),
+            (
+                Hash::Sha512,
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ),
+        ] {
+            assert_eq!(digest, hex(&hash.digest(&[b"abc"])));
+            assert_eq!(
+                hmac,
+                hex(&hash.hmac(b"Jefe", b"what do ya want for nothing?"))
+            );
+            assert_eq!(long_hmac, hex(&hash.hmac(&long_key, long_data)));
+        }
+
+        assert_eq!(
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            hex(&Hash::Sha1.digest(&[b""]))
+        );
+        assert_eq!(
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+            hex(&Hash::Sha1.digest(&[b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"]))
+        );
+        assert_eq!(
+            "34aa973cd4c4daa4f61eeb2bdbad27316534016f",
+            hex(&Hash::Sha1.digest(&[&[b'a'; 1_000_000]]))
+        );
+    }
+
+    #[test]
+    fn test_get_random_values() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: bool = this.eval(
+                r#"
+                const array = new Uint32Array(16);
+                crypto.getRandomValues(array) === array && array.some((value) => value !== 0);
+            "#,
+            )?;
+            assert!(result);
+
+            let errors: Vec<String> = this.eval(
+                r#"
+                [new Float32Array(1), new DataView(new ArrayBuffer(1)), new Uint8Array(65537)].map((array) => {
+                    try {
+                        crypto.getRandomValues(array);
+                    } catch (e) {
+                        return e.name;
+                    }
+                });
+            "#,
+            )?;
+            assert_eq!(
+                vec!["TypeMismatchError", "TypeMismatchError", "QuotaExceededError"],
+                errors
+            );
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_uuid() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let uuids: Vec<String> = this.eval("[crypto.randomUUID(), crypto.randomUUID()]")?;
+            assert_ne!(uuids[0], uuids[1]);
+            for uuid in uuids {
+                let groups: Vec<&str> = uuid.split('-').collect();
+                assert_eq!(
+                    vec![8, 4, 4, 4, 12],
+                    groups.iter().map(|g| g.len()).collect::<Vec<_>>()
+                );
+                assert!(groups[2].starts_with('4'));
+                assert!(matches!(
+                    groups[3].chars().next(),
+                    Some('8' | '9' | 'a' | 'b')
+                ));
+                assert!(uuid.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+            }
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_subtle_digest() -> Result<()> {
+        let runtime = runtime()?;
+        let result: Vec<String> = eval_async(
+            &runtime,
+            r#"
+            const hex = (buffer) => [...new Uint8Array(buffer)].map((b) => b.toString(16).padStart(2, '0')).join('');
+            const data = new Uint8Array([0x61, 0x62, 0x63]);
+            Promise.all([
+                crypto.subtle.digest('SHA-256', data).then(hex),
+                crypto.subtle.digest({ name: 'sha-1' }, data.buffer).then(hex),
+                crypto.subtle.digest('MD5', data).catch((e) => e.name),
+            ]).then((values) => globalThis.result = values);
+        "#,
+        )?;
+        assert_eq!(
+            vec![
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                "a9993e364706816aba3e25717850c26c9cd0d89d",
+                "NotSupportedError",
+            ],
+            result
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hmac() -> Result<()> {
+        let runtime = runtime()?;
+        let result: Vec<String> = eval_async(
+            &runtime,
+            r#"
+            const hex = (buffer) => [...new Uint8Array(buffer)].map((b) => b.toString(16).padStart(2, '0')).join('');
+            const bytes = (string) => new Uint8Array([...string].map((c) => c.charCodeAt(0)));
+            const data = bytes('what do ya want for nothing?');
+            (async () => {
+                const key = await crypto.subtle.importKey(
+                    'raw', bytes('Jefe'), { name: 'HMAC', hash: 'SHA-256' }, false, ['sign', 'verify']);
+                const signature = await crypto.subtle.sign('HMAC', key, data);
+                const tampered = new Uint8Array(signature).slice();
+                tampered[0] ^= 1;
+
+                const verifyOnly = await crypto.subtle.importKey(
+                    'raw', bytes('Jefe'), { name: 'HMAC', hash: { name: 'SHA-512' } }, true, ['verify']);
+                const generated = await crypto.subtle.generateKey(
+                    { name: 'HMAC', hash: 'SHA-384' }, true, ['sign']);
+
+                globalThis.result = [
+                    hex(signature),
+                    String(await crypto.subtle.verify({ name: 'HMAC' }, key, signature, data)),
+                    String(await crypto.subtle.verify('HMAC', key, tampered, data)),
+                    await crypto.subtle.sign('HMAC', verifyOnly, data).catch((e) => e.name),
+                    await crypto.subtle.exportKey('raw', key).catch((e) => e.name),
+                    String((await crypto.subtle.exportKey('raw', generated)).byteLength),
+                    `${generated.algorithm.hash.name} ${generated.algorithm.length} ${generated.type}`,
+                ];
+            })();
+        "#,
+        )?;
+        assert_eq!(
+            vec![
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "true",
+                "false",
+                "InvalidAccessError",
+                "InvalidAccessError",
+                "128",
+                "SHA-384 1024 secret",
+            ],
+            result
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,463 @@
+use base64::{
+    Engine,
+    alphabet::{STANDARD, URL_SAFE},
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+
+use crate::{
+    Args, hold, hold_and_release,
+    quickjs::{
+        Ctx, Exception, FromJs, Function, Object, String as JSString, TypedArray, Value,
+        convert::Coerced,
+    },
+    to_js_error, to_string_lossy,
+};
+use anyhow::{Error, Result, anyhow, bail};
+
+/// The engine used by `atob` and `Javy.Encoding.base64`. Padding is added when
+/// encoding, and optional when decoding.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+/// The engine used by `Javy.Encoding.base64url`. Padding is omitted when
+/// encoding, and optional when decoding.
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+    &URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+/// Register the `atob` and `btoa` functions on the global object.
+pub(crate) fn register_globals(this: Ctx<'_>) -> Result<()> {
+    let globals = this.globals();
+    globals.set(
+        "atob",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            atob(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    globals.set(
+        "btoa",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            btoa(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+
+    Ok::<_, Error>(())
+}
+
+/// Register the `Javy.Encoding` namespace, with `encode` and `decode`
+/// functions for the `base64`, `base64url` and `hex` encodings.
+pub(crate) fn register_javy(this: Ctx<'_>) -> Result<()> {
+    let globals = this.globals();
+    if globals.get::<_, Object>("Javy").is_err() {
+        globals.set("Javy", Object::new(this.clone())?)?
+    }
+
+    let encoding = Object::new(this.clone())?;
+
+    let base64 = Object::new(this.clone())?;
+    base64.set(
+        "encode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            base64_encode(hold!(cx.clone(), args), &BASE64).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    base64.set(
+        "decode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            base64_decode(hold!(cx.clone(), args), &BASE64).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    encoding.set("base64", base64)?;
+
+    let base64_url = Object::new(this.clone())?;
+    base64_url.set(
+        "encode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            base64_encode(hold!(cx.clone(), args), &BASE64_URL).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    base64_url.set(
+        "decode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            base64_decode(hold!(cx.clone(), args), &BASE64_URL).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    encoding.set("base64url", base64_url)?;
+
+    let hex = Object::new(this.clone())?;
+    hex.set(
+        "encode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            hex_encode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    hex.set(
+        "decode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            hex_decode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    encoding.set("hex", hex)?;
+
+    globals
+        .get::<_, Object>("Javy")?
+        .set("Encoding", encoding)?;
+
+    Ok::<_, Error>(())
+}
+
+/// An error named `InvalidCharacterError`, which would be a `DOMException` in
+/// browsers.
+fn invalid_character_error(cx: &Ctx<'_>, message: &str) -> Error {
+    let exception = match Exception::from_message(cx.clone(), message) {
+        Ok(exception) => exception,
+        Err(e) => return e.into(),
+    };
+    if let Err(e) = exception.set("name", "InvalidCharacterError") {
+        return e.into();
+    }
+    cx.throw(exception.into_value()).into()
+}
+
+/// Throws the `TypeError` of WebIDL if `name` was called without an argument.
+///
+/// Unlike [`crate::check_arg_count`], extra arguments are ignored.
+fn require_argument(cx: &Ctx<'_>, args: &[Value<'_>], name: &str) -> Result<()> {
+    if args.is_empty() {
+        bail!(Exception::throw_type(
+            cx,
+            &format!("Failed to execute '{name}': 1 argument required, but only 0 present")
+        ));
+    }
+    Ok(())
+}
+
+/// Decode a string of base64 data into a string whose code points are the
+/// decoded bytes, using the forgiving-base64 decode algorithm of the HTML
+/// Standard.
+fn atob(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    require_argument(&cx, &args, "atob")?;
+
+    let string = Coerced::<JSString>::from_js(&cx, args[0].clone())?;
+    let data = string
+        .to_string()
+        .unwrap_or_else(|error| to_string_lossy(&cx, &string, error));
+
+    let mut data: Vec<u8> = data
+        .bytes()
+        .filter(|b| !matches!(b, b'\t' | b'\n' | b'\x0C' | b'\r' | b' '))
+        .collect();
+    if data.len().is_multiple_of(4) {
+        for _ in 0..2 {
+            if data.last() == Some(&b'=') {
+                data.pop();
+            }
+        }
+    }
+    if data.len() % 4 == 1
+        || !data
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/'))
+    {
+        bail!(invalid_character_error(
+            &cx,
+            "The string to be decoded is not correctly encoded."
+        ));
+    }
+
+    let decoded = BASE64.decode(&data).map_err(|_| {
+        invalid_character_error(&cx, "The string to be decoded is not correctly encoded.")
+    })?;
+    let decoded: String = decoded.into_iter().map(char::from).collect();
+    Ok(Value::from_string(JSString::from_str(cx, &decoded)?))
+}
+
+/// Encode a string whose code points are all in the range U+0000 to U+00FF as
+/// base64.
+fn btoa(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    require_argument(&cx, &args, "btoa")?;
+
+    let invalid = || {
+        invalid_character_error(
+            &cx,
+            "The string to be encoded contains characters outside of the Latin1 range.",
+        )
+    };
+    let string = Coerced::<JSString>::from_js(&cx, args[0].clone())?;
+    // Lone surrogates are outside of the Latin1 range too.
+    let data = string.to_string().map_err(|_| invalid())?;
+    let bytes = data
+        .chars()
+        .map(|c| u8::try_from(c).map_err(|_| invalid()))
+        .collect::<Result<Vec<u8>>>()?;
+
+    Ok(Value::from_string(JSString::from_str(
+        cx,
+        &BASE64.encode(bytes),
+    )?))
+}
+
+/// The bytes viewed by a `Uint8Array`, empty if its buffer is detached.
+fn bytes<'a, 'js>(cx: &Ctx<'js>, args: &'a [Value<'js>]) -> Result<&'a [u8]> {
+    let array = args
+        .first()
+        .and_then(|value| value.as_object())
+        .and_then(|object| object.as_typed_array::<u8>())
+        .ok_or_else(|| anyhow!(Exception::throw_type(cx, "Data needs to be an Uint8Array")))?;
+    Ok(array.as_bytes().unwrap_or_default())
+}
+
+/// The string to decode, which must be a string primitive.
+fn encoded<'js>(cx: &Ctx<'js>, args: &[Value<'js>]) -> Result<String> {
+    let string = args
+        .first()
+        .and_then(|value| value.as_string())
+        .ok_or_else(|| anyhow!(Exception::throw_type(cx, "Data needs to be a string")))?;
+    Ok(string
+        .to_string()
+        .unwrap_or_else(|error| to_string_lossy(cx, string, error)))
+}
+
+/// Encode the bytes of a `Uint8Array` as base64 with `engine`.
+fn base64_encode<'js>(args: Args<'js>, engine: &GeneralPurpose) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let encoded = engine.encode(bytes(&cx, &args)?);
+    Ok(Value::from_string(JSString::from_str(cx, &encoded)?))
+}
+
+/// Decode a base64 string with `engine` into a `Uint8Array`. ASCII whitespace
+/// is ignored.
+fn base64_decode<'js>(args: Args<'js>, engine: &GeneralPurpose) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let mut data = encoded(&cx, &args)?;
+    if data.bytes().any(|b| b.is_ascii_whitespace()) {
+        data.retain(|c| !c.is_ascii_whitespace());
+    }
+
+    let decoded = engine.decode(data).map_err(|e| {
+        anyhow!(Exception::throw_syntax(
+            &cx,
+            &format!("Invalid base64 string: {e}")
+        ))
+    })?;
+    Ok(TypedArray::new(cx, decoded)?.into_value())
+}
+
+/// Encode the bytes of a `Uint8Array` as lowercase hexadecimal digits.
+fn hex_encode(args: Args<'_>) -> Result<Value<'_>> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let (cx, args) = args.release();
+    let bytes = bytes(&cx, &args)?;
+    let mut encoded = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        encoded.push(DIGITS[(byte >> 4) as usize] as char);
+        encoded.push(DIGITS[(byte & 0xF) as usize] as char);
+    }
+    Ok(Value::from_string(JSString::from_str(cx, &encoded)?))
+}
+
+/// Decode a string of hexadecimal digits, in either case, into a
+/// `Uint8Array`.
+fn hex_decode(args: Args<'_>) -> Result<Value<'_>> {
+    fn digit(byte: u8) -> Option<u8> {
+        (byte as char).to_digit(16).map(|d| d as u8)
+    }
+
+    let (cx, args) = args.release();
+    let data = encoded(&cx, &args)?;
+    let (pairs, rest) = data.as_bytes().as_chunks::<2>();
+    let decoded = if rest.is_empty() {
+        pairs
+            .iter()
+            .map(|&[high, low]| Some(digit(high)? << 4 | digit(low)?))
+            .collect::<Option<Vec<u8>>>()
+    } else {
+        None
+    };
+    let decoded =
+        decoded.ok_or_else(|| anyhow!(Exception::throw_syntax(&cx, "Invalid hex string")))?;
+    Ok(TypedArray::new(cx, decoded)?.into_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    fn runtime() -> Result<Runtime> {
+        let mut config = Config::default();
+        config.base64(true).javy_encoding(true);
+        Runtime::new(config)
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let default = Runtime::default();
+        default.context().with(|this| {
+            let globals = this.globals();
+            assert!(globals.get::<_, Value<'_>>("atob")?.is_undefined());
+            assert!(globals.get::<_, Value<'_>>("btoa")?.is_undefined());
+            assert!(globals.get::<_, Value<'_>>("Javy")?.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                [typeof atob, typeof btoa, typeof Javy.Encoding.base64.encode,
+                 typeof Javy.Encoding.base64url.decode, typeof Javy.Encoding.hex.encode].join(",")
+            "#,
+            )?;
+            assert_eq!("function,function,function,function,function", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_atob_btoa() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                [btoa(""), btoa("f"), btoa("fo"), btoa("foo"), btoa("\xff\xfe"), btoa(123),
+                 atob("Zm9v"), atob(" Zm\n9v "), atob("Zg"), atob("Zg=="), atob("Zh=="),
+                 atob("//4=").charCodeAt(0), atob(null).length].join(",")
+            "#,
+            )?;
+            assert_eq!(",Zg==,Zm8=,Zm9v,//4=,MTIz,foo,foo,f,f,f,255,3", result);
+
+            let result: String = this.eval(
+                r#"
+                const errors = [];
+                for (const input of ["Zg=", "Z", "Zm9v=", "Z===", "Zm+v-", "Zm=9"]) {
+                    try {
+                        atob(input);
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                for (const input of ["€", "\uD800"]) {
+                    try {
+                        btoa(input);
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                try {
+                    atob();
+                } catch (e) {
+                    errors.push(e.name);
+                }
+                errors.join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "InvalidCharacterError,InvalidCharacterError,InvalidCharacterError,InvalidCharacterError,InvalidCharacterError,InvalidCharacterError,InvalidCharacterError,InvalidCharacterError,TypeError",
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_base64() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const { base64, base64url } = Javy.Encoding;
+                const bytes = new Uint8Array([0, 1, 2, 0xfb, 0xff, 0xbf]);
+                const view = new Uint8Array(bytes.buffer, 3, 2);
+                [base64.encode(bytes), base64url.encode(bytes), base64.encode(view),
+                 base64url.encode(view), base64.encode(new Uint8Array()),
+                 base64.decode("AAEC+/+/").join(" "), base64.decode("+/8").join(" "),
+                 base64.decode("+/8=\n").join(" "), base64url.decode("-_8").join(" "),
+                 base64url.decode("-_8=").join(" "), base64.decode("") instanceof Uint8Array].join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "AAEC+/+/,AAEC-_-_,+/8=,-_8,,0 1 2 251 255 191,251 255,251 255,251 255,251 255,true",
+                result
+            );
+
+            let result: String = this.eval(
+                r#"
+                const errors = [];
+                for (const [encoding, input] of [["base64", "-_8"], ["base64url", "+/8"], ["base64", "A"], ["base64", 1]]) {
+                    try {
+                        Javy.Encoding[encoding].decode(input);
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                try {
+                    base64.encode([1, 2]);
+                } catch (e) {
+                    errors.push(e.name);
+                }
+                errors.join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "SyntaxError,SyntaxError,SyntaxError,TypeError,TypeError",
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_hex() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const { hex } = Javy.Encoding;
+                [hex.encode(new Uint8Array([0, 15, 16, 171, 255])), hex.encode(new Uint8Array()),
+                 hex.decode("000f10abFF").join(" "), hex.decode("").length].join(",")
+            "#,
+            )?;
+            assert_eq!("000f10abff,,0 15 16 171 255,0", result);
+
+            let result: String = this.eval(
+                r#"
+                const errors = [];
+                for (const input of ["abc", "zz", "0x00", "+1"]) {
+                    try {
+                        hex.decode(input);
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                errors.join(",")
+            "#,
+            )?;
+            assert_eq!("SyntaxError,SyntaxError,SyntaxError,SyntaxError", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
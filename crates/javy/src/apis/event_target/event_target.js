@@ -0,0 +1,558 @@
+(function () {
+    // Only available when timers are enabled.
+    const __javy_abortSignalTimeout = globalThis.__javy_abortSignalTimeout;
+    const console = globalThis.console;
+    const performance = globalThis.performance;
+
+    function checkArgs(args, required, name) {
+        if (args.length < required) {
+            throw new TypeError(
+                `Failed to execute '${name}': ${required} argument${required > 1 ? "s" : ""} required, but only ${args.length} present`,
+            );
+        }
+    }
+
+    function checkDictionary(value, name, type) {
+        if (value !== undefined && value !== null && typeof value !== "object" && typeof value !== "function") {
+            throw new TypeError(`Failed to construct '${name}': The provided value is not of type '${type}'`);
+        }
+    }
+
+    function createError(message, name) {
+        const error = new Error(message);
+        error.name = name;
+        return error;
+    }
+
+    // Exceptions thrown by listeners don't propagate to `dispatchEvent`, they
+    // are reported and the remaining listeners still run.
+    function reportException(error) {
+        console?.error("Uncaught", error);
+    }
+
+    const NONE = 0;
+    const CAPTURING_PHASE = 1;
+    const AT_TARGET = 2;
+    const BUBBLING_PHASE = 3;
+
+    // Functions to access the private state of the other classes, assigned in
+    // the static blocks below.
+    let isEvent;
+    let beginDispatch;
+    let endDispatch;
+    let isPropagationStopped;
+    let isImmediatePropagationStopped;
+    let setInPassiveListener;
+    let addListener;
+    let removeListener;
+    let dispatch;
+    let isAbortSignal;
+    let createAbortSignal;
+    let addAbortAlgorithm;
+    let signalAbort;
+
+    class Event {
+        #type;
+        #bubbles;
+        #cancelable;
+        #composed;
+        #target = null;
+        #currentTarget = null;
+        #eventPhase = NONE;
+        #stopPropagation = false;
+        #stopImmediatePropagation = false;
+        #canceled = false;
+        #inPassiveListener = false;
+        #dispatch = false;
+        #timeStamp = performance ? performance.now() : Date.now();
+
+        static {
+            isEvent = (value) => typeof value === "object" && value !== null && #type in value;
+            beginDispatch = (event, target) => {
+                if (event.#dispatch) {
+                    throw createError(
+                        "Failed to execute 'dispatchEvent' on 'EventTarget': The event is already being dispatched.",
+                        "InvalidStateError",
+                    );
+                }
+                event.#dispatch = true;
+                event.#target = target;
+                event.#currentTarget = target;
+                event.#eventPhase = AT_TARGET;
+            };
+            endDispatch = (event) => {
+                event.#eventPhase = NONE;
+                event.#currentTarget = null;
+                event.#dispatch = false;
+                event.#stopPropagation = false;
+                event.#stopImmediatePropagation = false;
+                return !event.#canceled;
+            };
+            isPropagationStopped = (event) => event.#stopPropagation;
+            isImmediatePropagationStopped = (event) => event.#stopImmediatePropagation;
+            setInPassiveListener = (event, value) => {
+                event.#inPassiveListener = value;
+            };
+        }
+
+        constructor(type, eventInitDict = undefined) {
+            checkArgs(arguments, 1, "Event");
+            checkDictionary(eventInitDict, "Event", "EventInit");
+            const { bubbles, cancelable, composed } = eventInitDict ?? {};
+            this.#type = `${type}`;
+            this.#bubbles = Boolean(bubbles);
+            this.#cancelable = Boolean(cancelable);
+            this.#composed = Boolean(composed);
+        }
+
+        get type() {
+            return this.#type;
+        }
+
+        get target() {
+            return this.#target;
+        }
+
+        get srcElement() {
+            return this.#target;
+        }
+
+        get currentTarget() {
+            return this.#currentTarget;
+        }
+
+        composedPath() {
+            return this.#currentTarget === null ? [] : [this.#currentTarget];
+        }
+
+        get eventPhase() {
+            return this.#eventPhase;
+        }
+
+        stopPropagation() {
+            this.#stopPropagation = true;
+        }
+
+        get cancelBubble() {
+            return this.#stopPropagation;
+        }
+
+        set cancelBubble(value) {
+            if (value) {
+                this.#stopPropagation = true;
+            }
+        }
+
+        stopImmediatePropagation() {
+            this.#stopPropagation = true;
+            this.#stopImmediatePropagation = true;
+        }
+
+        get bubbles() {
+            return this.#bubbles;
+        }
+
+        get cancelable() {
+            return this.#cancelable;
+        }
+
+        get returnValue() {
+            return !this.#canceled;
+        }
+
+        set returnValue(value) {
+            if (!value) {
+                this.preventDefault();
+            }
+        }
+
+        preventDefault() {
+            if (this.#cancelable && !this.#inPassiveListener) {
+                this.#canceled = true;
+            }
+        }
+
+        get defaultPrevented() {
+            return this.#canceled;
+        }
+
+        get composed() {
+            return this.#composed;
+        }
+
+        get isTrusted() {
+            return false;
+        }
+
+        get timeStamp() {
+            return this.#timeStamp;
+        }
+
+        initEvent(type, bubbles = false, cancelable = false) {
+            checkArgs(arguments, 1, "initEvent");
+            if (this.#dispatch) {
+                return;
+            }
+            this.#stopPropagation = false;
+            this.#stopImmediatePropagation = false;
+            this.#canceled = false;
+            this.#target = null;
+            this.#type = `${type}`;
+            this.#bubbles = Boolean(bubbles);
+            this.#cancelable = Boolean(cancelable);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "Event";
+        }
+    }
+
+    for (const [name, value] of Object.entries({ NONE, CAPTURING_PHASE, AT_TARGET, BUBBLING_PHASE })) {
+        Object.defineProperty(Event, name, { value, enumerable: true });
+        Object.defineProperty(Event.prototype, name, { value, enumerable: true });
+    }
+
+    class CustomEvent extends Event {
+        #detail;
+
+        constructor(type, eventInitDict = undefined) {
+            checkArgs(arguments, 1, "CustomEvent");
+            checkDictionary(eventInitDict, "CustomEvent", "CustomEventInit");
+            super(type, eventInitDict);
+            this.#detail = eventInitDict?.detail ?? null;
+        }
+
+        get detail() {
+            return this.#detail;
+        }
+
+        initCustomEvent(type, bubbles = false, cancelable = false, detail = null) {
+            checkArgs(arguments, 1, "initCustomEvent");
+            if (this.eventPhase !== NONE) {
+                return;
+            }
+            this.initEvent(type, bubbles, cancelable);
+            this.#detail = detail;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "CustomEvent";
+        }
+    }
+
+    // https://dom.spec.whatwg.org/#concept-flatten-options
+    function flattenOptions(options) {
+        if (typeof options === "object" && options !== null) {
+            return Boolean(options.capture);
+        }
+        return Boolean(options);
+    }
+
+    function checkCallback(callback, name) {
+        if (callback !== null && typeof callback !== "object" && typeof callback !== "function") {
+            throw new TypeError(`Failed to execute '${name}' on 'EventTarget': parameter 2 is not of type 'Object'`);
+        }
+    }
+
+    class EventTarget {
+        // Maps event types to their listeners.
+        #listeners = new Map();
+
+        static {
+            addListener = (target, type, callback, { capture = false, once = false, passive = false, signal = null }) => {
+                if (signal !== null && signal.aborted) {
+                    return;
+                }
+                let listeners = target.#listeners.get(type);
+                if (listeners === undefined) {
+                    listeners = [];
+                    target.#listeners.set(type, listeners);
+                }
+                if (listeners.some((listener) => listener.callback === callback && listener.capture === capture)) {
+                    return;
+                }
+                const listener = { callback, capture, once, passive, removed: false };
+                listeners.push(listener);
+                if (signal !== null) {
+                    addAbortAlgorithm(signal, () => removeListener(target, type, listener));
+                }
+            };
+            removeListener = (target, type, listener) => {
+                listener.removed = true;
+                const listeners = target.#listeners.get(type) ?? [];
+                const index = listeners.indexOf(listener);
+                if (index !== -1) {
+                    listeners.splice(index, 1);
+                }
+            };
+            // https://dom.spec.whatwg.org/#concept-event-dispatch
+            //
+            // Targets have no parents, so the event path only consists of the
+            // target itself: capturing listeners run first, then the others.
+            dispatch = (target, event) => {
+                beginDispatch(event, target);
+                const listeners = [...(target.#listeners.get(event.type) ?? [])];
+                for (const capture of [true, false]) {
+                    if (isPropagationStopped(event)) {
+                        break;
+                    }
+                    for (const listener of listeners) {
+                        if (listener.removed || listener.capture !== capture) {
+                            continue;
+                        }
+                        if (listener.once) {
+                            removeListener(target, event.type, listener);
+                        }
+                        setInPassiveListener(event, listener.passive);
+                        try {
+                            const { callback } = listener;
+                            if (typeof callback === "function") {
+                                callback.call(target, event);
+                            } else {
+                                const handleEvent = callback.handleEvent;
+                                if (typeof handleEvent !== "function") {
+                                    throw new TypeError("'handleEvent' property of event listener should be callable");
+                                }
+                                handleEvent.call(callback, event);
+                            }
+                        } catch (error) {
+                            reportException(error);
+                        }
+                        setInPassiveListener(event, false);
+                        if (isImmediatePropagationStopped(event)) {
+                            break;
+                        }
+                    }
+                }
+                return endDispatch(event);
+            };
+        }
+
+        addEventListener(type, callback, options = undefined) {
+            checkArgs(arguments, 2, "addEventListener");
+            checkCallback(callback, "addEventListener");
+            const capture = flattenOptions(options);
+            let once = false;
+            let passive = false;
+            let signal = null;
+            if (typeof options === "object" && options !== null) {
+                once = Boolean(options.once);
+                passive = Boolean(options.passive);
+                if (options.signal !== undefined) {
+                    if (!isAbortSignal(options.signal)) {
+                        throw new TypeError(
+                            "Failed to execute 'addEventListener' on 'EventTarget': member signal is not of type 'AbortSignal'",
+                        );
+                    }
+                    signal = options.signal;
+                }
+            }
+            if (callback === null) {
+                return;
+            }
+            addListener(this, `${type}`, callback, { capture, once, passive, signal });
+        }
+
+        removeEventListener(type, callback, options = undefined) {
+            checkArgs(arguments, 2, "removeEventListener");
+            checkCallback(callback, "removeEventListener");
+            const capture = flattenOptions(options);
+            type = `${type}`;
+            const listener = this.#listeners
+                .get(type)
+                ?.find((listener) => listener.callback === callback && listener.capture === capture);
+            if (listener !== undefined) {
+                removeListener(this, type, listener);
+            }
+        }
+
+        dispatchEvent(event) {
+            checkArgs(arguments, 1, "dispatchEvent");
+            if (!isEvent(event)) {
+                throw new TypeError("Failed to execute 'dispatchEvent' on 'EventTarget': parameter 1 is not of type 'Event'");
+            }
+            return dispatch(this, event);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "EventTarget";
+        }
+    }
+
+    // https://webidl.spec.whatwg.org/#abstract-opdef-converttoint
+    function toEnforcedUnsignedLongLong(value, name) {
+        const number = Math.trunc(Number(value));
+        if (!Number.isFinite(number) || number < 0 || number > Number.MAX_SAFE_INTEGER) {
+            throw new TypeError(`Failed to execute '${name}' on 'AbortSignal': Value is outside the 'unsigned long long' value range.`);
+        }
+        return number;
+    }
+
+    // Only `AbortController` and the static methods create signals.
+    const constructorKey = Symbol();
+
+    class AbortSignal extends EventTarget {
+        #aborted = false;
+        #reason = undefined;
+        #algorithms = [];
+        #onabort = null;
+        #onabortListener = false;
+        // Signals created by `AbortSignal.any` follow their source signals,
+        // which in turn keep track of them to abort them.
+        #dependent = false;
+        #sources = [];
+        #dependents = [];
+
+        static {
+            isAbortSignal = (value) => typeof value === "object" && value !== null && #aborted in value;
+            createAbortSignal = () => new AbortSignal(constructorKey);
+            addAbortAlgorithm = (signal, algorithm) => {
+                if (!signal.#aborted) {
+                    signal.#algorithms.push(algorithm);
+                }
+            };
+            // https://dom.spec.whatwg.org/#abortsignal-signal-abort
+            const runAbortSteps = (signal) => {
+                const algorithms = signal.#algorithms;
+                signal.#algorithms = [];
+                for (const algorithm of algorithms) {
+                    algorithm();
+                }
+                dispatch(signal, new Event("abort"));
+            };
+            signalAbort = (signal, reason) => {
+                if (signal.#aborted) {
+                    return;
+                }
+                signal.#aborted = true;
+                signal.#reason = reason === undefined ? createError("This operation was aborted", "AbortError") : reason;
+                const dependents = signal.#dependents.filter((dependent) => !dependent.#aborted);
+                for (const dependent of dependents) {
+                    dependent.#aborted = true;
+                    dependent.#reason = signal.#reason;
+                }
+                signal.#dependents = [];
+                runAbortSteps(signal);
+                for (const dependent of dependents) {
+                    runAbortSteps(dependent);
+                }
+            };
+        }
+
+        constructor(key = undefined) {
+            if (key !== constructorKey) {
+                throw new TypeError("Illegal constructor");
+            }
+            super();
+        }
+
+        static abort(reason = undefined) {
+            const signal = createAbortSignal();
+            signalAbort(signal, reason);
+            return signal;
+        }
+
+        static timeout(milliseconds) {
+            checkArgs(arguments, 1, "timeout");
+            const delay = toEnforcedUnsignedLongLong(milliseconds, "timeout");
+            const signal = createAbortSignal();
+            __javy_abortSignalTimeout(() => {
+                signalAbort(signal, createError("The operation was aborted due to timeout", "TimeoutError"));
+            }, delay);
+            return signal;
+        }
+
+        static any(signals) {
+            checkArgs(arguments, 1, "any");
+            if (typeof signals !== "object" || signals === null || typeof signals[Symbol.iterator] !== "function") {
+                throw new TypeError("Failed to execute 'any' on 'AbortSignal': The provided value cannot be converted to a sequence.");
+            }
+            signals = [...signals];
+            for (const signal of signals) {
+                if (!isAbortSignal(signal)) {
+                    throw new TypeError("Failed to execute 'any' on 'AbortSignal': Failed to convert value to 'AbortSignal'.");
+                }
+            }
+            const result = createAbortSignal();
+            const aborted = signals.find((signal) => signal.#aborted);
+            if (aborted !== undefined) {
+                result.#aborted = true;
+                result.#reason = aborted.#reason;
+                return result;
+            }
+            result.#dependent = true;
+            for (const signal of signals) {
+                for (const source of signal.#dependent ? signal.#sources : [signal]) {
+                    if (!result.#sources.includes(source)) {
+                        result.#sources.push(source);
+                        source.#dependents.push(result);
+                    }
+                }
+            }
+            return result;
+        }
+
+        get aborted() {
+            return this.#aborted;
+        }
+
+        get reason() {
+            return this.#reason;
+        }
+
+        throwIfAborted() {
+            if (this.#aborted) {
+                throw this.#reason;
+            }
+        }
+
+        get onabort() {
+            return this.#onabort;
+        }
+
+        // https://html.spec.whatwg.org/#event-handler-attributes
+        set onabort(value) {
+            this.#onabort = typeof value === "function" || (typeof value === "object" && value !== null) ? value : null;
+            if (this.#onabort !== null && !this.#onabortListener) {
+                this.#onabortListener = true;
+                addListener(this, "abort", (event) => {
+                    if (typeof this.#onabort === "function") {
+                        this.#onabort.call(this, event);
+                    }
+                }, {});
+            }
+        }
+
+        get [Symbol.toStringTag]() {
+            return "AbortSignal";
+        }
+    }
+
+    if (__javy_abortSignalTimeout === undefined) {
+        Reflect.deleteProperty(AbortSignal, "timeout");
+    }
+
+    class AbortController {
+        #signal = createAbortSignal();
+
+        get signal() {
+            return this.#signal;
+        }
+
+        abort(reason = undefined) {
+            signalAbort(this.#signal, reason);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "AbortController";
+        }
+    }
+
+    globalThis.Event = Event;
+    globalThis.CustomEvent = CustomEvent;
+    globalThis.EventTarget = EventTarget;
+    globalThis.AbortSignal = AbortSignal;
+    globalThis.AbortController = AbortController;
+
+    Reflect.deleteProperty(globalThis, "__javy_abortSignalTimeout");
+})();
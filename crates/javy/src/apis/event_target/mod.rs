@@ -0,0 +1,325 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    Args, hold, hold_and_release,
+    quickjs::{Ctx, Function, Value, context::EvalOptions, prelude::MutFn},
+    to_js_error,
+};
+use anyhow::{Error, Result, anyhow};
+
+use super::timers::{self, TimerQueue};
+
+/// Register the `EventTarget`, `Event`, `CustomEvent`, `AbortController` and
+/// `AbortSignal` classes on the global object.
+///
+/// Event targets have no parents, dispatching an event only invokes the
+/// listeners of the target itself. `AbortSignal.timeout` is only available
+/// when `queue` is provided, the timers it schedules don't keep the event loop
+/// running.
+pub(crate) fn register(this: Ctx<'_>, queue: Option<Rc<RefCell<TimerQueue>>>) -> Result<()> {
+    if let Some(queue) = queue {
+        this.globals().set(
+            "__javy_abortSignalTimeout",
+            Function::new(
+                this.clone(),
+                MutFn::new(move |cx, args| {
+                    let (cx, args) = hold_and_release!(cx, args);
+                    timeout(hold!(cx.clone(), args), &queue).map_err(|e| to_js_error(cx, e))
+                }),
+            )?,
+        )?;
+    }
+    let mut opts = EvalOptions::default();
+    opts.strict = false;
+    this.eval_with_options::<(), _>(include_str!("./event_target.js"), opts)?;
+
+    Ok::<_, Error>(())
+}
+
+/// Schedules the callback aborting the signal returned by
+/// `AbortSignal.timeout`, the delay is validated on the JavaScript side.
+fn timeout<'js>(args: Args<'js>, queue: &Rc<RefCell<TimerQueue>>) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let mut args = args.into_inner().into_iter();
+    let callback = args
+        .next()
+        .and_then(|v| v.into_function())
+        .ok_or_else(|| anyhow!("The callback argument must be of type function"))?;
+    let delay = args
+        .next()
+        .filter(|v| v.is_number())
+        .ok_or_else(|| anyhow!("The delay argument must be of type number"))?;
+    let delay = timers::delay_from_value(delay)?;
+    timers::schedule_unref(&cx, queue, callback, delay);
+
+    Ok(Value::new_undefined(cx))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    fn runtime() -> Result<Runtime> {
+        let mut config = Config::default();
+        config.event_target(true);
+        Runtime::new(config)
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        const CLASSES: [&str; 5] = [
+            "EventTarget",
+            "Event",
+            "CustomEvent",
+            "AbortController",
+            "AbortSignal",
+        ];
+
+        let default = Runtime::default();
+        default.context().with(|this| {
+            for class in CLASSES {
+                assert!(this.globals().get::<_, Value<'_>>(class)?.is_undefined());
+            }
+            Ok::<_, Error>(())
+        })?;
+
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let globals = this.globals();
+            for class in CLASSES {
+                assert!(globals.get::<_, Value<'_>>(class)?.is_function());
+            }
+            assert!(
+                globals
+                    .get::<_, Value<'_>>("__javy_abortSignalTimeout")?
+                    .is_undefined()
+            );
+            let timeout: Value<'_> = this.eval("AbortSignal.timeout")?;
+            assert!(timeout.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_target() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const target = new EventTarget();
+                const calls = [];
+                const a = (e) => calls.push(`a${e.eventPhase}`);
+                target.addEventListener("ping", a);
+                target.addEventListener("ping", a);
+                target.addEventListener("ping", function (e) { calls.push(this === target && e.target === target); });
+                target.addEventListener("ping", { handleEvent: () => calls.push("once") }, { once: true });
+                target.addEventListener("ping", () => calls.push("capture"), { capture: true });
+                target.addEventListener("pong", a);
+                target.dispatchEvent(new Event("ping"));
+                target.removeEventListener("ping", a, true);
+                target.dispatchEvent(new Event("ping"));
+                target.removeEventListener("ping", a);
+                target.dispatchEvent(new Event("ping"));
+                calls.join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "capture,a2,true,once,capture,a2,true,capture,true",
+                result
+            );
+
+            let result: String = this.eval(
+                r#"
+                const canceling = new EventTarget();
+                canceling.addEventListener("ping", (e) => e.preventDefault());
+                canceling.addEventListener("ping", (e) => e.stopImmediatePropagation());
+                canceling.addEventListener("ping", () => { throw new Error("unreachable"); });
+                const passive = new EventTarget();
+                passive.addEventListener("ping", (e) => e.preventDefault(), { passive: true });
+                const event = new Event("ping", { cancelable: true });
+                [canceling.dispatchEvent(new Event("ping")), canceling.dispatchEvent(event),
+                 event.defaultPrevented, event.eventPhase, event.currentTarget, event.target === canceling,
+                 passive.dispatchEvent(new Event("ping", { cancelable: true }))].join(",")
+            "#,
+            )?;
+            assert_eq!("true,false,true,0,,true,true", result);
+
+            let result: String = this.eval(
+                r#"
+                const errors = [];
+                const reentrant = new EventTarget();
+                reentrant.addEventListener("ping", (e) => {
+                    try {
+                        reentrant.dispatchEvent(e);
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                });
+                reentrant.dispatchEvent(new Event("ping"));
+                for (const f of [() => reentrant.dispatchEvent({ type: "ping" }),
+                                 () => reentrant.addEventListener("ping", 1),
+                                 () => reentrant.addEventListener("ping")]) {
+                    try {
+                        f();
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                errors.join(",")
+            "#,
+            )?;
+            assert_eq!("InvalidStateError,TypeError,TypeError,TypeError", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_event() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const event = new CustomEvent("ping", { bubbles: true, detail: 42 });
+                [event.type, event.bubbles, event.cancelable, event.composed, event.isTrusted,
+                 event.target, event.eventPhase, event.detail, new CustomEvent("pong").detail,
+                 event instanceof Event, Object.prototype.toString.call(event),
+                 Event.BUBBLING_PHASE, event.AT_TARGET, typeof event.timeStamp].join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "ping,true,false,false,false,,0,42,,true,[object CustomEvent],3,2,number",
+                result
+            );
+
+            let result: String = this.eval(
+                r#"
+                const initialized = new Event("a");
+                initialized.initEvent("b", true, true);
+                initialized.returnValue = false;
+                [initialized.type, initialized.bubbles, initialized.defaultPrevented,
+                 initialized.returnValue, initialized.composedPath().length].join(",")
+            "#,
+            )?;
+            assert_eq!("b,true,true,false,0", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_abort_controller() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const calls = [];
+                const controller = new AbortController();
+                const { signal } = controller;
+                const target = new EventTarget();
+                target.addEventListener("ping", () => calls.push("ping"), { signal });
+                signal.addEventListener("abort", (e) => calls.push(`listener:${e.type}`));
+                signal.onabort = (e) => calls.push(`onabort:${e.target === signal}`);
+                const any = AbortSignal.any([signal, new AbortController().signal]);
+                any.onabort = () => calls.push(`any:${any.reason === signal.reason}`);
+                target.dispatchEvent(new Event("ping"));
+                controller.abort();
+                controller.abort("ignored");
+                target.dispatchEvent(new Event("ping"));
+                calls.push(signal.aborted, signal.reason.name, signal.reason.message);
+                try {
+                    signal.throwIfAborted();
+                } catch (e) {
+                    calls.push(e === signal.reason);
+                }
+                calls.join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "ping,listener:abort,onabort:true,any:true,true,AbortError,This operation was aborted,true",
+                result
+            );
+
+            let result: String = this.eval(
+                r#"
+                const aborted = AbortSignal.abort("reason");
+                const listened = new EventTarget();
+                let called = false;
+                listened.addEventListener("ping", () => called = true, { signal: aborted });
+                listened.dispatchEvent(new Event("ping"));
+                const errors = [];
+                for (const f of [() => new AbortSignal(), () => AbortSignal.any([{}]),
+                                 () => listened.addEventListener("ping", () => {}, { signal: null })]) {
+                    try {
+                        f();
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                [aborted.aborted, aborted.reason, AbortSignal.any([aborted]).reason, called,
+                 Object.prototype.toString.call(aborted), ...errors].join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "true,reason,reason,false,[object AbortSignal],TypeError,TypeError,TypeError",
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_abort_signal_timeout() -> Result<()> {
+        let mut config = Config::default();
+        config.event_target(true).timers(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.eval::<(), _>(
+                r#"
+                globalThis.signal = AbortSignal.timeout(1);
+                globalThis.fired = [];
+                signal.onabort = () => fired.push(signal.reason.name, signal.reason.message);
+            "#,
+            )?;
+            Ok::<_, Error>(())
+        })?;
+
+        // The timeout alone doesn't keep the event loop running.
+        assert!(!runtime.has_pending_timers());
+        runtime.run_event_loop()?;
+        runtime.context().with(|this| {
+            let fired: String = this.eval("fired.join()")?;
+            assert_eq!("", fired);
+            Ok::<_, Error>(())
+        })?;
+
+        assert!(runtime.run_next_timer()?);
+        runtime.context().with(|this| {
+            let fired: String = this.eval("fired.join()")?;
+            assert_eq!(
+                "TimeoutError,The operation was aborted due to timeout",
+                fired
+            );
+
+            let result: String = this.eval(
+                r#"
+                const errors = [];
+                for (const delay of [-1, Infinity, NaN]) {
+                    try {
+                        AbortSignal.timeout(delay);
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                errors.join()
+            "#,
+            )?;
+            assert_eq!("TypeError,TypeError,TypeError", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
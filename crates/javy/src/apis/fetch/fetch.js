@@ -0,0 +1,1061 @@
+(function () {
+    const __javy_fetchEncodeUtf8 = globalThis.__javy_fetchEncodeUtf8;
+    const __javy_fetchDecodeUtf8 = globalThis.__javy_fetchDecodeUtf8;
+    const __javy_fetchParseURL = globalThis.__javy_fetchParseURL;
+    const __javy_fetchParseURLEncoded = globalThis.__javy_fetchParseURLEncoded;
+    const __javy_fetchParseMultipart = globalThis.__javy_fetchParseMultipart;
+    // Only available when the `URL` APIs are enabled.
+    const URLSearchParams = globalThis.URLSearchParams;
+    // Only available when the `EventTarget` APIs are enabled.
+    const AbortController = globalThis.AbortController;
+    const AbortSignal = globalThis.AbortSignal;
+
+    // https://webidl.spec.whatwg.org/#es-USVString
+    function toUSVString(value) {
+        return `${value}`.toWellFormed();
+    }
+
+    // https://webidl.spec.whatwg.org/#es-ByteString
+    function toByteString(value) {
+        const string = `${value}`;
+        for (let i = 0; i < string.length; i++) {
+            if (string.charCodeAt(i) > 0xff) {
+                throw new TypeError(
+                    `Cannot convert "${string}" to a ByteString because the character at index ${i} has a value of ${string.charCodeAt(i)} which is greater than 255`,
+                );
+            }
+        }
+        return string;
+    }
+
+    // https://webidl.spec.whatwg.org/#es-unsigned-short
+    function toUnsignedShort(value) {
+        const number = Math.trunc(Number(value));
+        return Number.isFinite(number) ? ((number % 65536) + 65536) % 65536 : 0;
+    }
+
+    function checkArgs(args, required, name) {
+        if (args.length < required) {
+            throw new TypeError(
+                `Failed to execute '${name}': ${required} argument${required > 1 ? "s" : ""} required, but only ${args.length} present`,
+            );
+        }
+    }
+
+    function checkDictionary(value, name, type) {
+        if (value !== undefined && value !== null && typeof value !== "object" && typeof value !== "function") {
+            throw new TypeError(`Failed to construct '${name}': The provided value is not of type '${type}'`);
+        }
+    }
+
+    function checkEnum(value, values, type) {
+        if (value === undefined) {
+            return undefined;
+        }
+        value = `${value}`;
+        if (!values.includes(value)) {
+            throw new TypeError(`The provided value '${value}' is not a valid enum value of type ${type}`);
+        }
+        return value;
+    }
+
+    function isObject(value) {
+        return value !== null && (typeof value === "object" || typeof value === "function");
+    }
+
+    function isBufferSource(value) {
+        return ArrayBuffer.isView(value) || value instanceof ArrayBuffer;
+    }
+
+    // A copy of the bytes of an `ArrayBuffer` or an `ArrayBuffer` view.
+    function copyBufferSource(source) {
+        if (ArrayBuffer.isView(source)) {
+            return new Uint8Array(source.buffer, source.byteOffset, source.byteLength).slice();
+        }
+        return new Uint8Array(source).slice();
+    }
+
+    function concatBytes(chunks) {
+        let length = 0;
+        for (const chunk of chunks) {
+            length += chunk.length;
+        }
+        const bytes = new Uint8Array(length);
+        let offset = 0;
+        for (const chunk of chunks) {
+            bytes.set(chunk, offset);
+            offset += chunk.length;
+        }
+        return bytes;
+    }
+
+    const TOKEN = /^[!#$%&'*+\-.^_`|~0-9A-Za-z]+$/;
+    const HTTP_WHITESPACE = /^[\t\n\r ]+|[\t\n\r ]+$/g;
+
+    function normalizeHeaderName(name) {
+        name = toByteString(name);
+        if (!TOKEN.test(name)) {
+            throw new TypeError(`Invalid header name: "${name}"`);
+        }
+        return name.toLowerCase();
+    }
+
+    function normalizeHeaderValue(value) {
+        value = toByteString(value).replace(HTTP_WHITESPACE, "");
+        if (/[\0\r\n]/.test(value)) {
+            throw new TypeError(`Invalid header value: "${value}"`);
+        }
+        return value;
+    }
+
+    // Functions to access the private state of the classes, assigned in the
+    // static blocks below.
+    let copyHeaders;
+    let setHeadersGuard;
+    let blobBytes;
+    let formDataList;
+
+    class Headers {
+        // Names are stored lowercased, which is how they're exposed.
+        #list = [];
+        // Either "none" or "immutable".
+        #guard = "none";
+
+        static {
+            copyHeaders = (headers) => {
+                const copy = new Headers();
+                copy.#list = headers.#list.map(([name, value]) => [name, value]);
+                copy.#guard = headers.#guard;
+                return copy;
+            };
+            setHeadersGuard = (headers, guard) => {
+                headers.#guard = guard;
+            };
+        }
+
+        constructor(init = undefined) {
+            if (init === undefined) {
+                return;
+            }
+            if (!isObject(init)) {
+                throw new TypeError(
+                    "Failed to construct 'Headers': The provided value is not of type '(record<ByteString, ByteString> or sequence<sequence<ByteString>>)'",
+                );
+            }
+            if (init[Symbol.iterator] !== undefined) {
+                for (const header of init) {
+                    if (!isObject(header)) {
+                        throw new TypeError("Failed to construct 'Headers': The provided value cannot be converted to a sequence");
+                    }
+                    const pair = [...header];
+                    if (pair.length !== 2) {
+                        throw new TypeError("Failed to construct 'Headers': Invalid value");
+                    }
+                    this.#append(pair[0], pair[1]);
+                }
+            } else {
+                for (const key of Reflect.ownKeys(init)) {
+                    const descriptor = Reflect.getOwnPropertyDescriptor(init, key);
+                    if (descriptor !== undefined && descriptor.enumerable) {
+                        this.#append(key, init[key]);
+                    }
+                }
+            }
+        }
+
+        #checkGuard() {
+            if (this.#guard === "immutable") {
+                throw new TypeError("Headers are immutable");
+            }
+        }
+
+        #append(name, value) {
+            name = normalizeHeaderName(name);
+            value = normalizeHeaderValue(value);
+            this.#checkGuard();
+            this.#list.push([name, value]);
+        }
+
+        #get(name) {
+            const values = this.#list.filter(([n]) => n === name).map(([, v]) => v);
+            return values.length === 0 ? null : values.join(", ");
+        }
+
+        // https://fetch.spec.whatwg.org/#concept-header-list-sort-and-combine
+        #sortAndCombine() {
+            const names = [...new Set(this.#list.map(([name]) => name))].sort();
+            const headers = [];
+            for (const name of names) {
+                if (name === "set-cookie") {
+                    for (const [, value] of this.#list.filter(([n]) => n === name)) {
+                        headers.push([name, value]);
+                    }
+                } else {
+                    headers.push([name, this.#get(name)]);
+                }
+            }
+            return headers;
+        }
+
+        append(name, value) {
+            checkArgs(arguments, 2, "append");
+            this.#append(name, value);
+        }
+
+        delete(name) {
+            checkArgs(arguments, 1, "delete");
+            name = normalizeHeaderName(name);
+            this.#checkGuard();
+            this.#list = this.#list.filter(([n]) => n !== name);
+        }
+
+        get(name) {
+            checkArgs(arguments, 1, "get");
+            return this.#get(normalizeHeaderName(name));
+        }
+
+        getSetCookie() {
+            return this.#list.filter(([n]) => n === "set-cookie").map(([, v]) => v);
+        }
+
+        has(name) {
+            checkArgs(arguments, 1, "has");
+            name = normalizeHeaderName(name);
+            return this.#list.some(([n]) => n === name);
+        }
+
+        set(name, value) {
+            checkArgs(arguments, 2, "set");
+            name = normalizeHeaderName(name);
+            value = normalizeHeaderValue(value);
+            this.#checkGuard();
+            const index = this.#list.findIndex(([n]) => n === name);
+            if (index === -1) {
+                this.#list.push([name, value]);
+            } else {
+                this.#list[index] = [name, value];
+                this.#list = this.#list.filter(([n], i) => n !== name || i === index);
+            }
+        }
+
+        forEach(callback, thisArg = undefined) {
+            checkArgs(arguments, 1, "forEach");
+            if (typeof callback !== "function") {
+                throw new TypeError("The callback provided as parameter 1 is not a function");
+            }
+            // The headers are sorted on each iteration, so that changes made
+            // by the callback are visible.
+            for (let i = 0; i < this.#sortAndCombine().length; i++) {
+                const [name, value] = this.#sortAndCombine()[i];
+                callback.call(thisArg, value, name, this);
+            }
+        }
+
+        *entries() {
+            for (let i = 0; i < this.#sortAndCombine().length; i++) {
+                yield this.#sortAndCombine()[i];
+            }
+        }
+
+        *keys() {
+            for (const [name] of this.entries()) {
+                yield name;
+            }
+        }
+
+        *values() {
+            for (const [, value] of this.entries()) {
+                yield value;
+            }
+        }
+
+        [Symbol.iterator]() {
+            return this.entries();
+        }
+
+        get [Symbol.toStringTag]() {
+            return "Headers";
+        }
+    }
+
+    // https://w3c.github.io/FileAPI/#dfn-BlobPropertyBag
+    function blobPropertyBag(options, name) {
+        checkDictionary(options, name, "BlobPropertyBag");
+        const endings = checkEnum(options?.endings, ["transparent", "native"], "EndingType") ?? "transparent";
+        const type = options?.type === undefined ? "" : `${options.type}`;
+        return { endings, type };
+    }
+
+    function normalizeBlobType(type) {
+        return /^[\x20-\x7E]*$/.test(type) ? type.toLowerCase() : "";
+    }
+
+    // https://w3c.github.io/FileAPI/#process-blob-parts
+    function processBlobParts(parts, endings, name) {
+        if (parts === undefined) {
+            return new Uint8Array(0);
+        }
+        if (!isObject(parts) || parts[Symbol.iterator] === undefined) {
+            throw new TypeError(`Failed to construct '${name}': The provided value cannot be converted to a sequence`);
+        }
+        const chunks = [];
+        for (const part of parts) {
+            if (part instanceof Blob) {
+                chunks.push(blobBytes(part));
+            } else if (isBufferSource(part)) {
+                chunks.push(copyBufferSource(part));
+            } else {
+                let string = toUSVString(part);
+                // Line endings are only converted to the native line ending,
+                // which is always a line feed in WASI.
+                if (endings === "native") {
+                    string = string.replace(/\r\n?/g, "\n");
+                }
+                chunks.push(__javy_fetchEncodeUtf8(string));
+            }
+        }
+        return concatBytes(chunks);
+    }
+
+    class Blob {
+        #bytes;
+        #type;
+
+        static {
+            blobBytes = (blob) => blob.#bytes;
+        }
+
+        constructor(blobParts = undefined, options = undefined) {
+            const { endings, type } = blobPropertyBag(options, "Blob");
+            this.#bytes = processBlobParts(blobParts, endings, new.target === File ? "File" : "Blob");
+            this.#type = normalizeBlobType(type);
+        }
+
+        get size() {
+            return this.#bytes.length;
+        }
+
+        get type() {
+            return this.#type;
+        }
+
+        slice(start = undefined, end = undefined, contentType = undefined) {
+            const size = this.#bytes.length;
+            const relative = (position, fallback) => {
+                if (position === undefined) {
+                    return fallback;
+                }
+                position = Math.trunc(Number(position)) || 0;
+                return position < 0 ? Math.max(size + position, 0) : Math.min(position, size);
+            };
+            const blob = new Blob();
+            blob.#bytes = this.#bytes.slice(relative(start, 0), relative(end, size));
+            blob.#type = contentType === undefined ? "" : normalizeBlobType(`${contentType}`);
+            return blob;
+        }
+
+        async arrayBuffer() {
+            return this.#bytes.slice().buffer;
+        }
+
+        async bytes() {
+            return this.#bytes.slice();
+        }
+
+        async text() {
+            return __javy_fetchDecodeUtf8(this.#bytes);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "Blob";
+        }
+    }
+
+    class File extends Blob {
+        #name;
+        #lastModified;
+
+        constructor(fileBits, fileName, options = undefined) {
+            checkArgs(arguments, 2, "File");
+            super(fileBits, options);
+            this.#name = toUSVString(fileName);
+            this.#lastModified =
+                options?.lastModified === undefined ? Date.now() : Math.trunc(Number(options.lastModified)) || 0;
+        }
+
+        get name() {
+            return this.#name;
+        }
+
+        get lastModified() {
+            return this.#lastModified;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "File";
+        }
+    }
+
+    // https://xhr.spec.whatwg.org/#create-an-entry
+    function createEntry(name, value, filename, args, method) {
+        name = toUSVString(name);
+        if (value instanceof Blob) {
+            if (!(value instanceof File) || filename !== undefined) {
+                const lastModified = value instanceof File ? value.lastModified : undefined;
+                filename = filename !== undefined ? toUSVString(filename) : value instanceof File ? value.name : "blob";
+                value = new File([value], filename, { type: value.type, lastModified });
+            }
+        } else if (args.length > 2) {
+            throw new TypeError(`Failed to execute '${method}' on 'FormData': parameter 2 is not of type 'Blob'`);
+        } else {
+            value = toUSVString(value);
+        }
+        return [name, value];
+    }
+
+    class FormData {
+        #list = [];
+
+        static {
+            formDataList = (formData) => formData.#list;
+        }
+
+        constructor(form = undefined) {
+            if (form !== undefined) {
+                throw new TypeError("Failed to construct 'FormData': parameter 1 is not of type 'HTMLFormElement'");
+            }
+        }
+
+        append(name, value, filename = undefined) {
+            checkArgs(arguments, 2, "append");
+            this.#list.push(createEntry(name, value, filename, arguments, "append"));
+        }
+
+        delete(name) {
+            checkArgs(arguments, 1, "delete");
+            name = toUSVString(name);
+            this.#list = this.#list.filter(([n]) => n !== name);
+        }
+
+        get(name) {
+            checkArgs(arguments, 1, "get");
+            name = toUSVString(name);
+            const entry = this.#list.find(([n]) => n === name);
+            return entry === undefined ? null : entry[1];
+        }
+
+        getAll(name) {
+            checkArgs(arguments, 1, "getAll");
+            name = toUSVString(name);
+            return this.#list.filter(([n]) => n === name).map(([, v]) => v);
+        }
+
+        has(name) {
+            checkArgs(arguments, 1, "has");
+            name = toUSVString(name);
+            return this.#list.some(([n]) => n === name);
+        }
+
+        set(name, value, filename = undefined) {
+            checkArgs(arguments, 2, "set");
+            const entry = createEntry(name, value, filename, arguments, "set");
+            const index = this.#list.findIndex(([n]) => n === entry[0]);
+            if (index === -1) {
+                this.#list.push(entry);
+            } else {
+                this.#list[index] = entry;
+                this.#list = this.#list.filter(([n], i) => n !== entry[0] || i === index);
+            }
+        }
+
+        forEach(callback, thisArg = undefined) {
+            checkArgs(arguments, 1, "forEach");
+            if (typeof callback !== "function") {
+                throw new TypeError("The callback provided as parameter 1 is not a function");
+            }
+            for (let i = 0; i < this.#list.length; i++) {
+                const [name, value] = this.#list[i];
+                callback.call(thisArg, value, name, this);
+            }
+        }
+
+        *entries() {
+            for (let i = 0; i < this.#list.length; i++) {
+                yield [...this.#list[i]];
+            }
+        }
+
+        *keys() {
+            for (let i = 0; i < this.#list.length; i++) {
+                yield this.#list[i][0];
+            }
+        }
+
+        *values() {
+            for (let i = 0; i < this.#list.length; i++) {
+                yield this.#list[i][1];
+            }
+        }
+
+        [Symbol.iterator]() {
+            return this.entries();
+        }
+
+        get [Symbol.toStringTag]() {
+            return "FormData";
+        }
+    }
+
+    function normalizeLineEndings(string) {
+        return string.replace(/\r\n|\r|\n/g, "\r\n");
+    }
+
+    function escapeMultipartName(name) {
+        return name.replace(/\n/g, "%0A").replace(/\r/g, "%0D").replace(/"/g, "%22");
+    }
+
+    // https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#multipart/form-data-encoding-algorithm
+    function serializeMultipart(list, boundary) {
+        const chunks = [];
+        for (const [name, value] of list) {
+            let header = `--${boundary}\r\nContent-Disposition: form-data; name="${escapeMultipartName(normalizeLineEndings(name))}"`;
+            if (typeof value === "string") {
+                chunks.push(__javy_fetchEncodeUtf8(`${header}\r\n\r\n${normalizeLineEndings(value)}\r\n`));
+            } else {
+                header += `; filename="${escapeMultipartName(value.name)}"\r\n`;
+                header += `Content-Type: ${value.type || "application/octet-stream"}\r\n\r\n`;
+                chunks.push(__javy_fetchEncodeUtf8(header), blobBytes(value), __javy_fetchEncodeUtf8("\r\n"));
+            }
+        }
+        chunks.push(__javy_fetchEncodeUtf8(`--${boundary}--\r\n`));
+        return concatBytes(chunks);
+    }
+
+    function multipartBoundary() {
+        let boundary = "----formdata-javy-";
+        for (let i = 0; i < 24; i++) {
+            boundary += Math.floor(Math.random() * 16).toString(16);
+        }
+        return boundary;
+    }
+
+    // https://fetch.spec.whatwg.org/#concept-bodyinit-extract
+    function extractBody(object) {
+        if (object instanceof Blob) {
+            return { bytes: blobBytes(object), type: object.type === "" ? null : object.type };
+        }
+        if (isBufferSource(object)) {
+            return { bytes: copyBufferSource(object), type: null };
+        }
+        if (object instanceof FormData) {
+            const boundary = multipartBoundary();
+            return {
+                bytes: serializeMultipart(formDataList(object), boundary),
+                type: `multipart/form-data; boundary=${boundary}`,
+            };
+        }
+        if (URLSearchParams !== undefined && object instanceof URLSearchParams) {
+            return {
+                bytes: __javy_fetchEncodeUtf8(object.toString()),
+                type: "application/x-www-form-urlencoded;charset=UTF-8",
+            };
+        }
+        return { bytes: __javy_fetchEncodeUtf8(toUSVString(object)), type: "text/plain;charset=UTF-8" };
+    }
+
+    function parseFormData(bytes, contentType) {
+        const essence = contentType === null ? "" : contentType.split(";")[0].trim().toLowerCase();
+        const formData = new FormData();
+        if (essence === "multipart/form-data") {
+            const boundary = /;\s*boundary=(?:"([^"]*)"|([^;]*))/i.exec(contentType);
+            const entries = boundary === null ? null : __javy_fetchParseMultipart(bytes, (boundary[1] ?? boundary[2]).trim());
+            if (entries === null) {
+                throw new TypeError("Failed to parse body as FormData");
+            }
+            for (const [name, value] of entries) {
+                formDataList(formData).push([
+                    name,
+                    typeof value === "string" ? value : new File([value.bytes], value.filename, { type: value.type }),
+                ]);
+            }
+        } else if (essence === "application/x-www-form-urlencoded") {
+            formDataList(formData).push(...__javy_fetchParseURLEncoded(bytes));
+        } else {
+            throw new TypeError("Failed to parse body as FormData");
+        }
+        return formData;
+    }
+
+    const METHODS = ["DELETE", "GET", "HEAD", "OPTIONS", "POST", "PUT"];
+    const FORBIDDEN_METHODS = ["CONNECT", "TRACE", "TRACK"];
+
+    class Request {
+        #method = "GET";
+        #url;
+        #headers;
+        #body = null;
+        #bodyUsed = false;
+        #mode = "cors";
+        #credentials = "same-origin";
+        #cache = "default";
+        #redirect = "follow";
+        #referrer = "about:client";
+        #referrerPolicy = "";
+        #integrity = "";
+        #keepalive = false;
+        #signal;
+
+        constructor(input, init = undefined) {
+            checkArgs(arguments, 1, "Request");
+            checkDictionary(init, "Request", "RequestInit");
+
+            let inputBody = null;
+            let inputSignal = null;
+            if (input instanceof Request) {
+                this.#method = input.#method;
+                this.#url = input.#url;
+                this.#mode = input.#mode;
+                this.#credentials = input.#credentials;
+                this.#cache = input.#cache;
+                this.#redirect = input.#redirect;
+                this.#referrer = input.#referrer;
+                this.#referrerPolicy = input.#referrerPolicy;
+                this.#integrity = input.#integrity;
+                this.#keepalive = input.#keepalive;
+                inputBody = input.#body;
+                inputSignal = input.#signal ?? null;
+            } else {
+                const url = __javy_fetchParseURL(toUSVString(input));
+                if (url === null) {
+                    throw new TypeError(`Failed to parse URL from ${input}`);
+                }
+                if (url.credentials) {
+                    throw new TypeError(`Request cannot be constructed from a URL that includes credentials: ${input}`);
+                }
+                this.#url = url.href;
+            }
+
+            // Dictionary members are read in lexicographical order.
+            const {
+                body,
+                cache,
+                credentials,
+                duplex,
+                headers,
+                integrity,
+                keepalive,
+                method,
+                mode,
+                redirect,
+                referrer,
+                referrerPolicy,
+                signal,
+                window,
+            } = init ?? {};
+
+            if (AbortSignal !== undefined) {
+                if (signal !== undefined && signal !== null && !(signal instanceof AbortSignal)) {
+                    throw new TypeError("Failed to construct 'Request': member signal is not of type 'AbortSignal'");
+                }
+                // The signal of the request follows the given one.
+                const followed = signal !== undefined ? signal : inputSignal;
+                this.#signal = followed !== null ? AbortSignal.any([followed]) : new AbortController().signal;
+            }
+            if (window !== undefined && window !== null) {
+                throw new TypeError("Failed to construct 'Request': 'window' can only be null");
+            }
+            const requestMode = checkEnum(mode, ["navigate", "same-origin", "no-cors", "cors"], "RequestMode");
+            if (requestMode === "navigate") {
+                throw new TypeError("Failed to construct 'Request': Cannot construct a Request with a RequestInit whose mode member is set as 'navigate'");
+            }
+            this.#mode = requestMode ?? this.#mode;
+            this.#cache =
+                checkEnum(cache, ["default", "no-store", "reload", "no-cache", "force-cache", "only-if-cached"], "RequestCache") ??
+                this.#cache;
+            this.#credentials = checkEnum(credentials, ["omit", "same-origin", "include"], "RequestCredentials") ?? this.#credentials;
+            checkEnum(duplex, ["half"], "RequestDuplex");
+            this.#redirect = checkEnum(redirect, ["follow", "error", "manual"], "RequestRedirect") ?? this.#redirect;
+            this.#referrerPolicy =
+                checkEnum(
+                    referrerPolicy,
+                    [
+                        "",
+                        "no-referrer",
+                        "no-referrer-when-downgrade",
+                        "same-origin",
+                        "origin",
+                        "strict-origin",
+                        "origin-when-cross-origin",
+                        "strict-origin-when-cross-origin",
+                        "unsafe-url",
+                    ],
+                    "ReferrerPolicy",
+                ) ?? this.#referrerPolicy;
+            if (referrer !== undefined) {
+                const value = toUSVString(referrer);
+                if (value === "" || value === "about:client") {
+                    this.#referrer = value;
+                } else {
+                    const url = __javy_fetchParseURL(value);
+                    if (url === null) {
+                        throw new TypeError(`Failed to construct 'Request': Referrer '${value}' is not a valid URL`);
+                    }
+                    this.#referrer = url.href;
+                }
+            }
+            if (integrity !== undefined) {
+                this.#integrity = `${integrity}`;
+            }
+            if (keepalive !== undefined) {
+                this.#keepalive = Boolean(keepalive);
+            }
+            if (method !== undefined) {
+                let value = toByteString(method);
+                if (!TOKEN.test(value)) {
+                    throw new TypeError(`Failed to construct 'Request': '${value}' is not a valid HTTP method`);
+                }
+                const upper = value.toUpperCase();
+                if (FORBIDDEN_METHODS.includes(upper)) {
+                    throw new TypeError(`Failed to construct 'Request': '${value}' HTTP method is unsupported`);
+                }
+                if (METHODS.includes(upper)) {
+                    value = upper;
+                }
+                this.#method = value;
+            }
+
+            if (headers !== undefined) {
+                this.#headers = new Headers(headers);
+            } else if (input instanceof Request) {
+                this.#headers = copyHeaders(input.#headers);
+            } else {
+                this.#headers = new Headers();
+            }
+
+            const hasBody = body !== undefined && body !== null;
+            if ((hasBody || inputBody !== null) && (this.#method === "GET" || this.#method === "HEAD")) {
+                throw new TypeError("Failed to construct 'Request': Request with GET/HEAD method cannot have body");
+            }
+            if (hasBody) {
+                const { bytes, type } = extractBody(body);
+                this.#body = bytes;
+                if (type !== null && !this.#headers.has("content-type")) {
+                    this.#headers.append("content-type", type);
+                }
+            } else if (inputBody !== null) {
+                if (input.#bodyUsed) {
+                    throw new TypeError("Failed to construct 'Request': Cannot construct a Request with a Request object that has already been used");
+                }
+                this.#body = inputBody;
+                input.#bodyUsed = true;
+            }
+        }
+
+        get method() {
+            return this.#method;
+        }
+
+        get url() {
+            return this.#url;
+        }
+
+        get headers() {
+            return this.#headers;
+        }
+
+        get destination() {
+            return "";
+        }
+
+        get referrer() {
+            return this.#referrer;
+        }
+
+        get referrerPolicy() {
+            return this.#referrerPolicy;
+        }
+
+        get mode() {
+            return this.#mode;
+        }
+
+        get credentials() {
+            return this.#credentials;
+        }
+
+        get cache() {
+            return this.#cache;
+        }
+
+        get redirect() {
+            return this.#redirect;
+        }
+
+        get integrity() {
+            return this.#integrity;
+        }
+
+        get keepalive() {
+            return this.#keepalive;
+        }
+
+        get isReloadNavigation() {
+            return false;
+        }
+
+        get isHistoryNavigation() {
+            return false;
+        }
+
+        get duplex() {
+            return "half";
+        }
+
+        get signal() {
+            return this.#signal;
+        }
+
+        get bodyUsed() {
+            return this.#bodyUsed;
+        }
+
+        #consume() {
+            if (this.#bodyUsed) {
+                throw new TypeError("Body is unusable: Body has already been read");
+            }
+            if (this.#body === null) {
+                return new Uint8Array(0);
+            }
+            this.#bodyUsed = true;
+            return this.#body;
+        }
+
+        async arrayBuffer() {
+            return this.#consume().slice().buffer;
+        }
+
+        async blob() {
+            return new Blob([this.#consume()], { type: this.#headers.get("content-type") ?? "" });
+        }
+
+        async bytes() {
+            return this.#consume().slice();
+        }
+
+        async formData() {
+            return parseFormData(this.#consume(), this.#headers.get("content-type"));
+        }
+
+        async json() {
+            return JSON.parse(__javy_fetchDecodeUtf8(this.#consume()));
+        }
+
+        async text() {
+            return __javy_fetchDecodeUtf8(this.#consume());
+        }
+
+        clone() {
+            if (this.#bodyUsed) {
+                throw new TypeError("Failed to execute 'clone' on 'Request': Request body is already used");
+            }
+            const clone = new Request(this.#url);
+            clone.#method = this.#method;
+            clone.#headers = copyHeaders(this.#headers);
+            clone.#body = this.#body;
+            clone.#mode = this.#mode;
+            clone.#credentials = this.#credentials;
+            clone.#cache = this.#cache;
+            clone.#redirect = this.#redirect;
+            clone.#referrer = this.#referrer;
+            clone.#referrerPolicy = this.#referrerPolicy;
+            clone.#integrity = this.#integrity;
+            clone.#keepalive = this.#keepalive;
+            if (this.#signal !== undefined) {
+                clone.#signal = AbortSignal.any([this.#signal]);
+            }
+            return clone;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "Request";
+        }
+    }
+
+    const NULL_BODY_STATUSES = [101, 103, 204, 205, 304];
+    const REDIRECT_STATUSES = [301, 302, 303, 307, 308];
+
+    class Response {
+        #type = "default";
+        #status = 200;
+        #statusText = "";
+        #headers;
+        #body = null;
+        #bodyUsed = false;
+
+        constructor(body = null, init = undefined) {
+            checkDictionary(init, "Response", "ResponseInit");
+            const { headers, status, statusText } = init ?? {};
+
+            this.#status = status === undefined ? 200 : toUnsignedShort(status);
+            if (this.#status < 200 || this.#status > 599) {
+                throw new RangeError(
+                    `Failed to construct 'Response': The status provided (${this.#status}) is outside the range [200, 599]`,
+                );
+            }
+            this.#statusText = statusText === undefined ? "" : toByteString(statusText);
+            if (!/^[\t\x20-\x7E\x80-\xFF]*$/.test(this.#statusText)) {
+                throw new TypeError("Failed to construct 'Response': Invalid statusText");
+            }
+            this.#headers = new Headers(headers);
+
+            if (body !== null) {
+                if (NULL_BODY_STATUSES.includes(this.#status)) {
+                    throw new TypeError("Failed to construct 'Response': Response with null body status cannot have body");
+                }
+                const { bytes, type } = extractBody(body);
+                this.#body = bytes;
+                if (type !== null && !this.#headers.has("content-type")) {
+                    this.#headers.append("content-type", type);
+                }
+            }
+        }
+
+        static error() {
+            const response = new Response();
+            response.#type = "error";
+            response.#status = 0;
+            setHeadersGuard(response.#headers, "immutable");
+            return response;
+        }
+
+        static redirect(url, status = 302) {
+            checkArgs(arguments, 1, "redirect");
+            const parsed = __javy_fetchParseURL(toUSVString(url));
+            if (parsed === null) {
+                throw new TypeError(`Failed to execute 'redirect' on 'Response': Failed to parse URL from ${url}`);
+            }
+            status = toUnsignedShort(status);
+            if (!REDIRECT_STATUSES.includes(status)) {
+                throw new RangeError(`Failed to execute 'redirect' on 'Response': Invalid status code ${status}`);
+            }
+            const response = new Response();
+            response.#status = status;
+            response.#headers.append("location", parsed.href);
+            setHeadersGuard(response.#headers, "immutable");
+            return response;
+        }
+
+        static json(data, init = undefined) {
+            const text = JSON.stringify(data);
+            if (text === undefined) {
+                throw new TypeError("Failed to execute 'json' on 'Response': The data is not JSON serializable");
+            }
+            const response = new Response(null, init);
+            if (NULL_BODY_STATUSES.includes(response.#status)) {
+                throw new TypeError("Failed to execute 'json' on 'Response': Response with null body status cannot have body");
+            }
+            response.#body = __javy_fetchEncodeUtf8(text);
+            if (!response.#headers.has("content-type")) {
+                response.#headers.append("content-type", "application/json");
+            }
+            return response;
+        }
+
+        get type() {
+            return this.#type;
+        }
+
+        get url() {
+            return "";
+        }
+
+        get redirected() {
+            return false;
+        }
+
+        get status() {
+            return this.#status;
+        }
+
+        get ok() {
+            return this.#status >= 200 && this.#status <= 299;
+        }
+
+        get statusText() {
+            return this.#statusText;
+        }
+
+        get headers() {
+            return this.#headers;
+        }
+
+        get bodyUsed() {
+            return this.#bodyUsed;
+        }
+
+        #consume() {
+            if (this.#bodyUsed) {
+                throw new TypeError("Body is unusable: Body has already been read");
+            }
+            if (this.#body === null) {
+                return new Uint8Array(0);
+            }
+            this.#bodyUsed = true;
+            return this.#body;
+        }
+
+        async arrayBuffer() {
+            return this.#consume().slice().buffer;
+        }
+
+        async blob() {
+            return new Blob([this.#consume()], { type: this.#headers.get("content-type") ?? "" });
+        }
+
+        async bytes() {
+            return this.#consume().slice();
+        }
+
+        async formData() {
+            return parseFormData(this.#consume(), this.#headers.get("content-type"));
+        }
+
+        async json() {
+            return JSON.parse(__javy_fetchDecodeUtf8(this.#consume()));
+        }
+
+        async text() {
+            return __javy_fetchDecodeUtf8(this.#consume());
+        }
+
+        clone() {
+            if (this.#bodyUsed) {
+                throw new TypeError("Failed to execute 'clone' on 'Response': Response body is already used");
+            }
+            const clone = new Response();
+            clone.#type = this.#type;
+            clone.#status = this.#status;
+            clone.#statusText = this.#statusText;
+            clone.#headers = copyHeaders(this.#headers);
+            clone.#body = this.#body;
+            return clone;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "Response";
+        }
+    }
+
+    globalThis.Headers = Headers;
+    globalThis.Blob = Blob;
+    globalThis.File = File;
+    globalThis.FormData = FormData;
+    globalThis.Request = Request;
+    globalThis.Response = Response;
+
+    Reflect.deleteProperty(globalThis, "__javy_fetchEncodeUtf8");
+    Reflect.deleteProperty(globalThis, "__javy_fetchDecodeUtf8");
+    Reflect.deleteProperty(globalThis, "__javy_fetchParseURL");
+    Reflect.deleteProperty(globalThis, "__javy_fetchParseURLEncoded");
+    Reflect.deleteProperty(globalThis, "__javy_fetchParseMultipart");
+})();
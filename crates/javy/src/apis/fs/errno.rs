@@ -0,0 +1,96 @@
+//! WASI errno values, surfaced on the errors thrown by `Javy.FS` and
+//! `Javy.IO`.
+use std::io::{self, ErrorKind};
+
+/// A WASI errno value, along with its name and description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Errno {
+    /// The name of the error, e.g. `ENOENT`.
+    pub code: &'static str,
+    /// The value of the error, as defined by WASI preview 1.
+    pub errno: u16,
+    pub description: &'static str,
+}
+
+macro_rules! errnos {
+    ($($name:ident = $errno:literal, $description:literal;)*) => {
+        $(pub(crate) const $name: Errno = Errno {
+            code: stringify!($name),
+            errno: $errno,
+            description: $description,
+        };)*
+
+        const ERRNOS: &[Errno] = &[$($name),*];
+    };
+}
+
+errnos! {
+    EACCES = 2, "permission denied";
+    EAGAIN = 6, "resource temporarily unavailable";
+    EBADF = 8, "bad file descriptor";
+    EBUSY = 10, "resource busy or locked";
+    EEXIST = 20, "file already exists";
+    EFBIG = 22, "file too large";
+    EINTR = 27, "interrupted system call";
+    EINVAL = 28, "invalid argument";
+    EIO = 29, "i/o error";
+    EISDIR = 31, "illegal operation on a directory";
+    ELOOP = 32, "too many symbolic links encountered";
+    EMFILE = 33, "too many open files";
+    ENAMETOOLONG = 37, "name too long";
+    ENOENT = 44, "no such file or directory";
+    ENOMEM = 48, "not enough memory";
+    ENOSPC = 51, "no space left on device";
+    ENOSYS = 52, "function not implemented";
+    ENOTDIR = 54, "not a directory";
+    ENOTEMPTY = 55, "directory not empty";
+    ENOTSUP = 58, "operation not supported";
+    EPERM = 63, "operation not permitted";
+    EPIPE = 64, "broken pipe";
+    EROFS = 69, "read-only file system";
+    ESPIPE = 70, "invalid seek";
+    EXDEV = 75, "cross-device link not permitted";
+    ENOTCAPABLE = 76, "capabilities insufficient";
+}
+
+impl Errno {
+    /// The errno value of an I/O error, if it's known.
+    ///
+    /// On WASI, the raw OS error is the WASI errno value. Elsewhere, which is
+    /// mostly useful for tests, the errno value is derived from the kind of
+    /// the error.
+    pub fn from_io_error(error: &io::Error) -> Option<Self> {
+        if cfg!(target_os = "wasi") {
+            let raw = error.raw_os_error()?;
+            return ERRNOS.iter().find(|e| i32::from(e.errno) == raw).copied();
+        }
+
+        // `EBADF` has no `ErrorKind`, its value is 9 on Linux and macOS.
+        if error.raw_os_error() == Some(9) {
+            return Some(EBADF);
+        }
+        let errno = match error.kind() {
+            ErrorKind::PermissionDenied => EACCES,
+            ErrorKind::WouldBlock => EAGAIN,
+            ErrorKind::ResourceBusy => EBUSY,
+            ErrorKind::AlreadyExists => EEXIST,
+            ErrorKind::FileTooLarge => EFBIG,
+            ErrorKind::Interrupted => EINTR,
+            ErrorKind::InvalidInput => EINVAL,
+            ErrorKind::IsADirectory => EISDIR,
+            ErrorKind::InvalidFilename => ENAMETOOLONG,
+            ErrorKind::NotFound => ENOENT,
+            ErrorKind::OutOfMemory => ENOMEM,
+            ErrorKind::StorageFull => ENOSPC,
+            ErrorKind::NotADirectory => ENOTDIR,
+            ErrorKind::DirectoryNotEmpty => ENOTEMPTY,
+            ErrorKind::Unsupported => ENOTSUP,
+            ErrorKind::BrokenPipe => EPIPE,
+            ErrorKind::ReadOnlyFilesystem => EROFS,
+            ErrorKind::NotSeekable => ESPIPE,
+            ErrorKind::CrossesDevices => EXDEV,
+            _ => return None,
+        };
+        Some(errno)
+    }
+}
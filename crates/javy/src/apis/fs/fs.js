@@ -0,0 +1,159 @@
+(function () {
+    const __javy_fs_open = globalThis.__javy_fs_open;
+    const __javy_fs_close = globalThis.__javy_fs_close;
+    const __javy_fs_readFile = globalThis.__javy_fs_readFile;
+    const __javy_fs_writeFile = globalThis.__javy_fs_writeFile;
+    const __javy_fs_readdir = globalThis.__javy_fs_readdir;
+    const __javy_fs_stat = globalThis.__javy_fs_stat;
+
+    function describe(value) {
+        if (value === null || value === undefined) {
+            return `${value}`;
+        }
+        if (typeof value === "function") {
+            return `function ${value.name}`;
+        }
+        if (typeof value === "object") {
+            return "an instance of " + (value.constructor?.name ?? "Object");
+        }
+        if (typeof value === "string") {
+            return `type string ('${value}')`;
+        }
+        return `type ${typeof value} (${String(value)})`;
+    }
+
+    function checkPath(path) {
+        if (typeof path !== "string") {
+            throw new TypeError(`The "path" argument must be of type string. Received ${describe(path)}`);
+        }
+        if (path.includes("\0")) {
+            throw new TypeError("The argument 'path' must be a string without null bytes");
+        }
+    }
+
+    function checkFd(fd) {
+        if (typeof fd !== "number") {
+            throw new TypeError(`The "fd" argument must be of type number. Received ${describe(fd)}`);
+        }
+        if (!Number.isInteger(fd) || fd < 0 || fd > 2147483647) {
+            throw new RangeError(`The value of "fd" is out of range. It must be an integer >= 0 && <= 2147483647. Received ${fd}`);
+        }
+    }
+
+    function checkPathOrFd(file) {
+        if (typeof file === "number") {
+            checkFd(file);
+        } else {
+            checkPath(file);
+        }
+    }
+
+    function checkFlags(flags) {
+        if (typeof flags !== "string") {
+            throw new TypeError(`The "flags" argument must be of type string. Received ${describe(flags)}`);
+        }
+    }
+
+    // Only UTF-8 is supported, `null` and `undefined` stand for bytes.
+    function checkEncoding(encoding) {
+        if (encoding === undefined || encoding === null) {
+            return false;
+        }
+        if (encoding === "utf8" || encoding === "utf-8") {
+            return true;
+        }
+        throw new TypeError(`The argument 'encoding' is invalid encoding. Received '${encoding}'`);
+    }
+
+    function toOptions(options, defaults) {
+        if (options === undefined || options === null) {
+            return defaults;
+        }
+        if (typeof options === "string") {
+            return { ...defaults, encoding: options };
+        }
+        if (typeof options !== "object") {
+            throw new TypeError(`The "options" argument must be of type object. Received ${describe(options)}`);
+        }
+        return { ...defaults, ...options };
+    }
+
+    class Stats {
+        #type;
+
+        constructor(stats) {
+            this.#type = stats.type;
+            this.size = stats.size;
+            this.atimeMs = stats.atimeMs;
+            this.mtimeMs = stats.mtimeMs;
+            this.birthtimeMs = stats.birthtimeMs;
+            this.atime = new Date(stats.atimeMs);
+            this.mtime = new Date(stats.mtimeMs);
+            this.birthtime = new Date(stats.birthtimeMs);
+        }
+
+        isFile() {
+            return this.#type === "file";
+        }
+
+        isDirectory() {
+            return this.#type === "directory";
+        }
+
+        isSymbolicLink() {
+            return this.#type === "symlink";
+        }
+
+        get [Symbol.toStringTag]() {
+            return "Stats";
+        }
+    }
+
+    globalThis.Javy.FS = {
+        openSync(path, flags = "r", mode = undefined) {
+            checkPath(path);
+            checkFlags(flags);
+            // WASI has no file permissions, `mode` is accepted for
+            // compatibility and ignored.
+            return __javy_fs_open(path, flags);
+        },
+        closeSync(fd) {
+            checkFd(fd);
+            __javy_fs_close(fd);
+        },
+        readFileSync(path, options = undefined) {
+            checkPathOrFd(path);
+            const { encoding } = toOptions(options, { encoding: null });
+            return __javy_fs_readFile(path, checkEncoding(encoding));
+        },
+        writeFileSync(file, data, options = undefined) {
+            checkPathOrFd(file);
+            const { encoding, flag } = toOptions(options, { encoding: "utf8", flag: "w" });
+            checkEncoding(encoding);
+            checkFlags(flag);
+            if (ArrayBuffer.isView(data)) {
+                data = new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+            } else if (typeof data !== "string") {
+                throw new TypeError(
+                    `The "data" argument must be of type string or an instance of TypedArray or DataView. Received ${describe(data)}`,
+                );
+            }
+            __javy_fs_writeFile(file, flag, data);
+        },
+        readdirSync(path) {
+            checkPath(path);
+            return __javy_fs_readdir(path);
+        },
+        statSync(path) {
+            checkPathOrFd(path);
+            return new Stats(__javy_fs_stat(path));
+        },
+    };
+
+    Reflect.deleteProperty(globalThis, "__javy_fs_open");
+    Reflect.deleteProperty(globalThis, "__javy_fs_close");
+    Reflect.deleteProperty(globalThis, "__javy_fs_readFile");
+    Reflect.deleteProperty(globalThis, "__javy_fs_writeFile");
+    Reflect.deleteProperty(globalThis, "__javy_fs_readdir");
+    Reflect.deleteProperty(globalThis, "__javy_fs_stat");
+})();
@@ -0,0 +1,793 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    os::fd::{AsRawFd, RawFd},
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    Args, hold, hold_and_release,
+    quickjs::{
+        Array, Ctx, Exception, Function, Object, String as JSString, TypedArray, Value,
+        context::EvalOptions,
+    },
+    to_js_error,
+};
+use anyhow::{Error, Result, anyhow, bail};
+
+mod errno;
+
+use errno::{EBADF, Errno};
+
+/// The files opened through `Javy.FS.openSync`, by file descriptor.
+///
+/// These are the only file descriptors the runtime closes and `stat`s. Other
+/// file descriptors, like the ones the host passes for side-channel data, are
+/// read and written as [`HostFd`]s, while the standard streams are only read
+/// from or written to.
+#[derive(Default)]
+pub(crate) struct OpenFiles {
+    files: HashMap<RawFd, File>,
+}
+
+impl OpenFiles {
+    fn insert(&mut self, file: File) -> RawFd {
+        let fd = file.as_raw_fd();
+        self.files.insert(fd, file);
+        fd
+    }
+
+    /// An open file, or an `EBADF` error.
+    fn get(&self, cx: &Ctx<'_>, fd: RawFd, syscall: &str) -> Result<&File> {
+        self.files
+            .get(&fd)
+            .ok_or_else(|| errno_error(cx, EBADF, syscall, None))
+    }
+
+    /// Reads from standard input, from an open file, or from a file
+    /// descriptor of the host.
+    pub(crate) fn read<T>(
+        &self,
+        cx: &Ctx<'_>,
+        fd: RawFd,
+        read: impl FnOnce(&mut dyn Read) -> io::Result<T>,
+    ) -> Result<T> {
+        let result = match (fd, self.files.get(&fd)) {
+            (_, Some(mut file)) => read(&mut file),
+            (0, None) => read(&mut io::stdin()),
+            (1 | 2, None) => return Err(errno_error(cx, EBADF, "read", None)),
+            (_, None) => read(&mut HostFd(fd)),
+        };
+        result.map_err(|e| io_error(cx, e, "read", None))
+    }
+
+    /// Writes to standard output or error, which are flushed, to an open
+    /// file, or to a file descriptor of the host.
+    pub(crate) fn write<T>(
+        &self,
+        cx: &Ctx<'_>,
+        fd: RawFd,
+        write: impl FnOnce(&mut dyn Write) -> io::Result<T>,
+    ) -> Result<T> {
+        fn flushed<T>(
+            mut stream: impl Write,
+            write: impl FnOnce(&mut dyn Write) -> io::Result<T>,
+        ) -> io::Result<T> {
+            let result = write(&mut stream)?;
+            stream.flush()?;
+            Ok(result)
+        }
+
+        let result = match (fd, self.files.get(&fd)) {
+            (_, Some(mut file)) => write(&mut file),
+            (1, None) => flushed(io::stdout(), write),
+            (2, None) => flushed(io::stderr(), write),
+            (0, None) => return Err(errno_error(cx, EBADF, "write", None)),
+            (_, None) => write(&mut HostFd(fd)),
+        };
+        result.map_err(|e| io_error(cx, e, "write", None))
+    }
+}
+
+/// A file descriptor the runtime doesn't own, e.g. one the host passes for
+/// side-channel data.
+///
+/// It's read and written with the WASI `fd_read` and `fd_write` imports
+/// rather than through a [`File`], which would close it when dropped.
+struct HostFd(RawFd);
+
+#[cfg(target_os = "wasi")]
+mod host {
+    use std::{io, os::fd::RawFd};
+
+    /// A WASI `ciovec` or `iovec`.
+    #[repr(C)]
+    struct IoVec {
+        buf: *const u8,
+        len: usize,
+    }
+
+    #[link(wasm_import_module = "wasi_snapshot_preview1")]
+    unsafe extern "C" {
+        fn fd_read(fd: RawFd, iovs: *const IoVec, iovs_len: usize, nread: *mut usize) -> i32;
+        fn fd_write(fd: RawFd, iovs: *const IoVec, iovs_len: usize, nwritten: *mut usize) -> i32;
+    }
+
+    fn result(errno: i32, n: usize) -> io::Result<usize> {
+        match errno {
+            0 => Ok(n),
+            errno => Err(io::Error::from_raw_os_error(errno)),
+        }
+    }
+
+    pub(super) fn read(fd: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+        let iov = IoVec {
+            buf: buf.as_mut_ptr(),
+            len: buf.len(),
+        };
+        let mut n = 0;
+        result(unsafe { fd_read(fd, &iov, 1, &mut n) }, n)
+    }
+
+    pub(super) fn write(fd: RawFd, buf: &[u8]) -> io::Result<usize> {
+        let iov = IoVec {
+            buf: buf.as_ptr(),
+            len: buf.len(),
+        };
+        let mut n = 0;
+        result(unsafe { fd_write(fd, &iov, 1, &mut n) }, n)
+    }
+}
+
+/// Outside of WASI, which is mostly useful for tests, the POSIX `read` and
+/// `write` functions are used instead.
+#[cfg(not(target_os = "wasi"))]
+mod host {
+    use std::{ffi::c_void, io, os::fd::RawFd};
+
+    unsafe extern "C" {
+        #[link_name = "read"]
+        fn posix_read(fd: RawFd, buf: *mut c_void, count: usize) -> isize;
+        #[link_name = "write"]
+        fn posix_write(fd: RawFd, buf: *const c_void, count: usize) -> isize;
+    }
+
+    fn result(n: isize) -> io::Result<usize> {
+        usize::try_from(n).map_err(|_| io::Error::last_os_error())
+    }
+
+    pub(super) fn read(fd: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+        result(unsafe { posix_read(fd, buf.as_mut_ptr().cast(), buf.len()) })
+    }
+
+    pub(super) fn write(fd: RawFd, buf: &[u8]) -> io::Result<usize> {
+        result(unsafe { posix_write(fd, buf.as_ptr().cast(), buf.len()) })
+    }
+}
+
+impl Read for HostFd {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        host::read(self.0, buf)
+    }
+}
+
+impl Write for HostFd {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        host::write(self.0, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Register the `Javy.FS` namespace, with `openSync`, `closeSync`,
+/// `readFileSync`, `writeFileSync`, `readdirSync` and `statSync` functions.
+///
+/// Paths are resolved against the WASI preopened directories. Failed
+/// operations throw errors with the WASI `errno` value of the failure, along
+/// with its `code`, e.g. `ENOENT`, the `syscall` and the `path`, like Node's
+/// errors.
+pub(crate) fn register(this: Ctx<'_>, files: Rc<RefCell<OpenFiles>>) -> Result<()> {
+    let globals = this.globals();
+    if globals.get::<_, Object>("Javy").is_err() {
+        globals.set("Javy", Object::new(this.clone())?)?
+    }
+
+    let open_files = files.clone();
+    globals.set(
+        "__javy_fs_open",
+        Function::new(this.clone(), move |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            open(hold!(cx.clone(), args), &open_files).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    let close_files = files.clone();
+    globals.set(
+        "__javy_fs_close",
+        Function::new(this.clone(), move |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            close(hold!(cx.clone(), args), &close_files).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    let read_files = files.clone();
+    globals.set(
+        "__javy_fs_readFile",
+        Function::new(this.clone(), move |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            read_file(hold!(cx.clone(), args), &read_files).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    let write_files = files.clone();
+    globals.set(
+        "__javy_fs_writeFile",
+        Function::new(this.clone(), move |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            write_file(hold!(cx.clone(), args), &write_files).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    globals.set(
+        "__javy_fs_readdir",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            read_dir(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    globals.set(
+        "__javy_fs_stat",
+        Function::new(this.clone(), move |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            stat(hold!(cx.clone(), args), &files).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    let mut opts = EvalOptions::default();
+    opts.strict = false;
+    this.eval_with_options::<(), _>(include_str!("./fs.js"), opts)?;
+
+    Ok::<_, Error>(())
+}
+
+/// An error for a failed file system operation, with the `errno`, `code`,
+/// `syscall` and `path` properties set.
+fn errno_error(cx: &Ctx<'_>, errno: Errno, syscall: &str, path: Option<&str>) -> Error {
+    let message = match path {
+        Some(path) => format!("{}: {}, {syscall} '{path}'", errno.code, errno.description),
+        None => format!("{}: {}, {syscall}", errno.code, errno.description),
+    };
+    let exception = (|| {
+        let exception = Exception::from_message(cx.clone(), &message)?;
+        exception.set("errno", errno.errno)?;
+        exception.set("code", errno.code)?;
+        exception.set("syscall", syscall)?;
+        if let Some(path) = path {
+            exception.set("path", path)?;
+        }
+        Ok::<_, rquickjs::Error>(exception)
+    })();
+    match exception {
+        Ok(exception) => cx.throw(exception.into_value()).into(),
+        Err(e) => e.into(),
+    }
+}
+
+/// Converts an I/O error into an [`errno_error`], if its errno value is
+/// known.
+fn io_error(cx: &Ctx<'_>, error: io::Error, syscall: &str, path: Option<&str>) -> Error {
+    match Errno::from_io_error(&error) {
+        Some(errno) => errno_error(cx, errno, syscall, path),
+        None => error.into(),
+    }
+}
+
+/// A file, referred to by its path or by a file descriptor.
+enum Target {
+    Path(String),
+    Fd(RawFd),
+}
+
+impl Target {
+    fn from_value(value: Option<&Value<'_>>) -> Result<Self> {
+        if let Some(fd) = value.and_then(|value| value.as_int()) {
+            return Ok(Self::Fd(fd));
+        }
+        match value.and_then(|value| value.as_string()) {
+            Some(path) => Ok(Self::Path(path.to_string()?)),
+            None => bail!("The path must be a string or a file descriptor"),
+        }
+    }
+}
+
+fn path_arg(args: &[Value<'_>]) -> Result<String> {
+    args.first()
+        .and_then(|path| path.as_string())
+        .ok_or_else(|| anyhow!("The path must be a string"))?
+        .to_string()
+        .map_err(Into::into)
+}
+
+fn fd_arg(args: &[Value<'_>]) -> Result<RawFd> {
+    args.first()
+        .and_then(|fd| fd.as_int())
+        .ok_or_else(|| anyhow!("The file descriptor must be a number"))
+}
+
+/// The options matching the flags of Node's `fs.open`.
+fn open_options(cx: &Ctx<'_>, flags: &str) -> Result<OpenOptions> {
+    let mut options = OpenOptions::new();
+    match flags {
+        "r" => options.read(true),
+        "r+" => options.read(true).write(true),
+        "w" => options.write(true).create(true).truncate(true),
+        "wx" => options.write(true).create_new(true),
+        "w+" => options.read(true).write(true).create(true).truncate(true),
+        "wx+" => options.read(true).write(true).create_new(true),
+        "a" => options.append(true).create(true),
+        "ax" => options.append(true).create_new(true),
+        "a+" => options.read(true).append(true).create(true),
+        "ax+" => options.read(true).append(true).create_new(true),
+        _ => bail!(Exception::throw_type(
+            cx,
+            &format!("The argument 'flags' is invalid. Received '{flags}'")
+        )),
+    };
+    Ok(options)
+}
+
+/// Opens the file at a path, returning its file descriptor.
+fn open<'js>(args: Args<'js>, files: &RefCell<OpenFiles>) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let path = path_arg(&args)?;
+    let flags = args
+        .get(1)
+        .and_then(|flags| flags.as_string())
+        .ok_or_else(|| anyhow!("The flags must be a string"))?
+        .to_string()?;
+    let file = open_options(&cx, &flags)?
+        .open(&path)
+        .map_err(|e| io_error(&cx, e, "open", Some(&path)))?;
+
+    let fd = files.borrow_mut().insert(file);
+
+    Ok(Value::new_int(cx, fd))
+}
+
+/// Closes a file descriptor returned by [`open`].
+fn close<'js>(args: Args<'js>, files: &RefCell<OpenFiles>) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let fd = fd_arg(&args)?;
+    files
+        .borrow_mut()
+        .files
+        .remove(&fd)
+        .ok_or_else(|| errno_error(&cx, EBADF, "close", None))?;
+
+    Ok(Value::new_undefined(cx))
+}
+
+/// Reads the whole content of a file, from the current position for file
+/// descriptors, as a `Uint8Array`, or as a string when the second argument is
+/// `true`.
+fn read_file<'js>(args: Args<'js>, files: &RefCell<OpenFiles>) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let target = Target::from_value(args.first())?;
+    let as_string = args.get(1).and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let mut bytes = vec![];
+    match target {
+        Target::Path(path) => File::open(&path)
+            .map_err(|e| io_error(&cx, e, "open", Some(&path)))?
+            .read_to_end(&mut bytes)
+            .map_err(|e| io_error(&cx, e, "read", None))?,
+        Target::Fd(fd) => files
+            .borrow()
+            .read(&cx, fd, |reader| reader.read_to_end(&mut bytes))?,
+    };
+
+    if as_string {
+        Ok(JSString::from_str(cx, &String::from_utf8_lossy(&bytes))?.into_value())
+    } else {
+        Ok(TypedArray::<u8>::new(cx, bytes)?.into_value())
+    }
+}
+
+/// Writes a string or the bytes of a `Uint8Array` to a file, opened with the
+/// given flags, or to a file descriptor.
+fn write_file<'js>(args: Args<'js>, files: &RefCell<OpenFiles>) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let [target, flags, data, ..] = args.as_slice() else {
+        bail!("Expected a path or file descriptor, flags and data");
+    };
+    let target = Target::from_value(Some(target))?;
+    let data = match data.as_string() {
+        Some(string) => string.to_string()?.into_bytes(),
+        None => data
+            .as_object()
+            .and_then(|object| object.as_typed_array::<u8>())
+            .ok_or_else(|| anyhow!("The data must be a string or a Uint8Array"))?
+            .as_bytes()
+            .unwrap_or_default()
+            .to_vec(),
+    };
+
+    match target {
+        Target::Path(path) => {
+            let flags = flags
+                .as_string()
+                .ok_or_else(|| anyhow!("The flags must be a string"))?
+                .to_string()?;
+            open_options(&cx, &flags)?
+                .open(&path)
+                .map_err(|e| io_error(&cx, e, "open", Some(&path)))?
+                .write_all(&data)
+                .map_err(|e| io_error(&cx, e, "write", None))?
+        }
+        Target::Fd(fd) => files
+            .borrow()
+            .write(&cx, fd, |writer| writer.write_all(&data))?,
+    };
+
+    Ok(Value::new_undefined(cx))
+}
+
+/// Lists the names of the entries of a directory, sorted.
+fn read_dir(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    let path = path_arg(&args)?;
+    let mut names = fs::read_dir(&path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .map_err(|e| io_error(&cx, e, "scandir", Some(&path)))?;
+    names.sort();
+
+    let array = Array::new(cx.clone())?;
+    for (i, name) in names.into_iter().enumerate() {
+        array.set(i, name)?;
+    }
+    Ok(array.into_value())
+}
+
+/// The type, size and timestamps of a file, as an object from which `Stats`
+/// are built.
+fn stat<'js>(args: Args<'js>, files: &RefCell<OpenFiles>) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let target = Target::from_value(args.first())?;
+    let metadata = match target {
+        Target::Path(path) => {
+            fs::metadata(&path).map_err(|e| io_error(&cx, e, "stat", Some(&path)))?
+        }
+        Target::Fd(fd) => files
+            .borrow()
+            .get(&cx, fd, "fstat")?
+            .metadata()
+            .map_err(|e| io_error(&cx, e, "fstat", None))?,
+    };
+
+    let file_type = metadata.file_type();
+    let kind = if file_type.is_file() {
+        "file"
+    } else if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_symlink() {
+        "symlink"
+    } else {
+        "other"
+    };
+    let millis = |time: io::Result<SystemTime>| {
+        time.ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(f64::NAN, |duration| duration.as_secs_f64() * 1000.0)
+    };
+
+    let stats = Object::new(cx.clone())?;
+    stats.set("type", kind)?;
+    stats.set("size", metadata.len() as f64)?;
+    stats.set("atimeMs", millis(metadata.accessed()))?;
+    stats.set("mtimeMs", millis(metadata.modified()))?;
+    stats.set("birthtimeMs", millis(metadata.created()))?;
+    Ok(stats.into_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::fd::AsRawFd, path::Path};
+
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    /// An empty scratch directory, created in the current directory, which is
+    /// the only directory preopened when running the tests with WASI.
+    fn scratch_dir(name: &str) -> Result<String> {
+        let dir = format!("javy-fs-{name}");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let default = Runtime::default();
+        default.context().with(|this| {
+            let fs: Value<'_> = this.eval("globalThis.Javy?.FS")?;
+            assert!(fs.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let mut config = Config::default();
+        config.javy_fs(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                Object.entries(Javy.FS).map(([name, f]) => `${name}:${typeof f}`).join()
+            "#,
+            )?;
+            assert_eq!(
+                "openSync:function,closeSync:function,readFileSync:function,writeFileSync:function,readdirSync:function,statSync:function",
+                result
+            );
+            let natives: bool =
+                this.eval("Object.keys(globalThis).some((key) => key.startsWith('__javy_fs'))")?;
+            assert!(!natives);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_and_write_files() -> Result<()> {
+        let dir = scratch_dir("files")?;
+        let mut config = Config::default();
+        config.javy_fs(true).javy_stream_io(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.globals().set("dir", dir.as_str())?;
+            let result: String = this.eval(
+                r#"
+                const path = `${dir}/file.txt`;
+                Javy.FS.writeFileSync(path, "héllo");
+                const results = [Javy.FS.readFileSync(path, "utf8")];
+                Javy.FS.writeFileSync(path, new Uint16Array([0x2021]), { flag: "a" });
+                const bytes = Javy.FS.readFileSync(path);
+                results.push(bytes instanceof Uint8Array, bytes.length, bytes[6]);
+                Javy.FS.writeFileSync(path, new Uint8Array([114, 101, 112, 108, 97, 99, 101, 100]));
+                results.push(Javy.FS.readFileSync(path, { encoding: "utf-8" }));
+                results.join()
+            "#,
+            )?;
+            assert_eq!("héllo,true,8,33,replaced", result);
+            Ok::<_, Error>(())
+        })?;
+        assert_eq!(
+            "replaced",
+            fs::read_to_string(Path::new(&dir).join("file.txt"))?
+        );
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_descriptors() -> Result<()> {
+        let dir = scratch_dir("fds")?;
+        let mut config = Config::default();
+        config.javy_fs(true).javy_stream_io(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.globals().set("dir", dir.as_str())?;
+            let result: String = this.eval(
+                r#"
+                const path = `${dir}/fd.txt`;
+                const written = Javy.FS.openSync(path, "w");
+                const n = Javy.IO.writeSync(written, new Uint8Array([104, 105]));
+                Javy.FS.writeFileSync(written, "!");
+                Javy.FS.closeSync(written);
+
+                const read = Javy.FS.openSync(path);
+                const buffer = new Uint8Array(1);
+                Javy.IO.readSync(read, buffer);
+                const rest = Javy.FS.readFileSync(read, "utf8");
+                const stats = Javy.FS.statSync(read);
+                Javy.FS.closeSync(read);
+                [typeof written, n, buffer[0], rest, stats.size].join()
+            "#,
+            )?;
+            assert_eq!("number,2,104,i!,3", result);
+            Ok::<_, Error>(())
+        })?;
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_host_file_descriptors() -> Result<()> {
+        let dir = scratch_dir("host")?;
+        fs::write(format!("{dir}/input.txt"), "from the host")?;
+        let input = fs::File::open(format!("{dir}/input.txt"))?;
+        let output = fs::File::create(format!("{dir}/output.txt"))?;
+
+        let mut config = Config::default();
+        config.javy_fs(true).javy_stream_io(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.globals().set("input", input.as_raw_fd())?;
+            this.globals().set("output", output.as_raw_fd())?;
+            let result: String = this.eval(
+                r#"
+                const buffer = new Uint8Array(4);
+                Javy.IO.readSync(input, buffer);
+                const rest = Javy.FS.readFileSync(input, "utf8");
+                Javy.IO.writeSync(output, new Uint8Array([104, 105]));
+                Javy.FS.writeFileSync(output, "!");
+                [String.fromCharCode(...buffer), rest].join()
+            "#,
+            )?;
+            assert_eq!("from, the host", result);
+            Ok::<_, Error>(())
+        })?;
+        assert_eq!("hi!", fs::read_to_string(format!("{dir}/output.txt"))?);
+
+        // The runtime leaves the files of the host open.
+        drop((input, output));
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_unowned_file_descriptors() -> Result<()> {
+        let dir = scratch_dir("owned")?;
+        let mut config = Config::default();
+        config.javy_fs(true).javy_stream_io(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.globals().set("dir", dir.as_str())?;
+            let result: String = this.eval(
+                r#"
+                const fd = Javy.FS.openSync(`${dir}/file.txt`, "w");
+                // Not open, so reading and writing fail too.
+                const unowned = 1 << 20;
+                const errors = [];
+                for (const f of [() => Javy.FS.closeSync(0),
+                                 () => Javy.FS.closeSync(1),
+                                 () => Javy.FS.closeSync(2),
+                                 () => Javy.FS.closeSync(unowned),
+                                 () => Javy.FS.readFileSync(unowned),
+                                 () => Javy.FS.writeFileSync(unowned, "x"),
+                                 () => Javy.FS.statSync(unowned),
+                                 () => Javy.IO.readSync(1, new Uint8Array(1)),
+                                 () => Javy.IO.writeSync(0, new Uint8Array(1)),
+                                 () => Javy.IO.writeSync(unowned, new Uint8Array(1)),
+                                 () => { Javy.FS.closeSync(fd); Javy.FS.closeSync(fd); }]) {
+                    try {
+                        f();
+                        errors.push("ok");
+                    } catch (e) {
+                        errors.push(`${e.code} ${e.syscall}`);
+                    }
+                }
+                errors.join()
+            "#,
+            )?;
+            assert_eq!(
+                [
+                    "EBADF close",
+                    "EBADF close",
+                    "EBADF close",
+                    "EBADF close",
+                    "EBADF read",
+                    "EBADF write",
+                    "EBADF fstat",
+                    "EBADF read",
+                    "EBADF write",
+                    "EBADF write",
+                    "EBADF close",
+                ]
+                .join(","),
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_readdir_and_stat() -> Result<()> {
+        let dir = scratch_dir("readdir")?;
+        fs::write(Path::new(&dir).join("b.txt"), "bbb")?;
+        fs::write(Path::new(&dir).join("a.txt"), "a")?;
+        fs::create_dir(Path::new(&dir).join("c"))?;
+        let mut config = Config::default();
+        config.javy_fs(true).javy_stream_io(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.globals().set("dir", dir.as_str())?;
+            let result: String = this.eval(
+                r#"
+                const file = Javy.FS.statSync(`${dir}/b.txt`);
+                const directory = Javy.FS.statSync(`${dir}/c`);
+                [Javy.FS.readdirSync(dir).join(" "), Javy.FS.readdirSync(`${dir}/c`).length,
+                 file.isFile(), file.isDirectory(), file.isSymbolicLink(), file.size,
+                 directory.isFile(), directory.isDirectory(), file.mtime instanceof Date,
+                 file.mtimeMs > 0, Object.prototype.toString.call(file)].join()
+            "#,
+            )?;
+            assert_eq!(
+                "a.txt b.txt c,0,true,false,false,3,false,true,true,true,[object Stats]",
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() -> Result<()> {
+        let dir = scratch_dir("errors")?;
+        fs::write(Path::new(&dir).join("file.txt"), "")?;
+        let mut config = Config::default();
+        config.javy_fs(true).javy_stream_io(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.globals().set("dir", dir.as_str())?;
+            let result: String = this.eval(
+                r#"
+                const errors = [];
+                for (const f of [() => Javy.FS.readFileSync(`${dir}/missing.txt`),
+                                 () => Javy.FS.openSync(`${dir}/file.txt`, "wx"),
+                                 () => Javy.FS.readdirSync(`${dir}/file.txt`),
+                                 () => Javy.FS.readFileSync(dir)]) {
+                    try {
+                        f();
+                    } catch (e) {
+                        errors.push([e.code, e.errno, e.syscall, e.path?.slice(dir.length)].join(" "));
+                    }
+                }
+                try {
+                    Javy.FS.statSync(`${dir}/missing.txt`);
+                } catch (e) {
+                    errors.push(e.message.replace(dir, "dir"));
+                }
+                for (const f of [() => Javy.FS.openSync(`${dir}/file.txt`, "q"),
+                                 () => Javy.FS.readFileSync(1.5),
+                                 () => Javy.FS.readFileSync({}),
+                                 () => Javy.FS.readFileSync(`${dir}/file.txt`, "latin1"),
+                                 () => Javy.FS.writeFileSync(`${dir}/file.txt`, 1),
+                                 () => Javy.IO.writeSync(-1, new Uint8Array(1))]) {
+                    try {
+                        f();
+                    } catch (e) {
+                        errors.push(`${e.name}${e.code ? ` ${e.code}` : ""}`);
+                    }
+                }
+                errors.join("\n")
+            "#,
+            )?;
+            assert_eq!(
+                [
+                    "ENOENT 44 open /missing.txt",
+                    "EEXIST 20 open /file.txt",
+                    "ENOTDIR 54 scandir /file.txt",
+                    "EISDIR 31 read ",
+                    "ENOENT: no such file or directory, stat 'dir/missing.txt'",
+                    "TypeError",
+                    "RangeError",
+                    "TypeError",
+                    "TypeError",
+                    "TypeError",
+                    "Error EBADF",
+                ]
+                .join("\n"),
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -9,22 +9,30 @@
 //!
 //! It's also important to note that this implementation optimizes for the hot
 //! path:
-//! - If `JSON.parse` is invoked with the reviver argument, the native QuickJS
-//!   `JSON.parse` is invoked instead.
-//! - If `JSON.stringify` is invoked with the replacer and/or space arguments, the
-//!   native QuickJS `JSON.stringify` is invoked instead.
+//! - If `JSON.parse` is invoked with a reviver, the parsed value is walked
+//!   afterwards to invoke the reviver on each of its properties.
+//! - If `JSON.stringify` is invoked with a replacer and/or a space, the value
+//!   is serialized with Serde JSON by walking it, calling `toJSON` and the
+//!   replacer along the way, instead of being transcoded. The space is handled
+//!   by Serde JSON's pretty formatter.
+//! - If a [`JsonBigIntPolicy`] other than `Number` is configured,
+//!   `JSON.stringify` always walks the value, since `BigInt`s can't be
+//!   transcoded.
 //!
 //! The reason behind this decision is simple: most use-cases will hit the
-//! hotpath and doing any sort of inline processing of the parsed or stringified
-//! values is likely to void any performance benefits.
+//! hotpath, which doesn't need to call back into JavaScript for each
+//! property.
 use crate::{
-    Args, hold, json,
+    Args, JsonBigIntPolicy,
+    apis::serializer::{
+        Classes, Format, Intrinsics, MAX_DEPTH, ValueSerializer, create_data_property, index_key,
+        length_of_array_like, stack_overflow, to_string,
+    },
+    hold, json,
     quickjs::{
-        Ctx, Exception, Function, Object, String as JSString, Value,
-        atom::PredefinedAtom,
-        function::This,
-        prelude::{MutFn, Rest},
-        qjs::JS_GetProperty,
+        Atom, Ctx, Error as JSError, Exception, FromJs, Function, Object, String as JSString,
+        Value, atom::PredefinedAtom, convert::Coerced, function::This, object::Filter,
+        prelude::Rest, qjs,
     },
     to_js_error, val_to_string,
 };
@@ -32,29 +40,18 @@ use crate::{
 use simd_json::Error as SError;
 
 use anyhow::{Result, anyhow, bail};
-use std::{sync::OnceLock, time::SystemTime};
-
-static DEFAULT_PARSE_KEY: OnceLock<String> = OnceLock::new();
 
 /// Use SIMD implementations for `JSON.parse` and `JSON.stringify`.
 pub(crate) fn register<'js>(this: Ctx<'js>) -> Result<()> {
     let global = this.globals();
+    let intrinsics = Intrinsics::new(&this)?;
 
-    let json: Object = global.get("JSON")?;
-    let default_parse: Function = json.get("parse")?;
-    let millis = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)?
-        .subsec_millis();
-    // Make the global key unstable so users can't rely on it being stable.
-    let default_parse_key = DEFAULT_PARSE_KEY.get_or_init(|| format!("__javy_{millis}_json_parse"));
-    global.set(default_parse_key, default_parse)?;
-
-    let parse = Function::new(
-        this.clone(),
-        MutFn::new(move |cx: Ctx<'js>, args: Rest<Value<'js>>| {
-            call_json_parse(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
-        }),
-    )?;
+    // Both functions are plain closures rather than `MutFn`s, since revivers,
+    // replacers and `toJSON` may call back into them.
+    let parse_intrinsics = intrinsics.clone();
+    let parse = Function::new(this.clone(), move |cx: Ctx<'js>, args: Rest<Value<'js>>| {
+        call_json_parse(hold!(cx.clone(), args), &parse_intrinsics).map_err(|e| to_js_error(cx, e))
+    })?;
 
     // Explicitly set the function's name and length properties.
     // In both the parse and the stringify case below, the spec tests
@@ -62,17 +59,13 @@ pub(crate) fn register<'js>(this: Ctx<'js>) -> Result<()> {
     parse.set_length(2)?;
     parse.set_name("parse")?;
 
-    let stringify = Function::new(
-        this.clone(),
-        MutFn::new(|cx: Ctx<'js>, args: Rest<Value<'js>>| {
-            call_json_stringify(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
-        }),
-    )?;
+    let stringify = Function::new(this.clone(), move |cx: Ctx<'js>, args: Rest<Value<'js>>| {
+        call_json_stringify(hold!(cx.clone(), args), &intrinsics).map_err(|e| to_js_error(cx, e))
+    })?;
 
     stringify.set_name("stringify")?;
     stringify.set_length(3)?;
 
-    let global = this.globals();
     let json: Object = global.get("JSON")?;
     json.set("parse", parse)?;
     json.set("stringify", stringify)?;
@@ -80,99 +73,261 @@ pub(crate) fn register<'js>(this: Ctx<'js>) -> Result<()> {
     Ok(())
 }
 
-fn call_json_parse(args: Args<'_>) -> Result<Value<'_>> {
+fn call_json_parse<'js>(args: Args<'js>, intrinsics: &Intrinsics) -> Result<Value<'js>> {
     let (this, args) = args.release();
 
-    match args.len() {
-        0 => bail!(Exception::throw_syntax(
+    if args.is_empty() {
+        bail!(Exception::throw_syntax(
             &this,
             "\"undefined\" is not valid JSON"
-        )),
-        1 => {
-            let val = args[0].clone();
-            // Fast path. Number and null are treated as identity.
-            if val.is_number() || val.is_null() {
-                return Ok(val);
-            }
+        ));
+    }
 
-            if val.is_symbol() {
-                bail!(Exception::throw_type(&this, "Expected string primitive"));
+    let val = args[0].clone();
+    // Fast path. Number and null are treated as identity.
+    let parsed = if val.is_number() || val.is_null() {
+        val
+    } else {
+        if val.is_symbol() {
+            bail!(Exception::throw_type(&this, "Expected string primitive"));
+        }
+
+        let mut string = val_to_string(&this, val)?;
+        let bytes = unsafe { string.as_bytes_mut() };
+        json::parse(this.clone(), bytes).map_err(|original| {
+            if original.downcast_ref::<SError>().is_none() {
+                return original;
             }
 
-            let mut string = val_to_string(&this, args[0].clone())?;
-            let bytes = unsafe { string.as_bytes_mut() };
-            json::parse(this.clone(), bytes).map_err(|original| {
-                if original.downcast_ref::<SError>().is_none() {
-                    return original;
-                }
+            let e = match original.downcast_ref::<SError>() {
+                Some(e) => e.to_string(),
+                None => "JSON parse error".into(),
+            };
+            anyhow!(Exception::throw_syntax(&this, &e))
+        })?
+    };
 
-                let e = match original.downcast_ref::<SError>() {
-                    Some(e) => e.to_string(),
-                    None => "JSON parse error".into(),
-                };
-                anyhow!(Exception::throw_syntax(&this, &e))
-            })
-        }
-        _ => {
-            // If there's more than one argument, defer to the built-in
-            // JSON.parse, which will take care of validating and invoking the
-            // reviver argument.
-            //
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/JSON/parse#reviver.
-            let default: Function = this.globals().get(DEFAULT_PARSE_KEY.get().unwrap())?;
-            default
-                .call((args[0].clone(), args[1].clone()))
-                .map_err(|e| anyhow!(e))
+    // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/JSON/parse#reviver.
+    match args.get(1).and_then(|reviver| reviver.as_function()) {
+        Some(reviver) => {
+            let reviver = Reviver {
+                cx: this.clone(),
+                reviver: reviver.clone(),
+                is_array: intrinsics.is_array.clone().restore(&this)?,
+            };
+            let root = Object::new(this.clone())?;
+            let name = JSString::from_str(this.clone(), "")?.into_value();
+            create_data_property(&root, &name, parsed)?;
+            Ok(reviver.internalize(&root, name, 0)?)
         }
+        None => Ok(parsed),
     }
 }
 
-fn call_json_stringify(args: Args<'_>) -> Result<Value<'_>> {
-    let (this, args) = args.release();
+/// Invokes the reviver of `JSON.parse` on the properties of the parsed value,
+/// from the innermost to the outermost, see
+/// <https://tc39.es/ecma262/#sec-internalizejsonproperty>.
+struct Reviver<'js> {
+    cx: Ctx<'js>,
+    reviver: Function<'js>,
+    is_array: Function<'js>,
+}
 
-    match args.len() {
-        0 => Ok(Value::new_undefined(this.clone())),
-        1 => {
-            let arg = args[0].clone();
-            let val: Value = if arg.is_object() {
-                if let Some(f) = get_to_json(&arg) {
-                    f.call((
-                        This(arg.clone()),
-                        JSString::from_str(arg.ctx().clone(), "")?.into_value(),
-                    ))?
-                } else {
-                    arg.clone()
+impl<'js> Reviver<'js> {
+    /// Internalizes the property `name` of `holder`, `depth` being the number
+    /// of objects it's nested in.
+    fn internalize(
+        &self,
+        holder: &Object<'js>,
+        name: Value<'js>,
+        depth: usize,
+    ) -> Result<Value<'js>, JSError> {
+        let value: Value<'js> = holder.get(name.clone())?;
+        if let Some(object) = value.as_object() {
+            if depth >= MAX_DEPTH {
+                return Err(stack_overflow(&self.cx));
+            }
+            if self.is_array.call((value.clone(),))? {
+                for index in 0..length_of_array_like(object)? {
+                    self.revive_property(object, index_key(&self.cx, index)?, depth + 1)?;
                 }
             } else {
-                arg.clone()
-            };
-            if val.is_function() || val.is_undefined() || val.is_symbol() {
-                return Ok(Value::new_undefined(arg.ctx().clone()));
+                let keys = object
+                    .own_keys::<JSString<'js>>(Filter::new().enum_only().string())
+                    .collect::<Result<Vec<_>, _>>()?;
+                for key in keys {
+                    self.revive_property(object, key.into_value(), depth + 1)?;
+                }
             }
+        }
+        self.reviver.call((This(holder.clone()), name, value))
+    }
+
+    fn revive_property(
+        &self,
+        object: &Object<'js>,
+        key: Value<'js>,
+        depth: usize,
+    ) -> Result<(), JSError> {
+        let element = self.internalize(object, key.clone(), depth)?;
+        if element.is_undefined() {
+            delete_property(object, &key)
+        } else {
+            create_data_property(object, &key, element)
+        }
+    }
+}
+
+fn call_json_stringify<'js>(args: Args<'js>, intrinsics: &Intrinsics) -> Result<Value<'js>> {
+    let (this, args) = args.release();
+
+    let Some(value) = args.first().cloned() else {
+        return Ok(Value::new_undefined(this.clone()));
+    };
+    let replacer = args.get(1).cloned();
+    let space = args.get(2).cloned();
 
-            let bytes = json::stringify(val.clone())?;
-            let str = String::from_utf8(bytes)?;
-            let str = JSString::from_str(this, &str)?;
-            Ok(str.into_value())
+    let big_int_policy = json::big_int_policy(&this);
+    let mut stringifier = ValueSerializer::new(&this, intrinsics, Format::Json(big_int_policy))?;
+    if let Some(replacer) = replacer.as_ref().and_then(|replacer| replacer.as_object()) {
+        if let Some(replacer) = replacer.as_function() {
+            stringifier.replacer = Some(replacer.clone());
+        } else if stringifier.is_array.call((replacer.clone(),))? {
+            stringifier.property_list = Some(property_list(replacer, intrinsics.classes)?);
         }
-        2 => Ok(this
-            .json_stringify_replacer(args[0].clone(), args[1].clone())?
-            .map_or_else(
-                || Value::new_undefined(this.clone()),
-                |str| str.into_value(),
-            )),
-        _ => Ok(this
-            .json_stringify_replacer_space(args[0].clone(), args[1].clone(), args[2].clone())?
-            .map_or_else(
-                || Value::new_undefined(this.clone()),
-                |str| str.into_value(),
-            )),
     }
+    let gap = match space {
+        Some(space) => gap(space, intrinsics.classes)?,
+        None => String::new(),
+    };
+
+    if stringifier.replacer.is_none()
+        && stringifier.property_list.is_none()
+        && gap.is_empty()
+        && big_int_policy == JsonBigIntPolicy::Number
+    {
+        return stringify_value(this, value);
+    }
+
+    match stringifier.serialize_with(value, |value| json::to_vec(value, &gap))? {
+        Some(bytes) => Ok(JSString::from_str(this, &String::from_utf8(bytes)?)?.into_value()),
+        None => Ok(Value::new_undefined(this.clone())),
+    }
+}
+
+/// Stringifies a value without a replacer nor a space, for
+/// [`json::stringify`] with a [`JsonBigIntPolicy`] supporting `BigInt`s,
+/// which can't be transcoded.
+pub(crate) fn stringify(value: Value<'_>, big_int_policy: JsonBigIntPolicy) -> Result<Vec<u8>> {
+    let cx = value.ctx().clone();
+    let stringifier =
+        ValueSerializer::new(&cx, &Intrinsics::new(&cx)?, Format::Json(big_int_policy))?;
+    Ok(stringifier
+        .serialize_with(value, |value| json::to_vec(value, ""))?
+        .unwrap_or_else(|| b"null".to_vec()))
+}
+
+/// Stringifies a value without a replacer nor a space, by transcoding it.
+fn stringify_value<'js>(this: Ctx<'js>, arg: Value<'js>) -> Result<Value<'js>> {
+    let val: Value = if arg.is_object() {
+        if let Some(f) = get_to_json(&arg) {
+            f.call((
+                This(arg.clone()),
+                JSString::from_str(arg.ctx().clone(), "")?.into_value(),
+            ))?
+        } else {
+            arg.clone()
+        }
+    } else {
+        arg.clone()
+    };
+    if val.is_function() || val.is_undefined() || val.is_symbol() {
+        return Ok(Value::new_undefined(arg.ctx().clone()));
+    }
+
+    let bytes = json::stringify(val.clone())?;
+    let str = String::from_utf8(bytes)?;
+    let str = JSString::from_str(this, &str)?;
+    Ok(str.into_value())
+}
+
+/// The property list of a replacer array, made of its strings and numbers,
+/// see step 4 of <https://tc39.es/ecma262/#sec-json.stringify>.
+fn property_list<'js>(replacer: &Object<'js>, classes: Classes) -> Result<Vec<Atom<'js>>> {
+    let cx = replacer.ctx();
+    let mut list: Vec<Atom<'js>> = vec![];
+    for index in 0..length_of_array_like(replacer)? {
+        let value: Value<'js> = replacer.get(index_key(cx, index)?)?;
+        let is_wrapper = value.as_object().is_some_and(|object| {
+            let class = unsafe { qjs::JS_GetClassID(object.as_raw()) };
+            class == classes.string || class == classes.number
+        });
+        if !(value.is_string() || value.is_number() || is_wrapper) {
+            continue;
+        }
+
+        let item = Coerced::<JSString<'js>>::from_js(cx, value)?.0;
+        let item = Atom::from_value(cx.clone(), &item.into_value())?;
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+    Ok(list)
+}
+
+/// The indentation for a space argument, of up to 10 spaces or the first 10
+/// code units of a string.
+fn gap<'js>(space: Value<'js>, classes: Classes) -> Result<String> {
+    let cx = space.ctx().clone();
+    let mut space = space;
+    if let Some(object) = space.as_object() {
+        let class = unsafe { qjs::JS_GetClassID(object.as_raw()) };
+        if class == classes.number {
+            space = Value::new_number(cx.clone(), Coerced::<f64>::from_js(&cx, space)?.0);
+        } else if class == classes.string {
+            space = Coerced::<JSString<'js>>::from_js(&cx, space)?
+                .0
+                .into_value();
+        }
+    }
+
+    if let Some(count) = space.as_number() {
+        let count = if count.is_nan() { 0.0 } else { count.trunc() };
+        Ok(" ".repeat(count.clamp(0.0, 10.0) as usize))
+    } else if let Some(space) = space.as_string() {
+        let mut units = 0;
+        Ok(to_string(&cx, space)
+            .chars()
+            .take_while(|c| {
+                units += c.len_utf16();
+                units <= 10
+            })
+            .collect())
+    } else {
+        Ok(String::new())
+    }
+}
+
+/// Deletes a property, without throwing if it can't be deleted.
+fn delete_property<'js>(object: &Object<'js>, key: &Value<'js>) -> Result<(), JSError> {
+    let cx = object.ctx().as_raw().as_ptr();
+    unsafe {
+        let atom = qjs::JS_ValueToAtom(cx, key.as_raw());
+        if atom == qjs::JS_ATOM_NULL {
+            return Err(JSError::Exception);
+        }
+        let result = qjs::JS_DeleteProperty(cx, object.as_raw(), atom, 0);
+        qjs::JS_FreeAtom(cx, atom);
+        if result < 0 {
+            return Err(JSError::Exception);
+        }
+    }
+    Ok(())
 }
 
 fn get_to_json<'a>(value: &Value<'a>) -> Option<Function<'a>> {
     let f = unsafe {
-        JS_GetProperty(
+        qjs::JS_GetProperty(
             value.ctx().as_raw().as_ptr(),
             value.as_raw(),
             PredefinedAtom::ToJSON as u32,
@@ -187,3 +342,227 @@ fn get_to_json<'a>(value: &Value<'a>) -> Option<Function<'a>> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, JsonBigIntPolicy, Runtime};
+    use anyhow::{Error, Result};
+
+    /// Evaluates the expressions with the native and the SIMD builtins, which
+    /// are expected to produce the same results.
+    fn assert_same_as_native(expressions: &[&str]) -> Result<()> {
+        let native = Runtime::default();
+        let mut config = Config::default();
+        config.simd_json_builtins(true);
+        let simd = Runtime::new(config)?;
+
+        let eval = |runtime: &Runtime, expression: &str| {
+            runtime.context().with(|this| {
+                let source = format!(
+                    r#"(() => {{
+                        const log = [];
+                        try {{
+                            const result = {expression};
+                            return typeof result + ":" + String(result) + ":" + log.join();
+                        }} catch (e) {{
+                            return e.constructor.name + ":" + log.join();
+                        }}
+                    }})()"#
+                );
+                let result: String = this.eval(source)?;
+                Ok::<_, Error>(result)
+            })
+        };
+
+        for expression in expressions {
+            assert_eq!(
+                eval(&native, expression)?,
+                eval(&simd, expression)?,
+                "{expression}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_reviver() -> Result<()> {
+        assert_same_as_native(&[
+            r#"JSON.stringify(JSON.parse('{"a":[1,{"b":2}],"c":"d"}', function (k, v) { log.push(k + "=" + JSON.stringify(v) + ":" + Array.isArray(this)); return v; }))"#,
+            r#"JSON.stringify(JSON.parse('{"a":1,"b":2,"c":[1,2,3]}', (k, v) => typeof v === "number" && v % 2 ? undefined : v))"#,
+            r#"JSON.parse('"2024-01-02T00:00:00.000Z"', (k, v) => new Date(v)).getTime()"#,
+            r#"JSON.parse('[1]', function (k, v) { return k === "" ? Object.getPrototypeOf(this) === Object.prototype && Object.keys(this).join() : v; })"#,
+            r#"JSON.stringify(JSON.parse('{"a":{"x":1},"b":2}', function (k, v) { if (k === "a") this.b = 3; return v; }))"#,
+            r#"JSON.stringify(JSON.parse('[1,2]', function (k, v) { if (k === "0") Object.freeze(this); return k === "1" ? undefined : v + 1; }))"#,
+            r#"JSON.parse('1', "not a function")"#,
+            r#"JSON.parse('{"a":1}', () => { throw new RangeError(); })"#,
+            r#"JSON.parse('[1]', (k, v) => v, "extra")[0]"#,
+            r#"JSON.parse('{"a":[1]}', function (k, v) { if (k === "a") return new Proxy(v, { ownKeys() { throw new URIError(); } }); return v; }).a[0]"#,
+            r#"JSON.parse('{', (k, v) => v)"#,
+        ])
+    }
+
+    #[test]
+    fn test_stringify_replacer_function() -> Result<()> {
+        assert_same_as_native(&[
+            r#"JSON.stringify({ a: 1, b: [2, { c: 3 }] }, function (k, v) { log.push(JSON.stringify(k) + ":" + Array.isArray(this)); return v; })"#,
+            r#"JSON.stringify({ a: 1, b: "x", c: [1, "y"] }, (k, v) => typeof v === "number" ? v * 2 : v)"#,
+            r#"JSON.stringify({ a: 1, b: 2 }, (k, v) => k === "a" ? undefined : v)"#,
+            r#"JSON.stringify([1, 2], (k, v) => k === "0" ? () => {} : v)"#,
+            r#"JSON.stringify({ d: new Date(0) }, (k, v) => { log.push(typeof v); return v; })"#,
+            r#"JSON.stringify({ n: 1 }, (k, v) => k === "n" ? new Number(5) : v)"#,
+            r#"JSON.stringify({ a: 1 }, (k, v) => k === "" ? [v, new String("s"), new Boolean(false), Object(Symbol())] : v)"#,
+            r#"JSON.stringify(1, () => undefined)"#,
+            r#"JSON.stringify({ a: 1 }, () => { throw new EvalError(); })"#,
+            r#"JSON.stringify({ a: 1 }, (k, v) => k === "a" ? 1n : v)"#,
+            r#"JSON.stringify({ toJSON(k) { log.push("toJSON:" + k); return { b: 1 }; } }, (k, v) => v)"#,
+            r#"(() => { const o = {}; return JSON.stringify({ a: 1 }, (k, v) => k === "a" ? o : (o.self = o, v)); })()"#,
+            r#"JSON.stringify({ a: [1, 1e21, 0.1, -0, NaN, Infinity, 1.5e-7] }, (k, v) => v)"#,
+        ])
+    }
+
+    #[test]
+    fn test_stringify_property_list() -> Result<()> {
+        assert_same_as_native(&[
+            r#"JSON.stringify({ a: 1, b: 2, c: { a: 3, d: 4 } }, ["c", "a"])"#,
+            r#"JSON.stringify({ 1: "one", 2: "two", b: 3 }, [1, new String("b"), new Number(2), {}, null, "b"])"#,
+            r#"JSON.stringify([{ a: 1, b: 2 }], ["b"])"#,
+            r#"JSON.stringify({ a: 1 }, new Proxy(["a"], {}))"#,
+            r#"JSON.stringify({ a: 1 }, [])"#,
+            r#"JSON.stringify({ a: 1 }, { 0: "a", length: 1 })"#,
+            r#"JSON.stringify({ a: undefined, b: 1 }, ["a", "b"])"#,
+        ])
+    }
+
+    #[test]
+    fn test_stringify_numbers() -> Result<()> {
+        assert_same_as_native(&[
+            "JSON.stringify([1e21, -1.5e-7, 0.1, 2 ** 53 + 2, -0, NaN, -Infinity])",
+            "JSON.stringify({ a: 1e100 }, null, 1)",
+        ])
+    }
+
+    #[test]
+    fn test_stringify_space() -> Result<()> {
+        assert_same_as_native(&[
+            r#"JSON.stringify({ a: [1, { b: 2 }, []], c: {} }, null, 2)"#,
+            r#"JSON.stringify([1, [2, [3]]], null, "\t")"#,
+            r#"JSON.stringify({ a: 1, b: [2] }, null, "abcdefghijklmnop")"#,
+            r#"JSON.stringify({ a: 1 }, null, 20)"#,
+            r#"JSON.stringify({ a: 1 }, null, -1)"#,
+            r#"JSON.stringify({ a: 1 }, null, 3.7)"#,
+            r#"JSON.stringify({ a: 1 }, null, new Number(3))"#,
+            r#"JSON.stringify({ a: 1 }, null, new String("--"))"#,
+            r#"JSON.stringify({ a: 1 }, null, "")"#,
+            r#"JSON.stringify({ a: 1 }, null, true)"#,
+            r#"JSON.stringify([], null, 2)"#,
+            r#"JSON.stringify("s", null, 2)"#,
+            r#"JSON.stringify({ a: 1, b: 2 }, ["b"], 1)"#,
+            r#"JSON.stringify({ a: "x" }, (k, v) => v, 4)"#,
+            r#"JSON.stringify(undefined, null, 2)"#,
+            r#"(() => { const a = [1]; a.push(a); return JSON.stringify(a, null, 2); })()"#,
+            r#"JSON.stringify({ a: 1n }, null, 2)"#,
+        ])
+    }
+
+    #[test]
+    fn test_nesting_depth() -> Result<()> {
+        let mut config = Config::default();
+        config
+            .simd_json_builtins(true)
+            .json_big_int_policy(JsonBigIntPolicy::BigInt);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            this.eval::<(), _>(
+                r#"
+                var error = (f) => { try { f(); return "ok"; } catch (e) { return e.constructor.name; } };
+                var nested = (depth) => "[".repeat(depth) + "]".repeat(depth);
+                var deep = [];
+                for (let i = 0; i < 512; i++) deep = [deep];
+            "#,
+            )?;
+            for (expression, expected) in [
+                ("JSON.parse(nested(512))", "ok"),
+                ("JSON.parse(nested(513))", "RangeError"),
+                (
+                    r#"JSON.parse('{"a":0,"b":0}', function (k, v) { if (k === "a") this.b = deep; return v; })"#,
+                    "RangeError",
+                ),
+                ("JSON.stringify(deep)", "RangeError"),
+                ("JSON.stringify(deep, (k, v) => v)", "RangeError"),
+            ] {
+                let result: String = this.eval(format!("error(() => {expression})"))?;
+                assert_eq!(expected, result, "{expression}");
+            }
+            Ok::<_, Error>(())
+        })
+    }
+
+    #[test]
+    fn test_big_int_policy() -> Result<()> {
+        let eval = |policy: JsonBigIntPolicy, expression: &str| {
+            let mut config = Config::default();
+            config.simd_json_builtins(true).json_big_int_policy(policy);
+            let runtime = Runtime::new(config)?;
+            runtime.context().with(|this| {
+                let result: String = this.eval(format!(
+                    "(() => {{ try {{ return String({expression}); }} catch (e) {{ return e.constructor.name; }} }})()"
+                ))?;
+                Ok::<_, Error>(result)
+            })
+        };
+
+        let payload = r#"'{"id":9007199254740993,"ids":[-9223372036854775808,18446744073709551615],"n":9007199254740991}'"#;
+        for (policy, expression, expected) in [
+            (
+                JsonBigIntPolicy::Number,
+                format!("JSON.stringify(JSON.parse({payload}))"),
+                r#"{"id":9007199254740992,"ids":[-9223372036854776000,18446744073709552000],"n":9007199254740991}"#,
+            ),
+            (
+                JsonBigIntPolicy::Number,
+                "JSON.stringify({ a: 1n })".into(),
+                "TypeError",
+            ),
+            (
+                JsonBigIntPolicy::BigInt,
+                format!("JSON.stringify(JSON.parse({payload}))"),
+                r#"{"id":9007199254740993,"ids":[-9223372036854775808,18446744073709551615],"n":9007199254740991}"#,
+            ),
+            (
+                JsonBigIntPolicy::BigInt,
+                format!("Object.values(JSON.parse({payload})).flat().map(v => typeof v)"),
+                "bigint,bigint,bigint,number",
+            ),
+            (
+                JsonBigIntPolicy::BigInt,
+                "JSON.stringify({ a: 10n ** 30n, b: [-1n, Object(2n)] }, null, 1)".into(),
+                "{\n \"a\": 1000000000000000000000000000000,\n \"b\": [\n  -1,\n  2\n ]\n}",
+            ),
+            (
+                JsonBigIntPolicy::String,
+                format!("Object.values(JSON.parse({payload})).flat().map(v => typeof v + '=' + v)"),
+                "string=9007199254740993,string=-9223372036854775808,string=18446744073709551615,number=9007199254740991",
+            ),
+            // simd-json's `big-int-as-float` feature parses integers which
+            // don't fit in 64 bits as floats, whatever the policy.
+            (
+                JsonBigIntPolicy::BigInt,
+                "JSON.parse('[123456789012345678901234567890]').map(v => typeof v + '=' + v)"
+                    .into(),
+                "number=1.2345678901234568e+29",
+            ),
+            (
+                JsonBigIntPolicy::String,
+                "JSON.stringify([1n, { a: 2n }])".into(),
+                r#"["1",{"a":"2"}]"#,
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                eval(policy, &expression)?,
+                "{policy:?}: {expression}"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use std::mem::MaybeUninit;
+
+use crate::{
+    Args, MemoryUsage, hold, hold_and_release,
+    quickjs::{Ctx, Function, Object, Value, qjs},
+    to_js_error,
+};
+use anyhow::{Error, Result};
+
+/// Register `Javy.memoryUsage` and `Javy.gc`.
+pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+    let globals = this.globals();
+    if globals.get::<_, Object>("Javy").is_err() {
+        globals.set("Javy", Object::new(this.clone())?)?
+    }
+    let javy: Object<'_> = globals.get("Javy")?;
+
+    javy.set(
+        "memoryUsage",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            memory_usage(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    javy.set(
+        "gc",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            gc(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+
+    Ok::<_, Error>(())
+}
+
+/// Returns the [`MemoryUsage`] of the runtime as an object, with the names of
+/// the fields in camel case.
+fn memory_usage(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, _) = args.release();
+    // The runtime is already borrowed while JavaScript runs, which rules out
+    // `rquickjs::Runtime::memory_usage`.
+    let usage = unsafe {
+        let mut usage = MaybeUninit::uninit();
+        qjs::JS_ComputeMemoryUsage(qjs::JS_GetRuntime(cx.as_raw().as_ptr()), usage.as_mut_ptr());
+        MemoryUsage::from(usage.assume_init())
+    };
+
+    let object = Object::new(cx)?;
+    // Numbers are exact up to 2^53, which is far beyond the memory available
+    // to a 32-bit WebAssembly module.
+    for (key, value) in [
+        ("allocatedBytes", usage.allocated_bytes),
+        ("allocationCount", usage.allocation_count),
+        ("usedBytes", usage.used_bytes),
+        ("usedCount", usage.used_count),
+        ("atomCount", usage.atom_count),
+        ("atomBytes", usage.atom_bytes),
+        ("stringCount", usage.string_count),
+        ("stringBytes", usage.string_bytes),
+        ("objectCount", usage.object_count),
+        ("objectBytes", usage.object_bytes),
+        ("propertyCount", usage.property_count),
+        ("propertyBytes", usage.property_bytes),
+        ("shapeCount", usage.shape_count),
+        ("shapeBytes", usage.shape_bytes),
+        ("functionCount", usage.function_count),
+        ("functionBytes", usage.function_bytes),
+        ("functionCodeBytes", usage.function_code_bytes),
+        ("nativeFunctionCount", usage.native_function_count),
+        ("arrayCount", usage.array_count),
+        ("fastArrayCount", usage.fast_array_count),
+        ("fastArrayElements", usage.fast_array_elements),
+        ("binaryObjectCount", usage.binary_object_count),
+        ("binaryObjectBytes", usage.binary_object_bytes),
+    ] {
+        object.set(key, value as f64)?;
+    }
+    Ok(object.into_value())
+}
+
+/// Runs the garbage collector.
+fn gc(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, _) = args.release();
+    unsafe { qjs::JS_RunGC(qjs::JS_GetRuntime(cx.as_raw().as_ptr())) };
+    Ok(Value::new_undefined(cx))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|this| {
+            let memory_usage: Value<'_> = this.eval("globalThis.Javy?.memoryUsage")?;
+            assert!(memory_usage.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let mut config = Config::default();
+        config.javy_memory(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval("`${typeof Javy.memoryUsage} ${typeof Javy.gc}`")?;
+            assert_eq!("function function", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_usage() -> Result<()> {
+        let runtime = Runtime::default();
+        let before = runtime.memory_usage();
+        assert!(before.allocated_bytes > 0);
+        assert!(before.used_bytes > 0);
+        assert!(before.object_count > 0);
+        assert!(before.native_function_count > 0);
+
+        runtime.context().with(|this| {
+            this.eval::<(), _>(
+                r#"
+                globalThis.retained = Array.from({ length: 1000 }, (_, i) => ({ i, s: `string ${i}` }));
+            "#,
+            )?;
+            Ok::<_, Error>(())
+        })?;
+        let after = runtime.memory_usage();
+        assert!(after.object_count >= before.object_count + 1000);
+        assert!(after.string_count >= before.string_count + 1000);
+        assert!(after.allocated_bytes > before.allocated_bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_js_memory_usage_and_gc() -> Result<()> {
+        let mut config = Config::default();
+        config.javy_memory(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const keys = Object.keys(Javy.memoryUsage());
+                const allNumbers = Object.values(Javy.memoryUsage())
+                    .every((value) => Number.isInteger(value) && value >= 0);
+
+                function cycles() {
+                    for (let i = 0; i < 1000; i++) {
+                        const a = {};
+                        const b = { a };
+                        a.b = b;
+                    }
+                }
+                cycles();
+                const before = Javy.memoryUsage().objectCount;
+                Javy.gc();
+                const after = Javy.memoryUsage().objectCount;
+                [keys.length, keys[0], allNumbers, after + 2000 <= before].join()
+            "#,
+            )?;
+            assert_eq!("23,allocatedBytes,true,true", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
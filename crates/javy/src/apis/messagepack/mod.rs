@@ -0,0 +1,143 @@
+use crate::{
+    Args, hold, hold_and_release, messagepack,
+    quickjs::{Ctx, Exception, Function, Object, TypedArray, Value},
+    to_js_error,
+};
+use anyhow::{Error, Result, anyhow};
+
+/// Register the `Javy.MessagePack` namespace, with `encode` and `decode`
+/// functions.
+pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+    let globals = this.globals();
+    if globals.get::<_, Object>("Javy").is_err() {
+        globals.set("Javy", Object::new(this.clone())?)?
+    }
+
+    let messagepack = Object::new(this.clone())?;
+    messagepack.set(
+        "encode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            encode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+    messagepack.set(
+        "decode",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            decode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+
+    globals
+        .get::<_, Object>("Javy")?
+        .set("MessagePack", messagepack)?;
+
+    Ok::<_, Error>(())
+}
+
+/// Encode a value as MessagePack into a `Uint8Array`.
+fn encode(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    let value = args
+        .first()
+        .cloned()
+        .unwrap_or_else(|| Value::new_undefined(cx.clone()));
+    let encoded = messagepack::transcode_output(value)?;
+    Ok(TypedArray::new(cx, encoded)?.into_value())
+}
+
+/// Decode the MessagePack bytes of a `Uint8Array` into a value.
+fn decode(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    let array = args
+        .first()
+        .and_then(|value| value.as_object())
+        .and_then(|object| object.as_typed_array::<u8>())
+        .ok_or_else(|| anyhow!(Exception::throw_type(&cx, "Data needs to be an Uint8Array")))?;
+    let bytes = array.as_bytes().unwrap_or_default();
+
+    messagepack::transcode_input(cx.clone(), bytes).map_err(|e| {
+        anyhow!(Exception::throw_syntax(
+            &cx,
+            &format!("Invalid MessagePack data: {e}")
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|this| {
+            let messagepack: Value<'_> = this.eval("globalThis.Javy?.MessagePack")?;
+            assert!(messagepack.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let mut config = Config::default();
+        config.javy_messagepack(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String =
+                this.eval("`${typeof Javy.MessagePack.encode} ${typeof Javy.MessagePack.decode}`")?;
+            assert_eq!("function function", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_and_decode() -> Result<()> {
+        let mut config = Config::default();
+        config.javy_messagepack(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const value = { a: 1, b: [true, null, "s", 1.5], c: { d: -2 } };
+                const encoded = Javy.MessagePack.encode(value);
+                [
+                    encoded instanceof Uint8Array,
+                    Array.from(Javy.MessagePack.encode([1, "a"])).join(" "),
+                    JSON.stringify(Javy.MessagePack.decode(encoded)),
+                    Javy.MessagePack.decode(new Uint8Array([0xc3])),
+                ].join()
+            "#,
+            )?;
+            assert_eq!(
+                r#"true,146 1 161 97,{"a":1,"b":[true,null,"s",1.5],"c":{"d":-2}},true"#,
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() -> Result<()> {
+        let mut config = Config::default();
+        config.javy_messagepack(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const error = (f) => { try { f(); } catch (e) { return e.constructor.name; } };
+                [
+                    error(() => Javy.MessagePack.encode(1n)),
+                    error(() => Javy.MessagePack.decode("not bytes")),
+                    error(() => Javy.MessagePack.decode(new Uint8Array([0xc1]))),
+                    error(() => Javy.MessagePack.decode(new Uint8Array([0x92, 0x01]))),
+                ].join()
+            "#,
+            )?;
+            assert_eq!("TypeError,TypeError,SyntaxError,SyntaxError", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
@@ -31,11 +31,97 @@
 //!
 //! ### `console`
 //!
-//! Adds an implementation of the `console.log` and `console.error`.
+//! Adds an implementation of `console`, including `log`, `error`, `warn`,
+//! `info`, `debug`, `trace`, `assert`, `count`, `time`, `group`, `table` and
+//! `dir`. Values are formatted in the same way as Node's `util.format`.
 //!
 //! ### `TextEncoding`
 //!
-//! Provides partial implementations of `TextEncoder` and `TextDecoder`.
+//! Provides implementations of `TextEncoder` and `TextDecoder`, including
+//! streaming decoding and `encodeInto`. `TextDecoder` supports the UTF-8,
+//! UTF-16LE, UTF-16BE and windows-1252 encodings.
+//! Disabled by default.
+//!
+//! ### `Base64`
+//!
+//! Provides implementations of `atob` and `btoa`. Requires the `base64` crate
+//! feature.
+//!
+//! Disabled by default.
+//!
+//! ### `StructuredClone`
+//!
+//! Provides an implementation of `structuredClone`, following the [structured
+//! clone
+//! algorithm](https://html.spec.whatwg.org/multipage/structured-data.html#safe-passing-of-structured-data)
+//! for the types supported by QuickJS, including `Map`, `Set`, `Date`,
+//! `RegExp`, errors, `ArrayBuffer`s and their views, and `ArrayBuffer`s
+//! passed in the `transfer` option. Values which can't be cloned, like
+//! functions, symbols and promises, throw a `DataCloneError`.
+//!
+//! Disabled by default.
+//!
+//! ### `Crypto`
+//!
+//! Provides a `crypto` global with `crypto.getRandomValues`,
+//! `crypto.randomUUID` and a subset of `crypto.subtle`: `digest` with SHA-1,
+//! SHA-256, SHA-384 and SHA-512, and HMAC with `importKey`, `generateKey`,
+//! `exportKey`, `sign` and `verify`. Random values are read from the host
+//! when they're requested, never from a snapshot. Requires the `crypto` crate
+//! feature.
+//!
+//! Disabled by default.
+//!
+//! ### `URL`
+//!
+//! Provides implementations of `URL`, including `URL.canParse` and
+//! `URL.parse`, and `URLSearchParams`, following the [URL
+//! Standard](https://url.spec.whatwg.org/). Requires the `url` crate feature.
+//!
+//! Disabled by default.
+//!
+//! ### `FetchTypes`
+//!
+//! Provides implementations of `Headers`, `Request`, `Response`, `Blob`,
+//! `File` and `FormData`, following the [Fetch
+//! Standard](https://fetch.spec.whatwg.org/) and the [File
+//! API](https://w3c.github.io/FileAPI/). Bodies are backed by byte buffers,
+//! and can be read with `text()`, `json()`, `arrayBuffer()`, `bytes()`,
+//! `blob()` and `formData()`. There is no `fetch` function since Javy doesn't
+//! provide networking. Requires the `fetch` crate feature.
+//!
+//! Disabled by default.
+//!
+//! ### `EventTarget`
+//!
+//! Provides implementations of `EventTarget`, `Event` and `CustomEvent`,
+//! following the [DOM Standard](https://dom.spec.whatwg.org/), along with
+//! `AbortController` and `AbortSignal`, including `AbortSignal.abort` and
+//! `AbortSignal.any`. Since there is no tree of targets, events are only
+//! dispatched to the listeners of their target. Exceptions thrown by
+//! listeners are reported through `console.error`. `AbortSignal.timeout` is
+//! only available when `Timers` are enabled, its timer doesn't keep the event
+//! loop running. When `FetchTypes` are also enabled, `Request` exposes a
+//! `signal`.
+//!
+//! Disabled by default.
+//!
+//! ### `Streams`
+//!
+//! Provides implementations of `ReadableStream`, `WritableStream` and
+//! `TransformStream`, along with their readers, writers and controllers,
+//! `ByteLengthQueuingStrategy` and `CountQueuingStrategy`, following the
+//! [Streams Standard](https://streams.spec.whatwg.org/). Byte streams, BYOB
+//! readers, `pipeTo`, `pipeThrough`, `tee`, async iteration and
+//! `ReadableStream.from` are supported. When `TextEncoding` is enabled,
+//! `TextEncoderStream` and `TextDecoderStream` are provided as well, and when
+//! `EventTarget` is enabled, pipes can be aborted with a `signal`. When
+//! `StreamIO` is enabled, `Javy.IO.stdin()` returns a byte `ReadableStream` of
+//! standard input and `Javy.IO.stdout()` and `Javy.IO.stderr()` return
+//! `WritableStream`s of `Uint8Array`s. Streams settle promises, so scripts
+//! using them need the event loop to be driven. Requires the `streams` crate
+//! feature.
+//!
 //! Disabled by default.
 //!
 //! ### `Random`
@@ -56,14 +142,119 @@
 //! Javy CLI. See [the extending Javy docs](/docs/docs-using-extending.md) for
 //! more details on using a WASI preview 1 plugin.
 //!
+//! Besides standard input, output and error, the files opened with
+//! `Javy.FS.openSync` and any other file descriptor passed by the host can be
+//! read from and written to. Failures throw errors with the WASI `errno` value
+//! of the failure, along with its `code`.
+//!
+//! ### `FS`
+//!
+//! Provides `Javy.FS.openSync`, `Javy.FS.closeSync`, `Javy.FS.readFileSync`,
+//! `Javy.FS.writeFileSync`, `Javy.FS.readdirSync` and `Javy.FS.statSync`,
+//! modeled after their counterparts in [Node's `fs`
+//! API](https://nodejs.org/api/fs.html). Paths are resolved against the WASI
+//! preopened directories. Besides the standard streams, only the file
+//! descriptors returned by `Javy.FS.openSync` can be used, and closed. Failed
+//! operations throw `Error`s with the WASI `errno` value of the failure, its
+//! `code`, e.g. `ENOENT`, the `syscall` and the `path` involved. Like
+//! `StreamIO`, it is strongly recommended to target WASI preview 1 when
+//! enabling this configuration.
+//!
+//! Disabled by default.
+//!
+//! ### `Process`
+//!
+//! Provides `Javy.args`, the arguments of the WASI context, including the
+//! program name, `Javy.env`, an object of its environment variables, and
+//! `Javy.exit`, which flushes the standard output and error and exits with
+//! the given exit code, `0` by default. `Javy.args` and `Javy.env` are read
+//! every time they are accessed, so their values are the ones of the WASI
+//! context the module runs in, and not of the one used when snapshotting.
+//!
+//! Disabled by default.
+//!
+//! ### `Memory`
+//!
+//! Provides `Javy.memoryUsage`, which returns the statistics of
+//! [`Runtime::memory_usage`](crate::Runtime::memory_usage) as an object with
+//! camel cased keys, e.g. `allocatedBytes` or `objectCount`, and `Javy.gc`,
+//! which runs the garbage collector.
+//!
+//! Disabled by default.
+//!
+//! ### `Encoding`
+//!
+//! Provides `Javy.Encoding.base64`, `Javy.Encoding.base64url` and
+//! `Javy.Encoding.hex`, each with an `encode` function, which encodes the
+//! bytes of a `Uint8Array` into a string, and a `decode` function, which
+//! decodes a string into a `Uint8Array`. Encoding and decoding is done in
+//! Rust, which is considerably faster than an implementation in JavaScript
+//! for large inputs. Requires the `base64` crate feature.
+//!
+//! Disabled by default.
+//!
+//! ### `MessagePack`
+//!
+//! Provides `Javy.MessagePack.encode`, which encodes a value as MessagePack
+//! into a `Uint8Array`, and `Javy.MessagePack.decode`, which decodes the
+//! MessagePack bytes of a `Uint8Array` into a value, with
+//! [`messagepack`](crate::messagepack). Requires the `messagepack` crate
+//! feature.
+//!
+//! Disabled by default.
+//!
+//! ### `CBOR`
+//!
+//! Provides `Javy.CBOR.encode`, which encodes a value as CBOR into a
+//! `Uint8Array`, and `Javy.CBOR.decode`, which decodes the CBOR bytes of a
+//! `Uint8Array` into a value, with [`cbor`](crate::cbor). `Date`s and byte
+//! strings map to date/time tags and `Uint8Array`s. Requires the `cbor` crate
+//! feature.
+//!
+//! Disabled by default.
+//!
+//! ### `Timers`
+//!
+//! Provides an implementation of `setTimeout`, `setInterval`, `clearTimeout`
+//! and `clearInterval`. Timers are tracked with the monotonic clock and only
+//! fire when the event loop is driven through
+//! [`Runtime::run_event_loop`](crate::Runtime::run_event_loop) or
+//! [`Runtime::run_next_timer`](crate::Runtime::run_next_timer).
+//! `queueMicrotask` is always available as part of the base intrinsics.
+//!
+//! Disabled by default.
+//!
 //! ###  `JSON`
 //! Provides an efficient implementation of JSON functions based on [`simd-json`](https://crates.io/crates/simd-json/0.13.10)
 //! and [`serde_json`](https://crates.io/crates/serde_json)
 //!
 //! Disabled by default.
+#[cfg(feature = "cbor")]
+pub(crate) mod cbor;
 pub(crate) mod console;
+#[cfg(feature = "crypto")]
+pub(crate) mod crypto;
+#[cfg(feature = "base64")]
+pub(crate) mod encoding;
+pub(crate) mod event_target;
+#[cfg(feature = "fetch")]
+pub(crate) mod fetch;
+pub(crate) mod fs;
 #[cfg(feature = "json")]
 pub(crate) mod json;
+pub(crate) mod memory;
+#[cfg(feature = "messagepack")]
+pub(crate) mod messagepack;
+pub(crate) mod process;
 pub(crate) mod random;
+pub(crate) mod serializer;
 pub(crate) mod stream_io;
+#[cfg(feature = "streams")]
+pub(crate) mod streams;
+pub(crate) mod structured_clone;
+#[cfg(all(test, any(feature = "crypto", feature = "fetch", feature = "streams")))]
+pub(crate) mod test_utils;
 pub(crate) mod text_encoding;
+pub(crate) mod timers;
+#[cfg(feature = "url")]
+pub(crate) mod url;
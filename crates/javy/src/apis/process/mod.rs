@@ -0,0 +1,216 @@
+use std::io::Write;
+
+use crate::{
+    Args, hold, hold_and_release,
+    quickjs::{Array, Ctx, Exception, Function, Object, Value, object::Accessor},
+    to_js_error,
+};
+use anyhow::{Error, Result, anyhow};
+
+/// Register `Javy.args`, `Javy.env` and `Javy.exit`.
+///
+/// `Javy.args` and `Javy.env` are getters, which read the arguments and the
+/// environment variables of the WASI context every time they're accessed.
+/// Their values are therefore the ones of the WASI context the module runs
+/// in, rather than the ones of the WASI context used when snapshotting the
+/// plugin.
+pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+    let globals = this.globals();
+    if globals.get::<_, Object>("Javy").is_err() {
+        globals.set("Javy", Object::new(this.clone())?)?
+    }
+    let javy: Object<'_> = globals.get("Javy")?;
+
+    javy.prop("args", Accessor::new_get(args).enumerable())?;
+    javy.prop("env", Accessor::new_get(env).enumerable())?;
+    javy.set(
+        "exit",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            exit(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+
+    Ok::<_, Error>(())
+}
+
+/// The arguments of the WASI context, including the program name.
+fn args(cx: Ctx<'_>) -> crate::quickjs::Result<Array<'_>> {
+    let array = Array::new(cx)?;
+    for (i, arg) in std::env::args_os().enumerate() {
+        array.set(i, arg.to_string_lossy().as_ref())?;
+    }
+    Ok(array)
+}
+
+/// The environment variables of the WASI context.
+fn env(cx: Ctx<'_>) -> crate::quickjs::Result<Object<'_>> {
+    let object = Object::new(cx)?;
+    for (key, value) in vars() {
+        object.set(key, value)?;
+    }
+    Ok(object)
+}
+
+/// Reads the environment variables with `environ_get`.
+///
+/// `std::env::vars` can't be used on WASI, since wasi-libc fills `environ`
+/// the first time it's used and then keeps it (see `__wasilibc_environ` in
+/// its `libc-bottom-half/sources/environ.c`), which would persist the
+/// environment used when snapshotting the plugin. Both the `wasip1` and the
+/// `wasip2` targets link wasi-libc, and `wasip2` components still import
+/// `environ_get` through the preview 1 adapter.
+#[cfg(target_os = "wasi")]
+fn vars() -> Vec<(String, String)> {
+    #[link(wasm_import_module = "wasi_snapshot_preview1")]
+    unsafe extern "C" {
+        fn environ_sizes_get(count: *mut usize, size: *mut usize) -> i32;
+        fn environ_get(environ: *mut *mut u8, buf: *mut u8) -> i32;
+    }
+
+    let (mut count, mut size) = (0, 0);
+    if unsafe { environ_sizes_get(&mut count, &mut size) } != 0 {
+        return vec![];
+    }
+    let mut pointers = vec![std::ptr::null_mut(); count];
+    let mut buf = vec![0; size];
+    if unsafe { environ_get(pointers.as_mut_ptr(), buf.as_mut_ptr()) } != 0 {
+        return vec![];
+    }
+
+    // The variables are laid out in `buf` as consecutive, NUL terminated,
+    // `KEY=value` strings.
+    buf.split(|b| *b == 0)
+        .take(count)
+        .map(|var| {
+            let var = String::from_utf8_lossy(var);
+            match var.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (var.into_owned(), String::new()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "wasi"))]
+fn vars() -> Vec<(String, String)> {
+    std::env::vars_os()
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .collect()
+}
+
+/// Flushes the standard output and error, and exits with the given exit
+/// code, `0` if none is given, through WASI's `proc_exit`.
+fn exit(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    let code = match args.first() {
+        None => 0,
+        Some(code) if code.is_undefined() => 0,
+        Some(code) => code
+            .as_int()
+            .or_else(|| {
+                code.as_float()
+                    .filter(|code| code.fract() == 0.0 && *code >= i32::MIN as f64)
+                    .filter(|code| *code <= i32::MAX as f64)
+                    .map(|code| code as i32)
+            })
+            .ok_or_else(|| {
+                anyhow!(Exception::throw_type(
+                    &cx,
+                    "The \"code\" argument must be an integer"
+                ))
+            })?,
+    };
+
+    // `std::process::exit` doesn't run destructors, flush what was written
+    // through `console` or `Javy.IO` beforehand.
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+    std::process::exit(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|this| {
+            let exit: Value<'_> = this.eval("globalThis.Javy?.exit")?;
+            assert!(exit.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let mut config = Config::default();
+        config.javy_process(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                Object.keys(Javy).join() + " " + typeof Javy.exit
+            "#,
+            )?;
+            assert_eq!("args,env,exit function", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_and_env() -> Result<()> {
+        let mut config = Config::default();
+        config.javy_process(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let args: Vec<String> = this.eval("Javy.args")?;
+            assert_eq!(std::env::args().collect::<Vec<_>>(), args);
+
+            let path: Option<String> = this.eval("Javy.env.PATH")?;
+            assert_eq!(std::env::var("PATH").ok(), path);
+
+            // The values are read on every access.
+            let fresh: bool = this.eval(
+                r#"
+                Javy.args.push("pushed");
+                Javy.env.JAVY_TEST = "set";
+                !Javy.args.includes("pushed") && Javy.env.JAVY_TEST === undefined
+            "#,
+            )?;
+            assert!(fresh);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_exit_validates_code() -> Result<()> {
+        let mut config = Config::default();
+        config.javy_process(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const errors = [];
+                for (const code of ["1", 1.5, NaN, 2 ** 32, null]) {
+                    try {
+                        Javy.exit(code);
+                    } catch (e) {
+                        errors.push(e.name);
+                    }
+                }
+                errors.join()
+            "#,
+            )?;
+            assert_eq!("TypeError,TypeError,TypeError,TypeError,TypeError", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//! Shared by the APIs which walk JavaScript values recursively.
+
+use crate::quickjs::{Ctx, Error as JSError, Exception};
+
+#[cfg(any(feature = "json", feature = "cbor"))]
+mod walker;
+
+#[cfg(any(feature = "json", feature = "cbor"))]
+pub(crate) use walker::*;
+
+/// The maximum nesting depth of the values walked when serializing, parsing
+/// and cloning, past which a `RangeError` is thrown, like QuickJS does once
+/// its stack overflows, rather than overflowing the Rust stack.
+pub(crate) const MAX_DEPTH: usize = 512;
+
+/// The `RangeError` thrown once a value is nested deeper than [`MAX_DEPTH`].
+pub(crate) fn stack_overflow(cx: &Ctx<'_>) -> JSError {
+    Exception::throw_range(cx, "Maximum call stack size exceeded")
+}
@@ -0,0 +1,482 @@
+//! Walks JavaScript values to serialize them with Serde, shared by
+//! `JSON.stringify` and `Javy.CBOR.encode`.
+//!
+//! Values are resolved like `JSON.stringify` does, see
+//! <https://tc39.es/ecma262/#sec-serializejsonproperty>: `toJSON` and the
+//! replacer function are called on the properties as they're serialized,
+//! primitive wrappers are unwrapped, `undefined`, functions and symbols are
+//! skipped in objects and replaced by `null` in arrays, and cycles throw a
+//! `TypeError`.
+use std::cell::RefCell;
+
+#[cfg(feature = "json")]
+use crate::JsonBigIntPolicy;
+use crate::{
+    quickjs::{
+        Atom, BigInt, Ctx, Error as JSError, Exception, FromJs, Function, Object, Persistent,
+        String as JSString, Value, atom::PredefinedAtom, convert::Coerced, function::This,
+        object::Filter, qjs,
+    },
+    to_string_lossy,
+};
+
+use super::{MAX_DEPTH, stack_overflow};
+use anyhow::Result;
+#[cfg(feature = "cbor")]
+use ciborium::{tag::Required, value::Integer};
+use serde::{
+    Serialize, Serializer,
+    ser::{SerializeMap, SerializeSeq},
+};
+#[cfg(feature = "json")]
+use serde_json::value::RawValue;
+
+/// The tag of standard date/time strings, see
+/// <https://www.rfc-editor.org/rfc/rfc8949#section-3.4.1>.
+#[cfg(feature = "cbor")]
+pub(crate) const DATE_TIME_TAG: u64 = 0;
+
+/// The class IDs of the primitive wrapper objects, which are unwrapped when
+/// serialized.
+#[derive(Clone, Copy)]
+pub(crate) struct Classes {
+    pub(crate) boolean: qjs::JSClassID,
+    pub(crate) number: qjs::JSClassID,
+    pub(crate) string: qjs::JSClassID,
+    pub(crate) big_int: qjs::JSClassID,
+}
+
+/// The intrinsics used to walk values, saved when registering the functions
+/// using them so they're not affected by changes to the globals.
+#[derive(Clone)]
+pub(crate) struct Intrinsics {
+    pub(crate) is_array: Persistent<Function<'static>>,
+    boolean_value_of: Persistent<Function<'static>>,
+    big_int_value_of: Persistent<Function<'static>>,
+    pub(crate) classes: Classes,
+}
+
+impl Intrinsics {
+    pub(crate) fn new<'js>(cx: &Ctx<'js>) -> Result<Self> {
+        let global = cx.globals();
+        let value_of = |constructor: &str| -> Result<Function<'js>> {
+            let constructor: Object = global.get(constructor)?;
+            let prototype: Object = constructor.get(PredefinedAtom::Prototype)?;
+            Ok(prototype.get(PredefinedAtom::ValueOf)?)
+        };
+
+        let object: Function = global.get("Object")?;
+        let class_of = |primitive: Value<'js>| -> Result<qjs::JSClassID> {
+            let wrapper: Value<'js> = object.call((primitive,))?;
+            Ok(unsafe { qjs::JS_GetClassID(wrapper.as_raw()) })
+        };
+        let classes = Classes {
+            boolean: class_of(Value::new_bool(cx.clone(), false))?,
+            number: class_of(Value::new_int(cx.clone(), 0))?,
+            string: class_of(JSString::from_str(cx.clone(), "")?.into_value())?,
+            big_int: class_of(BigInt::from_i64(cx.clone(), 0)?.into_value())?,
+        };
+
+        let array: Object = global.get("Array")?;
+        Ok(Self {
+            is_array: Persistent::save(cx, array.get::<_, Function>("isArray")?),
+            boolean_value_of: Persistent::save(cx, value_of("Boolean")?),
+            big_int_value_of: Persistent::save(cx, value_of("BigInt")?),
+            classes,
+        })
+    }
+}
+
+/// The format values are serialized to, for the representations which
+/// can't be expressed through the Serde data model alone.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Format {
+    /// JSON, with `BigInt`s serialized according to the policy.
+    #[cfg(feature = "json")]
+    Json(JsonBigIntPolicy),
+    /// CBOR, with `Date`s serialized as date/time strings, `Uint8Array`s as
+    /// byte strings and `BigInt`s as integers.
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+/// Serializes values, calling `toJSON` and the replacer function on the
+/// properties as they're serialized.
+pub(crate) struct ValueSerializer<'js> {
+    cx: Ctx<'js>,
+    pub(crate) replacer: Option<Function<'js>>,
+    pub(crate) property_list: Option<Vec<Atom<'js>>>,
+    pub(crate) is_array: Function<'js>,
+    boolean_value_of: Function<'js>,
+    big_int_value_of: Function<'js>,
+    classes: Classes,
+    format: Format,
+    /// The objects being serialized, to detect cycles.
+    stack: RefCell<Vec<Value<'js>>>,
+    /// The first JavaScript error raised during serialization, which Serde
+    /// can't carry.
+    error: RefCell<Option<JSError>>,
+}
+
+impl<'js> ValueSerializer<'js> {
+    pub(crate) fn new(cx: &Ctx<'js>, intrinsics: &Intrinsics, format: Format) -> Result<Self> {
+        Ok(Self {
+            cx: cx.clone(),
+            replacer: None,
+            property_list: None,
+            is_array: intrinsics.is_array.clone().restore(cx)?,
+            boolean_value_of: intrinsics.boolean_value_of.clone().restore(cx)?,
+            big_int_value_of: intrinsics.big_int_value_of.clone().restore(cx)?,
+            classes: intrinsics.classes,
+            format,
+            stack: RefCell::new(vec![]),
+            error: RefCell::new(None),
+        })
+    }
+
+    /// Serializes a value with `serialize`, or returns `None` if it doesn't
+    /// have a representation.
+    pub(crate) fn serialize_with<T, E: Into<anyhow::Error>>(
+        &self,
+        value: Value<'js>,
+        serialize: impl FnOnce(&SerializableValue<'_, 'js>) -> Result<T, E>,
+    ) -> Result<Option<T>> {
+        let wrapper = Object::new(self.cx.clone())?;
+        let key = JSString::from_str(self.cx.clone(), "")?.into_value();
+        create_data_property(&wrapper, &key, value.clone())?;
+        let value = self.resolve(&wrapper, key, value)?;
+        if !is_serializable(&value) {
+            return Ok(None);
+        }
+
+        let result = serialize(&SerializableValue {
+            serializer: self,
+            value,
+        });
+        match (result, self.error.take()) {
+            (Ok(result), _) => Ok(Some(result)),
+            (Err(_), Some(e)) => Err(e.into()),
+            (Err(e), None) => Err(e.into()),
+        }
+    }
+
+    /// Applies `toJSON`, the replacer function and unwraps primitive wrappers.
+    fn resolve(
+        &self,
+        holder: &Object<'js>,
+        key: Value<'js>,
+        mut value: Value<'js>,
+    ) -> Result<Value<'js>, JSError> {
+        if self.converts_with_to_json(&value) {
+            let to_json = get_property(&value, PredefinedAtom::ToJSON)?;
+            if let Some(to_json) = to_json.as_function() {
+                value = to_json.call((This(value.clone()), key.clone()))?;
+            }
+        }
+
+        if let Some(replacer) = &self.replacer {
+            value = replacer.call((This(holder.clone()), key, value))?;
+        }
+
+        if let Some(object) = value.as_object() {
+            let class = unsafe { qjs::JS_GetClassID(object.as_raw()) };
+            if class == self.classes.number {
+                let number = Coerced::<f64>::from_js(&self.cx, value)?.0;
+                value = Value::new_number(self.cx.clone(), number);
+            } else if class == self.classes.string {
+                value = Coerced::<JSString<'js>>::from_js(&self.cx, value)?
+                    .0
+                    .into_value();
+            } else if class == self.classes.boolean {
+                value = self.boolean_value_of.call((This(value),))?;
+            } else if class == self.classes.big_int {
+                value = self.big_int_value_of.call((This(value),))?;
+            }
+        }
+        Ok(value)
+    }
+
+    /// Whether `toJSON` is looked up on a value, which isn't the case for
+    /// `Date`s in CBOR, which have their own representation.
+    fn converts_with_to_json(&self, value: &Value<'js>) -> bool {
+        if !(value.is_object() || value.is_big_int()) {
+            return false;
+        }
+        match self.format {
+            #[cfg(feature = "json")]
+            Format::Json(_) => true,
+            #[cfg(feature = "cbor")]
+            Format::Cbor => !unsafe { qjs::JS_IsDate(value.as_raw()) },
+        }
+    }
+
+    /// Records a JavaScript error to return it once serialization fails.
+    fn fail<E: serde::ser::Error>(&self, error: JSError) -> E {
+        let message = error.to_string();
+        self.error.borrow_mut().get_or_insert(error);
+        E::custom(message)
+    }
+
+    fn serialize<S: Serializer>(
+        &self,
+        value: &Value<'js>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if let Some(b) = value.as_bool() {
+            serializer.serialize_bool(b)
+        } else if let Some(i) = value.as_int() {
+            serializer.serialize_i32(i)
+        } else if let Some(f) = value.as_float() {
+            serializer.serialize_f64(f)
+        } else if let Some(s) = value.as_string() {
+            serializer.serialize_str(&to_string(&self.cx, s))
+        } else if value.is_big_int() {
+            self.serialize_big_int(value, serializer)
+        } else if let Some(object) = value.as_object() {
+            #[cfg(feature = "cbor")]
+            if self.format == Format::Cbor {
+                if let Some(array) = object.as_typed_array::<u8>() {
+                    return serializer.serialize_bytes(array.as_bytes().unwrap_or_default());
+                }
+                if unsafe { qjs::JS_IsDate(value.as_raw()) } {
+                    let to_iso_string: Function =
+                        object.get("toISOString").map_err(|e| self.fail(e))?;
+                    let date: String = to_iso_string
+                        .call((This(value.clone()),))
+                        .map_err(|e| self.fail(e))?;
+                    return Required::<_, DATE_TIME_TAG>(date).serialize(serializer);
+                }
+            }
+
+            if self.stack.borrow().contains(value) {
+                return Err(self.fail(Exception::throw_type(&self.cx, "circular reference")));
+            }
+            if self.stack.borrow().len() >= MAX_DEPTH {
+                return Err(self.fail(stack_overflow(&self.cx)));
+            }
+            let is_array: bool = self
+                .is_array
+                .call((value.clone(),))
+                .map_err(|e| self.fail(e))?;
+
+            self.stack.borrow_mut().push(value.clone());
+            let result = if is_array {
+                self.serialize_array(object, serializer)
+            } else {
+                self.serialize_object(object, serializer)
+            };
+            self.stack.borrow_mut().pop();
+            result
+        } else {
+            serializer.serialize_unit()
+        }
+    }
+
+    fn serialize_big_int<S: Serializer>(
+        &self,
+        value: &Value<'js>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let digits =
+            Coerced::<JSString<'js>>::from_js(&self.cx, value.clone()).map_err(|e| self.fail(e))?;
+        let digits = to_string(&self.cx, &digits.0);
+        match self.format {
+            #[cfg(feature = "json")]
+            Format::Json(JsonBigIntPolicy::Number) => Err(self.fail(Exception::throw_type(
+                &self.cx,
+                "BigInt value can't be serialized in JSON",
+            ))),
+            #[cfg(feature = "json")]
+            Format::Json(JsonBigIntPolicy::String) => serializer.serialize_str(&digits),
+            #[cfg(feature = "json")]
+            Format::Json(JsonBigIntPolicy::BigInt) => RawValue::from_string(digits)
+                .map_err(serde::ser::Error::custom)?
+                .serialize(serializer),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => match digits
+                .parse::<i128>()
+                .ok()
+                .filter(|integer| Integer::try_from(*integer).is_ok())
+            {
+                Some(integer) => serializer.serialize_i128(integer),
+                None => Err(self.fail(Exception::throw_range(
+                    &self.cx,
+                    "BigInt value is out of the range of CBOR integers",
+                ))),
+            },
+        }
+    }
+
+    fn serialize_array<S: Serializer>(
+        &self,
+        array: &Object<'js>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let length = length_of_array_like(array).map_err(|e| self.fail(e))?;
+        let mut seq = serializer.serialize_seq(Some(length as usize))?;
+        for index in 0..length {
+            let value = index_key(&self.cx, index)
+                .and_then(|key| {
+                    let element = array.get(key.clone())?;
+                    self.resolve(array, key, element)
+                })
+                .map_err(|e| self.fail(e))?;
+            if is_serializable(&value) {
+                seq.serialize_element(&SerializableValue {
+                    serializer: self,
+                    value,
+                })?;
+            } else {
+                seq.serialize_element(&())?;
+            }
+        }
+        seq.end()
+    }
+
+    fn serialize_object<S: Serializer>(
+        &self,
+        object: &Object<'js>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let keys = match &self.property_list {
+            Some(list) => list.clone(),
+            None => object
+                .own_keys::<Atom<'js>>(Filter::new().enum_only().string())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| self.fail(e))?,
+        };
+
+        let entry = |key: Atom<'js>| {
+            key.to_js_string().and_then(|key| {
+                let value = object.get(key.clone())?;
+                let value = self.resolve(object, key.clone().into_value(), value)?;
+                Ok((key, value))
+            })
+        };
+
+        // CBOR maps are prefixed with their length, so their entries are
+        // resolved before being serialized.
+        #[cfg(feature = "cbor")]
+        if self.format == Format::Cbor {
+            let mut entries = vec![];
+            for key in keys {
+                let (key, value) = entry(key).map_err(|e| self.fail(e))?;
+                if is_serializable(&value) {
+                    entries.push((to_string(&self.cx, &key), value));
+                }
+            }
+            let mut map = serializer.serialize_map(Some(entries.len()))?;
+            for (key, value) in entries {
+                map.serialize_entry(
+                    &key,
+                    &SerializableValue {
+                        serializer: self,
+                        value,
+                    },
+                )?;
+            }
+            return map.end();
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        for key in keys {
+            let (key, value) = entry(key).map_err(|e| self.fail(e))?;
+            if is_serializable(&value) {
+                map.serialize_entry(
+                    &to_string(&self.cx, &key),
+                    &SerializableValue {
+                        serializer: self,
+                        value,
+                    },
+                )?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// A value resolved by a [`ValueSerializer`], to serialize with Serde.
+pub(crate) struct SerializableValue<'a, 'js> {
+    serializer: &'a ValueSerializer<'js>,
+    value: Value<'js>,
+}
+
+impl Serialize for SerializableValue<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serializer.serialize(&self.value, serializer)
+    }
+}
+
+/// Whether a value has a representation, other values are skipped in objects
+/// and replaced by `null` in arrays.
+fn is_serializable(value: &Value<'_>) -> bool {
+    !(value.is_undefined() || value.is_symbol() || value.is_function())
+}
+
+pub(crate) fn to_string<'js>(cx: &Ctx<'js>, string: &JSString<'js>) -> String {
+    string
+        .to_string()
+        .unwrap_or_else(|e| to_string_lossy(cx, string, e))
+}
+
+/// The key of an array index.
+pub(crate) fn index_key<'js>(cx: &Ctx<'js>, index: u64) -> Result<Value<'js>, JSError> {
+    Ok(JSString::from_str(cx.clone(), &index.to_string())?.into_value())
+}
+
+/// The `length` of an array-like object, converted with `ToLength`.
+pub(crate) fn length_of_array_like(object: &Object<'_>) -> Result<u64, JSError> {
+    let length = object.get::<_, Coerced<f64>>(PredefinedAtom::Length)?.0;
+    if length.is_nan() || length <= 0.0 {
+        Ok(0)
+    } else {
+        Ok(length.min(9007199254740991.0) as u64)
+    }
+}
+
+/// Gets a property of a value, which may be a primitive.
+fn get_property<'js>(value: &Value<'js>, atom: PredefinedAtom) -> Result<Value<'js>, JSError> {
+    let property = unsafe {
+        Value::from_raw(
+            value.ctx().clone(),
+            qjs::JS_GetProperty(
+                value.ctx().as_raw().as_ptr(),
+                value.as_raw(),
+                atom as qjs::JSAtom,
+            ),
+        )
+    };
+    if property.is_exception() {
+        Err(JSError::Exception)
+    } else {
+        Ok(property)
+    }
+}
+
+/// Defines an enumerable, writable and configurable property, like the
+/// `CreateDataProperty` operation, without throwing if it can't be defined.
+pub(crate) fn create_data_property<'js>(
+    object: &Object<'js>,
+    key: &Value<'js>,
+    value: Value<'js>,
+) -> Result<(), JSError> {
+    let cx = object.ctx().as_raw().as_ptr();
+    unsafe {
+        let atom = qjs::JS_ValueToAtom(cx, key.as_raw());
+        if atom == qjs::JS_ATOM_NULL {
+            return Err(JSError::Exception);
+        }
+        let result = qjs::JS_DefinePropertyValue(
+            cx,
+            object.as_raw(),
+            atom,
+            qjs::JS_DupValue(cx, value.as_raw()),
+            qjs::JS_PROP_C_W_E as _,
+        );
+        qjs::JS_FreeAtom(cx, atom);
+        if result < 0 {
+            return Err(JSError::Exception);
+        }
+    }
+    Ok(())
+}
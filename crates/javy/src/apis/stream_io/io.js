@@ -1,6 +1,9 @@
 (function () {
   const __javy_io_readSync = globalThis.__javy_io_readSync;
   const __javy_io_writeSync = globalThis.__javy_io_writeSync;
+  // Only available when streams are enabled.
+  const ReadableStream = globalThis.ReadableStream;
+  const WritableStream = globalThis.WritableStream;
   globalThis.Javy.IO = {
     readSync(fd, data) {
       if (!(data instanceof Uint8Array)) {
@@ -26,6 +29,59 @@
     },
   };
 
+  if (ReadableStream !== undefined) {
+    // A byte stream reading directly into the buffers of BYOB readers, or
+    // into chunks of `chunkSize` bytes for default readers.
+    const chunkSize = 16 * 1024;
+    globalThis.Javy.IO.stdin = function stdin() {
+      return new ReadableStream({
+        type: "bytes",
+        autoAllocateChunkSize: chunkSize,
+        pull(controller) {
+          const view = controller.byobRequest.view;
+          const n = __javy_io_readSync(
+            0,
+            view.buffer,
+            view.byteOffset,
+            view.byteLength
+          );
+          if (n === 0) {
+            controller.close();
+            controller.byobRequest.respond(0);
+          } else {
+            controller.byobRequest.respond(n);
+          }
+        },
+      });
+    };
+  }
+
+  if (WritableStream !== undefined) {
+    const writableStream = (fd) =>
+      new WritableStream({
+        write(chunk) {
+          if (!(chunk instanceof Uint8Array)) {
+            throw TypeError("Chunks need to be Uint8Arrays");
+          }
+          let offset = 0;
+          while (offset < chunk.byteLength) {
+            offset += __javy_io_writeSync(
+              fd,
+              chunk.buffer,
+              chunk.byteOffset + offset,
+              chunk.byteLength - offset
+            );
+          }
+        },
+      });
+    globalThis.Javy.IO.stdout = function stdout() {
+      return writableStream(1);
+    };
+    globalThis.Javy.IO.stderr = function stderr() {
+      return writableStream(2);
+    };
+  }
+
   Reflect.deleteProperty(globalThis, "__javy_io_readSync");
   Reflect.deleteProperty(globalThis, "__javy_io_writeSync");
 })();
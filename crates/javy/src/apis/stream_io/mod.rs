@@ -1,5 +1,5 @@
 use anyhow::{Error, Result, anyhow, bail};
-use std::io::{Read, Stdin, Write};
+use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     Args, hold, hold_and_release,
@@ -7,27 +7,39 @@ use crate::{
     to_js_error,
 };
 
+use super::fs::OpenFiles;
+
 /// Register `Javy.IO.readSync` and `Javy.IO.writeSync` functions on the
 /// global object.
-pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+///
+/// Standard input can be read from, standard output and error written to, and
+/// the files opened through `Javy.FS.openSync` and the other file descriptors
+/// of the host both. Failed reads and writes throw errors with the WASI
+/// `errno` value of the failure, like `Javy.FS`.
+///
+/// When streams are registered, `Javy.IO.stdin`, `Javy.IO.stdout` and
+/// `Javy.IO.stderr` are registered as well, returning streams backed by the
+/// same functions.
+pub(crate) fn register(this: Ctx<'_>, files: Rc<RefCell<OpenFiles>>) -> Result<()> {
     let globals = this.globals();
     if globals.get::<_, Object>("Javy").is_err() {
         globals.set("Javy", Object::new(this.clone())?)?
     }
 
+    let write_files = files.clone();
     globals.set(
         "__javy_io_writeSync",
-        Function::new(this.clone(), |cx, args| {
+        Function::new(this.clone(), move |cx, args| {
             let (cx, args) = hold_and_release!(cx, args);
-            write(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+            write(hold!(cx.clone(), args), &write_files).map_err(|e| to_js_error(cx, e))
         }),
     )?;
 
     globals.set(
         "__javy_io_readSync",
-        Function::new(this.clone(), |cx, args| {
+        Function::new(this.clone(), move |cx, args| {
             let (cx, args) = hold_and_release!(cx, args);
-            read(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+            read(hold!(cx.clone(), args), &files).map_err(|e| to_js_error(cx, e))
         }),
     )?;
 
@@ -61,24 +73,12 @@ fn extract_args<'a, 'js: 'a>(
     Ok((fd, data, offset, length))
 }
 
-fn write(args: Args<'_>) -> Result<Value<'_>> {
-    enum Fd {
-        Stdout,
-        Stderr,
-    }
-
+fn write<'js>(args: Args<'js>, files: &RefCell<OpenFiles>) -> Result<Value<'js>> {
     let (cx, args) = args.release();
     let (fd, data, offset, length) = extract_args(&args, "Javy.IO.writeSync")?;
-    let fd = match fd
+    let fd = fd
         .as_int()
-        .ok_or_else(|| anyhow!("File descriptor must be a number"))?
-    {
-        1 => Fd::Stdout,
-        2 => Fd::Stderr,
-        x => anyhow::bail!(
-            "Unsupported file descriptor: {x}. Only stdout(1) and stderr(2) are supported"
-        ),
-    };
+        .ok_or_else(|| anyhow!("File descriptor must be a number"))?;
     let data = data
         .as_object()
         .ok_or_else(|| anyhow!("Data must be an Object"))?
@@ -94,35 +94,18 @@ fn write(args: Args<'_>) -> Result<Value<'_>> {
         .as_number()
         .ok_or_else(|| anyhow!("offset must be a number"))? as usize;
     let data = &data[offset..(offset + length)];
-    let n = match fd {
-        Fd::Stdout => {
-            let mut fd = std::io::stdout();
-            let n = fd.write(data)?;
-            fd.flush()?;
-            n
-        }
-        Fd::Stderr => {
-            let mut fd = std::io::stderr();
-            let n = fd.write(data)?;
-            fd.flush()?;
-            n
-        }
-    };
+    let n = files.borrow().write(&cx, fd, |writer| writer.write(data))?;
 
     Ok(Value::new_number(cx, n as f64))
 }
 
-fn read(args: Args<'_>) -> Result<Value<'_>> {
+fn read<'js>(args: Args<'js>, files: &RefCell<OpenFiles>) -> Result<Value<'js>> {
     let (cx, args) = args.release();
     let (fd, data, offset, length) = extract_args(&args, "Javy.IO.readSync")?;
 
-    let mut fd: Stdin = match fd
+    let fd = fd
         .as_int()
-        .ok_or_else(|| anyhow!("File descriptor must be a number"))?
-    {
-        0 => std::io::stdin(),
-        x => anyhow::bail!("Unsupported file descriptor: {x}. Only stdin(0) is supported"),
-    };
+        .ok_or_else(|| anyhow!("File descriptor must be a number"))?;
 
     let offset = offset
         .as_number()
@@ -150,7 +133,7 @@ fn read(args: Args<'_>) -> Result<Value<'_>> {
     }?;
 
     let data = &mut data[offset..(offset + length)];
-    let n = fd.read(data)?;
+    let n = files.borrow().read(&cx, fd, |reader| reader.read(data))?;
 
     Ok(Value::new_number(cx, n as f64))
 }
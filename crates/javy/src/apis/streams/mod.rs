@@ -0,0 +1,439 @@
+use crate::quickjs::{Ctx, context::EvalOptions};
+use anyhow::{Error, Result};
+
+/// Register the `ReadableStream`, `WritableStream` and `TransformStream`
+/// classes, along with their readers, writers, controllers and the queuing
+/// strategies, on the global object.
+///
+/// `TextEncoderStream` and `TextDecoderStream` are registered too when the
+/// text encoding APIs are enabled, and `WritableStreamDefaultController` has a
+/// `signal` when the `EventTarget` APIs are enabled, which also allows
+/// passing a `signal` to `pipeTo` and `pipeThrough`.
+///
+/// The classes are implemented in `streams.js`, which adds about 140KB to the
+/// plugin and is only embedded when the `streams` crate feature is enabled.
+pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+    let mut opts = EvalOptions::default();
+    opts.strict = false;
+    this.eval_with_options::<(), _>(include_str!("./streams.js"), opts)?;
+
+    Ok::<_, Error>(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Config, Runtime,
+        apis::test_utils,
+        quickjs::{Value, convert::Coerced},
+    };
+    use anyhow::{Error, Result};
+
+    fn runtime() -> Result<Runtime> {
+        let mut config = Config::default();
+        config.streams(true);
+        Runtime::new(config)
+    }
+
+    /// Evaluates `source`, which is expected to push to `globalThis.result`,
+    /// reading the result as strings.
+    fn eval_async(runtime: &Runtime, source: &str) -> Result<Vec<String>> {
+        let result: Vec<Coerced<String>> = test_utils::eval_async(runtime, source)?;
+        Ok(result.into_iter().map(|value| value.0).collect())
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        const CLASSES: [&str; 13] = [
+            "ReadableStream",
+            "ReadableStreamDefaultReader",
+            "ReadableStreamBYOBReader",
+            "ReadableStreamDefaultController",
+            "ReadableByteStreamController",
+            "ReadableStreamBYOBRequest",
+            "WritableStream",
+            "WritableStreamDefaultWriter",
+            "WritableStreamDefaultController",
+            "TransformStream",
+            "TransformStreamDefaultController",
+            "ByteLengthQueuingStrategy",
+            "CountQueuingStrategy",
+        ];
+
+        let default = Runtime::default();
+        default.context().with(|this| {
+            for class in CLASSES {
+                assert!(this.globals().get::<_, Value<'_>>(class)?.is_undefined());
+            }
+            Ok::<_, Error>(())
+        })?;
+
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let globals = this.globals();
+            for class in CLASSES {
+                assert!(globals.get::<_, Value<'_>>(class)?.is_function());
+            }
+            // Depend on the text encoding APIs.
+            for class in ["TextEncoderStream", "TextDecoderStream"] {
+                assert!(globals.get::<_, Value<'_>>(class)?.is_undefined());
+            }
+            Ok::<_, Error>(())
+        })?;
+
+        let mut config = Config::default();
+        config.streams(true).text_encoding(true);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|this| {
+            let globals = this.globals();
+            for class in ["TextEncoderStream", "TextDecoderStream"] {
+                assert!(globals.get::<_, Value<'_>>(class)?.is_function());
+            }
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_readable_stream() -> Result<()> {
+        let runtime = runtime()?;
+        let result: Vec<String> = eval_async(
+            &runtime,
+            r#"
+            globalThis.result = [];
+            (async () => {
+                const stream = new ReadableStream({
+                    start(controller) {
+                        controller.enqueue("a");
+                        controller.enqueue("b");
+                        controller.close();
+                    },
+                });
+                const chunks = [];
+                for await (const chunk of stream) {
+                    chunks.push(chunk);
+                }
+                result.push(chunks.join(), stream.locked);
+
+                const [left, right] = ReadableStream.from([1, 2, 3]).tee();
+                const reader = left.getReader();
+                result.push((await reader.read()).value, (await right.getReader().read()).value);
+                reader.releaseLock();
+                try {
+                    await reader.read();
+                } catch (e) {
+                    result.push(e.name);
+                }
+
+                let reason;
+                const canceled = new ReadableStream({
+                    start(controller) {
+                        controller.enqueue(1);
+                        controller.enqueue(2);
+                    },
+                    cancel(r) {
+                        reason = r;
+                    },
+                });
+                for await (const chunk of canceled) {
+                    break;
+                }
+                result.push(reason, canceled.locked);
+
+                const errored = new ReadableStream({ pull(controller) { controller.error(new Error("boom")); } });
+                try {
+                    await errored.getReader().read();
+                } catch (e) {
+                    result.push(e.message);
+                }
+
+                const bad = new ReadableStream(
+                    {
+                        start(controller) {
+                            try {
+                                controller.enqueue(1);
+                            } catch (e) {
+                                result.push(e.name);
+                            }
+                        },
+                    },
+                    { size: () => -1 },
+                );
+                try {
+                    await bad.getReader().read();
+                } catch (e) {
+                    result.push(e.name);
+                }
+                for (const f of [() => new ReadableStream({ type: "foo" }),
+                                 () => new ReadableStream({}, { highWaterMark: -1 }),
+                                 () => new ReadableStreamDefaultController()]) {
+                    try {
+                        f();
+                    } catch (e) {
+                        result.push(e.name);
+                    }
+                }
+                result.push(Object.prototype.toString.call(stream));
+            })();
+        "#,
+        )?;
+        assert_eq!(
+            vec![
+                "a,b",
+                "false",
+                "1",
+                "1",
+                "TypeError",
+                "undefined",
+                "false",
+                "boom",
+                "RangeError",
+                "RangeError",
+                "TypeError",
+                "RangeError",
+                "TypeError",
+                "[object ReadableStream]"
+            ],
+            result
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_byte_stream() -> Result<()> {
+        let runtime = runtime()?;
+        let result: Vec<String> = eval_async(
+            &runtime,
+            r#"
+            globalThis.result = [];
+            (async () => {
+                const stream = new ReadableStream({
+                    type: "bytes",
+                    start(controller) {
+                        controller.enqueue(new Uint8Array([1, 2, 3, 4, 5]));
+                        controller.close();
+                    },
+                });
+                const reader = stream.getReader({ mode: "byob" });
+                let { done, value } = await reader.read(new Uint8Array(2));
+                result.push(`${done}:${value}`);
+                ({ done, value } = await reader.read(new Uint16Array(2)));
+                result.push(`${done}:${value.constructor.name}:${value.length}`);
+                ({ done, value } = await reader.read(new Uint8Array(4)));
+                result.push(`${done}:${value}`);
+                ({ done, value } = await reader.read(new Uint8Array(4)));
+                result.push(`${done}:${value.length}`);
+
+                const allocated = new ReadableStream({
+                    type: "bytes",
+                    autoAllocateChunkSize: 4,
+                    pull(controller) {
+                        controller.byobRequest.view[0] = 7;
+                        controller.byobRequest.respond(1);
+                        controller.close();
+                    },
+                }).getReader();
+                ({ done, value } = await allocated.read());
+                result.push(`${value}:${value.buffer.byteLength}`, (await allocated.read()).done);
+
+                const [left, right] = new ReadableStream({
+                    type: "bytes",
+                    start(controller) {
+                        controller.enqueue(new Uint8Array([9, 8]));
+                        controller.close();
+                    },
+                }).tee();
+                const view = new Uint8Array(4);
+                ({ value } = await left.getReader({ mode: "byob" }).read(view));
+                result.push(`${value}`, view.buffer.detached, `${(await right.getReader().read()).value}`);
+            })();
+        "#,
+        )?;
+        assert_eq!(
+            vec![
+                "false:1,2",
+                "false:Uint16Array:1",
+                "false:5",
+                "true:0",
+                "7:4",
+                "true",
+                "9,8",
+                "true",
+                "9,8"
+            ],
+            result
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_writable_stream() -> Result<()> {
+        let runtime = runtime()?;
+        let result: Vec<String> = eval_async(
+            &runtime,
+            r#"
+            globalThis.result = [];
+            (async () => {
+                const written = [];
+                const stream = new WritableStream(
+                    {
+                        write(chunk) {
+                            written.push(chunk);
+                        },
+                        close() {
+                            written.push("closed");
+                        },
+                    },
+                    new CountQueuingStrategy({ highWaterMark: 2 }),
+                );
+                const writer = stream.getWriter();
+                result.push(writer.desiredSize, stream.locked);
+                writer.write("a");
+                result.push(writer.desiredSize);
+                await writer.close();
+                result.push(written.join());
+
+                const failing = new WritableStream({ write() { throw new Error("fail"); } }).getWriter();
+                try {
+                    await failing.write(1);
+                } catch (e) {
+                    result.push(e.message);
+                }
+                try {
+                    await failing.closed;
+                } catch (e) {
+                    result.push(e.message);
+                }
+
+                let reason;
+                const aborted = new WritableStream({ abort(r) { reason = r; } }).getWriter();
+                await aborted.abort("bye");
+                try {
+                    await aborted.closed;
+                } catch (e) {
+                    result.push(e, reason);
+                }
+
+                const strategy = new ByteLengthQueuingStrategy({ highWaterMark: 16 });
+                result.push(strategy.highWaterMark, strategy.size(new Uint8Array(3)), strategy.size.name);
+            })();
+        "#,
+        )?;
+        assert_eq!(
+            vec![
+                "2", "true", "1", "a,closed", "fail", "fail", "bye", "bye", "16", "3", "size"
+            ],
+            result
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pipes_and_transform_stream() -> Result<()> {
+        let mut config = Config::default();
+        config.streams(true).event_target(true);
+        let runtime = Runtime::new(config)?;
+        let result: Vec<String> = eval_async(
+            &runtime,
+            r#"
+            globalThis.result = [];
+            (async () => {
+                const upper = new TransformStream({
+                    transform(chunk, controller) {
+                        controller.enqueue(chunk.toUpperCase());
+                    },
+                    flush(controller) {
+                        controller.enqueue("!");
+                    },
+                });
+                const chunks = [];
+                await ReadableStream.from(["a", "b"])
+                    .pipeThrough(upper)
+                    .pipeTo(new WritableStream({ write(chunk) { chunks.push(chunk); } }));
+                result.push(chunks.join(""));
+
+                let canceled;
+                const source = new ReadableStream({
+                    start(controller) {
+                        controller.enqueue(1);
+                    },
+                    cancel(reason) {
+                        canceled = reason.message;
+                    },
+                });
+                const dest = new WritableStream({ write() { throw new Error("dest"); } });
+                try {
+                    await source.pipeTo(dest);
+                } catch (e) {
+                    result.push(e.message, canceled);
+                }
+
+                let aborted;
+                const errored = new ReadableStream({ start(controller) { controller.error(new Error("source")); } });
+                try {
+                    await errored.pipeTo(new WritableStream({ abort(reason) { aborted = reason.message; } }));
+                } catch (e) {
+                    result.push(e.message, aborted);
+                }
+
+                const controller = new AbortController();
+                const pending = new ReadableStream({ pull: () => new Promise(() => {}) })
+                    .pipeTo(new WritableStream(), { signal: controller.signal });
+                controller.abort();
+                try {
+                    await pending;
+                } catch (e) {
+                    result.push(e.name);
+                }
+            })();
+        "#,
+        )?;
+        assert_eq!(
+            vec!["AB!", "dest", "dest", "source", "source", "AbortError"],
+            result
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_streams() -> Result<()> {
+        let mut config = Config::default();
+        config.streams(true).text_encoding(true);
+        let runtime = Runtime::new(config)?;
+        let result: Vec<String> = eval_async(
+            &runtime,
+            r#"
+            globalThis.result = [];
+            (async () => {
+                let text = "";
+                await ReadableStream.from(["h\ud83d", "\ude00i", "\ud83d"])
+                    .pipeThrough(new TextEncoderStream())
+                    .pipeThrough(new TextDecoderStream())
+                    .pipeTo(new WritableStream({ write(chunk) { text += chunk; } }));
+                result.push(text);
+
+                const decoder = new TextDecoderStream("utf-16le", { fatal: true });
+                result.push(decoder.encoding, decoder.fatal, decoder.ignoreBOM);
+                decoder.writable.getWriter().write(1).catch((e) => result.push(e.name));
+                try {
+                    await decoder.readable.getReader().read();
+                } catch (e) {
+                    result.push(e.name);
+                }
+            })();
+        "#,
+        )?;
+        assert_eq!(
+            vec![
+                "h😀i\u{fffd}",
+                "utf-16le",
+                "true",
+                "false",
+                "TypeError",
+                "TypeError"
+            ],
+            result
+        );
+        Ok(())
+    }
+}
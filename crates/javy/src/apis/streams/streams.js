@@ -0,0 +1,3475 @@
+// An implementation of the Streams Standard (https://streams.spec.whatwg.org/).
+//
+// Written for Javy from the algorithms of the standard, the names of which the
+// abstract operations below keep; no code is taken from the reference
+// implementation. Licensed under Apache-2.0 WITH LLVM-exception, like the rest
+// of Javy.
+//
+// Each public object keeps its internal slots in a plain record, stored in a
+// private field. The abstract operations of the standard work on these
+// records, which link to each other and to their public object through
+// `object`.
+(function () {
+    // Captured so that replacing globals or prototype methods doesn't affect
+    // the streams.
+    const Promise = globalThis.Promise;
+    const promiseThen = Promise.prototype.then;
+    const promiseResolve = Promise.resolve.bind(Promise);
+    const promiseReject = Promise.reject.bind(Promise);
+    const queueMicrotask = globalThis.queueMicrotask;
+    const ArrayBuffer = globalThis.ArrayBuffer;
+    const arrayBufferTransfer = ArrayBuffer.prototype.transfer;
+    const arrayBufferSlice = ArrayBuffer.prototype.slice;
+    const arrayBufferDetached = Object.getOwnPropertyDescriptor(ArrayBuffer.prototype, "detached").get;
+    const arrayBufferByteLength = Object.getOwnPropertyDescriptor(ArrayBuffer.prototype, "byteLength").get;
+    const Uint8Array = globalThis.Uint8Array;
+    const DataView = globalThis.DataView;
+    const typedArrayName = Object.getOwnPropertyDescriptor(
+        Object.getPrototypeOf(Uint8Array.prototype),
+        Symbol.toStringTag,
+    ).get;
+    const typedArrays = {};
+    for (const name of [
+        "Int8Array", "Uint8Array", "Uint8ClampedArray", "Int16Array", "Uint16Array", "Int32Array", "Uint32Array",
+        "BigInt64Array", "BigUint64Array", "Float16Array", "Float32Array", "Float64Array",
+    ]) {
+        if (globalThis[name] !== undefined) {
+            typedArrays[name] = globalThis[name];
+        }
+    }
+    const AsyncIteratorPrototype = Object.getPrototypeOf(Object.getPrototypeOf(async function* () {}).prototype);
+    // Only available when the `EventTarget` APIs are enabled.
+    const AbortController = globalThis.AbortController;
+    const AbortSignal = globalThis.AbortSignal;
+    const addEventListener = globalThis.EventTarget?.prototype.addEventListener;
+    const removeEventListener = globalThis.EventTarget?.prototype.removeEventListener;
+    // Only available when the text encoding APIs are enabled.
+    const TextEncoder = globalThis.TextEncoder;
+    const TextDecoder = globalThis.TextDecoder;
+
+    function checkArgs(args, required, name) {
+        if (args.length < required) {
+            throw new TypeError(
+                `Failed to execute '${name}': ${required} argument${required > 1 ? "s" : ""} required, but only ${args.length} present`,
+            );
+        }
+    }
+
+    // https://webidl.spec.whatwg.org/#es-dictionary
+    function toDictionary(value, context, type) {
+        if (value === undefined || value === null) {
+            return {};
+        }
+        if (typeof value !== "object" && typeof value !== "function") {
+            throw new TypeError(`${context}: The provided value is not of type '${type}'`);
+        }
+        return value;
+    }
+
+    // https://webidl.spec.whatwg.org/#es-callback-function
+    function toCallback(value, context, member) {
+        if (value !== undefined && typeof value !== "function") {
+            throw new TypeError(`${context}: member ${member} is not a function`);
+        }
+        return value;
+    }
+
+    // https://webidl.spec.whatwg.org/#abstract-opdef-converttoint
+    function toEnforcedUnsignedLongLong(value, context) {
+        const number = Math.trunc(Number(value));
+        if (!Number.isFinite(number) || number < 0 || number > Number.MAX_SAFE_INTEGER) {
+            throw new TypeError(`${context}: Value is outside the 'unsigned long long' value range`);
+        }
+        return number;
+    }
+
+    function isObject(value) {
+        return (typeof value === "object" && value !== null) || typeof value === "function";
+    }
+
+    function isArrayBufferView(value) {
+        return ArrayBuffer.isView(value);
+    }
+
+    function isDetached(buffer) {
+        return arrayBufferDetached.call(buffer);
+    }
+
+    // https://streams.spec.whatwg.org/#transfer-array-buffer
+    function transferArrayBuffer(buffer) {
+        return arrayBufferTransfer.call(buffer);
+    }
+
+    function copyDataBlockBytes(to, toIndex, from, fromIndex, count) {
+        new Uint8Array(to, toIndex, count).set(new Uint8Array(from, fromIndex, count));
+    }
+
+    // https://streams.spec.whatwg.org/#abstract-opdef-cloneasuint8array
+    function cloneAsUint8Array(view) {
+        return new Uint8Array(arrayBufferSlice.call(view.buffer, view.byteOffset, view.byteOffset + view.byteLength));
+    }
+
+    // A promise along with the functions to settle it, which also tracks
+    // whether it's settled.
+    function createDeferred() {
+        const deferred = { state: "pending" };
+        deferred.promise = new Promise((resolve, reject) => {
+            deferred.resolve = (value) => {
+                if (deferred.state === "pending") {
+                    deferred.state = "fulfilled";
+                    resolve(value);
+                }
+            };
+            deferred.reject = (reason) => {
+                if (deferred.state === "pending") {
+                    deferred.state = "rejected";
+                    reject(reason);
+                }
+            };
+        });
+        return deferred;
+    }
+
+    function resolvedDeferred(value) {
+        const deferred = createDeferred();
+        deferred.resolve(value);
+        return deferred;
+    }
+
+    function rejectedDeferred(reason) {
+        const deferred = createDeferred();
+        deferred.reject(reason);
+        setPromiseIsHandled(deferred.promise);
+        return deferred;
+    }
+
+    function noop() {}
+
+    function setPromiseIsHandled(promise) {
+        promiseThen.call(promise, undefined, noop);
+    }
+
+    function transformPromise(promise, onFulfilled, onRejected) {
+        return promiseThen.call(promise, onFulfilled, onRejected);
+    }
+
+    function uponPromise(promise, onFulfilled, onRejected) {
+        setPromiseIsHandled(promiseThen.call(promise, onFulfilled, onRejected));
+    }
+
+    // Calls `method`, returning a promise for its result, rejected if it
+    // throws.
+    function promiseCall(method, thisArg, ...args) {
+        try {
+            return promiseResolve(method.call(thisArg, ...args));
+        } catch (error) {
+            return promiseReject(error);
+        }
+    }
+
+    function resolvedUndefined() {
+        return promiseResolve(undefined);
+    }
+
+    // https://streams.spec.whatwg.org/#queue-with-sizes
+    function dequeueValue(container) {
+        const pair = container.queue.shift();
+        container.queueTotalSize -= pair.size;
+        if (container.queueTotalSize < 0) {
+            container.queueTotalSize = 0;
+        }
+        return pair.value;
+    }
+
+    function enqueueValueWithSize(container, value, size) {
+        if (typeof size !== "number" || Number.isNaN(size) || size < 0 || size === Infinity) {
+            throw new RangeError("The return value of a queuing strategy's size function must be a finite, non-NaN, non-negative number");
+        }
+        container.queue.push({ value, size });
+        container.queueTotalSize += size;
+    }
+
+    function peekQueueValue(container) {
+        return container.queue[0].value;
+    }
+
+    function resetQueue(container) {
+        container.queue = [];
+        container.queueTotalSize = 0;
+    }
+
+    // https://streams.spec.whatwg.org/#qs-api
+    function toQueuingStrategy(strategy, context) {
+        const { highWaterMark, size } = toDictionary(strategy, context, "QueuingStrategy");
+        return {
+            highWaterMark: highWaterMark === undefined ? undefined : Number(highWaterMark),
+            size: toCallback(size, context, "size"),
+        };
+    }
+
+    function extractHighWaterMark(strategy, defaultHWM) {
+        if (strategy.highWaterMark === undefined) {
+            return defaultHWM;
+        }
+        const highWaterMark = strategy.highWaterMark;
+        if (Number.isNaN(highWaterMark) || highWaterMark < 0) {
+            throw new RangeError("Invalid highWaterMark");
+        }
+        return highWaterMark;
+    }
+
+    function extractSizeAlgorithm(strategy) {
+        const size = strategy.size;
+        if (size === undefined) {
+            return () => 1;
+        }
+        return (chunk) => Number(size.call(undefined, chunk));
+    }
+
+    // Used by the public constructors to wrap records created internally.
+    const constructorKey = Symbol();
+
+    // Functions to access the records of the public objects, assigned in the
+    // static blocks below. They return `undefined` for other values.
+    let readableStreamRecord;
+    let writableStreamRecord;
+    let readerRecord;
+    let writerRecord;
+    let readableControllerRecord;
+    let byobRequestRecord;
+    let writableControllerRecord;
+    let transformStreamRecord;
+    let transformControllerRecord;
+
+    function wrap(Class, record) {
+        record.object = new Class(constructorKey, record);
+        return record;
+    }
+
+    // https://streams.spec.whatwg.org/#rs-abstract-ops
+    function newReadableStream() {
+        return {
+            object: undefined,
+            state: "readable",
+            reader: undefined,
+            storedError: undefined,
+            disturbed: false,
+            controller: undefined,
+        };
+    }
+
+    function acquireReadableStreamDefaultReader(stream) {
+        const reader = wrap(ReadableStreamDefaultReader, { object: undefined, type: "default" });
+        setUpReadableStreamDefaultReader(reader, stream);
+        return reader;
+    }
+
+    function acquireReadableStreamBYOBReader(stream) {
+        const reader = wrap(ReadableStreamBYOBReader, { object: undefined, type: "byob" });
+        setUpReadableStreamBYOBReader(reader, stream);
+        return reader;
+    }
+
+    function createReadableStream(startAlgorithm, pullAlgorithm, cancelAlgorithm, highWaterMark = 1, sizeAlgorithm = () => 1) {
+        const stream = wrap(ReadableStream, newReadableStream());
+        const controller = wrap(ReadableStreamDefaultController, { object: undefined, type: "default" });
+        setUpReadableStreamDefaultController(stream, controller, startAlgorithm, pullAlgorithm, cancelAlgorithm, highWaterMark, sizeAlgorithm);
+        return stream;
+    }
+
+    function createReadableByteStream(startAlgorithm, pullAlgorithm, cancelAlgorithm) {
+        const stream = wrap(ReadableStream, newReadableStream());
+        const controller = wrap(ReadableByteStreamController, { object: undefined, type: "bytes" });
+        setUpReadableByteStreamController(stream, controller, startAlgorithm, pullAlgorithm, cancelAlgorithm, 0, undefined);
+        return stream;
+    }
+
+    function isReadableStreamLocked(stream) {
+        return stream.reader !== undefined;
+    }
+
+    // https://streams.spec.whatwg.org/#readable-stream-from-iterable
+    function readableStreamFromIterable(asyncIterable) {
+        let stream;
+        const iteratorRecord = getAsyncIterator(asyncIterable);
+        const startAlgorithm = () => undefined;
+        const pullAlgorithm = () => {
+            let nextResult;
+            try {
+                nextResult = iteratorRecord.nextMethod.call(iteratorRecord.iterator);
+            } catch (error) {
+                return promiseReject(error);
+            }
+            return transformPromise(promiseResolve(nextResult), (iterResult) => {
+                if (!isObject(iterResult)) {
+                    throw new TypeError("The promise returned by the iterator.next() method must fulfill with an object");
+                }
+                if (iterResult.done) {
+                    readableStreamDefaultControllerClose(stream.controller);
+                } else {
+                    readableStreamDefaultControllerEnqueue(stream.controller, iterResult.value);
+                }
+            });
+        };
+        const cancelAlgorithm = (reason) => {
+            const iterator = iteratorRecord.iterator;
+            let returnResult;
+            try {
+                const returnMethod = iterator.return;
+                if (returnMethod === undefined || returnMethod === null) {
+                    return resolvedUndefined();
+                }
+                returnResult = returnMethod.call(iterator, reason);
+            } catch (error) {
+                return promiseReject(error);
+            }
+            return transformPromise(promiseResolve(returnResult), (iterResult) => {
+                if (!isObject(iterResult)) {
+                    throw new TypeError("The promise returned by the iterator.return() method must fulfill with an object");
+                }
+            });
+        };
+        stream = createReadableStream(startAlgorithm, pullAlgorithm, cancelAlgorithm, 0);
+        return stream;
+    }
+
+    // https://tc39.es/ecma262/#sec-getiterator with the async kind, sync
+    // iterators are wrapped in an async generator.
+    function getAsyncIterator(value) {
+        let method = value === undefined || value === null ? undefined : value[Symbol.asyncIterator];
+        if (method === undefined || method === null) {
+            const syncMethod = value === undefined || value === null ? undefined : value[Symbol.iterator];
+            if (typeof syncMethod !== "function") {
+                throw new TypeError("The provided value is not async iterable");
+            }
+            const syncIterator = syncMethod.call(value);
+            if (!isObject(syncIterator)) {
+                throw new TypeError("The result of the Symbol.iterator method is not an object");
+            }
+            const iterator = (async function* () {
+                yield* { [Symbol.iterator]: () => syncIterator };
+            })();
+            return { iterator, nextMethod: iterator.next };
+        }
+        if (typeof method !== "function") {
+            throw new TypeError("The provided value is not async iterable");
+        }
+        const iterator = method.call(value);
+        if (!isObject(iterator)) {
+            throw new TypeError("The result of the Symbol.asyncIterator method is not an object");
+        }
+        return { iterator, nextMethod: iterator.next };
+    }
+
+    // https://streams.spec.whatwg.org/#readable-stream-pipe-to
+    function readableStreamPipeTo(source, dest, preventClose, preventAbort, preventCancel, signal) {
+        const reader = acquireReadableStreamDefaultReader(source);
+        const writer = acquireWritableStreamDefaultWriter(dest);
+        source.disturbed = true;
+        let shuttingDown = false;
+        let currentWrite = resolvedUndefined();
+        const promise = createDeferred();
+
+        const waitForWritesToFinish = () => {
+            const oldCurrentWrite = currentWrite;
+            return transformPromise(currentWrite, () =>
+                oldCurrentWrite !== currentWrite ? waitForWritesToFinish() : undefined,
+            );
+        };
+        const finalize = (isError, error) => {
+            writableStreamDefaultWriterRelease(writer);
+            readableStreamDefaultReaderRelease(reader);
+            if (signal !== undefined) {
+                removeEventListener.call(signal, "abort", abortAlgorithm);
+            }
+            if (isError) {
+                promise.reject(error);
+            } else {
+                promise.resolve(undefined);
+            }
+        };
+        const shutdownWithAnAction = (action, originalIsError, originalError) => {
+            if (shuttingDown) {
+                return;
+            }
+            shuttingDown = true;
+            const doTheRest = () => {
+                uponPromise(
+                    action(),
+                    () => finalize(originalIsError, originalError),
+                    (newError) => finalize(true, newError),
+                );
+            };
+            if (dest.state === "writable" && !writableStreamCloseQueuedOrInFlight(dest)) {
+                uponPromise(waitForWritesToFinish(), doTheRest);
+            } else {
+                doTheRest();
+            }
+        };
+        const shutdown = (isError, error) => {
+            if (shuttingDown) {
+                return;
+            }
+            shuttingDown = true;
+            if (dest.state === "writable" && !writableStreamCloseQueuedOrInFlight(dest)) {
+                uponPromise(waitForWritesToFinish(), () => finalize(isError, error));
+            } else {
+                finalize(isError, error);
+            }
+        };
+        const abortAlgorithm = () => {
+            const error = signal.reason;
+            const actions = [];
+            if (!preventAbort) {
+                actions.push(() => (dest.state === "writable" ? writableStreamAbort(dest, error) : resolvedUndefined()));
+            }
+            if (!preventCancel) {
+                actions.push(() => (source.state === "readable" ? readableStreamCancel(source, error) : resolvedUndefined()));
+            }
+            shutdownWithAnAction(() => Promise.all(actions.map((action) => action())), true, error);
+        };
+
+        if (signal !== undefined) {
+            if (signal.aborted) {
+                abortAlgorithm();
+                return promise.promise;
+            }
+            addEventListener.call(signal, "abort", abortAlgorithm);
+        }
+
+        const pipeStep = () => {
+            if (shuttingDown) {
+                return promiseResolve(true);
+            }
+            return transformPromise(writer.ready.promise, () =>
+                new Promise((resolveRead, rejectRead) => {
+                    readableStreamDefaultReaderRead(reader, {
+                        chunk: (chunk) => {
+                            currentWrite = transformPromise(writableStreamDefaultWriterWrite(writer, chunk), undefined, noop);
+                            resolveRead(false);
+                        },
+                        close: () => resolveRead(true),
+                        error: rejectRead,
+                    });
+                }),
+            );
+        };
+        const pipeLoop = () =>
+            new Promise((resolveLoop, rejectLoop) => {
+                const next = (done) => {
+                    if (done) {
+                        resolveLoop();
+                    } else {
+                        promiseThen.call(pipeStep(), next, rejectLoop);
+                    }
+                };
+                next(false);
+            });
+
+        // Errors must be propagated forward.
+        if (source.state === "errored") {
+            if (!preventAbort) {
+                shutdownWithAnAction(() => writableStreamAbort(dest, source.storedError), true, source.storedError);
+            } else {
+                shutdown(true, source.storedError);
+            }
+        } else {
+            uponPromise(reader.closed.promise, undefined, (storedError) => {
+                if (!preventAbort) {
+                    shutdownWithAnAction(() => writableStreamAbort(dest, storedError), true, storedError);
+                } else {
+                    shutdown(true, storedError);
+                }
+            });
+        }
+        // Errors must be propagated backward.
+        if (dest.state === "errored") {
+            if (!preventCancel) {
+                shutdownWithAnAction(() => readableStreamCancel(source, dest.storedError), true, dest.storedError);
+            } else {
+                shutdown(true, dest.storedError);
+            }
+        } else {
+            uponPromise(writer.closed.promise, undefined, (storedError) => {
+                if (!preventCancel) {
+                    shutdownWithAnAction(() => readableStreamCancel(source, storedError), true, storedError);
+                } else {
+                    shutdown(true, storedError);
+                }
+            });
+        }
+        // Closing must be propagated forward.
+        if (source.state === "closed") {
+            if (!preventClose) {
+                shutdownWithAnAction(() => writableStreamDefaultWriterCloseWithErrorPropagation(writer));
+            } else {
+                shutdown();
+            }
+        } else {
+            uponPromise(reader.closed.promise, () => {
+                if (!preventClose) {
+                    shutdownWithAnAction(() => writableStreamDefaultWriterCloseWithErrorPropagation(writer));
+                } else {
+                    shutdown();
+                }
+            });
+        }
+        // Closing must be propagated backward.
+        if (writableStreamCloseQueuedOrInFlight(dest) || dest.state === "closed") {
+            const destClosed = new TypeError("The destination writable stream closed before all the data could be piped to it");
+            if (!preventCancel) {
+                shutdownWithAnAction(() => readableStreamCancel(source, destClosed), true, destClosed);
+            } else {
+                shutdown(true, destClosed);
+            }
+        }
+
+        setPromiseIsHandled(pipeLoop());
+        return promise.promise;
+    }
+
+    // https://streams.spec.whatwg.org/#readable-stream-tee
+    function readableStreamTee(stream) {
+        if (stream.controller.type === "bytes") {
+            return readableByteStreamTee(stream);
+        }
+        return readableStreamDefaultTee(stream);
+    }
+
+    function readableStreamDefaultTee(stream) {
+        const reader = acquireReadableStreamDefaultReader(stream);
+        let reading = false;
+        let readAgain = false;
+        let canceled1 = false;
+        let canceled2 = false;
+        let reason1;
+        let reason2;
+        let branch1;
+        let branch2;
+        const cancelPromise = createDeferred();
+
+        const pullAlgorithm = () => {
+            if (reading) {
+                readAgain = true;
+                return resolvedUndefined();
+            }
+            reading = true;
+            readableStreamDefaultReaderRead(reader, {
+                chunk: (chunk) => {
+                    queueMicrotask(() => {
+                        readAgain = false;
+                        if (!canceled1) {
+                            readableStreamDefaultControllerEnqueue(branch1.controller, chunk);
+                        }
+                        if (!canceled2) {
+                            readableStreamDefaultControllerEnqueue(branch2.controller, chunk);
+                        }
+                        reading = false;
+                        if (readAgain) {
+                            pullAlgorithm();
+                        }
+                    });
+                },
+                close: () => {
+                    reading = false;
+                    if (!canceled1) {
+                        readableStreamDefaultControllerClose(branch1.controller);
+                    }
+                    if (!canceled2) {
+                        readableStreamDefaultControllerClose(branch2.controller);
+                    }
+                    if (!canceled1 || !canceled2) {
+                        cancelPromise.resolve(undefined);
+                    }
+                },
+                error: () => {
+                    reading = false;
+                },
+            });
+            return resolvedUndefined();
+        };
+        const cancel1Algorithm = (reason) => {
+            canceled1 = true;
+            reason1 = reason;
+            if (canceled2) {
+                cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+            }
+            return cancelPromise.promise;
+        };
+        const cancel2Algorithm = (reason) => {
+            canceled2 = true;
+            reason2 = reason;
+            if (canceled1) {
+                cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+            }
+            return cancelPromise.promise;
+        };
+        const startAlgorithm = () => undefined;
+        branch1 = createReadableStream(startAlgorithm, pullAlgorithm, cancel1Algorithm);
+        branch2 = createReadableStream(startAlgorithm, pullAlgorithm, cancel2Algorithm);
+        uponPromise(reader.closed.promise, undefined, (r) => {
+            readableStreamDefaultControllerError(branch1.controller, r);
+            readableStreamDefaultControllerError(branch2.controller, r);
+            if (!canceled1 || !canceled2) {
+                cancelPromise.resolve(undefined);
+            }
+        });
+        return [branch1, branch2];
+    }
+
+    function readableByteStreamTee(stream) {
+        let reader = acquireReadableStreamDefaultReader(stream);
+        let reading = false;
+        let readAgainForBranch1 = false;
+        let readAgainForBranch2 = false;
+        let canceled1 = false;
+        let canceled2 = false;
+        let reason1;
+        let reason2;
+        let branch1;
+        let branch2;
+        const cancelPromise = createDeferred();
+
+        const forwardReaderError = (thisReader) => {
+            uponPromise(thisReader.closed.promise, undefined, (r) => {
+                if (thisReader !== reader) {
+                    return;
+                }
+                readableByteStreamControllerError(branch1.controller, r);
+                readableByteStreamControllerError(branch2.controller, r);
+                if (!canceled1 || !canceled2) {
+                    cancelPromise.resolve(undefined);
+                }
+            });
+        };
+        const pullWithDefaultReader = () => {
+            if (reader.type === "byob") {
+                readableStreamBYOBReaderRelease(reader);
+                reader = acquireReadableStreamDefaultReader(stream);
+                forwardReaderError(reader);
+            }
+            readableStreamDefaultReaderRead(reader, {
+                chunk: (chunk) => {
+                    queueMicrotask(() => {
+                        readAgainForBranch1 = false;
+                        readAgainForBranch2 = false;
+                        let chunk2 = chunk;
+                        if (!canceled1 && !canceled2) {
+                            try {
+                                chunk2 = cloneAsUint8Array(chunk);
+                            } catch (error) {
+                                readableByteStreamControllerError(branch1.controller, error);
+                                readableByteStreamControllerError(branch2.controller, error);
+                                cancelPromise.resolve(readableStreamCancel(stream, error));
+                                return;
+                            }
+                        }
+                        if (!canceled1) {
+                            readableByteStreamControllerEnqueue(branch1.controller, chunk);
+                        }
+                        if (!canceled2) {
+                            readableByteStreamControllerEnqueue(branch2.controller, chunk2);
+                        }
+                        reading = false;
+                        if (readAgainForBranch1) {
+                            pull1Algorithm();
+                        } else if (readAgainForBranch2) {
+                            pull2Algorithm();
+                        }
+                    });
+                },
+                close: () => {
+                    reading = false;
+                    if (!canceled1) {
+                        readableByteStreamControllerClose(branch1.controller);
+                    }
+                    if (!canceled2) {
+                        readableByteStreamControllerClose(branch2.controller);
+                    }
+                    if (branch1.controller.pendingPullIntos.length > 0) {
+                        readableByteStreamControllerRespond(branch1.controller, 0);
+                    }
+                    if (branch2.controller.pendingPullIntos.length > 0) {
+                        readableByteStreamControllerRespond(branch2.controller, 0);
+                    }
+                    if (!canceled1 || !canceled2) {
+                        cancelPromise.resolve(undefined);
+                    }
+                },
+                error: () => {
+                    reading = false;
+                },
+            });
+        };
+        const pullWithBYOBReader = (view, forBranch2) => {
+            if (reader.type === "default") {
+                readableStreamDefaultReaderRelease(reader);
+                reader = acquireReadableStreamBYOBReader(stream);
+                forwardReaderError(reader);
+            }
+            const byobBranch = forBranch2 ? branch2 : branch1;
+            const otherBranch = forBranch2 ? branch1 : branch2;
+            readableStreamBYOBReaderRead(reader, view, 1, {
+                chunk: (chunk) => {
+                    queueMicrotask(() => {
+                        readAgainForBranch1 = false;
+                        readAgainForBranch2 = false;
+                        const byobCanceled = forBranch2 ? canceled2 : canceled1;
+                        const otherCanceled = forBranch2 ? canceled1 : canceled2;
+                        if (!otherCanceled) {
+                            let clonedChunk;
+                            try {
+                                clonedChunk = cloneAsUint8Array(chunk);
+                            } catch (error) {
+                                readableByteStreamControllerError(byobBranch.controller, error);
+                                readableByteStreamControllerError(otherBranch.controller, error);
+                                cancelPromise.resolve(readableStreamCancel(stream, error));
+                                return;
+                            }
+                            if (!byobCanceled) {
+                                readableByteStreamControllerRespondWithNewView(byobBranch.controller, chunk);
+                            }
+                            readableByteStreamControllerEnqueue(otherBranch.controller, clonedChunk);
+                        } else if (!byobCanceled) {
+                            readableByteStreamControllerRespondWithNewView(byobBranch.controller, chunk);
+                        }
+                        reading = false;
+                        if (readAgainForBranch1) {
+                            pull1Algorithm();
+                        } else if (readAgainForBranch2) {
+                            pull2Algorithm();
+                        }
+                    });
+                },
+                close: (chunk) => {
+                    reading = false;
+                    const byobCanceled = forBranch2 ? canceled2 : canceled1;
+                    const otherCanceled = forBranch2 ? canceled1 : canceled2;
+                    if (!byobCanceled) {
+                        readableByteStreamControllerClose(byobBranch.controller);
+                    }
+                    if (!otherCanceled) {
+                        readableByteStreamControllerClose(otherBranch.controller);
+                    }
+                    if (chunk !== undefined) {
+                        if (!byobCanceled) {
+                            readableByteStreamControllerRespondWithNewView(byobBranch.controller, chunk);
+                        }
+                        if (!otherCanceled && otherBranch.controller.pendingPullIntos.length > 0) {
+                            readableByteStreamControllerRespond(otherBranch.controller, 0);
+                        }
+                    }
+                    if (!byobCanceled || !otherCanceled) {
+                        cancelPromise.resolve(undefined);
+                    }
+                },
+                error: () => {
+                    reading = false;
+                },
+            });
+        };
+        const pull1Algorithm = () => {
+            if (reading) {
+                readAgainForBranch1 = true;
+                return resolvedUndefined();
+            }
+            reading = true;
+            const byobRequest = readableByteStreamControllerGetBYOBRequest(branch1.controller);
+            if (byobRequest === null) {
+                pullWithDefaultReader();
+            } else {
+                pullWithBYOBReader(byobRequest.view, false);
+            }
+            return resolvedUndefined();
+        };
+        const pull2Algorithm = () => {
+            if (reading) {
+                readAgainForBranch2 = true;
+                return resolvedUndefined();
+            }
+            reading = true;
+            const byobRequest = readableByteStreamControllerGetBYOBRequest(branch2.controller);
+            if (byobRequest === null) {
+                pullWithDefaultReader();
+            } else {
+                pullWithBYOBReader(byobRequest.view, true);
+            }
+            return resolvedUndefined();
+        };
+        const cancel1Algorithm = (reason) => {
+            canceled1 = true;
+            reason1 = reason;
+            if (canceled2) {
+                cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+            }
+            return cancelPromise.promise;
+        };
+        const cancel2Algorithm = (reason) => {
+            canceled2 = true;
+            reason2 = reason;
+            if (canceled1) {
+                cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+            }
+            return cancelPromise.promise;
+        };
+        const startAlgorithm = () => undefined;
+        branch1 = createReadableByteStream(startAlgorithm, pull1Algorithm, cancel1Algorithm);
+        branch2 = createReadableByteStream(startAlgorithm, pull2Algorithm, cancel2Algorithm);
+        forwardReaderError(reader);
+        return [branch1, branch2];
+    }
+
+    // https://streams.spec.whatwg.org/#rs-abstract-ops-used-by-controllers
+    function readableStreamAddReadIntoRequest(stream, readIntoRequest) {
+        stream.reader.readIntoRequests.push(readIntoRequest);
+    }
+
+    function readableStreamAddReadRequest(stream, readRequest) {
+        stream.reader.readRequests.push(readRequest);
+    }
+
+    function readableStreamCancel(stream, reason) {
+        stream.disturbed = true;
+        if (stream.state === "closed") {
+            return resolvedUndefined();
+        }
+        if (stream.state === "errored") {
+            return promiseReject(stream.storedError);
+        }
+        readableStreamClose(stream);
+        const reader = stream.reader;
+        if (reader !== undefined && reader.type === "byob") {
+            const readIntoRequests = reader.readIntoRequests;
+            reader.readIntoRequests = [];
+            for (const readIntoRequest of readIntoRequests) {
+                readIntoRequest.close(undefined);
+            }
+        }
+        const controller = stream.controller;
+        const sourceCancelPromise =
+            controller.type === "bytes"
+                ? readableByteStreamControllerCancelSteps(controller, reason)
+                : readableStreamDefaultControllerCancelSteps(controller, reason);
+        return transformPromise(sourceCancelPromise, () => undefined);
+    }
+
+    function readableStreamClose(stream) {
+        stream.state = "closed";
+        const reader = stream.reader;
+        if (reader === undefined) {
+            return;
+        }
+        reader.closed.resolve(undefined);
+        if (reader.type === "default") {
+            const readRequests = reader.readRequests;
+            reader.readRequests = [];
+            for (const readRequest of readRequests) {
+                readRequest.close();
+            }
+        }
+    }
+
+    function readableStreamError(stream, e) {
+        stream.state = "errored";
+        stream.storedError = e;
+        const reader = stream.reader;
+        if (reader === undefined) {
+            return;
+        }
+        reader.closed.reject(e);
+        setPromiseIsHandled(reader.closed.promise);
+        if (reader.type === "default") {
+            readableStreamDefaultReaderErrorReadRequests(reader, e);
+        } else {
+            readableStreamBYOBReaderErrorReadIntoRequests(reader, e);
+        }
+    }
+
+    function readableStreamFulfillReadIntoRequest(stream, chunk, done) {
+        const readIntoRequest = stream.reader.readIntoRequests.shift();
+        if (done) {
+            readIntoRequest.close(chunk);
+        } else {
+            readIntoRequest.chunk(chunk);
+        }
+    }
+
+    function readableStreamFulfillReadRequest(stream, chunk, done) {
+        const readRequest = stream.reader.readRequests.shift();
+        if (done) {
+            readRequest.close();
+        } else {
+            readRequest.chunk(chunk);
+        }
+    }
+
+    function readableStreamGetNumReadIntoRequests(stream) {
+        return stream.reader.readIntoRequests.length;
+    }
+
+    function readableStreamGetNumReadRequests(stream) {
+        return stream.reader.readRequests.length;
+    }
+
+    function readableStreamHasBYOBReader(stream) {
+        return stream.reader !== undefined && stream.reader.type === "byob";
+    }
+
+    function readableStreamHasDefaultReader(stream) {
+        return stream.reader !== undefined && stream.reader.type === "default";
+    }
+
+    // https://streams.spec.whatwg.org/#rs-reader-abstract-ops
+    function readableStreamReaderGenericCancel(reader, reason) {
+        return readableStreamCancel(reader.stream, reason);
+    }
+
+    function readableStreamReaderGenericInitialize(reader, stream) {
+        reader.stream = stream;
+        stream.reader = reader;
+        if (stream.state === "readable") {
+            reader.closed = createDeferred();
+        } else if (stream.state === "closed") {
+            reader.closed = resolvedDeferred(undefined);
+        } else {
+            reader.closed = rejectedDeferred(stream.storedError);
+        }
+    }
+
+    function readableStreamReaderGenericRelease(reader) {
+        const stream = reader.stream;
+        const error = new TypeError("The reader was released");
+        if (stream.state === "readable") {
+            reader.closed.reject(error);
+        } else {
+            reader.closed = rejectedDeferred(error);
+        }
+        setPromiseIsHandled(reader.closed.promise);
+        if (stream.controller.type === "bytes") {
+            readableByteStreamControllerReleaseSteps(stream.controller);
+        }
+        stream.reader = undefined;
+        reader.stream = undefined;
+    }
+
+    function readableStreamBYOBReaderErrorReadIntoRequests(reader, e) {
+        const readIntoRequests = reader.readIntoRequests;
+        reader.readIntoRequests = [];
+        for (const readIntoRequest of readIntoRequests) {
+            readIntoRequest.error(e);
+        }
+    }
+
+    function readableStreamBYOBReaderRead(reader, view, min, readIntoRequest) {
+        const stream = reader.stream;
+        stream.disturbed = true;
+        if (stream.state === "errored") {
+            readIntoRequest.error(stream.storedError);
+        } else {
+            readableByteStreamControllerPullInto(stream.controller, view, min, readIntoRequest);
+        }
+    }
+
+    function readableStreamBYOBReaderRelease(reader) {
+        readableStreamReaderGenericRelease(reader);
+        readableStreamBYOBReaderErrorReadIntoRequests(reader, new TypeError("The reader was released"));
+    }
+
+    function readableStreamDefaultReaderErrorReadRequests(reader, e) {
+        const readRequests = reader.readRequests;
+        reader.readRequests = [];
+        for (const readRequest of readRequests) {
+            readRequest.error(e);
+        }
+    }
+
+    function readableStreamDefaultReaderRead(reader, readRequest) {
+        const stream = reader.stream;
+        stream.disturbed = true;
+        if (stream.state === "closed") {
+            readRequest.close();
+        } else if (stream.state === "errored") {
+            readRequest.error(stream.storedError);
+        } else if (stream.controller.type === "bytes") {
+            readableByteStreamControllerPullSteps(stream.controller, readRequest);
+        } else {
+            readableStreamDefaultControllerPullSteps(stream.controller, readRequest);
+        }
+    }
+
+    function readableStreamDefaultReaderRelease(reader) {
+        readableStreamReaderGenericRelease(reader);
+        readableStreamDefaultReaderErrorReadRequests(reader, new TypeError("The reader was released"));
+    }
+
+    function setUpReadableStreamBYOBReader(reader, stream) {
+        if (isReadableStreamLocked(stream)) {
+            throw new TypeError("ReadableStream is locked");
+        }
+        if (stream.controller.type !== "bytes") {
+            throw new TypeError("Cannot use a BYOB reader with a non-byte stream");
+        }
+        readableStreamReaderGenericInitialize(reader, stream);
+        reader.readIntoRequests = [];
+    }
+
+    function setUpReadableStreamDefaultReader(reader, stream) {
+        if (isReadableStreamLocked(stream)) {
+            throw new TypeError("ReadableStream is locked");
+        }
+        readableStreamReaderGenericInitialize(reader, stream);
+        reader.readRequests = [];
+    }
+
+    // https://streams.spec.whatwg.org/#rs-default-controller-abstract-ops
+    function readableStreamDefaultControllerCallPullIfNeeded(controller) {
+        if (!readableStreamDefaultControllerShouldCallPull(controller)) {
+            return;
+        }
+        if (controller.pulling) {
+            controller.pullAgain = true;
+            return;
+        }
+        controller.pulling = true;
+        uponPromise(
+            controller.pullAlgorithm(),
+            () => {
+                controller.pulling = false;
+                if (controller.pullAgain) {
+                    controller.pullAgain = false;
+                    readableStreamDefaultControllerCallPullIfNeeded(controller);
+                }
+            },
+            (e) => readableStreamDefaultControllerError(controller, e),
+        );
+    }
+
+    function readableStreamDefaultControllerShouldCallPull(controller) {
+        const stream = controller.stream;
+        if (!readableStreamDefaultControllerCanCloseOrEnqueue(controller) || !controller.started) {
+            return false;
+        }
+        if (isReadableStreamLocked(stream) && readableStreamGetNumReadRequests(stream) > 0) {
+            return true;
+        }
+        return readableStreamDefaultControllerGetDesiredSize(controller) > 0;
+    }
+
+    function readableStreamDefaultControllerClearAlgorithms(controller) {
+        controller.pullAlgorithm = undefined;
+        controller.cancelAlgorithm = undefined;
+        controller.strategySizeAlgorithm = undefined;
+    }
+
+    function readableStreamDefaultControllerClose(controller) {
+        if (!readableStreamDefaultControllerCanCloseOrEnqueue(controller)) {
+            return;
+        }
+        controller.closeRequested = true;
+        if (controller.queue.length === 0) {
+            readableStreamDefaultControllerClearAlgorithms(controller);
+            readableStreamClose(controller.stream);
+        }
+    }
+
+    function readableStreamDefaultControllerEnqueue(controller, chunk) {
+        if (!readableStreamDefaultControllerCanCloseOrEnqueue(controller)) {
+            return;
+        }
+        const stream = controller.stream;
+        if (isReadableStreamLocked(stream) && readableStreamGetNumReadRequests(stream) > 0) {
+            readableStreamFulfillReadRequest(stream, chunk, false);
+        } else {
+            try {
+                const chunkSize = controller.strategySizeAlgorithm(chunk);
+                enqueueValueWithSize(controller, chunk, chunkSize);
+            } catch (error) {
+                readableStreamDefaultControllerError(controller, error);
+                throw error;
+            }
+        }
+        readableStreamDefaultControllerCallPullIfNeeded(controller);
+    }
+
+    function readableStreamDefaultControllerError(controller, e) {
+        const stream = controller.stream;
+        if (stream.state !== "readable") {
+            return;
+        }
+        resetQueue(controller);
+        readableStreamDefaultControllerClearAlgorithms(controller);
+        readableStreamError(stream, e);
+    }
+
+    function readableStreamDefaultControllerGetDesiredSize(controller) {
+        const state = controller.stream.state;
+        if (state === "errored") {
+            return null;
+        }
+        if (state === "closed") {
+            return 0;
+        }
+        return controller.strategyHWM - controller.queueTotalSize;
+    }
+
+    function readableStreamDefaultControllerHasBackpressure(controller) {
+        return !readableStreamDefaultControllerShouldCallPull(controller);
+    }
+
+    function readableStreamDefaultControllerCanCloseOrEnqueue(controller) {
+        return !controller.closeRequested && controller.stream.state === "readable";
+    }
+
+    function readableStreamDefaultControllerCancelSteps(controller, reason) {
+        resetQueue(controller);
+        const result = controller.cancelAlgorithm(reason);
+        readableStreamDefaultControllerClearAlgorithms(controller);
+        return result;
+    }
+
+    function readableStreamDefaultControllerPullSteps(controller, readRequest) {
+        const stream = controller.stream;
+        if (controller.queue.length > 0) {
+            const chunk = dequeueValue(controller);
+            if (controller.closeRequested && controller.queue.length === 0) {
+                readableStreamDefaultControllerClearAlgorithms(controller);
+                readableStreamClose(stream);
+            } else {
+                readableStreamDefaultControllerCallPullIfNeeded(controller);
+            }
+            readRequest.chunk(chunk);
+        } else {
+            readableStreamAddReadRequest(stream, readRequest);
+            readableStreamDefaultControllerCallPullIfNeeded(controller);
+        }
+    }
+
+    function setUpReadableStreamDefaultController(
+        stream,
+        controller,
+        startAlgorithm,
+        pullAlgorithm,
+        cancelAlgorithm,
+        highWaterMark,
+        sizeAlgorithm,
+    ) {
+        controller.stream = stream;
+        resetQueue(controller);
+        controller.started = false;
+        controller.closeRequested = false;
+        controller.pullAgain = false;
+        controller.pulling = false;
+        controller.strategySizeAlgorithm = sizeAlgorithm;
+        controller.strategyHWM = highWaterMark;
+        controller.pullAlgorithm = pullAlgorithm;
+        controller.cancelAlgorithm = cancelAlgorithm;
+        stream.controller = controller;
+        const startResult = startAlgorithm();
+        uponPromise(
+            promiseResolve(startResult),
+            () => {
+                controller.started = true;
+                readableStreamDefaultControllerCallPullIfNeeded(controller);
+            },
+            (r) => readableStreamDefaultControllerError(controller, r),
+        );
+    }
+
+    function setUpReadableStreamDefaultControllerFromUnderlyingSource(
+        stream,
+        underlyingSource,
+        underlyingSourceDict,
+        highWaterMark,
+        sizeAlgorithm,
+    ) {
+        const controller = wrap(ReadableStreamDefaultController, { object: undefined, type: "default" });
+        const { start, pull, cancel } = underlyingSourceDict;
+        const startAlgorithm = start === undefined ? () => undefined : () => start.call(underlyingSource, controller.object);
+        const pullAlgorithm =
+            pull === undefined ? resolvedUndefined : () => promiseCall(pull, underlyingSource, controller.object);
+        const cancelAlgorithm =
+            cancel === undefined ? resolvedUndefined : (reason) => promiseCall(cancel, underlyingSource, reason);
+        setUpReadableStreamDefaultController(stream, controller, startAlgorithm, pullAlgorithm, cancelAlgorithm, highWaterMark, sizeAlgorithm);
+    }
+
+    // https://streams.spec.whatwg.org/#rbs-controller-abstract-ops
+    function readableByteStreamControllerCallPullIfNeeded(controller) {
+        if (!readableByteStreamControllerShouldCallPull(controller)) {
+            return;
+        }
+        if (controller.pulling) {
+            controller.pullAgain = true;
+            return;
+        }
+        controller.pulling = true;
+        uponPromise(
+            controller.pullAlgorithm(),
+            () => {
+                controller.pulling = false;
+                if (controller.pullAgain) {
+                    controller.pullAgain = false;
+                    readableByteStreamControllerCallPullIfNeeded(controller);
+                }
+            },
+            (e) => readableByteStreamControllerError(controller, e),
+        );
+    }
+
+    function readableByteStreamControllerClearAlgorithms(controller) {
+        controller.pullAlgorithm = undefined;
+        controller.cancelAlgorithm = undefined;
+    }
+
+    function readableByteStreamControllerClearPendingPullIntos(controller) {
+        readableByteStreamControllerInvalidateBYOBRequest(controller);
+        controller.pendingPullIntos = [];
+    }
+
+    function readableByteStreamControllerClose(controller) {
+        const stream = controller.stream;
+        if (controller.closeRequested || stream.state !== "readable") {
+            return;
+        }
+        if (controller.queueTotalSize > 0) {
+            controller.closeRequested = true;
+            return;
+        }
+        if (controller.pendingPullIntos.length > 0) {
+            const firstPendingPullInto = controller.pendingPullIntos[0];
+            if (firstPendingPullInto.bytesFilled % firstPendingPullInto.elementSize !== 0) {
+                const e = new TypeError("Insufficient bytes to fill elements in the given buffer");
+                readableByteStreamControllerError(controller, e);
+                throw e;
+            }
+        }
+        readableByteStreamControllerClearAlgorithms(controller);
+        readableStreamClose(stream);
+    }
+
+    function readableByteStreamControllerCommitPullIntoDescriptor(stream, pullIntoDescriptor) {
+        const done = stream.state === "closed";
+        const filledView = readableByteStreamControllerConvertPullIntoDescriptor(pullIntoDescriptor);
+        if (pullIntoDescriptor.readerType === "default") {
+            readableStreamFulfillReadRequest(stream, filledView, done);
+        } else {
+            readableStreamFulfillReadIntoRequest(stream, filledView, done);
+        }
+    }
+
+    function readableByteStreamControllerConvertPullIntoDescriptor(pullIntoDescriptor) {
+        const { bytesFilled, elementSize } = pullIntoDescriptor;
+        const buffer = transferArrayBuffer(pullIntoDescriptor.buffer);
+        return new pullIntoDescriptor.viewConstructor(buffer, pullIntoDescriptor.byteOffset, bytesFilled / elementSize);
+    }
+
+    function readableByteStreamControllerEnqueue(controller, chunk) {
+        const stream = controller.stream;
+        if (controller.closeRequested || stream.state !== "readable") {
+            return;
+        }
+        const { buffer, byteOffset, byteLength } = chunk;
+        if (isDetached(buffer)) {
+            throw new TypeError("The chunk's buffer is detached");
+        }
+        const transferredBuffer = transferArrayBuffer(buffer);
+        if (controller.pendingPullIntos.length > 0) {
+            const firstPendingPullInto = controller.pendingPullIntos[0];
+            if (isDetached(firstPendingPullInto.buffer)) {
+                throw new TypeError("The BYOB request's buffer has been detached and so cannot be filled with an enqueued chunk");
+            }
+            readableByteStreamControllerInvalidateBYOBRequest(controller);
+            firstPendingPullInto.buffer = transferArrayBuffer(firstPendingPullInto.buffer);
+            if (firstPendingPullInto.readerType === "none") {
+                readableByteStreamControllerEnqueueDetachedPullIntoToQueue(controller, firstPendingPullInto);
+            }
+        }
+        if (readableStreamHasDefaultReader(stream)) {
+            readableByteStreamControllerProcessReadRequestsUsingQueue(controller);
+            if (readableStreamGetNumReadRequests(stream) === 0) {
+                readableByteStreamControllerEnqueueChunkToQueue(controller, transferredBuffer, byteOffset, byteLength);
+            } else {
+                if (controller.pendingPullIntos.length > 0) {
+                    readableByteStreamControllerShiftPendingPullInto(controller);
+                }
+                readableStreamFulfillReadRequest(stream, new Uint8Array(transferredBuffer, byteOffset, byteLength), false);
+            }
+        } else if (readableStreamHasBYOBReader(stream)) {
+            readableByteStreamControllerEnqueueChunkToQueue(controller, transferredBuffer, byteOffset, byteLength);
+            const filledPullIntos = readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(controller);
+            for (const filledPullInto of filledPullIntos) {
+                readableByteStreamControllerCommitPullIntoDescriptor(stream, filledPullInto);
+            }
+        } else {
+            readableByteStreamControllerEnqueueChunkToQueue(controller, transferredBuffer, byteOffset, byteLength);
+        }
+        readableByteStreamControllerCallPullIfNeeded(controller);
+    }
+
+    function readableByteStreamControllerEnqueueChunkToQueue(controller, buffer, byteOffset, byteLength) {
+        controller.queue.push({ buffer, byteOffset, byteLength });
+        controller.queueTotalSize += byteLength;
+    }
+
+    function readableByteStreamControllerEnqueueClonedChunkToQueue(controller, buffer, byteOffset, byteLength) {
+        let cloneResult;
+        try {
+            cloneResult = arrayBufferSlice.call(buffer, byteOffset, byteOffset + byteLength);
+        } catch (error) {
+            readableByteStreamControllerError(controller, error);
+            throw error;
+        }
+        readableByteStreamControllerEnqueueChunkToQueue(controller, cloneResult, 0, byteLength);
+    }
+
+    function readableByteStreamControllerEnqueueDetachedPullIntoToQueue(controller, pullIntoDescriptor) {
+        if (pullIntoDescriptor.bytesFilled > 0) {
+            readableByteStreamControllerEnqueueClonedChunkToQueue(
+                controller,
+                pullIntoDescriptor.buffer,
+                pullIntoDescriptor.byteOffset,
+                pullIntoDescriptor.bytesFilled,
+            );
+        }
+        readableByteStreamControllerShiftPendingPullInto(controller);
+    }
+
+    function readableByteStreamControllerError(controller, e) {
+        const stream = controller.stream;
+        if (stream.state !== "readable") {
+            return;
+        }
+        readableByteStreamControllerClearPendingPullIntos(controller);
+        resetQueue(controller);
+        readableByteStreamControllerClearAlgorithms(controller);
+        readableStreamError(stream, e);
+    }
+
+    function readableByteStreamControllerFillHeadPullIntoDescriptor(controller, size, pullIntoDescriptor) {
+        pullIntoDescriptor.bytesFilled += size;
+    }
+
+    function readableByteStreamControllerFillPullIntoDescriptorFromQueue(controller, pullIntoDescriptor) {
+        const maxBytesToCopy = Math.min(controller.queueTotalSize, pullIntoDescriptor.byteLength - pullIntoDescriptor.bytesFilled);
+        const maxBytesFilled = pullIntoDescriptor.bytesFilled + maxBytesToCopy;
+        let totalBytesToCopyRemaining = maxBytesToCopy;
+        let ready = false;
+        const remainderBytes = maxBytesFilled % pullIntoDescriptor.elementSize;
+        const maxAlignedBytes = maxBytesFilled - remainderBytes;
+        if (maxAlignedBytes >= pullIntoDescriptor.minimumFill) {
+            totalBytesToCopyRemaining = maxAlignedBytes - pullIntoDescriptor.bytesFilled;
+            ready = true;
+        }
+        const queue = controller.queue;
+        while (totalBytesToCopyRemaining > 0) {
+            const headOfQueue = queue[0];
+            const bytesToCopy = Math.min(totalBytesToCopyRemaining, headOfQueue.byteLength);
+            const destStart = pullIntoDescriptor.byteOffset + pullIntoDescriptor.bytesFilled;
+            copyDataBlockBytes(pullIntoDescriptor.buffer, destStart, headOfQueue.buffer, headOfQueue.byteOffset, bytesToCopy);
+            if (headOfQueue.byteLength === bytesToCopy) {
+                queue.shift();
+            } else {
+                headOfQueue.byteOffset += bytesToCopy;
+                headOfQueue.byteLength -= bytesToCopy;
+            }
+            controller.queueTotalSize -= bytesToCopy;
+            readableByteStreamControllerFillHeadPullIntoDescriptor(controller, bytesToCopy, pullIntoDescriptor);
+            totalBytesToCopyRemaining -= bytesToCopy;
+        }
+        return ready;
+    }
+
+    function readableByteStreamControllerFillReadRequestFromQueue(controller, readRequest) {
+        const entry = controller.queue.shift();
+        controller.queueTotalSize -= entry.byteLength;
+        readableByteStreamControllerHandleQueueDrain(controller);
+        readRequest.chunk(new Uint8Array(entry.buffer, entry.byteOffset, entry.byteLength));
+    }
+
+    function readableByteStreamControllerGetBYOBRequest(controller) {
+        if (controller.byobRequest === null && controller.pendingPullIntos.length > 0) {
+            const firstDescriptor = controller.pendingPullIntos[0];
+            const view = new Uint8Array(
+                firstDescriptor.buffer,
+                firstDescriptor.byteOffset + firstDescriptor.bytesFilled,
+                firstDescriptor.byteLength - firstDescriptor.bytesFilled,
+            );
+            controller.byobRequest = wrap(ReadableStreamBYOBRequest, { object: undefined, controller, view });
+        }
+        return controller.byobRequest;
+    }
+
+    function readableByteStreamControllerGetDesiredSize(controller) {
+        const state = controller.stream.state;
+        if (state === "errored") {
+            return null;
+        }
+        if (state === "closed") {
+            return 0;
+        }
+        return controller.strategyHWM - controller.queueTotalSize;
+    }
+
+    function readableByteStreamControllerHandleQueueDrain(controller) {
+        if (controller.queueTotalSize === 0 && controller.closeRequested) {
+            readableByteStreamControllerClearAlgorithms(controller);
+            readableStreamClose(controller.stream);
+        } else {
+            readableByteStreamControllerCallPullIfNeeded(controller);
+        }
+    }
+
+    function readableByteStreamControllerInvalidateBYOBRequest(controller) {
+        if (controller.byobRequest === null) {
+            return;
+        }
+        controller.byobRequest.controller = undefined;
+        controller.byobRequest.view = null;
+        controller.byobRequest = null;
+    }
+
+    function readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(controller) {
+        const filledPullIntos = [];
+        while (controller.pendingPullIntos.length > 0) {
+            if (controller.queueTotalSize === 0) {
+                break;
+            }
+            const pullIntoDescriptor = controller.pendingPullIntos[0];
+            if (readableByteStreamControllerFillPullIntoDescriptorFromQueue(controller, pullIntoDescriptor)) {
+                readableByteStreamControllerShiftPendingPullInto(controller);
+                filledPullIntos.push(pullIntoDescriptor);
+            }
+        }
+        return filledPullIntos;
+    }
+
+    function readableByteStreamControllerProcessReadRequestsUsingQueue(controller) {
+        const reader = controller.stream.reader;
+        while (reader.readRequests.length > 0) {
+            if (controller.queueTotalSize === 0) {
+                return;
+            }
+            const readRequest = reader.readRequests.shift();
+            readableByteStreamControllerFillReadRequestFromQueue(controller, readRequest);
+        }
+    }
+
+    function readableByteStreamControllerPullInto(controller, view, min, readIntoRequest) {
+        const stream = controller.stream;
+        const name = typedArrayName.call(view);
+        const ctor = name === undefined ? DataView : typedArrays[name];
+        const elementSize = name === undefined ? 1 : ctor.BYTES_PER_ELEMENT;
+        const minimumFill = min * elementSize;
+        const { byteOffset, byteLength } = view;
+        let buffer;
+        try {
+            buffer = transferArrayBuffer(view.buffer);
+        } catch (error) {
+            readIntoRequest.error(error);
+            return;
+        }
+        const pullIntoDescriptor = {
+            buffer,
+            bufferByteLength: arrayBufferByteLength.call(buffer),
+            byteOffset,
+            byteLength,
+            bytesFilled: 0,
+            minimumFill,
+            elementSize,
+            viewConstructor: ctor,
+            readerType: "byob",
+        };
+        if (controller.pendingPullIntos.length > 0) {
+            controller.pendingPullIntos.push(pullIntoDescriptor);
+            readableStreamAddReadIntoRequest(stream, readIntoRequest);
+            return;
+        }
+        if (stream.state === "closed") {
+            readIntoRequest.close(new ctor(pullIntoDescriptor.buffer, pullIntoDescriptor.byteOffset, 0));
+            return;
+        }
+        if (controller.queueTotalSize > 0) {
+            if (readableByteStreamControllerFillPullIntoDescriptorFromQueue(controller, pullIntoDescriptor)) {
+                const filledView = readableByteStreamControllerConvertPullIntoDescriptor(pullIntoDescriptor);
+                readableByteStreamControllerHandleQueueDrain(controller);
+                readIntoRequest.chunk(filledView);
+                return;
+            }
+            if (controller.closeRequested) {
+                const e = new TypeError("Insufficient bytes to fill elements in the given buffer");
+                readableByteStreamControllerError(controller, e);
+                readIntoRequest.error(e);
+                return;
+            }
+        }
+        controller.pendingPullIntos.push(pullIntoDescriptor);
+        readableStreamAddReadIntoRequest(stream, readIntoRequest);
+        readableByteStreamControllerCallPullIfNeeded(controller);
+    }
+
+    function readableByteStreamControllerRespond(controller, bytesWritten) {
+        const firstDescriptor = controller.pendingPullIntos[0];
+        const state = controller.stream.state;
+        if (state === "closed") {
+            if (bytesWritten !== 0) {
+                throw new TypeError("bytesWritten must be 0 when calling respond() on a closed stream");
+            }
+        } else {
+            if (bytesWritten === 0) {
+                throw new TypeError("bytesWritten must be greater than 0 when calling respond() on a readable stream");
+            }
+            if (firstDescriptor.bytesFilled + bytesWritten > firstDescriptor.byteLength) {
+                throw new RangeError("bytesWritten out of range");
+            }
+        }
+        firstDescriptor.buffer = transferArrayBuffer(firstDescriptor.buffer);
+        readableByteStreamControllerRespondInternal(controller, bytesWritten);
+    }
+
+    function readableByteStreamControllerRespondInClosedState(controller, firstDescriptor) {
+        if (firstDescriptor.readerType === "none") {
+            readableByteStreamControllerShiftPendingPullInto(controller);
+        }
+        const stream = controller.stream;
+        if (readableStreamHasBYOBReader(stream)) {
+            const filledPullIntos = [];
+            while (filledPullIntos.length < readableStreamGetNumReadIntoRequests(stream)) {
+                filledPullIntos.push(readableByteStreamControllerShiftPendingPullInto(controller));
+            }
+            for (const filledPullInto of filledPullIntos) {
+                readableByteStreamControllerCommitPullIntoDescriptor(stream, filledPullInto);
+            }
+        }
+    }
+
+    function readableByteStreamControllerRespondInReadableState(controller, bytesWritten, pullIntoDescriptor) {
+        readableByteStreamControllerFillHeadPullIntoDescriptor(controller, bytesWritten, pullIntoDescriptor);
+        if (pullIntoDescriptor.readerType === "none") {
+            readableByteStreamControllerEnqueueDetachedPullIntoToQueue(controller, pullIntoDescriptor);
+            const filledPullIntos = readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(controller);
+            for (const filledPullInto of filledPullIntos) {
+                readableByteStreamControllerCommitPullIntoDescriptor(controller.stream, filledPullInto);
+            }
+            return;
+        }
+        if (pullIntoDescriptor.bytesFilled < pullIntoDescriptor.minimumFill) {
+            return;
+        }
+        readableByteStreamControllerShiftPendingPullInto(controller);
+        const remainderSize = pullIntoDescriptor.bytesFilled % pullIntoDescriptor.elementSize;
+        if (remainderSize > 0) {
+            const end = pullIntoDescriptor.byteOffset + pullIntoDescriptor.bytesFilled;
+            readableByteStreamControllerEnqueueClonedChunkToQueue(controller, pullIntoDescriptor.buffer, end - remainderSize, remainderSize);
+        }
+        pullIntoDescriptor.bytesFilled -= remainderSize;
+        const filledPullIntos = readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(controller);
+        readableByteStreamControllerCommitPullIntoDescriptor(controller.stream, pullIntoDescriptor);
+        for (const filledPullInto of filledPullIntos) {
+            readableByteStreamControllerCommitPullIntoDescriptor(controller.stream, filledPullInto);
+        }
+    }
+
+    function readableByteStreamControllerRespondInternal(controller, bytesWritten) {
+        const firstDescriptor = controller.pendingPullIntos[0];
+        readableByteStreamControllerInvalidateBYOBRequest(controller);
+        if (controller.stream.state === "closed") {
+            readableByteStreamControllerRespondInClosedState(controller, firstDescriptor);
+        } else {
+            readableByteStreamControllerRespondInReadableState(controller, bytesWritten, firstDescriptor);
+        }
+        readableByteStreamControllerCallPullIfNeeded(controller);
+    }
+
+    function readableByteStreamControllerRespondWithNewView(controller, view) {
+        const firstDescriptor = controller.pendingPullIntos[0];
+        const state = controller.stream.state;
+        if (state === "closed") {
+            if (view.byteLength !== 0) {
+                throw new TypeError("The view's length must be 0 when calling respondWithNewView() on a closed stream");
+            }
+        } else if (view.byteLength === 0) {
+            throw new TypeError("The view's length must be greater than 0 when calling respondWithNewView() on a readable stream");
+        }
+        if (firstDescriptor.byteOffset + firstDescriptor.bytesFilled !== view.byteOffset) {
+            throw new RangeError("The region specified by view does not match byobRequest");
+        }
+        if (firstDescriptor.bufferByteLength !== arrayBufferByteLength.call(view.buffer)) {
+            throw new RangeError("The buffer of view has different capacity than byobRequest");
+        }
+        if (firstDescriptor.bytesFilled + view.byteLength > firstDescriptor.byteLength) {
+            throw new RangeError("The region specified by view is larger than byobRequest");
+        }
+        const viewByteLength = view.byteLength;
+        firstDescriptor.buffer = transferArrayBuffer(view.buffer);
+        readableByteStreamControllerRespondInternal(controller, viewByteLength);
+    }
+
+    function readableByteStreamControllerShiftPendingPullInto(controller) {
+        return controller.pendingPullIntos.shift();
+    }
+
+    function readableByteStreamControllerShouldCallPull(controller) {
+        const stream = controller.stream;
+        if (stream.state !== "readable" || controller.closeRequested || !controller.started) {
+            return false;
+        }
+        if (readableStreamHasDefaultReader(stream) && readableStreamGetNumReadRequests(stream) > 0) {
+            return true;
+        }
+        if (readableStreamHasBYOBReader(stream) && readableStreamGetNumReadIntoRequests(stream) > 0) {
+            return true;
+        }
+        return readableByteStreamControllerGetDesiredSize(controller) > 0;
+    }
+
+    function readableByteStreamControllerCancelSteps(controller, reason) {
+        readableByteStreamControllerClearPendingPullIntos(controller);
+        resetQueue(controller);
+        const result = controller.cancelAlgorithm(reason);
+        readableByteStreamControllerClearAlgorithms(controller);
+        return result;
+    }
+
+    function readableByteStreamControllerPullSteps(controller, readRequest) {
+        const stream = controller.stream;
+        if (controller.queueTotalSize > 0) {
+            readableByteStreamControllerFillReadRequestFromQueue(controller, readRequest);
+            return;
+        }
+        const autoAllocateChunkSize = controller.autoAllocateChunkSize;
+        if (autoAllocateChunkSize !== undefined) {
+            let buffer;
+            try {
+                buffer = new ArrayBuffer(autoAllocateChunkSize);
+            } catch (error) {
+                readRequest.error(error);
+                return;
+            }
+            controller.pendingPullIntos.push({
+                buffer,
+                bufferByteLength: autoAllocateChunkSize,
+                byteOffset: 0,
+                byteLength: autoAllocateChunkSize,
+                bytesFilled: 0,
+                minimumFill: 1,
+                elementSize: 1,
+                viewConstructor: Uint8Array,
+                readerType: "default",
+            });
+        }
+        readableStreamAddReadRequest(stream, readRequest);
+        readableByteStreamControllerCallPullIfNeeded(controller);
+    }
+
+    function readableByteStreamControllerReleaseSteps(controller) {
+        if (controller.pendingPullIntos.length > 0) {
+            const firstPendingPullInto = controller.pendingPullIntos[0];
+            firstPendingPullInto.readerType = "none";
+            controller.pendingPullIntos = [firstPendingPullInto];
+        }
+    }
+
+    function setUpReadableByteStreamController(
+        stream,
+        controller,
+        startAlgorithm,
+        pullAlgorithm,
+        cancelAlgorithm,
+        highWaterMark,
+        autoAllocateChunkSize,
+    ) {
+        controller.stream = stream;
+        controller.pullAgain = false;
+        controller.pulling = false;
+        controller.byobRequest = null;
+        resetQueue(controller);
+        controller.closeRequested = false;
+        controller.started = false;
+        controller.strategyHWM = highWaterMark;
+        controller.pullAlgorithm = pullAlgorithm;
+        controller.cancelAlgorithm = cancelAlgorithm;
+        controller.autoAllocateChunkSize = autoAllocateChunkSize;
+        controller.pendingPullIntos = [];
+        stream.controller = controller;
+        const startResult = startAlgorithm();
+        uponPromise(
+            promiseResolve(startResult),
+            () => {
+                controller.started = true;
+                readableByteStreamControllerCallPullIfNeeded(controller);
+            },
+            (r) => readableByteStreamControllerError(controller, r),
+        );
+    }
+
+    function setUpReadableByteStreamControllerFromUnderlyingSource(stream, underlyingSource, underlyingSourceDict, highWaterMark) {
+        const controller = wrap(ReadableByteStreamController, { object: undefined, type: "bytes" });
+        const { start, pull, cancel, autoAllocateChunkSize } = underlyingSourceDict;
+        const startAlgorithm = start === undefined ? () => undefined : () => start.call(underlyingSource, controller.object);
+        const pullAlgorithm =
+            pull === undefined ? resolvedUndefined : () => promiseCall(pull, underlyingSource, controller.object);
+        const cancelAlgorithm =
+            cancel === undefined ? resolvedUndefined : (reason) => promiseCall(cancel, underlyingSource, reason);
+        if (autoAllocateChunkSize === 0) {
+            throw new TypeError("autoAllocateChunkSize must be greater than 0");
+        }
+        setUpReadableByteStreamController(
+            stream,
+            controller,
+            startAlgorithm,
+            pullAlgorithm,
+            cancelAlgorithm,
+            highWaterMark,
+            autoAllocateChunkSize,
+        );
+    }
+
+    // https://streams.spec.whatwg.org/#ws-abstract-ops
+    function newWritableStream() {
+        return {
+            object: undefined,
+            state: "writable",
+            storedError: undefined,
+            writer: undefined,
+            controller: undefined,
+            inFlightWriteRequest: undefined,
+            closeRequest: undefined,
+            inFlightCloseRequest: undefined,
+            pendingAbortRequest: undefined,
+            writeRequests: [],
+            backpressure: false,
+        };
+    }
+
+    function acquireWritableStreamDefaultWriter(stream) {
+        const writer = wrap(WritableStreamDefaultWriter, { object: undefined });
+        setUpWritableStreamDefaultWriter(writer, stream);
+        return writer;
+    }
+
+    function createWritableStream(startAlgorithm, writeAlgorithm, closeAlgorithm, abortAlgorithm, highWaterMark, sizeAlgorithm) {
+        const stream = wrap(WritableStream, newWritableStream());
+        const controller = wrap(WritableStreamDefaultController, { object: undefined });
+        setUpWritableStreamDefaultController(
+            stream,
+            controller,
+            startAlgorithm,
+            writeAlgorithm,
+            closeAlgorithm,
+            abortAlgorithm,
+            highWaterMark,
+            sizeAlgorithm,
+        );
+        return stream;
+    }
+
+    function isWritableStreamLocked(stream) {
+        return stream.writer !== undefined;
+    }
+
+    function writableStreamAbort(stream, reason) {
+        if (stream.state === "closed" || stream.state === "errored") {
+            return resolvedUndefined();
+        }
+        stream.controller.abortController?.abort(reason);
+        const state = stream.state;
+        if (state === "closed" || state === "errored") {
+            return resolvedUndefined();
+        }
+        if (stream.pendingAbortRequest !== undefined) {
+            return stream.pendingAbortRequest.promise.promise;
+        }
+        let wasAlreadyErroring = false;
+        if (state === "erroring") {
+            wasAlreadyErroring = true;
+            reason = undefined;
+        }
+        const promise = createDeferred();
+        stream.pendingAbortRequest = { promise, reason, wasAlreadyErroring };
+        if (!wasAlreadyErroring) {
+            writableStreamStartErroring(stream, reason);
+        }
+        return promise.promise;
+    }
+
+    function writableStreamClose(stream) {
+        const state = stream.state;
+        if (state === "closed" || state === "errored") {
+            return promiseReject(new TypeError("The stream is not in the writable state and cannot be closed"));
+        }
+        const promise = createDeferred();
+        stream.closeRequest = promise;
+        const writer = stream.writer;
+        if (writer !== undefined && stream.backpressure && state === "writable") {
+            writer.ready.resolve(undefined);
+        }
+        writableStreamDefaultControllerClose(stream.controller);
+        return promise.promise;
+    }
+
+    // https://streams.spec.whatwg.org/#ws-abstract-ops-used-by-controllers
+    function writableStreamAddWriteRequest(stream) {
+        const promise = createDeferred();
+        stream.writeRequests.push(promise);
+        return promise.promise;
+    }
+
+    function writableStreamDealWithRejection(stream, error) {
+        if (stream.state === "writable") {
+            writableStreamStartErroring(stream, error);
+            return;
+        }
+        writableStreamFinishErroring(stream);
+    }
+
+    function writableStreamFinishErroring(stream) {
+        stream.state = "errored";
+        resetQueue(stream.controller);
+        const storedError = stream.storedError;
+        for (const writeRequest of stream.writeRequests) {
+            writeRequest.reject(storedError);
+        }
+        stream.writeRequests = [];
+        if (stream.pendingAbortRequest === undefined) {
+            writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+            return;
+        }
+        const abortRequest = stream.pendingAbortRequest;
+        stream.pendingAbortRequest = undefined;
+        if (abortRequest.wasAlreadyErroring) {
+            abortRequest.promise.reject(storedError);
+            writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+            return;
+        }
+        const promise = writableStreamDefaultControllerAbortSteps(stream.controller, abortRequest.reason);
+        uponPromise(
+            promise,
+            () => {
+                abortRequest.promise.resolve(undefined);
+                writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+            },
+            (reason) => {
+                abortRequest.promise.reject(reason);
+                writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+            },
+        );
+    }
+
+    function writableStreamFinishInFlightClose(stream) {
+        stream.inFlightCloseRequest.resolve(undefined);
+        stream.inFlightCloseRequest = undefined;
+        if (stream.state === "erroring") {
+            stream.storedError = undefined;
+            if (stream.pendingAbortRequest !== undefined) {
+                stream.pendingAbortRequest.promise.resolve(undefined);
+                stream.pendingAbortRequest = undefined;
+            }
+        }
+        stream.state = "closed";
+        if (stream.writer !== undefined) {
+            stream.writer.closed.resolve(undefined);
+        }
+    }
+
+    function writableStreamFinishInFlightCloseWithError(stream, error) {
+        stream.inFlightCloseRequest.reject(error);
+        stream.inFlightCloseRequest = undefined;
+        if (stream.pendingAbortRequest !== undefined) {
+            stream.pendingAbortRequest.promise.reject(error);
+            stream.pendingAbortRequest = undefined;
+        }
+        writableStreamDealWithRejection(stream, error);
+    }
+
+    function writableStreamFinishInFlightWrite(stream) {
+        stream.inFlightWriteRequest.resolve(undefined);
+        stream.inFlightWriteRequest = undefined;
+    }
+
+    function writableStreamFinishInFlightWriteWithError(stream, error) {
+        stream.inFlightWriteRequest.reject(error);
+        stream.inFlightWriteRequest = undefined;
+        writableStreamDealWithRejection(stream, error);
+    }
+
+    function writableStreamCloseQueuedOrInFlight(stream) {
+        return stream.closeRequest !== undefined || stream.inFlightCloseRequest !== undefined;
+    }
+
+    function writableStreamHasOperationMarkedInFlight(stream) {
+        return stream.inFlightWriteRequest !== undefined || stream.inFlightCloseRequest !== undefined;
+    }
+
+    function writableStreamMarkCloseRequestInFlight(stream) {
+        stream.inFlightCloseRequest = stream.closeRequest;
+        stream.closeRequest = undefined;
+    }
+
+    function writableStreamMarkFirstWriteRequestInFlight(stream) {
+        stream.inFlightWriteRequest = stream.writeRequests.shift();
+    }
+
+    function writableStreamRejectCloseAndClosedPromiseIfNeeded(stream) {
+        if (stream.closeRequest !== undefined) {
+            stream.closeRequest.reject(stream.storedError);
+            stream.closeRequest = undefined;
+        }
+        const writer = stream.writer;
+        if (writer !== undefined) {
+            writer.closed.reject(stream.storedError);
+            setPromiseIsHandled(writer.closed.promise);
+        }
+    }
+
+    function writableStreamStartErroring(stream, reason) {
+        const controller = stream.controller;
+        stream.state = "erroring";
+        stream.storedError = reason;
+        const writer = stream.writer;
+        if (writer !== undefined) {
+            writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, reason);
+        }
+        if (!writableStreamHasOperationMarkedInFlight(stream) && controller.started) {
+            writableStreamFinishErroring(stream);
+        }
+    }
+
+    function writableStreamUpdateBackpressure(stream, backpressure) {
+        const writer = stream.writer;
+        if (writer !== undefined && backpressure !== stream.backpressure) {
+            if (backpressure) {
+                writer.ready = createDeferred();
+            } else {
+                writer.ready.resolve(undefined);
+            }
+        }
+        stream.backpressure = backpressure;
+    }
+
+    // https://streams.spec.whatwg.org/#ws-writer-abstract-ops
+    function writableStreamDefaultWriterAbort(writer, reason) {
+        return writableStreamAbort(writer.stream, reason);
+    }
+
+    function writableStreamDefaultWriterClose(writer) {
+        return writableStreamClose(writer.stream);
+    }
+
+    function writableStreamDefaultWriterCloseWithErrorPropagation(writer) {
+        const stream = writer.stream;
+        const state = stream.state;
+        if (writableStreamCloseQueuedOrInFlight(stream) || state === "closed") {
+            return resolvedUndefined();
+        }
+        if (state === "errored") {
+            return promiseReject(stream.storedError);
+        }
+        return writableStreamDefaultWriterClose(writer);
+    }
+
+    function writableStreamDefaultWriterEnsureClosedPromiseRejected(writer, error) {
+        if (writer.closed.state === "pending") {
+            writer.closed.reject(error);
+        } else {
+            writer.closed = rejectedDeferred(error);
+        }
+        setPromiseIsHandled(writer.closed.promise);
+    }
+
+    function writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, error) {
+        if (writer.ready.state === "pending") {
+            writer.ready.reject(error);
+        } else {
+            writer.ready = rejectedDeferred(error);
+        }
+        setPromiseIsHandled(writer.ready.promise);
+    }
+
+    function writableStreamDefaultWriterGetDesiredSize(writer) {
+        const stream = writer.stream;
+        const state = stream.state;
+        if (state === "errored" || state === "erroring") {
+            return null;
+        }
+        if (state === "closed") {
+            return 0;
+        }
+        return writableStreamDefaultControllerGetDesiredSize(stream.controller);
+    }
+
+    function writableStreamDefaultWriterRelease(writer) {
+        const stream = writer.stream;
+        const releasedError = new TypeError("The writer was released");
+        writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, releasedError);
+        writableStreamDefaultWriterEnsureClosedPromiseRejected(writer, releasedError);
+        stream.writer = undefined;
+        writer.stream = undefined;
+    }
+
+    function writableStreamDefaultWriterWrite(writer, chunk) {
+        const stream = writer.stream;
+        const controller = stream.controller;
+        const chunkSize = writableStreamDefaultControllerGetChunkSize(controller, chunk);
+        if (stream !== writer.stream) {
+            return promiseReject(new TypeError("The writer was released"));
+        }
+        const state = stream.state;
+        if (state === "errored") {
+            return promiseReject(stream.storedError);
+        }
+        if (writableStreamCloseQueuedOrInFlight(stream) || state === "closed") {
+            return promiseReject(new TypeError("The stream is closing or closed"));
+        }
+        if (state === "erroring") {
+            return promiseReject(stream.storedError);
+        }
+        const promise = writableStreamAddWriteRequest(stream);
+        writableStreamDefaultControllerWrite(controller, chunk, chunkSize);
+        return promise;
+    }
+
+    function setUpWritableStreamDefaultWriter(writer, stream) {
+        if (isWritableStreamLocked(stream)) {
+            throw new TypeError("WritableStream is locked");
+        }
+        writer.stream = stream;
+        stream.writer = writer;
+        const state = stream.state;
+        if (state === "writable") {
+            if (!writableStreamCloseQueuedOrInFlight(stream) && stream.backpressure) {
+                writer.ready = createDeferred();
+            } else {
+                writer.ready = resolvedDeferred(undefined);
+            }
+            writer.closed = createDeferred();
+        } else if (state === "erroring") {
+            writer.ready = rejectedDeferred(stream.storedError);
+            writer.closed = createDeferred();
+        } else if (state === "closed") {
+            writer.ready = resolvedDeferred(undefined);
+            writer.closed = resolvedDeferred(undefined);
+        } else {
+            writer.ready = rejectedDeferred(stream.storedError);
+            writer.closed = rejectedDeferred(stream.storedError);
+        }
+    }
+
+    // https://streams.spec.whatwg.org/#ws-default-controller-abstract-ops
+    const closeSentinel = Symbol();
+
+    function setUpWritableStreamDefaultController(
+        stream,
+        controller,
+        startAlgorithm,
+        writeAlgorithm,
+        closeAlgorithm,
+        abortAlgorithm,
+        highWaterMark,
+        sizeAlgorithm,
+    ) {
+        controller.stream = stream;
+        stream.controller = controller;
+        resetQueue(controller);
+        controller.abortController = AbortController === undefined ? undefined : new AbortController();
+        controller.started = false;
+        controller.strategySizeAlgorithm = sizeAlgorithm;
+        controller.strategyHWM = highWaterMark;
+        controller.writeAlgorithm = writeAlgorithm;
+        controller.closeAlgorithm = closeAlgorithm;
+        controller.abortAlgorithm = abortAlgorithm;
+        writableStreamUpdateBackpressure(stream, writableStreamDefaultControllerGetBackpressure(controller));
+        const startResult = startAlgorithm();
+        uponPromise(
+            promiseResolve(startResult),
+            () => {
+                controller.started = true;
+                writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+            },
+            (r) => {
+                controller.started = true;
+                writableStreamDealWithRejection(stream, r);
+            },
+        );
+    }
+
+    function setUpWritableStreamDefaultControllerFromUnderlyingSink(
+        stream,
+        underlyingSink,
+        underlyingSinkDict,
+        highWaterMark,
+        sizeAlgorithm,
+    ) {
+        const controller = wrap(WritableStreamDefaultController, { object: undefined });
+        const { start, write, close, abort } = underlyingSinkDict;
+        const startAlgorithm = start === undefined ? () => undefined : () => start.call(underlyingSink, controller.object);
+        const writeAlgorithm =
+            write === undefined ? resolvedUndefined : (chunk) => promiseCall(write, underlyingSink, chunk, controller.object);
+        const closeAlgorithm = close === undefined ? resolvedUndefined : () => promiseCall(close, underlyingSink);
+        const abortAlgorithm =
+            abort === undefined ? resolvedUndefined : (reason) => promiseCall(abort, underlyingSink, reason);
+        setUpWritableStreamDefaultController(
+            stream,
+            controller,
+            startAlgorithm,
+            writeAlgorithm,
+            closeAlgorithm,
+            abortAlgorithm,
+            highWaterMark,
+            sizeAlgorithm,
+        );
+    }
+
+    function writableStreamDefaultControllerAdvanceQueueIfNeeded(controller) {
+        const stream = controller.stream;
+        if (!controller.started || stream.inFlightWriteRequest !== undefined) {
+            return;
+        }
+        if (stream.state === "erroring") {
+            writableStreamFinishErroring(stream);
+            return;
+        }
+        if (controller.queue.length === 0) {
+            return;
+        }
+        const value = peekQueueValue(controller);
+        if (value === closeSentinel) {
+            writableStreamDefaultControllerProcessClose(controller);
+        } else {
+            writableStreamDefaultControllerProcessWrite(controller, value);
+        }
+    }
+
+    function writableStreamDefaultControllerClearAlgorithms(controller) {
+        controller.writeAlgorithm = undefined;
+        controller.closeAlgorithm = undefined;
+        controller.abortAlgorithm = undefined;
+        controller.strategySizeAlgorithm = undefined;
+    }
+
+    function writableStreamDefaultControllerClose(controller) {
+        enqueueValueWithSize(controller, closeSentinel, 0);
+        writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+    }
+
+    function writableStreamDefaultControllerError(controller, error) {
+        writableStreamDefaultControllerClearAlgorithms(controller);
+        writableStreamStartErroring(controller.stream, error);
+    }
+
+    function writableStreamDefaultControllerErrorIfNeeded(controller, error) {
+        if (controller.stream.state === "writable") {
+            writableStreamDefaultControllerError(controller, error);
+        }
+    }
+
+    function writableStreamDefaultControllerGetBackpressure(controller) {
+        return writableStreamDefaultControllerGetDesiredSize(controller) <= 0;
+    }
+
+    function writableStreamDefaultControllerGetChunkSize(controller, chunk) {
+        if (controller.strategySizeAlgorithm === undefined) {
+            return 1;
+        }
+        try {
+            return controller.strategySizeAlgorithm(chunk);
+        } catch (error) {
+            writableStreamDefaultControllerErrorIfNeeded(controller, error);
+            return 1;
+        }
+    }
+
+    function writableStreamDefaultControllerGetDesiredSize(controller) {
+        return controller.strategyHWM - controller.queueTotalSize;
+    }
+
+    function writableStreamDefaultControllerProcessClose(controller) {
+        const stream = controller.stream;
+        writableStreamMarkCloseRequestInFlight(stream);
+        dequeueValue(controller);
+        const sinkClosePromise = controller.closeAlgorithm();
+        writableStreamDefaultControllerClearAlgorithms(controller);
+        uponPromise(
+            sinkClosePromise,
+            () => writableStreamFinishInFlightClose(stream),
+            (reason) => writableStreamFinishInFlightCloseWithError(stream, reason),
+        );
+    }
+
+    function writableStreamDefaultControllerProcessWrite(controller, chunk) {
+        const stream = controller.stream;
+        writableStreamMarkFirstWriteRequestInFlight(stream);
+        const sinkWritePromise = controller.writeAlgorithm(chunk);
+        uponPromise(
+            sinkWritePromise,
+            () => {
+                writableStreamFinishInFlightWrite(stream);
+                const state = stream.state;
+                dequeueValue(controller);
+                if (!writableStreamCloseQueuedOrInFlight(stream) && state === "writable") {
+                    writableStreamUpdateBackpressure(stream, writableStreamDefaultControllerGetBackpressure(controller));
+                }
+                writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+            },
+            (reason) => {
+                if (stream.state === "writable") {
+                    writableStreamDefaultControllerClearAlgorithms(controller);
+                }
+                writableStreamFinishInFlightWriteWithError(stream, reason);
+            },
+        );
+    }
+
+    function writableStreamDefaultControllerWrite(controller, chunk, chunkSize) {
+        try {
+            enqueueValueWithSize(controller, chunk, chunkSize);
+        } catch (error) {
+            writableStreamDefaultControllerErrorIfNeeded(controller, error);
+            return;
+        }
+        const stream = controller.stream;
+        if (!writableStreamCloseQueuedOrInFlight(stream) && stream.state === "writable") {
+            writableStreamUpdateBackpressure(stream, writableStreamDefaultControllerGetBackpressure(controller));
+        }
+        writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+    }
+
+    function writableStreamDefaultControllerAbortSteps(controller, reason) {
+        const result = controller.abortAlgorithm(reason);
+        writableStreamDefaultControllerClearAlgorithms(controller);
+        return result;
+    }
+
+    // https://streams.spec.whatwg.org/#ts-abstract-ops
+    function initializeTransformStream(
+        stream,
+        startPromise,
+        writableHighWaterMark,
+        writableSizeAlgorithm,
+        readableHighWaterMark,
+        readableSizeAlgorithm,
+    ) {
+        const startAlgorithm = () => startPromise;
+        stream.writable = createWritableStream(
+            startAlgorithm,
+            (chunk) => transformStreamDefaultSinkWriteAlgorithm(stream, chunk),
+            () => transformStreamDefaultSinkCloseAlgorithm(stream),
+            (reason) => transformStreamDefaultSinkAbortAlgorithm(stream, reason),
+            writableHighWaterMark,
+            writableSizeAlgorithm,
+        );
+        stream.readable = createReadableStream(
+            startAlgorithm,
+            () => transformStreamDefaultSourcePullAlgorithm(stream),
+            (reason) => transformStreamDefaultSourceCancelAlgorithm(stream, reason),
+            readableHighWaterMark,
+            readableSizeAlgorithm,
+        );
+        stream.backpressure = undefined;
+        stream.backpressureChangePromise = undefined;
+        transformStreamSetBackpressure(stream, true);
+        stream.controller = undefined;
+    }
+
+    function transformStreamError(stream, e) {
+        readableStreamDefaultControllerError(stream.readable.controller, e);
+        transformStreamErrorWritableAndUnblockWrite(stream, e);
+    }
+
+    function transformStreamErrorWritableAndUnblockWrite(stream, e) {
+        transformStreamDefaultControllerClearAlgorithms(stream.controller);
+        writableStreamDefaultControllerErrorIfNeeded(stream.writable.controller, e);
+        transformStreamUnblockWrite(stream);
+    }
+
+    function transformStreamSetBackpressure(stream, backpressure) {
+        stream.backpressureChangePromise?.resolve(undefined);
+        stream.backpressureChangePromise = createDeferred();
+        stream.backpressure = backpressure;
+    }
+
+    function transformStreamUnblockWrite(stream) {
+        if (stream.backpressure) {
+            transformStreamSetBackpressure(stream, false);
+        }
+    }
+
+    // https://streams.spec.whatwg.org/#ts-default-controller-abstract-ops
+    function setUpTransformStreamDefaultController(stream, controller, transformAlgorithm, flushAlgorithm, cancelAlgorithm) {
+        controller.stream = stream;
+        stream.controller = controller;
+        controller.transformAlgorithm = transformAlgorithm;
+        controller.flushAlgorithm = flushAlgorithm;
+        controller.cancelAlgorithm = cancelAlgorithm;
+        controller.finishPromise = undefined;
+    }
+
+    function setUpTransformStreamDefaultControllerFromTransformer(stream, transformer, transformerDict) {
+        const controller = wrap(TransformStreamDefaultController, { object: undefined });
+        const { transform, flush, cancel } = transformerDict;
+        const transformAlgorithm =
+            transform === undefined
+                ? (chunk) => {
+                      try {
+                          transformStreamDefaultControllerEnqueue(controller, chunk);
+                      } catch (error) {
+                          return promiseReject(error);
+                      }
+                      return resolvedUndefined();
+                  }
+                : (chunk) => promiseCall(transform, transformer, chunk, controller.object);
+        const flushAlgorithm = flush === undefined ? resolvedUndefined : () => promiseCall(flush, transformer, controller.object);
+        const cancelAlgorithm =
+            cancel === undefined ? resolvedUndefined : (reason) => promiseCall(cancel, transformer, reason);
+        setUpTransformStreamDefaultController(stream, controller, transformAlgorithm, flushAlgorithm, cancelAlgorithm);
+    }
+
+    function transformStreamDefaultControllerClearAlgorithms(controller) {
+        controller.transformAlgorithm = undefined;
+        controller.flushAlgorithm = undefined;
+        controller.cancelAlgorithm = undefined;
+    }
+
+    function transformStreamDefaultControllerEnqueue(controller, chunk) {
+        const stream = controller.stream;
+        const readableController = stream.readable.controller;
+        if (!readableStreamDefaultControllerCanCloseOrEnqueue(readableController)) {
+            throw new TypeError("Readable side is not in a state that permits enqueue");
+        }
+        try {
+            readableStreamDefaultControllerEnqueue(readableController, chunk);
+        } catch (error) {
+            transformStreamErrorWritableAndUnblockWrite(stream, error);
+            throw stream.readable.storedError;
+        }
+        const backpressure = readableStreamDefaultControllerHasBackpressure(readableController);
+        if (backpressure !== stream.backpressure) {
+            transformStreamSetBackpressure(stream, true);
+        }
+    }
+
+    function transformStreamDefaultControllerError(controller, e) {
+        transformStreamError(controller.stream, e);
+    }
+
+    function transformStreamDefaultControllerPerformTransform(controller, chunk) {
+        const transformPromise_ = controller.transformAlgorithm(chunk);
+        return transformPromise(transformPromise_, undefined, (r) => {
+            transformStreamError(controller.stream, r);
+            throw r;
+        });
+    }
+
+    function transformStreamDefaultControllerTerminate(controller) {
+        const stream = controller.stream;
+        readableStreamDefaultControllerClose(stream.readable.controller);
+        transformStreamErrorWritableAndUnblockWrite(stream, new TypeError("The TransformStream has been terminated"));
+    }
+
+    // https://streams.spec.whatwg.org/#ts-default-sink-abstract-ops
+    function transformStreamDefaultSinkWriteAlgorithm(stream, chunk) {
+        const controller = stream.controller;
+        if (stream.backpressure) {
+            return transformPromise(stream.backpressureChangePromise.promise, () => {
+                const writable = stream.writable;
+                if (writable.state === "erroring") {
+                    throw writable.storedError;
+                }
+                return transformStreamDefaultControllerPerformTransform(controller, chunk);
+            });
+        }
+        return transformStreamDefaultControllerPerformTransform(controller, chunk);
+    }
+
+    function transformStreamDefaultSinkAbortAlgorithm(stream, reason) {
+        const controller = stream.controller;
+        if (controller.finishPromise !== undefined) {
+            return controller.finishPromise.promise;
+        }
+        const readable = stream.readable;
+        controller.finishPromise = createDeferred();
+        const cancelPromise = controller.cancelAlgorithm(reason);
+        transformStreamDefaultControllerClearAlgorithms(controller);
+        uponPromise(
+            cancelPromise,
+            () => {
+                if (readable.state === "errored") {
+                    controller.finishPromise.reject(readable.storedError);
+                } else {
+                    readableStreamDefaultControllerError(readable.controller, reason);
+                    controller.finishPromise.resolve(undefined);
+                }
+            },
+            (r) => {
+                readableStreamDefaultControllerError(readable.controller, r);
+                controller.finishPromise.reject(r);
+            },
+        );
+        return controller.finishPromise.promise;
+    }
+
+    function transformStreamDefaultSinkCloseAlgorithm(stream) {
+        const controller = stream.controller;
+        if (controller.finishPromise !== undefined) {
+            return controller.finishPromise.promise;
+        }
+        const readable = stream.readable;
+        controller.finishPromise = createDeferred();
+        const flushPromise = controller.flushAlgorithm();
+        transformStreamDefaultControllerClearAlgorithms(controller);
+        uponPromise(
+            flushPromise,
+            () => {
+                if (readable.state === "errored") {
+                    controller.finishPromise.reject(readable.storedError);
+                } else {
+                    readableStreamDefaultControllerClose(readable.controller);
+                    controller.finishPromise.resolve(undefined);
+                }
+            },
+            (r) => {
+                readableStreamDefaultControllerError(readable.controller, r);
+                controller.finishPromise.reject(r);
+            },
+        );
+        return controller.finishPromise.promise;
+    }
+
+    // https://streams.spec.whatwg.org/#ts-default-source-abstract-ops
+    function transformStreamDefaultSourceCancelAlgorithm(stream, reason) {
+        const controller = stream.controller;
+        if (controller.finishPromise !== undefined) {
+            return controller.finishPromise.promise;
+        }
+        const writable = stream.writable;
+        controller.finishPromise = createDeferred();
+        const cancelPromise = controller.cancelAlgorithm(reason);
+        transformStreamDefaultControllerClearAlgorithms(controller);
+        uponPromise(
+            cancelPromise,
+            () => {
+                if (writable.state === "errored") {
+                    controller.finishPromise.reject(writable.storedError);
+                } else {
+                    writableStreamDefaultControllerErrorIfNeeded(writable.controller, reason);
+                    transformStreamUnblockWrite(stream);
+                    controller.finishPromise.resolve(undefined);
+                }
+            },
+            (r) => {
+                writableStreamDefaultControllerErrorIfNeeded(writable.controller, r);
+                transformStreamUnblockWrite(stream);
+                controller.finishPromise.reject(r);
+            },
+        );
+        return controller.finishPromise.promise;
+    }
+
+    function transformStreamDefaultSourcePullAlgorithm(stream) {
+        transformStreamSetBackpressure(stream, false);
+        return stream.backpressureChangePromise.promise;
+    }
+
+    // https://streams.spec.whatwg.org/#transformstream-set-up, used by the
+    // transform streams of other standards.
+    function setUpTransformStream(transformAlgorithm, flushAlgorithm) {
+        const stream = wrap(TransformStream, {});
+        const toPromise = (algorithm) => (...args) => {
+            try {
+                return promiseResolve(algorithm(...args));
+            } catch (error) {
+                return promiseReject(error);
+            }
+        };
+        initializeTransformStream(stream, resolvedUndefined(), 1, () => 1, 0, () => 1);
+        const controller = wrap(TransformStreamDefaultController, { object: undefined });
+        setUpTransformStreamDefaultController(
+            stream,
+            controller,
+            toPromise((chunk) => transformAlgorithm(chunk, controller)),
+            toPromise(() => flushAlgorithm(controller)),
+            resolvedUndefined,
+        );
+        return stream;
+    }
+
+    class ReadableStream {
+        #stream;
+
+        static {
+            readableStreamRecord = (value) => (isObject(value) && #stream in value ? value.#stream : undefined);
+        }
+
+        constructor(underlyingSource = undefined, strategy = undefined) {
+            if (underlyingSource === constructorKey) {
+                this.#stream = strategy;
+                return;
+            }
+            const context = "Failed to construct 'ReadableStream'";
+            if (underlyingSource !== undefined && underlyingSource !== null && !isObject(underlyingSource)) {
+                throw new TypeError(`${context}: The provided value is not of type 'object'`);
+            }
+            strategy = toQueuingStrategy(strategy, context);
+            const { autoAllocateChunkSize, cancel, pull, start, type } = toDictionary(underlyingSource, context, "UnderlyingSource");
+            const underlyingSourceDict = {
+                autoAllocateChunkSize:
+                    autoAllocateChunkSize === undefined ? undefined : toEnforcedUnsignedLongLong(autoAllocateChunkSize, context),
+                cancel: toCallback(cancel, context, "cancel"),
+                pull: toCallback(pull, context, "pull"),
+                start: toCallback(start, context, "start"),
+                type: type === undefined ? undefined : `${type}`,
+            };
+            if (underlyingSourceDict.type !== undefined && underlyingSourceDict.type !== "bytes") {
+                throw new TypeError(`${context}: The provided value '${underlyingSourceDict.type}' is not a valid enum value of type ReadableStreamType`);
+            }
+            const stream = newReadableStream();
+            stream.object = this;
+            this.#stream = stream;
+            if (underlyingSourceDict.type === "bytes") {
+                if (strategy.size !== undefined) {
+                    throw new RangeError(`${context}: The strategy for a byte stream cannot have a size function`);
+                }
+                const highWaterMark = extractHighWaterMark(strategy, 0);
+                setUpReadableByteStreamControllerFromUnderlyingSource(stream, underlyingSource, underlyingSourceDict, highWaterMark);
+            } else {
+                const sizeAlgorithm = extractSizeAlgorithm(strategy);
+                const highWaterMark = extractHighWaterMark(strategy, 1);
+                setUpReadableStreamDefaultControllerFromUnderlyingSource(
+                    stream,
+                    underlyingSource,
+                    underlyingSourceDict,
+                    highWaterMark,
+                    sizeAlgorithm,
+                );
+            }
+        }
+
+        static from(asyncIterable) {
+            checkArgs(arguments, 1, "from");
+            return readableStreamFromIterable(asyncIterable).object;
+        }
+
+        get locked() {
+            return isReadableStreamLocked(this.#stream);
+        }
+
+        cancel(reason = undefined) {
+            if (isReadableStreamLocked(this.#stream)) {
+                return promiseReject(new TypeError("Cannot cancel a locked ReadableStream"));
+            }
+            return readableStreamCancel(this.#stream, reason);
+        }
+
+        getReader(options = undefined) {
+            const context = "Failed to execute 'getReader' on 'ReadableStream'";
+            const { mode } = toDictionary(options, context, "ReadableStreamGetReaderOptions");
+            if (mode === undefined) {
+                return acquireReadableStreamDefaultReader(this.#stream).object;
+            }
+            if (`${mode}` !== "byob") {
+                throw new TypeError(`${context}: The provided value '${mode}' is not a valid enum value of type ReadableStreamReaderMode`);
+            }
+            return acquireReadableStreamBYOBReader(this.#stream).object;
+        }
+
+        pipeThrough(transform, options = undefined) {
+            checkArgs(arguments, 1, "pipeThrough");
+            const context = "Failed to execute 'pipeThrough' on 'ReadableStream'";
+            const { readable, writable } = toDictionary(transform, context, "ReadableWritablePair");
+            const readableRecord = readableStreamRecord(readable);
+            if (readableRecord === undefined) {
+                throw new TypeError(`${context}: Failed to read the 'readable' property from 'ReadableWritablePair'`);
+            }
+            const writableRecord = writableStreamRecord(writable);
+            if (writableRecord === undefined) {
+                throw new TypeError(`${context}: Failed to read the 'writable' property from 'ReadableWritablePair'`);
+            }
+            const { preventAbort, preventCancel, preventClose, signal } = toStreamPipeOptions(options, context);
+            if (isReadableStreamLocked(this.#stream)) {
+                throw new TypeError(`${context}: Cannot pipe a locked stream`);
+            }
+            if (isWritableStreamLocked(writableRecord)) {
+                throw new TypeError(`${context}: Cannot pipe to a locked stream`);
+            }
+            const promise = readableStreamPipeTo(this.#stream, writableRecord, preventClose, preventAbort, preventCancel, signal);
+            setPromiseIsHandled(promise);
+            return readable;
+        }
+
+        pipeTo(destination, options = undefined) {
+            const context = "Failed to execute 'pipeTo' on 'ReadableStream'";
+            const dest = writableStreamRecord(destination);
+            if (dest === undefined) {
+                return promiseReject(new TypeError(`${context}: parameter 1 is not of type 'WritableStream'`));
+            }
+            let pipeOptions;
+            try {
+                pipeOptions = toStreamPipeOptions(options, context);
+            } catch (error) {
+                return promiseReject(error);
+            }
+            if (isReadableStreamLocked(this.#stream)) {
+                return promiseReject(new TypeError(`${context}: Cannot pipe a locked stream`));
+            }
+            if (isWritableStreamLocked(dest)) {
+                return promiseReject(new TypeError(`${context}: Cannot pipe to a locked stream`));
+            }
+            const { preventAbort, preventCancel, preventClose, signal } = pipeOptions;
+            return readableStreamPipeTo(this.#stream, dest, preventClose, preventAbort, preventCancel, signal);
+        }
+
+        tee() {
+            return readableStreamTee(this.#stream).map((branch) => branch.object);
+        }
+
+        values(options = undefined) {
+            const context = "Failed to execute 'values' on 'ReadableStream'";
+            const { preventCancel } = toDictionary(options, context, "ReadableStreamIteratorOptions");
+            const reader = acquireReadableStreamDefaultReader(this.#stream);
+            return new ReadableStreamAsyncIterator(constructorKey, { reader, preventCancel: Boolean(preventCancel) });
+        }
+
+        [Symbol.asyncIterator](options = undefined) {
+            return this.values(options);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ReadableStream";
+        }
+    }
+
+    function toStreamPipeOptions(options, context) {
+        const { preventAbort, preventCancel, preventClose, signal } = toDictionary(options, context, "StreamPipeOptions");
+        if (signal !== undefined && (AbortSignal === undefined || !(signal instanceof AbortSignal))) {
+            throw new TypeError(`${context}: member signal is not of type 'AbortSignal'`);
+        }
+        return {
+            preventAbort: Boolean(preventAbort),
+            preventCancel: Boolean(preventCancel),
+            preventClose: Boolean(preventClose),
+            signal,
+        };
+    }
+
+    // https://webidl.spec.whatwg.org/#es-asynchronous-iterator-prototype-object
+    class ReadableStreamAsyncIterator {
+        #reader;
+        #preventCancel;
+        #ongoingPromise = undefined;
+        #isFinished = false;
+
+        constructor(key, { reader, preventCancel }) {
+            if (key !== constructorKey) {
+                throw new TypeError("Illegal constructor");
+            }
+            this.#reader = reader;
+            this.#preventCancel = preventCancel;
+        }
+
+        #nextSteps() {
+            if (this.#isFinished) {
+                return promiseResolve({ value: undefined, done: true });
+            }
+            const reader = this.#reader;
+            const promise = createDeferred();
+            readableStreamDefaultReaderRead(reader, {
+                chunk: (chunk) => promise.resolve({ value: chunk, done: false }),
+                close: () => {
+                    readableStreamDefaultReaderRelease(reader);
+                    promise.resolve(undefined);
+                },
+                error: (e) => {
+                    readableStreamDefaultReaderRelease(reader);
+                    promise.reject(e);
+                },
+            });
+            return transformPromise(
+                promise.promise,
+                (result) => {
+                    this.#ongoingPromise = undefined;
+                    if (result === undefined) {
+                        this.#isFinished = true;
+                        return { value: undefined, done: true };
+                    }
+                    return result;
+                },
+                (reason) => {
+                    this.#ongoingPromise = undefined;
+                    this.#isFinished = true;
+                    throw reason;
+                },
+            );
+        }
+
+        #returnSteps(value) {
+            if (this.#isFinished) {
+                return promiseResolve({ value, done: true });
+            }
+            this.#isFinished = true;
+            const reader = this.#reader;
+            if (!this.#preventCancel) {
+                const result = readableStreamReaderGenericCancel(reader, value);
+                readableStreamDefaultReaderRelease(reader);
+                return result;
+            }
+            readableStreamDefaultReaderRelease(reader);
+            return resolvedUndefined();
+        }
+
+        next() {
+            const nextSteps = () => this.#nextSteps();
+            this.#ongoingPromise =
+                this.#ongoingPromise === undefined ? nextSteps() : transformPromise(this.#ongoingPromise, nextSteps, nextSteps);
+            return this.#ongoingPromise;
+        }
+
+        return(value = undefined) {
+            const returnSteps = () => this.#returnSteps(value);
+            const returnPromise =
+                this.#ongoingPromise === undefined
+                    ? returnSteps()
+                    : transformPromise(this.#ongoingPromise, returnSteps, returnSteps);
+            return transformPromise(returnPromise, () => ({ value, done: true }));
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ReadableStream AsyncIterator";
+        }
+    }
+
+    Object.setPrototypeOf(ReadableStreamAsyncIterator.prototype, AsyncIteratorPrototype);
+
+    class ReadableStreamDefaultReader {
+        #reader;
+
+        static {
+            const record = (value) => (isObject(value) && #reader in value ? value.#reader : undefined);
+            const previous = readerRecord;
+            readerRecord = (value) => record(value) ?? previous?.(value);
+        }
+
+        constructor(stream) {
+            if (stream === constructorKey) {
+                this.#reader = arguments[1];
+                return;
+            }
+            checkArgs(arguments, 1, "ReadableStreamDefaultReader");
+            const record = readableStreamRecord(stream);
+            if (record === undefined) {
+                throw new TypeError("Failed to construct 'ReadableStreamDefaultReader': parameter 1 is not of type 'ReadableStream'");
+            }
+            this.#reader = { object: this, type: "default" };
+            setUpReadableStreamDefaultReader(this.#reader, record);
+        }
+
+        get closed() {
+            return this.#reader.closed.promise;
+        }
+
+        cancel(reason = undefined) {
+            if (this.#reader.stream === undefined) {
+                return promiseReject(new TypeError("This readable stream reader has been released and cannot be used to cancel its previous owner stream"));
+            }
+            return readableStreamReaderGenericCancel(this.#reader, reason);
+        }
+
+        read() {
+            if (this.#reader.stream === undefined) {
+                return promiseReject(new TypeError("This readable stream reader has been released and cannot be used to read from its previous owner stream"));
+            }
+            const promise = createDeferred();
+            readableStreamDefaultReaderRead(this.#reader, {
+                chunk: (chunk) => promise.resolve({ value: chunk, done: false }),
+                close: () => promise.resolve({ value: undefined, done: true }),
+                error: (e) => promise.reject(e),
+            });
+            return promise.promise;
+        }
+
+        releaseLock() {
+            if (this.#reader.stream !== undefined) {
+                readableStreamDefaultReaderRelease(this.#reader);
+            }
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ReadableStreamDefaultReader";
+        }
+    }
+
+    class ReadableStreamBYOBReader {
+        #reader;
+
+        static {
+            const record = (value) => (isObject(value) && #reader in value ? value.#reader : undefined);
+            const previous = readerRecord;
+            readerRecord = (value) => record(value) ?? previous?.(value);
+        }
+
+        constructor(stream) {
+            if (stream === constructorKey) {
+                this.#reader = arguments[1];
+                return;
+            }
+            checkArgs(arguments, 1, "ReadableStreamBYOBReader");
+            const record = readableStreamRecord(stream);
+            if (record === undefined) {
+                throw new TypeError("Failed to construct 'ReadableStreamBYOBReader': parameter 1 is not of type 'ReadableStream'");
+            }
+            this.#reader = { object: this, type: "byob" };
+            setUpReadableStreamBYOBReader(this.#reader, record);
+        }
+
+        get closed() {
+            return this.#reader.closed.promise;
+        }
+
+        cancel(reason = undefined) {
+            if (this.#reader.stream === undefined) {
+                return promiseReject(new TypeError("This readable stream reader has been released and cannot be used to cancel its previous owner stream"));
+            }
+            return readableStreamReaderGenericCancel(this.#reader, reason);
+        }
+
+        read(view, options = undefined) {
+            const context = "Failed to execute 'read' on 'ReadableStreamBYOBReader'";
+            if (arguments.length < 1) {
+                return promiseReject(new TypeError(`${context}: 1 argument required, but only 0 present`));
+            }
+            if (!isArrayBufferView(view)) {
+                return promiseReject(new TypeError(`${context}: parameter 1 is not of type 'ArrayBufferView'`));
+            }
+            let min;
+            try {
+                const { min: value } = toDictionary(options, context, "ReadableStreamBYOBReaderReadOptions");
+                min = value === undefined ? 1 : toEnforcedUnsignedLongLong(value, context);
+            } catch (error) {
+                return promiseReject(error);
+            }
+            if (view.byteLength === 0) {
+                return promiseReject(new TypeError(`${context}: This readable stream reader cannot be used to read as the view has byte length equal to 0`));
+            }
+            if (arrayBufferByteLength.call(view.buffer) === 0) {
+                return promiseReject(new TypeError(`${context}: This readable stream reader cannot be used to read as the viewed array buffer has 0 byte length`));
+            }
+            if (isDetached(view.buffer)) {
+                return promiseReject(new TypeError(`${context}: This readable stream reader cannot be used to read as the viewed array buffer is detached`));
+            }
+            if (min === 0) {
+                return promiseReject(new TypeError(`${context}: options.min must be greater than 0`));
+            }
+            const length = typedArrayName.call(view) === undefined ? view.byteLength : view.length;
+            if (min > length) {
+                return promiseReject(new RangeError(`${context}: options.min must be less than or equal to the view's length`));
+            }
+            if (this.#reader.stream === undefined) {
+                return promiseReject(new TypeError("This readable stream reader has been released and cannot be used to read from its previous owner stream"));
+            }
+            const promise = createDeferred();
+            readableStreamBYOBReaderRead(this.#reader, view, min, {
+                chunk: (chunk) => promise.resolve({ value: chunk, done: false }),
+                close: (chunk) => promise.resolve({ value: chunk, done: true }),
+                error: (e) => promise.reject(e),
+            });
+            return promise.promise;
+        }
+
+        releaseLock() {
+            if (this.#reader.stream !== undefined) {
+                readableStreamBYOBReaderRelease(this.#reader);
+            }
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ReadableStreamBYOBReader";
+        }
+    }
+
+    class ReadableStreamDefaultController {
+        #controller;
+
+        constructor(key = undefined, controller = undefined) {
+            if (key !== constructorKey) {
+                throw new TypeError("Illegal constructor");
+            }
+            this.#controller = controller;
+        }
+
+        get desiredSize() {
+            return readableStreamDefaultControllerGetDesiredSize(this.#controller);
+        }
+
+        close() {
+            if (!readableStreamDefaultControllerCanCloseOrEnqueue(this.#controller)) {
+                throw new TypeError("Failed to execute 'close' on 'ReadableStreamDefaultController': Unable to close a stream that is already closed or closing");
+            }
+            readableStreamDefaultControllerClose(this.#controller);
+        }
+
+        enqueue(chunk = undefined) {
+            if (!readableStreamDefaultControllerCanCloseOrEnqueue(this.#controller)) {
+                throw new TypeError("Failed to execute 'enqueue' on 'ReadableStreamDefaultController': Cannot enqueue a chunk into a readable stream that is closed or has been requested to be closed");
+            }
+            readableStreamDefaultControllerEnqueue(this.#controller, chunk);
+        }
+
+        error(e = undefined) {
+            readableStreamDefaultControllerError(this.#controller, e);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ReadableStreamDefaultController";
+        }
+    }
+
+    class ReadableByteStreamController {
+        #controller;
+
+        constructor(key = undefined, controller = undefined) {
+            if (key !== constructorKey) {
+                throw new TypeError("Illegal constructor");
+            }
+            this.#controller = controller;
+        }
+
+        get byobRequest() {
+            return readableByteStreamControllerGetBYOBRequest(this.#controller)?.object ?? null;
+        }
+
+        get desiredSize() {
+            return readableByteStreamControllerGetDesiredSize(this.#controller);
+        }
+
+        close() {
+            const context = "Failed to execute 'close' on 'ReadableByteStreamController'";
+            if (this.#controller.closeRequested) {
+                throw new TypeError(`${context}: The stream has already been closed`);
+            }
+            if (this.#controller.stream.state !== "readable") {
+                throw new TypeError(`${context}: The stream is not in the readable state and cannot be closed`);
+            }
+            readableByteStreamControllerClose(this.#controller);
+        }
+
+        enqueue(chunk) {
+            const context = "Failed to execute 'enqueue' on 'ReadableByteStreamController'";
+            checkArgs(arguments, 1, "enqueue");
+            if (!isArrayBufferView(chunk)) {
+                throw new TypeError(`${context}: parameter 1 is not of type 'ArrayBufferView'`);
+            }
+            if (chunk.byteLength === 0) {
+                throw new TypeError(`${context}: The chunk must have a non-zero byteLength`);
+            }
+            if (arrayBufferByteLength.call(chunk.buffer) === 0) {
+                throw new TypeError(`${context}: The chunk's buffer must have a non-zero byteLength`);
+            }
+            if (this.#controller.closeRequested) {
+                throw new TypeError(`${context}: The stream has already been closed`);
+            }
+            if (this.#controller.stream.state !== "readable") {
+                throw new TypeError(`${context}: The stream is not in the readable state and cannot be enqueued to`);
+            }
+            readableByteStreamControllerEnqueue(this.#controller, chunk);
+        }
+
+        error(e = undefined) {
+            readableByteStreamControllerError(this.#controller, e);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ReadableByteStreamController";
+        }
+    }
+
+    class ReadableStreamBYOBRequest {
+        #request;
+
+        constructor(key = undefined, request = undefined) {
+            if (key !== constructorKey) {
+                throw new TypeError("Illegal constructor");
+            }
+            this.#request = request;
+        }
+
+        get view() {
+            return this.#request.view;
+        }
+
+        respond(bytesWritten) {
+            const context = "Failed to execute 'respond' on 'ReadableStreamBYOBRequest'";
+            checkArgs(arguments, 1, "respond");
+            bytesWritten = toEnforcedUnsignedLongLong(bytesWritten, context);
+            if (this.#request.controller === undefined) {
+                throw new TypeError(`${context}: This BYOB request has been invalidated`);
+            }
+            if (isDetached(this.#request.view.buffer)) {
+                throw new TypeError(`${context}: The BYOB request's buffer has been detached and so cannot be used as a response`);
+            }
+            readableByteStreamControllerRespond(this.#request.controller, bytesWritten);
+        }
+
+        respondWithNewView(view) {
+            const context = "Failed to execute 'respondWithNewView' on 'ReadableStreamBYOBRequest'";
+            checkArgs(arguments, 1, "respondWithNewView");
+            if (!isArrayBufferView(view)) {
+                throw new TypeError(`${context}: parameter 1 is not of type 'ArrayBufferView'`);
+            }
+            if (this.#request.controller === undefined) {
+                throw new TypeError(`${context}: This BYOB request has been invalidated`);
+            }
+            if (isDetached(view.buffer)) {
+                throw new TypeError(`${context}: The given view's buffer has been detached and so cannot be used as a response`);
+            }
+            readableByteStreamControllerRespondWithNewView(this.#request.controller, view);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ReadableStreamBYOBRequest";
+        }
+    }
+
+    class WritableStream {
+        #stream;
+
+        static {
+            writableStreamRecord = (value) => (isObject(value) && #stream in value ? value.#stream : undefined);
+        }
+
+        constructor(underlyingSink = undefined, strategy = undefined) {
+            if (underlyingSink === constructorKey) {
+                this.#stream = strategy;
+                return;
+            }
+            const context = "Failed to construct 'WritableStream'";
+            if (underlyingSink !== undefined && underlyingSink !== null && !isObject(underlyingSink)) {
+                throw new TypeError(`${context}: The provided value is not of type 'object'`);
+            }
+            strategy = toQueuingStrategy(strategy, context);
+            const { abort, close, start, type, write } = toDictionary(underlyingSink, context, "UnderlyingSink");
+            const underlyingSinkDict = {
+                abort: toCallback(abort, context, "abort"),
+                close: toCallback(close, context, "close"),
+                start: toCallback(start, context, "start"),
+                write: toCallback(write, context, "write"),
+            };
+            if (type !== undefined) {
+                throw new RangeError(`${context}: Invalid type is specified`);
+            }
+            const stream = newWritableStream();
+            stream.object = this;
+            this.#stream = stream;
+            const sizeAlgorithm = extractSizeAlgorithm(strategy);
+            const highWaterMark = extractHighWaterMark(strategy, 1);
+            setUpWritableStreamDefaultControllerFromUnderlyingSink(stream, underlyingSink, underlyingSinkDict, highWaterMark, sizeAlgorithm);
+        }
+
+        get locked() {
+            return isWritableStreamLocked(this.#stream);
+        }
+
+        abort(reason = undefined) {
+            if (isWritableStreamLocked(this.#stream)) {
+                return promiseReject(new TypeError("Cannot abort a locked WritableStream"));
+            }
+            return writableStreamAbort(this.#stream, reason);
+        }
+
+        close() {
+            if (isWritableStreamLocked(this.#stream)) {
+                return promiseReject(new TypeError("Cannot close a locked WritableStream"));
+            }
+            if (writableStreamCloseQueuedOrInFlight(this.#stream)) {
+                return promiseReject(new TypeError("Cannot close a WritableStream that is already closing"));
+            }
+            return writableStreamClose(this.#stream);
+        }
+
+        getWriter() {
+            return acquireWritableStreamDefaultWriter(this.#stream).object;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "WritableStream";
+        }
+    }
+
+    class WritableStreamDefaultWriter {
+        #writer;
+
+        constructor(stream) {
+            if (stream === constructorKey) {
+                this.#writer = arguments[1];
+                return;
+            }
+            checkArgs(arguments, 1, "WritableStreamDefaultWriter");
+            const record = writableStreamRecord(stream);
+            if (record === undefined) {
+                throw new TypeError("Failed to construct 'WritableStreamDefaultWriter': parameter 1 is not of type 'WritableStream'");
+            }
+            this.#writer = { object: this };
+            setUpWritableStreamDefaultWriter(this.#writer, record);
+        }
+
+        get closed() {
+            return this.#writer.closed.promise;
+        }
+
+        get desiredSize() {
+            if (this.#writer.stream === undefined) {
+                throw new TypeError("This writable stream writer has been released and cannot be used to get the desired size");
+            }
+            return writableStreamDefaultWriterGetDesiredSize(this.#writer);
+        }
+
+        get ready() {
+            return this.#writer.ready.promise;
+        }
+
+        abort(reason = undefined) {
+            if (this.#writer.stream === undefined) {
+                return promiseReject(new TypeError("This writable stream writer has been released and cannot be used to abort its previous owner stream"));
+            }
+            return writableStreamDefaultWriterAbort(this.#writer, reason);
+        }
+
+        close() {
+            const stream = this.#writer.stream;
+            if (stream === undefined) {
+                return promiseReject(new TypeError("This writable stream writer has been released and cannot be used to close its previous owner stream"));
+            }
+            if (writableStreamCloseQueuedOrInFlight(stream)) {
+                return promiseReject(new TypeError("Cannot close a WritableStream that is already closing"));
+            }
+            return writableStreamDefaultWriterClose(this.#writer);
+        }
+
+        releaseLock() {
+            if (this.#writer.stream !== undefined) {
+                writableStreamDefaultWriterRelease(this.#writer);
+            }
+        }
+
+        write(chunk = undefined) {
+            if (this.#writer.stream === undefined) {
+                return promiseReject(new TypeError("This writable stream writer has been released and cannot be used to write to its previous owner stream"));
+            }
+            return writableStreamDefaultWriterWrite(this.#writer, chunk);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "WritableStreamDefaultWriter";
+        }
+    }
+
+    class WritableStreamDefaultController {
+        #controller;
+
+        constructor(key = undefined, controller = undefined) {
+            if (key !== constructorKey) {
+                throw new TypeError("Illegal constructor");
+            }
+            this.#controller = controller;
+        }
+
+        get signal() {
+            return this.#controller.abortController?.signal;
+        }
+
+        error(e = undefined) {
+            if (this.#controller.stream.state === "writable") {
+                writableStreamDefaultControllerError(this.#controller, e);
+            }
+        }
+
+        get [Symbol.toStringTag]() {
+            return "WritableStreamDefaultController";
+        }
+    }
+
+    class TransformStream {
+        #stream;
+
+        static {
+            transformStreamRecord = (value) => (isObject(value) && #stream in value ? value.#stream : undefined);
+        }
+
+        constructor(transformer = undefined, writableStrategy = undefined, readableStrategy = undefined) {
+            if (transformer === constructorKey) {
+                this.#stream = writableStrategy;
+                return;
+            }
+            const context = "Failed to construct 'TransformStream'";
+            if (transformer !== undefined && transformer !== null && !isObject(transformer)) {
+                throw new TypeError(`${context}: The provided value is not of type 'object'`);
+            }
+            writableStrategy = toQueuingStrategy(writableStrategy, context);
+            readableStrategy = toQueuingStrategy(readableStrategy, context);
+            const { cancel, flush, readableType, start, transform, writableType } = toDictionary(transformer, context, "Transformer");
+            const transformerDict = {
+                cancel: toCallback(cancel, context, "cancel"),
+                flush: toCallback(flush, context, "flush"),
+                start: toCallback(start, context, "start"),
+                transform: toCallback(transform, context, "transform"),
+            };
+            if (readableType !== undefined) {
+                throw new RangeError(`${context}: Invalid readableType specified`);
+            }
+            if (writableType !== undefined) {
+                throw new RangeError(`${context}: Invalid writableType specified`);
+            }
+            const readableHighWaterMark = extractHighWaterMark(readableStrategy, 0);
+            const readableSizeAlgorithm = extractSizeAlgorithm(readableStrategy);
+            const writableHighWaterMark = extractHighWaterMark(writableStrategy, 1);
+            const writableSizeAlgorithm = extractSizeAlgorithm(writableStrategy);
+            const stream = { object: this };
+            this.#stream = stream;
+            const startPromise = createDeferred();
+            initializeTransformStream(
+                stream,
+                startPromise.promise,
+                writableHighWaterMark,
+                writableSizeAlgorithm,
+                readableHighWaterMark,
+                readableSizeAlgorithm,
+            );
+            setUpTransformStreamDefaultControllerFromTransformer(stream, transformer, transformerDict);
+            if (transformerDict.start !== undefined) {
+                startPromise.resolve(transformerDict.start.call(transformer, stream.controller.object));
+            } else {
+                startPromise.resolve(undefined);
+            }
+        }
+
+        get readable() {
+            return this.#stream.readable.object;
+        }
+
+        get writable() {
+            return this.#stream.writable.object;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "TransformStream";
+        }
+    }
+
+    class TransformStreamDefaultController {
+        #controller;
+
+        constructor(key = undefined, controller = undefined) {
+            if (key !== constructorKey) {
+                throw new TypeError("Illegal constructor");
+            }
+            this.#controller = controller;
+        }
+
+        get desiredSize() {
+            return readableStreamDefaultControllerGetDesiredSize(this.#controller.stream.readable.controller);
+        }
+
+        enqueue(chunk = undefined) {
+            transformStreamDefaultControllerEnqueue(this.#controller, chunk);
+        }
+
+        error(reason = undefined) {
+            transformStreamDefaultControllerError(this.#controller, reason);
+        }
+
+        terminate() {
+            transformStreamDefaultControllerTerminate(this.#controller);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "TransformStreamDefaultController";
+        }
+    }
+
+    // https://streams.spec.whatwg.org/#blqs-class
+    function byteLengthSize(chunk) {
+        return chunk.byteLength;
+    }
+    Object.defineProperty(byteLengthSize, "name", { value: "size" });
+
+    class ByteLengthQueuingStrategy {
+        #highWaterMark;
+
+        constructor(init) {
+            checkArgs(arguments, 1, "ByteLengthQueuingStrategy");
+            const context = "Failed to construct 'ByteLengthQueuingStrategy'";
+            const { highWaterMark } = toDictionary(init, context, "QueuingStrategyInit");
+            if (highWaterMark === undefined) {
+                throw new TypeError(`${context}: required member highWaterMark is undefined`);
+            }
+            this.#highWaterMark = Number(highWaterMark);
+        }
+
+        get highWaterMark() {
+            return this.#highWaterMark;
+        }
+
+        get size() {
+            return byteLengthSize;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "ByteLengthQueuingStrategy";
+        }
+    }
+
+    // https://streams.spec.whatwg.org/#cqs-class
+    function countSize() {
+        return 1;
+    }
+    Object.defineProperty(countSize, "name", { value: "size" });
+
+    class CountQueuingStrategy {
+        #highWaterMark;
+
+        constructor(init) {
+            checkArgs(arguments, 1, "CountQueuingStrategy");
+            const context = "Failed to construct 'CountQueuingStrategy'";
+            const { highWaterMark } = toDictionary(init, context, "QueuingStrategyInit");
+            if (highWaterMark === undefined) {
+                throw new TypeError(`${context}: required member highWaterMark is undefined`);
+            }
+            this.#highWaterMark = Number(highWaterMark);
+        }
+
+        get highWaterMark() {
+            return this.#highWaterMark;
+        }
+
+        get size() {
+            return countSize;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "CountQueuingStrategy";
+        }
+    }
+
+    globalThis.ReadableStream = ReadableStream;
+    globalThis.ReadableStreamDefaultReader = ReadableStreamDefaultReader;
+    globalThis.ReadableStreamBYOBReader = ReadableStreamBYOBReader;
+    globalThis.ReadableStreamDefaultController = ReadableStreamDefaultController;
+    globalThis.ReadableByteStreamController = ReadableByteStreamController;
+    globalThis.ReadableStreamBYOBRequest = ReadableStreamBYOBRequest;
+    globalThis.WritableStream = WritableStream;
+    globalThis.WritableStreamDefaultWriter = WritableStreamDefaultWriter;
+    globalThis.WritableStreamDefaultController = WritableStreamDefaultController;
+    globalThis.TransformStream = TransformStream;
+    globalThis.TransformStreamDefaultController = TransformStreamDefaultController;
+    globalThis.ByteLengthQueuingStrategy = ByteLengthQueuingStrategy;
+    globalThis.CountQueuingStrategy = CountQueuingStrategy;
+
+    // https://encoding.spec.whatwg.org/#interface-textencoderstream
+    if (TextEncoder !== undefined) {
+        const encode = TextEncoder.prototype.encode;
+
+        class TextEncoderStream {
+            #encoder = new TextEncoder();
+            #transform;
+            // A high surrogate ending the last chunk, waiting for the low
+            // surrogate starting the next one.
+            #pendingHighSurrogate = null;
+
+            constructor() {
+                this.#transform = setUpTransformStream(
+                    (chunk, controller) => {
+                        let string = `${chunk}`;
+                        if (this.#pendingHighSurrogate !== null) {
+                            string = this.#pendingHighSurrogate + string;
+                            this.#pendingHighSurrogate = null;
+                        }
+                        const last = string.charCodeAt(string.length - 1);
+                        if (last >= 0xd800 && last <= 0xdbff) {
+                            this.#pendingHighSurrogate = string[string.length - 1];
+                            string = string.slice(0, -1);
+                        }
+                        if (string !== "") {
+                            transformStreamDefaultControllerEnqueue(controller, encode.call(this.#encoder, string));
+                        }
+                    },
+                    (controller) => {
+                        if (this.#pendingHighSurrogate !== null) {
+                            transformStreamDefaultControllerEnqueue(controller, new Uint8Array([0xef, 0xbf, 0xbd]));
+                        }
+                    },
+                );
+            }
+
+            get encoding() {
+                return "utf-8";
+            }
+
+            get readable() {
+                return this.#transform.readable.object;
+            }
+
+            get writable() {
+                return this.#transform.writable.object;
+            }
+
+            get [Symbol.toStringTag]() {
+                return "TextEncoderStream";
+            }
+        }
+
+        globalThis.TextEncoderStream = TextEncoderStream;
+    }
+
+    // https://encoding.spec.whatwg.org/#interface-textdecoderstream
+    if (TextDecoder !== undefined) {
+        const decode = TextDecoder.prototype.decode;
+
+        class TextDecoderStream {
+            #decoder;
+            #transform;
+
+            constructor(label = "utf-8", options = undefined) {
+                this.#decoder = new TextDecoder(label, options);
+                this.#transform = setUpTransformStream(
+                    (chunk, controller) => {
+                        if (!(chunk instanceof ArrayBuffer) && !isArrayBufferView(chunk)) {
+                            throw new TypeError("The provided value is not of type '(ArrayBuffer or ArrayBufferView)'");
+                        }
+                        const output = decode.call(this.#decoder, chunk, { stream: true });
+                        if (output !== "") {
+                            transformStreamDefaultControllerEnqueue(controller, output);
+                        }
+                    },
+                    (controller) => {
+                        const output = decode.call(this.#decoder);
+                        if (output !== "") {
+                            transformStreamDefaultControllerEnqueue(controller, output);
+                        }
+                    },
+                );
+            }
+
+            get encoding() {
+                return this.#decoder.encoding;
+            }
+
+            get fatal() {
+                return this.#decoder.fatal;
+            }
+
+            get ignoreBOM() {
+                return this.#decoder.ignoreBOM;
+            }
+
+            get readable() {
+                return this.#transform.readable.object;
+            }
+
+            get writable() {
+                return this.#transform.writable.object;
+            }
+
+            get [Symbol.toStringTag]() {
+                return "TextDecoderStream";
+            }
+        }
+
+        globalThis.TextDecoderStream = TextDecoderStream;
+    }
+})();
@@ -0,0 +1,50 @@
+// The intrinsics used to clone values, captured before any other code runs so
+// that replacing globals or prototype methods doesn't affect `structuredClone`.
+// Some of them are missing when the corresponding intrinsics are disabled, in
+// which case no values of these types can exist either.
+(function () {
+    const getter = (object, name) => object && Object.getOwnPropertyDescriptor(object.prototype, name).get;
+    const { ArrayBuffer, Date, RegExp, Map, Set, DataView } = globalThis;
+
+    return {
+        Object,
+        ArrayBuffer,
+        Date,
+        RegExp,
+        Map,
+        Set,
+        DataView,
+        toStringTag: Symbol.toStringTag,
+        getOwnPropertyDescriptor: Object.getOwnPropertyDescriptor,
+        booleanValueOf: Boolean.prototype.valueOf,
+        numberValueOf: Number.prototype.valueOf,
+        stringValueOf: String.prototype.valueOf,
+        bigIntValueOf: BigInt.prototype.valueOf,
+        arrayBufferResizable: getter(ArrayBuffer, "resizable"),
+        arrayBufferMaxByteLength: getter(ArrayBuffer, "maxByteLength"),
+        dateGetTime: Date?.prototype.getTime,
+        regExpSource: getter(RegExp, "source"),
+        regExpFlags: getter(RegExp, "flags"),
+        mapForEach: Map?.prototype.forEach,
+        mapSet: Map?.prototype.set,
+        setForEach: Set?.prototype.forEach,
+        setAdd: Set?.prototype.add,
+        dataViewBuffer: getter(DataView, "buffer"),
+        dataViewByteOffset: getter(DataView, "byteOffset"),
+        dataViewByteLength: getter(DataView, "byteLength"),
+        // Indexed by the typed array types of QuickJS.
+        typedArrays: [
+            "Uint8ClampedArray", "Int8Array", "Uint8Array", "Int16Array", "Uint16Array", "Int32Array",
+            "Uint32Array", "BigInt64Array", "BigUint64Array", "Float16Array", "Float32Array", "Float64Array",
+        ].map((name) => globalThis[name]),
+        errors: { Error, EvalError, RangeError, ReferenceError, SyntaxError, TypeError, URIError },
+        // Instances used to identify the classes of objects.
+        samples: {
+            object: {},
+            boolean: new Boolean(false),
+            number: new Number(0),
+            string: new String(""),
+            bigInt: Object(0n),
+        },
+    };
+})();
@@ -0,0 +1,710 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{
+    Args, hold, hold_and_release,
+    quickjs::{
+        Array, ArrayBuffer, Atom, Constructor, Ctx, Error as JSError, Exception, FromJs, Function,
+        Object, Persistent, String as JSString, Symbol, Value,
+        convert::Coerced,
+        function::{Rest, This},
+        object::{Filter, ObjectIter, Property},
+        qjs,
+    },
+    to_js_error, to_string_lossy, val_to_string,
+};
+
+use super::serializer::{MAX_DEPTH, stack_overflow};
+use anyhow::{Error, Result, anyhow, bail};
+
+/// The names of the errors which keep their type when cloned, any other error
+/// is cloned as an `Error`.
+const ERROR_NAMES: [&str; 7] = [
+    "Error",
+    "EvalError",
+    "RangeError",
+    "ReferenceError",
+    "SyntaxError",
+    "TypeError",
+    "URIError",
+];
+
+/// The class IDs of the objects which can only be recognized by their class.
+#[derive(Clone, Copy)]
+struct Classes {
+    object: qjs::JSClassID,
+    boolean: qjs::JSClassID,
+    number: qjs::JSClassID,
+    string: qjs::JSClassID,
+    big_int: qjs::JSClassID,
+}
+
+/// Register the `structuredClone` function on the global object.
+pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
+    let intrinsics: Object<'_> = this.eval(include_str!("./intrinsics.js"))?;
+
+    let samples: Object<'_> = intrinsics.get("samples")?;
+    let class_of = |name: &str| -> Result<qjs::JSClassID> {
+        let sample: Value<'_> = samples.get(name)?;
+        Ok(unsafe { qjs::JS_GetClassID(sample.as_raw()) })
+    };
+    let classes = Classes {
+        object: class_of("object")?,
+        boolean: class_of("boolean")?,
+        number: class_of("number")?,
+        string: class_of("string")?,
+        big_int: class_of("bigInt")?,
+    };
+    intrinsics.remove("samples")?;
+
+    let intrinsics = Persistent::save(&this, intrinsics);
+    this.globals().set(
+        "structuredClone",
+        Function::new(this.clone(), move |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            structured_clone(hold!(cx.clone(), args), intrinsics.clone(), classes)
+                .map_err(|e| to_js_error(cx, e))
+        })?,
+    )?;
+
+    Ok::<_, Error>(())
+}
+
+/// An error named `DataCloneError`, which would be a `DOMException` in
+/// browsers.
+fn data_clone_error(cx: &Ctx<'_>, message: &str) -> Error {
+    let exception = match Exception::from_message(cx.clone(), message) {
+        Ok(exception) => exception,
+        Err(e) => return e.into(),
+    };
+    if let Err(e) = exception.set("name", "DataCloneError") {
+        return e.into();
+    }
+    cx.throw(exception.into_value()).into()
+}
+
+/// Clone a value with the [structured clone
+/// algorithm](https://html.spec.whatwg.org/multipage/structured-data.html#safe-passing-of-structured-data),
+/// transferring the `ArrayBuffer`s listed in the `transfer` option.
+fn structured_clone<'js>(
+    args: Args<'js>,
+    intrinsics: Persistent<Object<'static>>,
+    classes: Classes,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    if args.is_empty() {
+        bail!(Exception::throw_type(
+            &cx,
+            "Failed to execute 'structuredClone': 1 argument required, but only 0 present"
+        ));
+    }
+
+    let mut cloner = Cloner {
+        cx: cx.clone(),
+        intrinsics: intrinsics.restore(&cx)?,
+        classes,
+        memory: HashMap::new(),
+        depth: 0,
+    };
+
+    let transfer = transfer_list(&cx, args.get(1))?;
+    for buffer in &transfer {
+        let copy = cloner.clone_array_buffer(buffer)?;
+        cloner.memory.insert(buffer.as_value().clone(), copy);
+    }
+
+    let clone = cloner.clone_value(args[0].clone())?;
+
+    for buffer in transfer {
+        unsafe { qjs::JS_DetachArrayBuffer(cx.as_raw().as_ptr(), buffer.as_raw()) };
+    }
+    Ok(clone)
+}
+
+/// The `ArrayBuffer`s listed in the `transfer` option of `structuredClone`.
+fn transfer_list<'js>(cx: &Ctx<'js>, options: Option<&Value<'js>>) -> Result<Vec<Object<'js>>> {
+    let Some(options) = options.filter(|options| !options.is_undefined() && !options.is_null())
+    else {
+        return Ok(Vec::new());
+    };
+    let Some(options) = options.as_object() else {
+        bail!(Exception::throw_type(
+            cx,
+            "Failed to execute 'structuredClone': The options argument must be an object"
+        ));
+    };
+
+    let transfer: Value<'js> = options.get("transfer")?;
+    if transfer.is_undefined() {
+        return Ok(Vec::new());
+    }
+    let Some(transfer) = transfer.as_array() else {
+        bail!(Exception::throw_type(
+            cx,
+            "Failed to execute 'structuredClone': The transfer option must be an array"
+        ));
+    };
+
+    let mut buffers: Vec<Object<'js>> = Vec::with_capacity(transfer.len());
+    for value in transfer.iter::<Value<'js>>() {
+        let value = value?;
+        let buffer = match value.as_object() {
+            Some(object) if unsafe { qjs::JS_IsArrayBuffer(object.as_raw()) } => object.clone(),
+            _ => bail!(data_clone_error(
+                cx,
+                "Found invalid value in the transfer list."
+            )),
+        };
+        if buffers.contains(&buffer) {
+            bail!(data_clone_error(
+                cx,
+                "The transfer list contains a duplicate ArrayBuffer."
+            ));
+        }
+        if ArrayBuffer::from_object(buffer.clone()).is_none() {
+            bail!(data_clone_error(
+                cx,
+                "An ArrayBuffer is detached and could not be transferred."
+            ));
+        }
+        buffers.push(buffer);
+    }
+    Ok(buffers)
+}
+
+/// The state of a single call to `structuredClone`.
+struct Cloner<'js> {
+    cx: Ctx<'js>,
+    /// The functions and constructors captured when `structuredClone` was
+    /// registered.
+    intrinsics: Object<'js>,
+    classes: Classes,
+    /// The clones of the objects which were already visited, which preserves
+    /// the identity of objects that are referenced more than once, including
+    /// cycles.
+    memory: HashMap<Value<'js>, Value<'js>>,
+    /// The number of objects being cloned, which is limited to
+    /// [`MAX_DEPTH`].
+    depth: usize,
+}
+
+impl<'js> Cloner<'js> {
+    fn intrinsic<T: FromJs<'js>>(&self, name: &str) -> Result<T> {
+        Ok(self.intrinsics.get(name)?)
+    }
+
+    /// Call the intrinsic function `name` with `this` and `args`.
+    fn call(&self, name: &str, this: &Object<'js>, args: &[Value<'js>]) -> Result<Value<'js>> {
+        let function: Function<'js> = self.intrinsic(name)?;
+        Ok(function.call((This(this.clone()), Rest(args.to_vec())))?)
+    }
+
+    /// Construct a new object with the intrinsic constructor `name`.
+    fn construct(&self, name: &str, args: &[Value<'js>]) -> Result<Object<'js>> {
+        let constructor: Constructor<'js> = self.intrinsic(name)?;
+        Ok(constructor.construct((Rest(args.to_vec()),))?)
+    }
+
+    /// Turn the result of a raw QuickJS call into a value.
+    fn raw_value(&self, value: qjs::JSValue) -> Result<Value<'js>> {
+        let value = unsafe { Value::from_raw(self.cx.clone(), value) };
+        if value.is_exception() {
+            return Err(JSError::Exception.into());
+        }
+        Ok(value)
+    }
+
+    fn clone_value(&mut self, value: Value<'js>) -> Result<Value<'js>> {
+        if value.is_symbol() {
+            return Err(self.uncloneable(value));
+        }
+        let Some(object) = value.as_object() else {
+            return Ok(value);
+        };
+        if let Some(clone) = self.memory.get(&value) {
+            return Ok(clone.clone());
+        }
+
+        if self.depth >= MAX_DEPTH {
+            return Err(stack_overflow(&self.cx).into());
+        }
+        self.depth += 1;
+        let clone = self.clone_object(value.clone(), object);
+        self.depth -= 1;
+        clone
+    }
+
+    /// Clone an object, recursing into the containers. Kept apart from
+    /// [`Self::clone_leaf`] so that the frames of the recursion stay small.
+    fn clone_object(&mut self, value: Value<'js>, object: &Object<'js>) -> Result<Value<'js>> {
+        let raw = object.as_raw();
+        // `JS_IsArray` sees through proxies.
+        if unsafe { qjs::JS_IsProxy(raw) } {
+            return self.clone_leaf(value, object);
+        }
+
+        if unsafe { qjs::JS_IsMap(raw) } {
+            self.clone_map(value, object)
+        } else if unsafe { qjs::JS_IsSet(raw) } {
+            self.clone_set(value, object)
+        } else if unsafe { qjs::JS_IsError(raw) } {
+            self.clone_error(value, object)
+        } else if unsafe { qjs::JS_IsArray(raw) } {
+            let clone = self.new_array(object)?;
+            self.clone_properties(value, object, clone)
+        } else if unsafe { qjs::JS_GetClassID(raw) } == self.classes.object {
+            let clone = Object::new(self.cx.clone())?;
+            self.clone_properties(value, object, clone)
+        } else {
+            self.clone_leaf(value, object)
+        }
+    }
+
+    /// The `DataCloneError` of a symbol or function.
+    fn uncloneable(&self, value: Value<'js>) -> Error {
+        match val_to_string(&self.cx, value) {
+            Ok(value) => data_clone_error(&self.cx, &format!("{value} could not be cloned.")),
+            Err(e) => e,
+        }
+    }
+
+    /// An empty array with the same length as `object`.
+    fn new_array(&self, object: &Object<'js>) -> Result<Object<'js>> {
+        let length = object.get::<_, Value<'js>>("length")?;
+        let clone = Array::new(self.cx.clone())?.into_object();
+        clone.set("length", length)?;
+        Ok(clone)
+    }
+
+    /// Clone an object which doesn't contain other values, or throw if it
+    /// can't be cloned.
+    fn clone_leaf(&mut self, value: Value<'js>, object: &Object<'js>) -> Result<Value<'js>> {
+        let raw = object.as_raw();
+        if unsafe { qjs::JS_IsProxy(raw) } {
+            bail!(data_clone_error(&self.cx, "#<Object> could not be cloned."));
+        }
+        if value.is_function() {
+            return Err(self.uncloneable(value));
+        }
+
+        let class = unsafe { qjs::JS_GetClassID(raw) };
+        let clone = if class == self.classes.boolean {
+            self.clone_primitive_wrapper(object, "booleanValueOf")?
+        } else if class == self.classes.number {
+            self.clone_primitive_wrapper(object, "numberValueOf")?
+        } else if class == self.classes.string {
+            self.clone_primitive_wrapper(object, "stringValueOf")?
+        } else if class == self.classes.big_int {
+            self.clone_primitive_wrapper(object, "bigIntValueOf")?
+        } else if unsafe { qjs::JS_IsDate(raw) } {
+            let time = self.call("dateGetTime", object, &[])?;
+            self.construct("Date", &[time])?.into_value()
+        } else if unsafe { qjs::JS_IsRegExp(raw) } {
+            let source = self.call("regExpSource", object, &[])?;
+            let flags = self.call("regExpFlags", object, &[])?;
+            self.construct("RegExp", &[source, flags])?.into_value()
+        } else if unsafe { qjs::JS_IsArrayBuffer(raw) } {
+            self.clone_array_buffer(object)?
+        } else if unsafe { qjs::JS_GetTypedArrayType(raw) } >= 0 {
+            self.clone_typed_array(object)?
+        } else if unsafe { qjs::JS_IsDataView(raw) } {
+            self.clone_data_view(object)?
+        } else {
+            let tag: Value<'js> = object.get(self.intrinsic::<Symbol<'js>>("toStringTag")?)?;
+            let tag = match tag.as_string() {
+                Some(tag) => tag
+                    .to_string()
+                    .unwrap_or_else(|e| to_string_lossy(&self.cx, tag, e)),
+                None => "Object".into(),
+            };
+            bail!(data_clone_error(
+                &self.cx,
+                &format!("#<{tag}> could not be cloned.")
+            ));
+        };
+
+        self.memory.insert(value, clone.clone());
+        Ok(clone)
+    }
+
+    fn clone_primitive_wrapper(&self, object: &Object<'js>, value_of: &str) -> Result<Value<'js>> {
+        let primitive = self.call(value_of, object, &[])?;
+        let object_constructor: Function<'js> = self.intrinsic("Object")?;
+        Ok(object_constructor.call((primitive,))?)
+    }
+
+    /// Copy the bytes of an `ArrayBuffer` into a new one, which is resizable
+    /// up to the same maximum length if the original one is.
+    fn clone_array_buffer(&self, object: &Object<'js>) -> Result<Value<'js>> {
+        let Some(buffer) = ArrayBuffer::from_object(object.clone()) else {
+            bail!(data_clone_error(
+                &self.cx,
+                "An ArrayBuffer is detached and could not be cloned."
+            ));
+        };
+        let bytes = buffer.as_bytes().unwrap_or_default();
+
+        let resizable = self.call("arrayBufferResizable", object, &[])?;
+        if !resizable.as_bool().unwrap_or_default() {
+            return Ok(ArrayBuffer::new(self.cx.clone(), bytes.to_vec())?.into_value());
+        }
+
+        let max_byte_length = self.call("arrayBufferMaxByteLength", object, &[])?;
+        let options = Object::new(self.cx.clone())?;
+        options.set("maxByteLength", max_byte_length)?;
+        let length = Value::new_number(self.cx.clone(), bytes.len() as f64);
+        let clone = self.construct("ArrayBuffer", &[length, options.into_value()])?;
+        let raw = ArrayBuffer::from_object(clone.clone())
+            .and_then(|clone| clone.as_raw())
+            .ok_or_else(|| anyhow!("Expected a new ArrayBuffer"))?;
+        // SAFETY: the new buffer has the same length as `bytes`, and the two
+        // buffers don't overlap.
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), raw.ptr.as_ptr(), bytes.len()) };
+        Ok(clone.into_value())
+    }
+
+    /// Clone a typed array along with its buffer, which is shared with the
+    /// other views cloned by the same call.
+    fn clone_typed_array(&mut self, object: &Object<'js>) -> Result<Value<'js>> {
+        let kind = unsafe { qjs::JS_GetTypedArrayType(object.as_raw()) };
+        let (mut offset, mut length, mut bytes_per_element) = (0, 0, 0);
+        let buffer = self.raw_value(unsafe {
+            qjs::JS_GetTypedArrayBuffer(
+                self.cx.as_raw().as_ptr(),
+                object.as_raw(),
+                &mut offset,
+                &mut length,
+                &mut bytes_per_element,
+            )
+        })?;
+        let buffer = self.clone_value(buffer)?;
+
+        let constructors: Array<'js> = self.intrinsic("typedArrays")?;
+        let constructor: Constructor<'js> = constructors.get(kind as usize)?;
+        Ok(constructor
+            .construct::<_, Object<'js>>((buffer, offset, length / bytes_per_element.max(1)))?
+            .into_value())
+    }
+
+    fn clone_data_view(&mut self, object: &Object<'js>) -> Result<Value<'js>> {
+        let buffer = self.call("dataViewBuffer", object, &[])?;
+        let offset = self.call("dataViewByteOffset", object, &[])?;
+        let length = self.call("dataViewByteLength", object, &[])?;
+        let buffer = self.clone_value(buffer)?;
+        Ok(self
+            .construct("DataView", &[buffer, offset, length])?
+            .into_value())
+    }
+
+    /// Collect the arguments passed to the callbacks of the intrinsic
+    /// `forEach` function `name`.
+    fn entries(&self, name: &str, object: &Object<'js>) -> Result<Vec<(Value<'js>, Value<'js>)>> {
+        let entries = Rc::new(RefCell::new(Vec::new()));
+        let callback = Function::new(self.cx.clone(), {
+            let entries = entries.clone();
+            move |value: Value<'js>, key: Value<'js>| entries.borrow_mut().push((key, value))
+        })?;
+        self.call(name, object, &[callback.into_value()])?;
+        Ok(entries.take())
+    }
+
+    fn clone_map(&mut self, value: Value<'js>, object: &Object<'js>) -> Result<Value<'js>> {
+        let clone = self.construct("Map", &[])?;
+        self.memory.insert(value, clone.clone().into_value());
+        for (key, value) in self.entries("mapForEach", object)? {
+            let key = self.clone_value(key)?;
+            let value = self.clone_value(value)?;
+            self.call("mapSet", &clone, &[key, value])?;
+        }
+        Ok(clone.into_value())
+    }
+
+    fn clone_set(&mut self, value: Value<'js>, object: &Object<'js>) -> Result<Value<'js>> {
+        let clone = self.construct("Set", &[])?;
+        self.memory.insert(value, clone.clone().into_value());
+        for (_, value) in self.entries("setForEach", object)? {
+            let value = self.clone_value(value)?;
+            self.call("setAdd", &clone, &[value])?;
+        }
+        Ok(clone.into_value())
+    }
+
+    /// Clone an error, keeping its type if it's one of the native errors, and
+    /// its own `message`, `cause` and `stack` data properties.
+    fn clone_error(&mut self, value: Value<'js>, object: &Object<'js>) -> Result<Value<'js>> {
+        let name: Value<'js> = object.get("name")?;
+        let name = match name.as_string() {
+            Some(name) => name
+                .to_string()
+                .unwrap_or_else(|e| to_string_lossy(&self.cx, name, e)),
+            None => String::new(),
+        };
+        let name = if ERROR_NAMES.contains(&name.as_str()) {
+            name.as_str()
+        } else {
+            "Error"
+        };
+
+        let errors: Object<'js> = self.intrinsic("errors")?;
+        let constructor: Constructor<'js> = errors.get(name)?;
+        let clone: Object<'js> = match self.own_data_property(object, "message")? {
+            Some(message) => {
+                constructor.construct((Coerced::<JSString<'js>>::from_js(&self.cx, message)?.0,))?
+            }
+            None => constructor.construct(())?,
+        };
+        self.memory.insert(value, clone.clone().into_value());
+
+        if let Some(stack) = self.own_data_property(object, "stack")?
+            && stack.is_string()
+        {
+            clone.set("stack", stack)?;
+        }
+        if let Some(cause) = self.own_data_property(object, "cause")? {
+            let cause = self.clone_value(cause)?;
+            clone.prop("cause", Property::from(cause).writable().configurable())?;
+        }
+        Ok(clone.into_value())
+    }
+
+    /// The value of the own data property `key` of `object`, if it has one.
+    fn own_data_property(&self, object: &Object<'js>, key: &str) -> Result<Option<Value<'js>>> {
+        let function: Function<'js> = self.intrinsic("getOwnPropertyDescriptor")?;
+        let descriptor: Option<Object<'js>> = function.call((object.clone(), key))?;
+        match descriptor {
+            Some(descriptor) if descriptor.contains_key("value")? => {
+                Ok(Some(descriptor.get("value")?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Clone the own enumerable string-keyed properties of `object` into
+    /// `clone`.
+    fn clone_properties(
+        &mut self,
+        value: Value<'js>,
+        object: &Object<'js>,
+        clone: Object<'js>,
+    ) -> Result<Value<'js>> {
+        self.memory.insert(value, clone.clone().into_value());
+        let keys: ObjectIter<'js, Atom<'js>, Value<'js>> =
+            object.own_props(Filter::new().string().enum_only());
+        for entry in keys {
+            let (key, value) = entry?;
+            let value = self.clone_value(value)?;
+            clone.set(key, value)?;
+        }
+        Ok(clone.into_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    fn runtime() -> Result<Runtime> {
+        let mut config = Config::default();
+        config.structured_clone(true);
+        Runtime::new(config)
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let default = Runtime::default();
+        default.context().with(|this| {
+            let value: Value<'_> = this.globals().get("structuredClone")?;
+            assert!(value.is_undefined());
+            Ok::<_, Error>(())
+        })?;
+
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval("typeof structuredClone")?;
+            assert_eq!("function", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_objects() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const object = { a: 1, b: [1, "x", null, undefined, 2n], date: new Date(5) };
+                object.self = object;
+                object.b.push(object.b);
+                Object.defineProperty(object, "hidden", { value: 1, enumerable: false });
+                const clone = structuredClone(object);
+                [clone !== object, clone.self === clone, clone.b[5] === clone.b,
+                 clone.b.slice(0, 5).map(String).join(" "), clone.date.getTime(),
+                 "hidden" in clone, structuredClone(-0) === 0].join(",")
+            "#,
+            )?;
+            assert_eq!("true,true,true,1 x null undefined 2,5,false,true", result);
+
+            let result: String = this.eval(
+                r#"
+                const key = {};
+                const map = new Map([[key, /a+/gi], ["key", key]]);
+                map.set("map", map);
+                const mapClone = structuredClone(map);
+                const [keyClone, regExpClone] = mapClone.entries().next().value;
+                const set = structuredClone(new Set([1, new String("s"), Object(3n)]));
+                [mapClone.get("key") === keyClone, mapClone.get("map") === mapClone,
+                 regExpClone.source, regExpClone.flags,
+                 [...set].map((v) => Object.prototype.toString.call(v)).join(" ")].join(",")
+            "#,
+            )?;
+            assert_eq!(
+                "true,true,a+,gi,[object Number] [object String] [object BigInt]",
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_buffers() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const buffer = new ArrayBuffer(8);
+                const bytes = new Uint8Array(buffer, 2, 4);
+                const view = new DataView(buffer, 1, 3);
+                bytes[0] = 9;
+                const clone = structuredClone({ bytes, view, buffer });
+                bytes[0] = 1;
+                const resizable = structuredClone(new ArrayBuffer(2, { maxByteLength: 4 }));
+                [clone.buffer !== buffer, clone.bytes.buffer === clone.buffer,
+                 clone.view.buffer === clone.buffer, clone.bytes.byteOffset, clone.bytes.length,
+                 clone.bytes[0], clone.view.byteOffset, clone.view.byteLength,
+                 structuredClone(new Float64Array([1.5])).constructor.name,
+                 resizable.resizable, resizable.maxByteLength].join(",")
+            "#,
+            )?;
+            assert_eq!("true,true,true,2,4,9,1,3,Float64Array,true,4", result);
+
+            let result: String = this.eval(
+                r#"
+                const transferred = new ArrayBuffer(4);
+                new Uint8Array(transferred)[0] = 7;
+                const transferredClone = structuredClone(
+                    { bytes: new Uint8Array(transferred) },
+                    { transfer: [transferred] },
+                );
+                [transferred.byteLength, transferredClone.bytes[0]].join(",")
+            "#,
+            )?;
+            assert_eq!("0,7", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_errors() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const renamed = new TypeError("renamed", { cause: 1 });
+                renamed.name = "Custom";
+                const errors = [new RangeError("range"), renamed, new (class extends Error {})()];
+                errors.map((error) => {
+                    const clone = structuredClone(error);
+                    return [clone.constructor.name, clone.name, clone.message, clone.cause].join(" ");
+                }).join(",")
+            "#,
+            )?;
+            assert_eq!("RangeError RangeError range ,Error Error renamed 1,Error Error  ", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_clone_error() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const detached = new ArrayBuffer(1);
+                structuredClone(detached, { transfer: [detached] });
+                const buffer = new ArrayBuffer(1);
+                const inputs = [
+                    [function f() {}], [Symbol("s")], [{ nested: Symbol() }], [Promise.resolve()],
+                    [new Proxy({}, {})], [new WeakMap()], [detached], [1, { transfer: [{}] }],
+                    [1, { transfer: [buffer, buffer] }], [1, { transfer: [detached] }],
+                ];
+                inputs.map((args) => {
+                    try {
+                        structuredClone(...args);
+                    } catch (e) {
+                        return `${e.name}: ${e.message}`;
+                    }
+                }).join("\n")
+            "#,
+            )?;
+            assert_eq!(
+                [
+                    "DataCloneError: function f() {} could not be cloned.",
+                    "DataCloneError: Symbol(s) could not be cloned.",
+                    "DataCloneError: Symbol() could not be cloned.",
+                    "DataCloneError: #<Promise> could not be cloned.",
+                    "DataCloneError: #<Object> could not be cloned.",
+                    "DataCloneError: #<WeakMap> could not be cloned.",
+                    "DataCloneError: An ArrayBuffer is detached and could not be cloned.",
+                    "DataCloneError: Found invalid value in the transfer list.",
+                    "DataCloneError: The transfer list contains a duplicate ArrayBuffer.",
+                    "DataCloneError: An ArrayBuffer is detached and could not be transferred.",
+                ]
+                .join("\n"),
+                result
+            );
+
+            let result: String = this.eval(
+                r#"
+                try {
+                    structuredClone();
+                } catch (e) {
+                    e.name
+                }
+            "#,
+            )?;
+            assert_eq!("TypeError", result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_nesting_depth() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|this| {
+            this.eval::<(), _>(
+                r#"
+                var error = (f) => { try { f(); return "ok"; } catch (e) { return e.constructor.name; } };
+                var nested = (depth) => { let value = []; for (let i = 0; i < depth; i++) value = [value]; return value; };
+            "#,
+            )?;
+            for (expression, expected) in [
+                ("structuredClone(nested(511))", "ok"),
+                ("structuredClone(nested(512))", "RangeError"),
+                ("structuredClone(nested(200000))", "RangeError"),
+                (
+                    "structuredClone(new Map([[0, nested(512)]]))",
+                    "RangeError",
+                ),
+            ] {
+                let result: String = this.eval(format!("error(() => {expression})"))?;
+                assert_eq!(expected, result, "{expression}");
+            }
+            Ok::<_, Error>(())
+        })
+    }
+}
@@ -0,0 +1,19 @@
+//! Helpers shared by the tests of the APIs.
+
+use crate::{Runtime, quickjs::FromJs};
+use anyhow::{Error, Result};
+
+/// Evaluates `source`, which is expected to set `globalThis.result`,
+/// resolving pending jobs before reading the result.
+pub(crate) fn eval_async<T>(runtime: &Runtime, source: &str) -> Result<T>
+where
+    T: for<'js> FromJs<'js>,
+{
+    runtime
+        .context()
+        .with(|this| this.eval::<(), _>(source).map_err(Error::from))?;
+    runtime.resolve_pending_jobs()?;
+    runtime
+        .context()
+        .with(|this| this.globals().get("result").map_err(Error::from))
+}
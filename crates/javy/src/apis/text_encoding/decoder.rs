@@ -0,0 +1,202 @@
+//! Streaming decoders for the encodings supported by `TextDecoder`.
+//!
+//! The decoders follow the [Encoding Standard](https://encoding.spec.whatwg.org/):
+//! invalid sequences are replaced by U+FFFD, or reported as errors in fatal
+//! mode, and incomplete sequences at the end of a chunk are kept until the
+//! next chunk when streaming.
+use std::str;
+
+/// The encodings supported by `TextDecoder`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Windows1252,
+}
+
+impl Encoding {
+    /// The encoding for a canonical encoding name, as exposed by
+    /// `TextDecoder.prototype.encoding`.
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "utf-8" => Some(Self::Utf8),
+            "utf-16le" => Some(Self::Utf16Le),
+            "utf-16be" => Some(Self::Utf16Be),
+            "windows-1252" => Some(Self::Windows1252),
+            _ => None,
+        }
+    }
+
+    pub(super) fn name(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16be",
+            Self::Windows1252 => "windows-1252",
+        }
+    }
+}
+
+/// The code points of the bytes 0x80 to 0x9F in windows-1252. The other bytes
+/// map to the code point of the same value.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// The input contained an invalid sequence and the decoder is in fatal mode.
+#[derive(Debug)]
+pub(super) struct DecodeError;
+
+/// The state of a `TextDecoder`.
+pub(super) struct Decoder {
+    encoding: Encoding,
+    fatal: bool,
+    ignore_bom: bool,
+    /// Whether the start of the stream has been decoded, after which a BOM is
+    /// no longer stripped.
+    bom_seen: bool,
+    /// Whether the previous call was streaming, in which case the state is
+    /// kept for the next call.
+    do_not_flush: bool,
+    /// The bytes of an incomplete sequence at the end of the previous chunk.
+    pending: Vec<u8>,
+}
+
+impl Decoder {
+    pub(super) fn new(encoding: Encoding, fatal: bool, ignore_bom: bool) -> Self {
+        Self {
+            encoding,
+            fatal,
+            ignore_bom,
+            bom_seen: false,
+            do_not_flush: false,
+            pending: vec![],
+        }
+    }
+
+    /// Decodes a chunk of input.
+    ///
+    /// When `stream` is `true`, an incomplete sequence at the end of the
+    /// input is kept for the next call. Otherwise the stream ends with this
+    /// chunk and the next call starts a new one.
+    pub(super) fn decode(&mut self, input: &[u8], stream: bool) -> Result<String, DecodeError> {
+        if !self.do_not_flush {
+            self.pending.clear();
+            self.bom_seen = false;
+        }
+        self.do_not_flush = stream;
+
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(input);
+
+        let mut output = String::with_capacity(bytes.len());
+        let consumed = match self.encoding {
+            Encoding::Utf8 => self.decode_utf8(&bytes, stream, &mut output),
+            Encoding::Utf16Le => self.decode_utf16(&bytes, stream, u16::from_le_bytes, &mut output),
+            Encoding::Utf16Be => self.decode_utf16(&bytes, stream, u16::from_be_bytes, &mut output),
+            Encoding::Windows1252 => {
+                output.extend(bytes.iter().map(|&byte| match byte {
+                    0x80..=0x9F => WINDOWS_1252[(byte - 0x80) as usize],
+                    _ => byte as char,
+                }));
+                Ok(bytes.len())
+            }
+        }?;
+        self.pending = bytes.split_off(consumed);
+
+        if !self.bom_seen && !output.is_empty() {
+            self.bom_seen = true;
+            if !self.ignore_bom && output.starts_with('\u{FEFF}') {
+                output.remove(0);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Decodes UTF-8, returning the number of bytes consumed.
+    fn decode_utf8(
+        &self,
+        bytes: &[u8],
+        stream: bool,
+        output: &mut String,
+    ) -> Result<usize, DecodeError> {
+        let mut rest = bytes;
+        loop {
+            match str::from_utf8(rest) {
+                Ok(valid) => {
+                    output.push_str(valid);
+                    return Ok(bytes.len());
+                }
+                Err(error) => {
+                    let (valid, invalid) = rest.split_at(error.valid_up_to());
+                    // SAFETY: `valid_up_to` is the length of the valid prefix.
+                    output.push_str(unsafe { str::from_utf8_unchecked(valid) });
+                    match error.error_len() {
+                        Some(len) => {
+                            self.replace(output)?;
+                            rest = &invalid[len..];
+                        }
+                        // The input ends in the middle of a sequence.
+                        None if stream => return Ok(bytes.len() - invalid.len()),
+                        None => {
+                            self.replace(output)?;
+                            return Ok(bytes.len());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decodes UTF-16, returning the number of bytes consumed.
+    fn decode_utf16(
+        &self,
+        bytes: &[u8],
+        stream: bool,
+        to_unit: fn([u8; 2]) -> u16,
+        output: &mut String,
+    ) -> Result<usize, DecodeError> {
+        let (pairs, _) = bytes.as_chunks::<2>();
+        let mut units: Vec<u16> = pairs.iter().map(|&pair| to_unit(pair)).collect();
+        let mut consumed = units.len() * 2;
+
+        // Keep a trailing lead surrogate, which may be followed by its trail
+        // surrogate in the next chunk.
+        if stream
+            && units
+                .last()
+                .is_some_and(|unit| (0xD800..0xDC00).contains(unit))
+        {
+            units.pop();
+            consumed -= 2;
+        }
+
+        for c in char::decode_utf16(units) {
+            match c {
+                Ok(c) => output.push(c),
+                Err(_) => self.replace(output)?,
+            }
+        }
+
+        if consumed < bytes.len() && !stream {
+            // A lone trailing byte at the end of the stream.
+            self.replace(output)?;
+            consumed = bytes.len();
+        }
+        Ok(consumed)
+    }
+
+    /// Handles an invalid sequence, either by emitting U+FFFD, or with an
+    /// error in fatal mode.
+    fn replace(&self, output: &mut String) -> Result<(), DecodeError> {
+        if self.fatal {
+            return Err(DecodeError);
+        }
+        output.push(char::REPLACEMENT_CHARACTER);
+        Ok(())
+    }
+}
@@ -1,22 +1,27 @@
-use std::str;
+use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     Args, hold, hold_and_release,
     quickjs::{
-        Ctx, Exception, Function, String as JSString, TypedArray, Value, context::EvalOptions,
+        Ctx, Exception, Function, Object, String as JSString, TypedArray, Value,
+        context::EvalOptions, prelude::MutFn,
     },
     to_js_error, to_string_lossy,
 };
 use anyhow::{Error, Result, anyhow, bail};
 
+mod decoder;
+
+use decoder::{Decoder, Encoding};
+
 /// Register `TextDecoder` and `TextEncoder` classes.
 pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
     let globals = this.globals();
     globals.set(
-        "__javy_decodeUtf8BufferToString",
+        "__javy_createTextDecoder",
         Function::new(this.clone(), |cx, args| {
             let (cx, args) = hold_and_release!(cx, args);
-            decode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+            create_decoder(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
         }),
     )?;
     globals.set(
@@ -26,6 +31,13 @@ pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
             encode(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
         }),
     )?;
+    globals.set(
+        "__javy_encodeStringIntoUtf8Buffer",
+        Function::new(this.clone(), |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            encode_into(hold!(cx.clone(), args)).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
     let mut opts = EvalOptions::default();
     opts.strict = false;
     this.eval_with_options::<(), _>(include_str!("./text-encoding.js"), opts)?;
@@ -33,12 +45,51 @@ pub(crate) fn register(this: Ctx<'_>) -> Result<()> {
     Ok::<_, Error>(())
 }
 
-/// Decode a UTF-8 byte buffer as a JavaScript String.
-fn decode(args: Args<'_>) -> Result<Value<'_>> {
+/// Create a decoding function for a `TextDecoder`, which keeps the state of
+/// the decoder between calls.
+fn create_decoder(args: Args<'_>) -> Result<Value<'_>> {
     let (cx, args) = args.release();
-    if args.len() != 5 {
+    if args.len() != 3 {
         bail!(
-            "Wrong number of arguments. Expected 5 arguments. Got: {}",
+            "Wrong number of arguments. Expected 3 arguments. Got: {}",
+            args.len()
+        );
+    }
+
+    let name = args[0]
+        .as_string()
+        .ok_or_else(|| anyhow!("encoding must be a string"))?
+        .to_string()?;
+    let encoding =
+        Encoding::from_name(&name).ok_or_else(|| anyhow!("Unsupported encoding: {name}"))?;
+    let fatal = args[1]
+        .as_bool()
+        .ok_or_else(|| anyhow!("fatal must be a boolean"))?;
+    let ignore_bom = args[2]
+        .as_bool()
+        .ok_or_else(|| anyhow!("ignore_bom must be a boolean"))?;
+
+    let decoder = Rc::new(RefCell::new(Decoder::new(encoding, fatal, ignore_bom)));
+    let function = Function::new(
+        cx.clone(),
+        MutFn::new(move |cx, args| {
+            let (cx, args) = hold_and_release!(cx, args);
+            decode(hold!(cx.clone(), args), encoding, &decoder).map_err(|e| to_js_error(cx, e))
+        }),
+    )?;
+    Ok(function.into_value())
+}
+
+/// Decode a chunk of a byte buffer as a JavaScript String.
+fn decode<'js>(
+    args: Args<'js>,
+    encoding: Encoding,
+    decoder: &Rc<RefCell<Decoder>>,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    if args.len() != 4 {
+        bail!(
+            "Wrong number of arguments. Expected 4 arguments. Got: {}",
             args.len()
         );
     }
@@ -57,37 +108,22 @@ fn decode(args: Args<'_>) -> Result<Value<'_>> {
     let byte_length = args[2]
         .as_number()
         .ok_or_else(|| anyhow!("byte_length must be a number"))? as usize;
-    let fatal = args[3]
+    let stream = args[3]
         .as_bool()
-        .ok_or_else(|| anyhow!("fatal must be a boolean"))?;
-    let ignore_bom = args[4]
-        .as_bool()
-        .ok_or_else(|| anyhow!("ignore_bom must be a boolean"))?;
+        .ok_or_else(|| anyhow!("stream must be a boolean"))?;
 
-    let mut view = buffer
+    let view = buffer
         .get(byte_offset..(byte_offset + byte_length))
         .ok_or_else(|| anyhow!("Provided offset and length is not valid for provided buffer"))?;
 
-    if !ignore_bom {
-        view = match view {
-            // [0xEF, 0xBB, 0xBF] is the UTF-8 BOM which we want to strip
-            [0xEF, 0xBB, 0xBF, rest @ ..] => rest,
-            _ => view,
-        };
-    }
-
-    let js_string = if fatal {
-        JSString::from_str(
-            cx.clone(),
-            str::from_utf8(view)
-                .map_err(|_| Exception::throw_type(&cx, "The encoded data was not valid utf-8"))?,
+    let decoded = decoder.borrow_mut().decode(view, stream).map_err(|_| {
+        Exception::throw_type(
+            &cx,
+            &format!("The encoded data was not valid {}", encoding.name()),
         )
-    } else {
-        let str = String::from_utf8_lossy(view);
-        JSString::from_str(cx, &str)
-    };
+    })?;
 
-    Ok(Value::from_string(js_string?))
+    Ok(Value::from_string(JSString::from_str(cx, &decoded)?))
 }
 
 /// Encode a JavaScript String into a JavaScript UInt8Array.
@@ -111,16 +147,79 @@ fn encode(args: Args<'_>) -> Result<Value<'_>> {
         .to_owned())
 }
 
+/// Encode a JavaScript String into the bytes of an ArrayBuffer, returning the
+/// number of UTF-16 code units read and the number of bytes written.
+///
+/// Only complete characters are written, the encoding stops at the first
+/// character which doesn't fit.
+fn encode_into(args: Args<'_>) -> Result<Value<'_>> {
+    let (cx, args) = args.release();
+    if args.len() != 4 {
+        bail!(
+            "Wrong number of arguments. Expected 4 arguments. Got: {}",
+            args.len()
+        );
+    }
+
+    let js_string = args[0]
+        .as_string()
+        .ok_or_else(|| anyhow!("Argument must be a String"))?;
+    let source = js_string
+        .to_string()
+        .unwrap_or_else(|error| to_string_lossy(&cx, js_string, error));
+
+    let raw = args[1]
+        .as_object()
+        .ok_or_else(|| anyhow!("buffer must be an object"))?
+        .as_array_buffer()
+        .ok_or_else(|| anyhow!("buffer must be an ArrayBuffer"))?
+        .as_raw()
+        .ok_or_else(|| anyhow!("Couldn't retrive &mut [u8] from buffer"))?;
+    let byte_offset = args[2]
+        .as_number()
+        .ok_or_else(|| anyhow!("offset must be a number"))? as usize;
+    let byte_length = args[3]
+        .as_number()
+        .ok_or_else(|| anyhow!("byte_length must be a number"))? as usize;
+
+    // SAFETY: The buffer is kept alive by the arguments and isn't otherwise
+    // accessed while the slice is in use.
+    let buffer = unsafe { std::slice::from_raw_parts_mut(raw.ptr.as_ptr(), raw.len) };
+    let destination = buffer
+        .get_mut(byte_offset..(byte_offset + byte_length))
+        .ok_or_else(|| anyhow!("Provided offset and length is not valid for provided buffer"))?;
+
+    let (mut read, mut written) = (0, 0);
+    for c in source.chars() {
+        let len = c.len_utf8();
+        if written + len > destination.len() {
+            break;
+        }
+        c.encode_utf8(&mut destination[written..]);
+        written += len;
+        read += c.len_utf16();
+    }
+
+    let result = Object::new(cx)?;
+    result.set("read", read)?;
+    result.set("written", written)?;
+    Ok(result.into_value())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Config, Runtime, quickjs::Value};
     use anyhow::{Error, Result};
 
-    #[test]
-    fn test_text_encoder_decoder() -> Result<()> {
+    fn runtime() -> Result<Runtime> {
         let mut config = Config::default();
         config.text_encoding(true);
-        let runtime = Runtime::new(config)?;
+        Runtime::new(config)
+    }
+
+    #[test]
+    fn test_text_encoder_decoder() -> Result<()> {
+        let runtime = runtime()?;
 
         runtime.context().with(|this| {
             let result: Value<'_> = this.eval(
@@ -138,4 +237,154 @@ mod tests {
         })?;
         Ok(())
     }
+
+    #[test]
+    fn test_streaming_decode() -> Result<()> {
+        let runtime = runtime()?;
+
+        runtime.context().with(|this| {
+            let result: String = this.eval(
+                r#"
+                const decoder = new TextDecoder();
+                const bytes = new TextEncoder().encode('\uFEFFa€😀');
+                let result = '';
+                for (const byte of bytes) {
+                    result += decoder.decode(new Uint8Array([byte]), { stream: true });
+                }
+                result + decoder.decode();
+            "#,
+            )?;
+            assert_eq!("a€😀", result);
+
+            let result: String = this.eval(
+                r#"
+                const flushing = new TextDecoder();
+                flushing.decode(new Uint8Array([0xF0, 0x9F]), { stream: true }) +
+                    flushing.decode() +
+                    flushing.decode(new Uint8Array([0x98, 0x80]));
+            "#,
+            )?;
+            assert_eq!("\u{FFFD}\u{FFFD}\u{FFFD}", result);
+
+            let result: bool = this.eval(
+                r#"
+                const fatal = new TextDecoder('utf-8', { fatal: true });
+                fatal.decode(new Uint8Array([0xE2, 0x82]), { stream: true });
+                try {
+                    fatal.decode();
+                    false;
+                } catch (e) {
+                    e instanceof TypeError;
+                }
+            "#,
+            )?;
+            assert!(result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_utf16_decode() -> Result<()> {
+        let runtime = runtime()?;
+
+        runtime.context().with(|this| {
+            let result: Vec<String> = this.eval(
+                r#"
+                const le = new TextDecoder('utf-16');
+                const be = new TextDecoder('UTF-16BE');
+                const chunks = [[0xFF, 0xFE, 0x61], [0x00, 0x3D, 0xD8], [0x00], [0xDE]];
+                [
+                    le.encoding,
+                    be.encoding,
+                    chunks.map((chunk) => le.decode(new Uint8Array(chunk), { stream: true })).join('') + le.decode(),
+                    be.decode(new Uint8Array([0xFE, 0xFF, 0x00, 0x61, 0xD8, 0x3D])),
+                    le.decode(new Uint8Array([0x61, 0x00, 0x62])),
+                    new TextDecoder('utf-16le', { ignoreBOM: true }).decode(new Uint8Array([0xFF, 0xFE, 0x61, 0x00])),
+                ];
+            "#,
+            )?;
+            assert_eq!(
+                vec![
+                    "utf-16le",
+                    "utf-16be",
+                    "a😀",
+                    "a\u{FFFD}",
+                    "a\u{FFFD}",
+                    "\u{FEFF}a"
+                ],
+                result
+            );
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_windows_1252_decode() -> Result<()> {
+        let runtime = runtime()?;
+
+        runtime.context().with(|this| {
+            let result: Vec<String> = this.eval(
+                r#"
+                const decoder = new TextDecoder(' Latin1 ');
+                [decoder.encoding, decoder.decode(new Uint8Array([0x63, 0x61, 0x66, 0xE9, 0x20, 0x80, 0x81, 0x9F, 0xFF]))];
+            "#,
+            )?;
+            assert_eq!(vec!["windows-1252", "café €\u{81}Ÿÿ"], result);
+
+            let result: bool = this.eval(
+                r#"
+                try {
+                    new TextDecoder('replacement');
+                    false;
+                } catch (e) {
+                    e instanceof RangeError;
+                }
+            "#,
+            )?;
+            assert!(result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_into() -> Result<()> {
+        let runtime = runtime()?;
+
+        runtime.context().with(|this| {
+            let result: Vec<usize> = this.eval(
+                r#"
+                const encoder = new TextEncoder();
+                const buffer = new Uint8Array(8).fill(0xAA);
+                const destination = buffer.subarray(1, 7);
+                const { read, written } = encoder.encodeInto('a€😀', destination);
+                const empty = encoder.encodeInto('a', new Uint8Array());
+                const lone = encoder.encodeInto('\uD800b', new Uint8Array(4));
+                [read, written, ...buffer, empty.read, empty.written, lone.read, lone.written];
+            "#,
+            )?;
+            assert_eq!(
+                vec![
+                    2, 4, 0xAA, 0x61, 0xE2, 0x82, 0xAC, 0xAA, 0xAA, 0xAA, 0, 0, 2, 4
+                ],
+                result
+            );
+
+            let result: bool = this.eval(
+                r#"
+                try {
+                    encoder.encodeInto('a', new ArrayBuffer(4));
+                    false;
+                } catch (e) {
+                    e instanceof TypeError;
+                }
+            "#,
+            )?;
+            assert!(result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
 }
@@ -1,31 +1,46 @@
 (function () {
-    const __javy_decodeUtf8BufferToString = globalThis.__javy_decodeUtf8BufferToString;
+    const __javy_createTextDecoder = globalThis.__javy_createTextDecoder;
     const __javy_encodeStringToUtf8Buffer = globalThis.__javy_encodeStringToUtf8Buffer;
+    const __javy_encodeStringIntoUtf8Buffer = globalThis.__javy_encodeStringIntoUtf8Buffer;
+
+    // https://encoding.spec.whatwg.org/#names-and-labels
+    const encodingLabels = {
+        "utf-8": ["unicode-1-1-utf-8", "unicode11utf8", "unicode20utf8", "utf-8", "utf8", "x-unicode20utf8"],
+        "utf-16be": ["unicodefffe", "utf-16be"],
+        "utf-16le": ["csunicode", "iso-10646-ucs-2", "ucs-2", "unicode", "unicodefeff", "utf-16", "utf-16le"],
+        "windows-1252": [
+            "ansi_x3.4-1968", "ascii", "cp1252", "cp819", "csisolatin1", "ibm819", "iso-8859-1", "iso-ir-100",
+            "iso8859-1", "iso88591", "iso_8859-1", "iso_8859-1:1987", "l1", "latin1", "us-ascii", "windows-1252",
+            "x-cp1252",
+        ],
+    };
+    const encodings = new Map();
+    for (const [encoding, labels] of Object.entries(encodingLabels)) {
+        for (const label of labels) {
+            encodings.set(label, encoding);
+        }
+    }
 
     class TextDecoder {
+        #decode;
+
         constructor(label = "utf-8", options = {}) {
-            label = label.trim().toLowerCase();
-            const acceptedLabels = ["utf-8", "utf8", "unicode-1-1-utf-8", "unicode11utf8", "unicode20utf8", "x-unicode20utf8"];
-            if (!acceptedLabels.includes(label)) {
-                // Not spec-compliant behaviour
-                throw new RangeError("The encoding label provided must be utf-8");
+            label = String(label)
+                .replace(/^[\t\n\f\r ]+|[\t\n\f\r ]+$/g, "")
+                .replace(/[A-Z]/g, (c) => c.toLowerCase());
+            const encoding = encodings.get(label);
+            if (encoding === undefined) {
+                throw new RangeError(`The encoding label provided ('${label}') is invalid`);
             }
             Object.defineProperties(this, {
-                encoding: { value: "utf-8", enumerable: true, writable: false },
+                encoding: { value: encoding, enumerable: true, writable: false },
                 fatal: { value: !!options.fatal, enumerable: true, writable: false },
                 ignoreBOM: { value: !!options.ignoreBOM, enumerable: true, writable: false },
             })
+            this.#decode = __javy_createTextDecoder(encoding, this.fatal, this.ignoreBOM);
         }
 
-        decode(input, options = {}) {
-            if (input === undefined) {
-                return "";
-            }
-
-            if (options.stream) {
-                throw new Error("Streaming decode is not supported");
-            }
-
+        decode(input = new Uint8Array(), options = {}) {
             // backing buffer would not have byteOffset and may have different byteLength
             let byteOffset = input.byteOffset || 0;
             let byteLength = input.byteLength;
@@ -37,7 +52,7 @@
                 throw new TypeError("The provided value is not of type '(ArrayBuffer or ArrayBufferView)'");
             }
 
-            return __javy_decodeUtf8BufferToString(input, byteOffset, byteLength, this.fatal, this.ignoreBOM);
+            return this.#decode(input, byteOffset, byteLength, !!options?.stream);
         }
     }
 
@@ -54,13 +69,22 @@
         }
 
         encodeInto(source, destination) {
-            throw new Error("encodeInto is not supported");
+            source = String(source);
+            if (!(destination instanceof Uint8Array)) {
+                throw new TypeError("The provided value is not of type 'Uint8Array'");
+            }
+            // Nothing can be written into an empty or detached buffer.
+            if (destination.byteLength === 0) {
+                return { read: 0, written: 0 };
+            }
+            return __javy_encodeStringIntoUtf8Buffer(source, destination.buffer, destination.byteOffset, destination.byteLength);
         }
     }
 
     globalThis.TextDecoder = TextDecoder;
     globalThis.TextEncoder = TextEncoder;
 
-    Reflect.deleteProperty(globalThis, "__javy_decodeUtf8BufferToString");
+    Reflect.deleteProperty(globalThis, "__javy_createTextDecoder");
     Reflect.deleteProperty(globalThis, "__javy_encodeStringToUtf8Buffer");
+    Reflect.deleteProperty(globalThis, "__javy_encodeStringIntoUtf8Buffer");
 })();
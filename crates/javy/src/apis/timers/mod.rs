@@ -0,0 +1,409 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Args, hold, hold_and_release,
+    quickjs::{
+        Ctx, Function, Persistent, Result as JSResult, Value,
+        convert::Coerced,
+        prelude::{MutFn, Rest},
+    },
+    to_js_error,
+};
+use anyhow::{Result, anyhow};
+
+/// A timer scheduled through `setTimeout` or `setInterval`.
+struct Timer {
+    /// The identifier returned to JavaScript.
+    id: u32,
+    /// The instant, according to the monotonic clock, at which the timer
+    /// fires.
+    deadline: Instant,
+    /// The repetition interval, only set for timers created through
+    /// `setInterval`.
+    interval: Option<Duration>,
+    /// The function to invoke.
+    callback: Persistent<Function<'static>>,
+    /// The extra arguments passed to `setTimeout` or `setInterval`, forwarded
+    /// to the callback.
+    args: Vec<Persistent<Value<'static>>>,
+    /// Whether the event loop keeps running until the timer fires. Timers
+    /// scheduled by the runtime itself, like the one behind
+    /// `AbortSignal.timeout`, don't keep it running.
+    keep_alive: bool,
+}
+
+/// The queue of pending timers.
+///
+/// Timers are ordered by their deadline, timers sharing the same deadline
+/// fire in the order in which they were scheduled.
+#[derive(Default)]
+pub(crate) struct TimerQueue {
+    /// The last identifier handed out.
+    last_id: u32,
+    /// The pending timers.
+    timers: Vec<Timer>,
+}
+
+impl TimerQueue {
+    /// Whether there are no timers keeping the event loop running. Timers
+    /// which don't keep it running may still be waiting to fire.
+    pub(crate) fn is_idle(&self) -> bool {
+        !self.timers.iter().any(|t| t.keep_alive)
+    }
+
+    /// Removes all the timers.
+    pub(crate) fn clear(&mut self) {
+        self.timers.clear();
+    }
+
+    /// The deadline of the next timer to fire, if any.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.next().map(|i| self.timers[i].deadline)
+    }
+
+    fn next(&self) -> Option<usize> {
+        self.timers
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, t)| (t.deadline, t.id))
+            .map(|(i, _)| i)
+    }
+
+    fn push(&mut self, mut timer: Timer) -> u32 {
+        self.last_id = self.last_id.wrapping_add(1).max(1);
+        timer.id = self.last_id;
+        self.timers.push(timer);
+        self.last_id
+    }
+
+    fn remove(&mut self, id: u32) {
+        self.timers.retain(|t| t.id != id);
+    }
+}
+
+/// Register `setTimeout`, `setInterval`, `clearTimeout` and `clearInterval`
+/// on the global object.
+pub(crate) fn register(this: Ctx<'_>, queue: Rc<RefCell<TimerQueue>>) -> Result<()> {
+    let globals = this.globals();
+
+    let timeout_queue = queue.clone();
+    globals.set(
+        "setTimeout",
+        Function::new(
+            this.clone(),
+            MutFn::new(move |cx, args| {
+                let (cx, args) = hold_and_release!(cx, args);
+                schedule(hold!(cx.clone(), args), &timeout_queue, false)
+                    .map_err(|e| to_js_error(cx, e))
+            }),
+        )?,
+    )?;
+
+    let interval_queue = queue.clone();
+    globals.set(
+        "setInterval",
+        Function::new(
+            this.clone(),
+            MutFn::new(move |cx, args| {
+                let (cx, args) = hold_and_release!(cx, args);
+                schedule(hold!(cx.clone(), args), &interval_queue, true)
+                    .map_err(|e| to_js_error(cx, e))
+            }),
+        )?,
+    )?;
+
+    // Timeouts and intervals share the same pool of identifiers, as such
+    // `clearTimeout` and `clearInterval` are interchangeable.
+    for name in ["clearTimeout", "clearInterval"] {
+        let clear_queue = queue.clone();
+        globals.set(
+            name,
+            Function::new(
+                this.clone(),
+                MutFn::new(move |cx, args| {
+                    let (cx, args) = hold_and_release!(cx, args);
+                    clear(hold!(cx.clone(), args), &clear_queue).map_err(|e| to_js_error(cx, e))
+                }),
+            )?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Schedules a new timer, returning its identifier.
+fn schedule<'js>(
+    args: Args<'js>,
+    queue: &Rc<RefCell<TimerQueue>>,
+    repeat: bool,
+) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    let mut args = args.into_inner().into_iter();
+
+    let callback = args
+        .next()
+        .and_then(|v| v.into_function())
+        .ok_or_else(|| anyhow!("The callback argument must be of type function"))?;
+    let delay = match args.next() {
+        Some(delay) => delay_from_value(delay)?,
+        None => Duration::ZERO,
+    };
+
+    let timer = Timer {
+        id: 0,
+        deadline: deadline_after(delay),
+        interval: repeat.then_some(delay),
+        callback: Persistent::save(&cx, callback),
+        args: args.map(|arg| Persistent::save(&cx, arg)).collect(),
+        keep_alive: true,
+    };
+    let id = queue.borrow_mut().push(timer);
+
+    Ok(Value::new_number(cx, id as f64))
+}
+
+/// Schedules `callback` to run once after `delay`, without keeping the event
+/// loop running until then.
+pub(crate) fn schedule_unref<'js>(
+    cx: &Ctx<'js>,
+    queue: &Rc<RefCell<TimerQueue>>,
+    callback: Function<'js>,
+    delay: Duration,
+) {
+    queue.borrow_mut().push(Timer {
+        id: 0,
+        deadline: deadline_after(delay),
+        interval: None,
+        callback: Persistent::save(cx, callback),
+        args: Vec::new(),
+        keep_alive: false,
+    });
+}
+
+/// Cancels a timer. Unknown identifiers are ignored.
+fn clear<'js>(args: Args<'js>, queue: &Rc<RefCell<TimerQueue>>) -> Result<Value<'js>> {
+    let (cx, args) = args.release();
+    if let Some(id) = args.first().and_then(|id| id.as_number()) {
+        queue.borrow_mut().remove(id as u32);
+    }
+
+    Ok(Value::new_undefined(cx))
+}
+
+/// The largest delay, in milliseconds, the HTML spec allows. Larger delays
+/// overflow the signed 32-bit integer browsers store them in, and are treated
+/// as `0`.
+const MAX_DELAY_MILLIS: f64 = i32::MAX as f64;
+
+/// Converts the delay argument to a [`Duration`], following the HTML spec,
+/// values that can't be converted to a positive number, or which are larger
+/// than 2^31-1 milliseconds, are treated as `0`.
+pub(crate) fn delay_from_value(delay: Value<'_>) -> Result<Duration> {
+    let millis = delay.get::<Coerced<f64>>()?.0;
+    if millis > 0.0 && millis <= MAX_DELAY_MILLIS {
+        Ok(Duration::from_secs_f64(millis / 1000.0))
+    } else {
+        Ok(Duration::ZERO)
+    }
+}
+
+/// The instant at which a timer scheduled now with `delay` fires. A deadline
+/// the monotonic clock can't represent fires right away.
+fn deadline_after(delay: Duration) -> Instant {
+    let now = Instant::now();
+    now.checked_add(delay).unwrap_or(now)
+}
+
+/// Fires the next timer, if its deadline has been reached.
+///
+/// Returns `true` if a timer fired. Intervals are rescheduled before the
+/// callback runs, so clearing an interval from within its own callback
+/// cancels it.
+pub(crate) fn fire_next(cx: &Ctx<'_>, queue: &Rc<RefCell<TimerQueue>>) -> JSResult<bool> {
+    let (callback, args) = {
+        let mut queue = queue.borrow_mut();
+        let Some(index) = queue.next() else {
+            return Ok(false);
+        };
+        if queue.timers[index].deadline > Instant::now() {
+            return Ok(false);
+        }
+
+        match queue.timers[index].interval {
+            Some(interval) => {
+                let timer = &mut queue.timers[index];
+                timer.deadline = deadline_after(interval);
+                (timer.callback.clone(), timer.args.clone())
+            }
+            None => {
+                let timer = queue.timers.remove(index);
+                (timer.callback, timer.args)
+            }
+        }
+    };
+
+    let callback = callback.restore(cx)?;
+    let args = args
+        .into_iter()
+        .map(|arg| arg.restore(cx))
+        .collect::<JSResult<Vec<_>>>()?;
+    callback.call::<_, ()>((Rest(args),))?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    fn runtime() -> Result<Runtime> {
+        let mut config = Config::default();
+        config.timers(true);
+        Runtime::new(config)
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|cx| {
+            for name in ["setTimeout", "setInterval", "clearTimeout", "clearInterval"] {
+                assert!(cx.globals().get::<_, Value<'_>>(name)?.is_function());
+            }
+            Ok::<_, Error>(())
+        })?;
+
+        assert!(Runtime::default().context().with(|cx| {
+            cx.globals()
+                .get::<_, Value<'_>>("setTimeout")
+                .unwrap()
+                .is_undefined()
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_timers_fire_in_order() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|cx| {
+            cx.eval::<(), _>(
+                r#"
+                globalThis.order = [];
+                setTimeout(() => order.push("c"), 2);
+                setTimeout((a, b) => order.push(a + b), 0, "a", "b");
+                setTimeout(() => order.push("b"), 1);
+                queueMicrotask(() => order.push("microtask"));
+                Promise.resolve().then(() => order.push("job"));
+            "#,
+            )
+        })?;
+        assert!(runtime.has_pending_timers());
+
+        runtime.run_event_loop()?;
+
+        assert!(!runtime.has_pending_timers());
+        runtime.context().with(|cx| {
+            let order: String = cx.eval("order.join()")?;
+            assert_eq!("microtask,job,ab,b,c", order);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear_timers() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|cx| {
+            cx.eval::<(), _>(
+                r#"
+                globalThis.ticks = 0;
+                globalThis.cleared = false;
+                const id = setTimeout(() => { cleared = true; }, 0);
+                clearTimeout(id);
+                const interval = setInterval(() => {
+                    ticks += 1;
+                    if (ticks === 3) {
+                        clearInterval(interval);
+                    }
+                }, 1);
+            "#,
+            )
+        })?;
+
+        runtime.run_event_loop()?;
+
+        runtime.context().with(|cx| {
+            assert_eq!(3, cx.eval::<i32, _>("ticks")?);
+            assert!(!cx.eval::<bool, _>("cleared")?);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_range_delays_fire_right_away() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|cx| {
+            cx.eval::<(), _>(
+                r#"
+                globalThis.fired = [];
+                for (const delay of [1e300, 2 ** 31, -1, NaN, Infinity, -Infinity, "soon"]) {
+                    setTimeout(() => fired.push(String(delay)), delay);
+                }
+                let ticks = 0;
+                const interval = setInterval(() => {
+                    if (++ticks === 2) {
+                        clearInterval(interval);
+                    }
+                }, 1e300);
+            "#,
+            )
+        })?;
+
+        runtime.run_event_loop()?;
+
+        runtime.context().with(|cx| {
+            let fired: String = cx.eval("fired.join()")?;
+            assert_eq!("1e+300,2147483648,-1,NaN,Infinity,-Infinity,soon", fired);
+            assert_eq!(2, cx.eval::<i32, _>("ticks")?);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_timer_errors_are_propagated() -> Result<()> {
+        let runtime = runtime()?;
+        runtime
+            .context()
+            .with(|cx| cx.eval::<(), _>("setTimeout(() => { throw new Error('boom'); }, 0);"))?;
+
+        let err = runtime.run_event_loop().unwrap_err();
+        assert!(err.to_string().starts_with("Error: boom"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_callback() -> Result<()> {
+        let runtime = runtime()?;
+        runtime.context().with(|cx| {
+            let result: bool = cx.eval(
+                r#"
+                try {
+                    setTimeout("1 + 1", 0);
+                    false
+                } catch (e) {
+                    e.message === "The callback argument must be of type function"
+                }
+            "#,
+            )?;
+            assert!(result);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+(function () {
+    const __javy_urlParse = globalThis.__javy_urlParse;
+    const __javy_urlSet = globalThis.__javy_urlSet;
+    const __javy_urlSearchParamsParse = globalThis.__javy_urlSearchParamsParse;
+    const __javy_urlSearchParamsSerialize = globalThis.__javy_urlSearchParamsSerialize;
+
+    // https://webidl.spec.whatwg.org/#es-USVString
+    function toUSVString(value) {
+        return String(value).toWellFormed();
+    }
+
+    function checkArgs(args, required, name) {
+        if (args.length < required) {
+            throw new TypeError(
+                `Failed to execute '${name}': ${required} argument${required > 1 ? "s" : ""} required, but only ${args.length} present`,
+            );
+        }
+    }
+
+    // Functions to access the private state of the other class, assigned in
+    // the static blocks below.
+    let setSearchParamsList;
+    let linkSearchParams;
+    let updateURLSearch;
+
+    class URLSearchParams {
+        #list = [];
+        #url = null;
+
+        static {
+            setSearchParamsList = (params, query) => {
+                params.#list = __javy_urlSearchParamsParse(query);
+            };
+            linkSearchParams = (params, url) => {
+                params.#url = url;
+            };
+        }
+
+        constructor(init = "") {
+            if (typeof init === "object" || typeof init === "function") {
+                // `null` is converted to an empty record.
+                init ??= {};
+                if (init[Symbol.iterator] !== undefined) {
+                    for (const pair of init) {
+                        if (pair === null || (typeof pair !== "object" && typeof pair !== "function")) {
+                            throw new TypeError("Each query pair must be an iterable [name, value] tuple");
+                        }
+                        const tuple = [...pair];
+                        if (tuple.length !== 2) {
+                            throw new TypeError("Each query pair must be an iterable [name, value] tuple");
+                        }
+                        this.#list.push([toUSVString(tuple[0]), toUSVString(tuple[1])]);
+                    }
+                } else {
+                    for (const key of Reflect.ownKeys(init)) {
+                        const descriptor = Reflect.getOwnPropertyDescriptor(init, key);
+                        if (descriptor !== undefined && descriptor.enumerable) {
+                            this.#list.push([toUSVString(key), toUSVString(init[key])]);
+                        }
+                    }
+                }
+            } else {
+                let query = toUSVString(init);
+                if (query.startsWith("?")) {
+                    query = query.slice(1);
+                }
+                this.#list = __javy_urlSearchParamsParse(query);
+            }
+        }
+
+        #update() {
+            if (this.#url !== null) {
+                updateURLSearch(this.#url, __javy_urlSearchParamsSerialize(this.#list));
+            }
+        }
+
+        get size() {
+            return this.#list.length;
+        }
+
+        append(name, value) {
+            checkArgs(arguments, 2, "append");
+            this.#list.push([toUSVString(name), toUSVString(value)]);
+            this.#update();
+        }
+
+        delete(name, value) {
+            checkArgs(arguments, 1, "delete");
+            name = toUSVString(name);
+            if (value === undefined) {
+                this.#list = this.#list.filter(([n]) => n !== name);
+            } else {
+                value = toUSVString(value);
+                this.#list = this.#list.filter(([n, v]) => n !== name || v !== value);
+            }
+            this.#update();
+        }
+
+        get(name) {
+            checkArgs(arguments, 1, "get");
+            name = toUSVString(name);
+            const pair = this.#list.find(([n]) => n === name);
+            return pair === undefined ? null : pair[1];
+        }
+
+        getAll(name) {
+            checkArgs(arguments, 1, "getAll");
+            name = toUSVString(name);
+            return this.#list.filter(([n]) => n === name).map(([, v]) => v);
+        }
+
+        has(name, value) {
+            checkArgs(arguments, 1, "has");
+            name = toUSVString(name);
+            if (value === undefined) {
+                return this.#list.some(([n]) => n === name);
+            }
+            value = toUSVString(value);
+            return this.#list.some(([n, v]) => n === name && v === value);
+        }
+
+        set(name, value) {
+            checkArgs(arguments, 2, "set");
+            name = toUSVString(name);
+            value = toUSVString(value);
+            const index = this.#list.findIndex(([n]) => n === name);
+            if (index === -1) {
+                this.#list.push([name, value]);
+            } else {
+                this.#list[index][1] = value;
+                this.#list = this.#list.filter(([n], i) => n !== name || i <= index);
+            }
+            this.#update();
+        }
+
+        sort() {
+            // Names are compared by code units, and `Array.prototype.sort` is
+            // stable.
+            this.#list.sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0));
+            this.#update();
+        }
+
+        forEach(callback, thisArg = undefined) {
+            checkArgs(arguments, 1, "forEach");
+            if (typeof callback !== "function") {
+                throw new TypeError("The callback provided as parameter 1 is not a function");
+            }
+            // The list is read on each iteration, so that changes made by the
+            // callback are visible.
+            for (let i = 0; i < this.#list.length; i++) {
+                const [name, value] = this.#list[i];
+                callback.call(thisArg, value, name, this);
+            }
+        }
+
+        *entries() {
+            for (let i = 0; i < this.#list.length; i++) {
+                yield [...this.#list[i]];
+            }
+        }
+
+        *keys() {
+            for (let i = 0; i < this.#list.length; i++) {
+                yield this.#list[i][0];
+            }
+        }
+
+        *values() {
+            for (let i = 0; i < this.#list.length; i++) {
+                yield this.#list[i][1];
+            }
+        }
+
+        [Symbol.iterator]() {
+            return this.entries();
+        }
+
+        toString() {
+            return __javy_urlSearchParamsSerialize(this.#list);
+        }
+
+        get [Symbol.toStringTag]() {
+            return "URLSearchParams";
+        }
+    }
+
+    class URL {
+        #components;
+        #searchParams;
+
+        static {
+            updateURLSearch = (url, query) => {
+                url.#components = __javy_urlSet(url.#components.href, "search", query);
+            };
+        }
+
+        constructor(url, base = undefined) {
+            checkArgs(arguments, 1, "URL");
+            const components = __javy_urlParse(toUSVString(url), base === undefined ? undefined : toUSVString(base));
+            if (components === null) {
+                throw new TypeError(`Invalid URL: ${url}`);
+            }
+            this.#components = components;
+            this.#searchParams = new URLSearchParams(components.search);
+            linkSearchParams(this.#searchParams, this);
+        }
+
+        static canParse(url, base = undefined) {
+            checkArgs(arguments, 1, "canParse");
+            return __javy_urlParse(toUSVString(url), base === undefined ? undefined : toUSVString(base)) !== null;
+        }
+
+        static parse(url, base = undefined) {
+            checkArgs(arguments, 1, "parse");
+            try {
+                return new URL(url, base);
+            } catch {
+                return null;
+            }
+        }
+
+        #set(component, value) {
+            this.#components = __javy_urlSet(this.#components.href, component, toUSVString(value));
+        }
+
+        get href() {
+            return this.#components.href;
+        }
+
+        set href(value) {
+            const components = __javy_urlSet(this.#components.href, "href", toUSVString(value));
+            if (components === null) {
+                throw new TypeError(`Invalid URL: ${value}`);
+            }
+            this.#components = components;
+            setSearchParamsList(this.#searchParams, components.search.slice(1));
+        }
+
+        get origin() {
+            return this.#components.origin;
+        }
+
+        get protocol() {
+            return this.#components.protocol;
+        }
+
+        set protocol(value) {
+            this.#set("protocol", value);
+        }
+
+        get username() {
+            return this.#components.username;
+        }
+
+        set username(value) {
+            this.#set("username", value);
+        }
+
+        get password() {
+            return this.#components.password;
+        }
+
+        set password(value) {
+            this.#set("password", value);
+        }
+
+        get host() {
+            return this.#components.host;
+        }
+
+        set host(value) {
+            this.#set("host", value);
+        }
+
+        get hostname() {
+            return this.#components.hostname;
+        }
+
+        set hostname(value) {
+            this.#set("hostname", value);
+        }
+
+        get port() {
+            return this.#components.port;
+        }
+
+        set port(value) {
+            this.#set("port", value);
+        }
+
+        get pathname() {
+            return this.#components.pathname;
+        }
+
+        set pathname(value) {
+            this.#set("pathname", value);
+        }
+
+        get search() {
+            return this.#components.search;
+        }
+
+        set search(value) {
+            this.#set("search", value);
+            setSearchParamsList(this.#searchParams, this.#components.search.slice(1));
+        }
+
+        get searchParams() {
+            return this.#searchParams;
+        }
+
+        get hash() {
+            return this.#components.hash;
+        }
+
+        set hash(value) {
+            this.#set("hash", value);
+        }
+
+        toString() {
+            return this.#components.href;
+        }
+
+        toJSON() {
+            return this.#components.href;
+        }
+
+        get [Symbol.toStringTag]() {
+            return "URL";
+        }
+    }
+
+    globalThis.URL = URL;
+    globalThis.URLSearchParams = URLSearchParams;
+
+    Reflect.deleteProperty(globalThis, "__javy_urlParse");
+    Reflect.deleteProperty(globalThis, "__javy_urlSet");
+    Reflect.deleteProperty(globalThis, "__javy_urlSearchParamsParse");
+    Reflect.deleteProperty(globalThis, "__javy_urlSearchParamsSerialize");
+})();
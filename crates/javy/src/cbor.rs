@@ -0,0 +1,246 @@
+use crate::{
+    apis::serializer::{DATE_TIME_TAG, Format, Intrinsics, ValueSerializer},
+    quickjs::{
+        Array, BigInt, Constructor, Ctx, Exception, Function, Object, String as JSString,
+        TypedArray, Value, object::Property,
+    },
+};
+use anyhow::{Result, bail};
+use ciborium::value::{Integer, Value as Cbor};
+
+/// The tag of epoch-based date/times, see
+/// <https://www.rfc-editor.org/rfc/rfc8949#section-3.4.2>.
+const EPOCH_TIME_TAG: u64 = 1;
+/// The largest integer which can be represented exactly by a number.
+const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
+// Unlike MessagePack, payloads are decoded through a `ciborium::Value` rather
+// than being transcoded: ciborium hands tags to Serde as enums, which
+// `serde_transcode` can't forward, and `rquickjs_serde` neither supports byte
+// strings nor creates `Date`s. Values are encoded by the same walker as
+// `JSON.stringify`.
+
+/// Transcodes a byte slice containing a CBOR encoded payload into a [`Value`].
+///
+/// Date/time strings and epoch-based date/times, tags 0 and 1, are converted
+/// to `Date`s, and byte strings to `Uint8Array`s. Other tags are ignored, so
+/// byte strings tagged as typed arrays, e.g. tag 64, are converted to
+/// `Uint8Array`s as well. Integers outside of the safe range are converted to
+/// `BigInt`s and integer keys of maps to strings. Keys are defined as own
+/// properties, so a `"__proto__"` key doesn't replace the prototype.
+pub fn transcode_input<'js>(context: Ctx<'js>, bytes: &[u8]) -> Result<Value<'js>> {
+    let value: Cbor = ciborium::from_reader(bytes)?;
+    to_js(&context, value)
+}
+
+/// Transcodes a [`Value`] into a CBOR encoded byte vector.
+///
+/// `Date`s are encoded as date/time strings, tag 0, `Uint8Array`s as byte
+/// strings and `BigInt`s as integers, as long as they fit in CBOR's 64-bit
+/// integers. Like with `JSON.stringify`, `toJSON` is called on objects which
+/// have it, and `undefined`, functions and symbols are omitted from objects
+/// and encoded as `null` in arrays.
+pub fn transcode_output(val: Value<'_>) -> Result<Vec<u8>> {
+    let cx = val.ctx().clone();
+    let serializer = ValueSerializer::new(&cx, &Intrinsics::new(&cx)?, Format::Cbor)?;
+    let mut output = Vec::new();
+    let encoded =
+        serializer.serialize_with(val, |value| ciborium::into_writer(value, &mut output))?;
+    // Like in arrays, values without a representation are encoded as `null`.
+    if encoded.is_none() {
+        ciborium::into_writer(&Cbor::Null, &mut output)?;
+    }
+    Ok(output)
+}
+
+fn to_js<'js>(cx: &Ctx<'js>, value: Cbor) -> Result<Value<'js>> {
+    Ok(match value {
+        Cbor::Null => Value::new_null(cx.clone()),
+        Cbor::Bool(b) => Value::new_bool(cx.clone(), b),
+        Cbor::Integer(integer) => integer_to_js(cx, integer)?,
+        // Not `Value::new_number`, which turns `-0` into `0`.
+        Cbor::Float(float) => Value::new_float(cx.clone(), float),
+        Cbor::Text(text) => JSString::from_str(cx.clone(), &text)?.into_value(),
+        Cbor::Bytes(bytes) => TypedArray::<u8>::new(cx.clone(), bytes)?.into_value(),
+        Cbor::Tag(DATE_TIME_TAG, content) => match *content {
+            Cbor::Text(text) => new_date(cx, JSString::from_str(cx.clone(), &text)?.into_value())?,
+            content => to_js(cx, content)?,
+        },
+        Cbor::Tag(EPOCH_TIME_TAG, content) => match *content {
+            Cbor::Integer(seconds) => {
+                let millis = i128::from(seconds) as f64 * 1000.0;
+                new_date(cx, Value::new_float(cx.clone(), millis))?
+            }
+            Cbor::Float(seconds) => new_date(cx, Value::new_float(cx.clone(), seconds * 1000.0))?,
+            content => to_js(cx, content)?,
+        },
+        Cbor::Tag(_, content) => to_js(cx, *content)?,
+        Cbor::Array(items) => {
+            let array = Array::new(cx.clone())?;
+            for (index, item) in items.into_iter().enumerate() {
+                array.set(index, to_js(cx, item)?)?;
+            }
+            array.into_value()
+        }
+        Cbor::Map(entries) => {
+            let object = Object::new(cx.clone())?;
+            for (key, value) in entries {
+                let key = match key {
+                    Cbor::Text(text) => text,
+                    Cbor::Integer(integer) => i128::from(integer).to_string(),
+                    _ => bail!(Exception::throw_type(
+                        cx,
+                        "Only text and integer CBOR map keys are supported"
+                    )),
+                };
+                let value = to_js(cx, value)?;
+                object.prop(
+                    key,
+                    Property::from(value).writable().enumerable().configurable(),
+                )?;
+            }
+            object.into_value()
+        }
+        _ => bail!(Exception::throw_type(cx, "Unsupported CBOR value")),
+    })
+}
+
+/// Converts an integer to a number, or to a `BigInt` if it's outside of the
+/// safe range.
+fn integer_to_js<'js>(cx: &Ctx<'js>, integer: Integer) -> Result<Value<'js>> {
+    let integer = i128::from(integer);
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&integer) {
+        return Ok(Value::new_number(cx.clone(), integer as f64));
+    }
+
+    let big_int = if let Ok(integer) = i64::try_from(integer) {
+        BigInt::from_i64(cx.clone(), integer)?.into_value()
+    } else if let Ok(integer) = u64::try_from(integer) {
+        BigInt::from_u64(cx.clone(), integer)?.into_value()
+    } else {
+        // Negative integers down to `-2^64`.
+        let constructor: Function = cx.globals().get("BigInt")?;
+        constructor.call((integer.to_string(),))?
+    };
+    Ok(big_int)
+}
+
+fn new_date<'js>(cx: &Ctx<'js>, value: Value<'js>) -> Result<Value<'js>> {
+    let date: Constructor = cx.globals().get("Date")?;
+    Ok(date.construct((value,))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{transcode_input, transcode_output};
+    use crate::{Runtime, quickjs::Value};
+    use anyhow::Result;
+
+    #[test]
+    fn round_trips_values() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            let value: Value = cx.eval(
+                r#"({
+                    a: [1, -1.5, "s", true, null, undefined],
+                    b: { c: new Date(Date.UTC(2024, 0, 2, 3, 4, 5, 6)) },
+                    d: new Uint8Array([1, 2, 3]),
+                    e: 2n ** 63n,
+                    f: -(2n ** 64n),
+                    g: undefined,
+                })"#,
+            )?;
+            let bytes = transcode_output(value)?;
+            let value = transcode_input(cx.clone(), &bytes)?;
+            cx.globals().set("value", value)?;
+            let result: String = cx.eval(
+                r#"[
+                    JSON.stringify(value.a),
+                    value.b.c instanceof Date && value.b.c.toISOString(),
+                    value.d instanceof Uint8Array && value.d.join(" "),
+                    typeof value.e + "=" + value.e,
+                    typeof value.f + "=" + value.f,
+                    "g" in value,
+                ].join()"#,
+            )?;
+            assert_eq!(
+                r#"[1,-1.5,"s",true,null,null],2024-01-02T03:04:05.006Z,1 2 3,bigint=9223372036854775808,bigint=-18446744073709551616,false"#,
+                result
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn maps_tags() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            // {"t": 0("2024-01-02T00:00:00Z"), "e": 1(1.5), "b": 64(h'0102'), 1: 2}
+            let bytes = [
+                0xa4, 0x61, b't', 0xc0, 0x74, b'2', b'0', b'2', b'4', b'-', b'0', b'1', b'-', b'0',
+                b'2', b'T', b'0', b'0', b':', b'0', b'0', b':', b'0', b'0', b'Z', 0x61, b'e', 0xc1,
+                0xf9, 0x3e, 0x00, 0x61, b'b', 0xd8, 0x40, 0x42, 0x01, 0x02, 0x01, 0x02,
+            ];
+            let value = transcode_input(cx.clone(), &bytes)?;
+            cx.globals().set("value", value)?;
+            let result: String = cx.eval(
+                r#"[
+                    value.t.toISOString(),
+                    value.e.getTime(),
+                    value.b instanceof Uint8Array && value.b.join(" "),
+                    value[1],
+                ].join()"#,
+            )?;
+            assert_eq!("2024-01-02T00:00:00.000Z,1500,1 2,2", result);
+
+            let date: Value = cx.eval("new Date(0)")?;
+            let mut expected = vec![0xc0, 0x78, 0x18];
+            expected.extend(b"1970-01-01T00:00:00.000Z");
+            assert_eq!(expected, transcode_output(date)?);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn defines_keys_as_own_properties() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            // {"__proto__": {"polluted": true}}
+            let mut bytes = vec![0xa1, 0x69];
+            bytes.extend(b"__proto__");
+            bytes.extend([0xa1, 0x68]);
+            bytes.extend(b"polluted");
+            bytes.push(0xf5);
+            let value = transcode_input(cx.clone(), &bytes)?;
+            cx.globals().set("value", value)?;
+            let result: String = cx.eval(
+                r#"[
+                    Object.getPrototypeOf(value) === Object.prototype,
+                    Object.keys(value),
+                    value.polluted,
+                    Object.getOwnPropertyDescriptor(value, "__proto__").value.polluted,
+                ].join()"#,
+            )?;
+            assert_eq!("true,__proto__,,true", result);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn errors() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            assert!(transcode_input(cx.clone(), &[0x82, 0x01]).is_err());
+            for expression in [
+                "2n ** 64n",
+                "(() => { const a = []; a.push(a); return a; })()",
+                "(() => { let a = []; for (let i = 0; i < 512; i++) a = [a]; return a; })()",
+            ] {
+                let value: Value = cx.eval(expression)?;
+                assert!(transcode_output(value).is_err(), "{expression}");
+                cx.catch();
+            }
+            Ok(())
+        })
+    }
+}
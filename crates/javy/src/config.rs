@@ -1,10 +1,17 @@
 use std::{
     fmt::Debug,
     io::{self, Write},
+    time::{Duration, Instant},
 };
 
 use anyhow::{Result, bail};
 use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+use crate::quickjs::{
+    loader::{Loader, Resolver},
+    runtime::InterruptHandler,
+};
 
 bitflags! {
     /// Flags to represent available JavaScript features.
@@ -27,6 +34,14 @@ bitflags! {
         // Removed 15 representing STRING_NORMALIZE.
         const WEAK_REF = 1 << 16;
         const PERFORMANCE = 1 << 17;
+        const TIMERS = 1 << 18;
+        const CRYPTO = 1 << 19;
+        const URL = 1 << 20;
+        const BASE64 = 1 << 21;
+        const STRUCTURED_CLONE = 1 << 22;
+        const FETCH_TYPES = 1 << 23;
+        const EVENT_TARGET = 1 << 24;
+        const STREAMS = 1 << 25;
     }
 }
 
@@ -46,9 +61,103 @@ bitflags! {
     #[derive(Debug)]
     pub(crate) struct JavyIntrinsics: u32 {
         const STREAM_IO = 1;
+        const ENCODING = 1 << 1;
+        const FS = 1 << 2;
+        const PROCESS = 1 << 3;
+        const MEMORY = 1 << 4;
+        const MESSAGEPACK = 1 << 5;
+        const CBOR = 1 << 6;
+    }
+}
+
+/// How often QuickJS calls the interrupt handler, in operations.
+const INTERRUPT_CHECK_INTERVAL: u64 = 10_000;
+
+/// A policy to interrupt the execution of JavaScript, to stop runaway
+/// scripts.
+///
+/// QuickJS checks whether to interrupt the execution every 10,000
+/// operations, an operation being a function call or a loop iteration.
+/// Interrupted scripts get an uncatchable `InternalError: interrupted`, and
+/// the error returned to Rust is an [`Interrupted`](crate::Interrupted).
+///
+/// Once a budget is exhausted, or a timeout has elapsed, any JavaScript
+/// executed afterwards by the [`Runtime`](crate::Runtime) is interrupted as
+/// well.
+pub enum InterruptPolicy {
+    /// Interrupts the execution after the given number of operations.
+    Budget(u64),
+    /// Interrupts the execution once the given duration has elapsed, measured
+    /// with the monotonic clock from the first check.
+    ///
+    /// The clock starts when JavaScript first executes, rather than when the
+    /// [`Runtime`](crate::Runtime) is created, so the timeout isn't affected
+    /// by snapshotting the runtime ahead of time.
+    Timeout(Duration),
+    /// Interrupts the execution when the callback returns `true`.
+    Callback(Box<dyn FnMut() -> bool>),
+}
+
+impl InterruptPolicy {
+    /// The interrupt handler implementing the policy.
+    pub(crate) fn into_handler(self) -> InterruptHandler {
+        match self {
+            Self::Budget(budget) => {
+                let mut operations = 0u64;
+                Box::new(move || {
+                    operations = operations.saturating_add(INTERRUPT_CHECK_INTERVAL);
+                    operations > budget
+                })
+            }
+            Self::Timeout(timeout) => {
+                let mut start = None;
+                Box::new(move || start.get_or_insert_with(Instant::now).elapsed() >= timeout)
+            }
+            Self::Callback(callback) => callback,
+        }
     }
 }
 
+/// What to do with promises which are rejected without a rejection handler.
+///
+/// Rejections are checked once the pending jobs have been resolved, so a
+/// handler attached by a later job of the same run, e.g. after an `await`,
+/// still counts as handling the rejection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PromiseRejectionPolicy {
+    /// Ignores unhandled rejections.
+    #[default]
+    Ignore,
+    /// Writes unhandled rejections to the
+    /// [error stream](Config::err_stream), along with the stack of their
+    /// reason.
+    Warn,
+    /// Fails with an [`UnhandledRejection`](crate::UnhandledRejection) error
+    /// carrying the reason of the first unhandled rejection.
+    Fail,
+}
+
+/// How the Rust JSON implementation, [`json`](crate::json) and the
+/// [SIMD JSON builtins](Config::simd_json_builtins), handles integers outside
+/// of the safe integer range of JavaScript numbers, `±(2^53 - 1)`.
+///
+/// Integers which don't fit in 64 bits are always parsed as numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonBigIntPolicy {
+    /// Parses integers as numbers, rounding them, and fails to stringify
+    /// `BigInt`s, like `JSON.parse` and `JSON.stringify`.
+    #[default]
+    Number,
+    /// Parses integers outside of the safe range as `BigInt`s and stringifies
+    /// `BigInt`s as numbers.
+    BigInt,
+    /// Parses integers outside of the safe range as strings of their digits
+    /// and stringifies `BigInt`s as strings.
+    String,
+}
+
 /// A configuration for [`Runtime`](crate::Runtime).
 ///
 /// These are the global configuration options to create a [`Runtime`](crate::Runtime),
@@ -64,6 +173,10 @@ pub struct Config {
     /// This setting requires the `JSON` intrinsic to be enabled, and the `json`
     /// crate feature to be enabled as well.
     pub(crate) simd_json_builtins: bool,
+    /// How JSON integers outside of the safe range and `BigInt`s are handled
+    /// by the Rust JSON implementation.
+    #[cfg(feature = "json")]
+    pub(crate) json_big_int_policy: JsonBigIntPolicy,
     /// The threshold to trigger garbage collection. Default is usize::MAX.
     pub(crate) gc_threshold: usize,
     /// The limit on the max amount of memory the runtime will use. Default is
@@ -76,6 +189,18 @@ pub struct Config {
     pub(crate) log_stream: Box<dyn Write>,
     /// The stream to use for calls to `console.error`.
     pub(crate) err_stream: Box<dyn Write>,
+    /// The module resolvers, tried in order.
+    pub(crate) module_resolvers: Vec<Box<dyn Resolver>>,
+    /// The module loaders, tried in order.
+    pub(crate) module_loaders: Vec<Box<dyn Loader>>,
+    /// The policy to interrupt the execution of JavaScript. Default is none.
+    pub(crate) interrupt_policy: Option<InterruptPolicy>,
+    /// Whether to drop the QuickJS runtime and contexts when they're dropped.
+    /// Default is false.
+    pub(crate) free_on_drop: bool,
+    /// What to do with unhandled promise rejections. Default is to ignore
+    /// them.
+    pub(crate) promise_rejection_policy: PromiseRejectionPolicy,
 }
 
 impl Default for Config {
@@ -85,15 +210,30 @@ impl Default for Config {
         intrinsics.set(JSIntrinsics::TEXT_ENCODING, false);
         intrinsics.set(JSIntrinsics::WEAK_REF, false);
         intrinsics.set(JSIntrinsics::PERFORMANCE, false);
+        intrinsics.set(JSIntrinsics::TIMERS, false);
+        intrinsics.set(JSIntrinsics::CRYPTO, false);
+        intrinsics.set(JSIntrinsics::URL, false);
+        intrinsics.set(JSIntrinsics::BASE64, false);
+        intrinsics.set(JSIntrinsics::STRUCTURED_CLONE, false);
+        intrinsics.set(JSIntrinsics::FETCH_TYPES, false);
+        intrinsics.set(JSIntrinsics::EVENT_TARGET, false);
+        intrinsics.set(JSIntrinsics::STREAMS, false);
         Self {
             intrinsics,
             javy_intrinsics: JavyIntrinsics::empty(),
             simd_json_builtins: false,
+            #[cfg(feature = "json")]
+            json_big_int_policy: JsonBigIntPolicy::Number,
             gc_threshold: usize::MAX,
             memory_limit: usize::MAX,
             max_stack_size: 256 * 1024, // from rquickjs
             log_stream: Box::new(std::io::stdout()),
             err_stream: Box::new(std::io::stderr()),
+            module_resolvers: vec![],
+            module_loaders: vec![],
+            interrupt_policy: None,
+            free_on_drop: false,
+            promise_rejection_policy: PromiseRejectionPolicy::Ignore,
         }
     }
 }
@@ -179,6 +319,72 @@ impl Config {
         self
     }
 
+    /// Whether the `Javy.Encoding` intrinsic will be available, providing
+    /// `encode` and `decode` functions to convert between `Uint8Array`s and
+    /// strings for the `base64`, `base64url` and `hex` encodings.
+    /// This setting requires the `base64` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "base64")]
+    pub fn javy_encoding(&mut self, enable: bool) -> &mut Self {
+        self.javy_intrinsics.set(JavyIntrinsics::ENCODING, enable);
+        self
+    }
+
+    /// Whether the `Javy.FS` intrinsic will be available, providing
+    /// `openSync`, `closeSync`, `readFileSync`, `writeFileSync`,
+    /// `readdirSync` and `statSync` functions operating on the WASI
+    /// preopened directories. Failures throw errors carrying the WASI errno
+    /// value.
+    /// Disabled by default. Like [`Self::javy_stream_io`], it is strongly
+    /// recommended to target WASI preview 1 when enabling this configuration.
+    pub fn javy_fs(&mut self, enable: bool) -> &mut Self {
+        self.javy_intrinsics.set(JavyIntrinsics::FS, enable);
+        self
+    }
+
+    /// Whether the `Javy.args`, `Javy.env` and `Javy.exit` intrinsics will be
+    /// available. `Javy.args` and `Javy.env` are read from the WASI context
+    /// when accessed, rather than when the runtime is created, and
+    /// `Javy.exit` exits with the given code through WASI's `proc_exit`.
+    /// Disabled by default.
+    pub fn javy_process(&mut self, enable: bool) -> &mut Self {
+        self.javy_intrinsics.set(JavyIntrinsics::PROCESS, enable);
+        self
+    }
+
+    /// Whether the `Javy.memoryUsage` and `Javy.gc` intrinsics will be
+    /// available. `Javy.memoryUsage` returns the statistics of
+    /// [`Runtime::memory_usage`](crate::Runtime::memory_usage) and `Javy.gc`
+    /// runs the garbage collector.
+    /// Disabled by default.
+    pub fn javy_memory(&mut self, enable: bool) -> &mut Self {
+        self.javy_intrinsics.set(JavyIntrinsics::MEMORY, enable);
+        self
+    }
+
+    /// Whether the `Javy.MessagePack` intrinsic will be available, providing
+    /// `encode` and `decode` functions to convert between values and
+    /// `Uint8Array`s of MessagePack.
+    /// This setting requires the `messagepack` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "messagepack")]
+    pub fn javy_messagepack(&mut self, enable: bool) -> &mut Self {
+        self.javy_intrinsics
+            .set(JavyIntrinsics::MESSAGEPACK, enable);
+        self
+    }
+
+    /// Whether the `Javy.CBOR` intrinsic will be available, providing
+    /// `encode` and `decode` functions to convert between values and
+    /// `Uint8Array`s of CBOR.
+    /// This setting requires the `cbor` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "cbor")]
+    pub fn javy_cbor(&mut self, enable: bool) -> &mut Self {
+        self.javy_intrinsics.set(JavyIntrinsics::CBOR, enable);
+        self
+    }
+
     /// Enables whether the output of console.log will be redirected to
     /// `stderr`.
     pub fn redirect_stdout_to_stderr(&mut self, enable: bool) -> &mut Self {
@@ -202,6 +408,16 @@ impl Config {
         self
     }
 
+    /// How the Rust JSON implementation, used by [`json`](crate::json) and
+    /// the SIMD JSON builtins, handles integers outside of the safe range
+    /// and `BigInt`s. See [`JsonBigIntPolicy`].
+    /// Integers are parsed as numbers by default.
+    #[cfg(feature = "json")]
+    pub fn json_big_int_policy(&mut self, policy: JsonBigIntPolicy) -> &mut Self {
+        self.json_big_int_policy = policy;
+        self
+    }
+
     /// The number of bytes to use to trigger garbage collection.
     /// The default is usize::MAX.
     pub fn gc_threshold(&mut self, bytes: usize) -> &mut Self {
@@ -223,13 +439,43 @@ impl Config {
         self
     }
 
+    /// The policy to interrupt the execution of JavaScript with, for example
+    /// to stop scripts which loop indefinitely. See [`InterruptPolicy`].
+    /// Disabled by default.
+    pub fn interrupt_policy(&mut self, policy: InterruptPolicy) -> &mut Self {
+        self.interrupt_policy = Some(policy);
+        self
+    }
+
+    /// Whether the QuickJS runtime and contexts are freed when the
+    /// [`Runtime`](crate::Runtime) and its [`Realm`](crate::Realm)s are
+    /// dropped.
+    /// Disabled by default, in which case they are leaked, which saves the
+    /// substantial cost of freeing every object for short-lived programs,
+    /// where the host reclaims the memory of the instance once execution
+    /// ends. Long-lived embeddings which create and drop runtimes or realms
+    /// should enable it.
+    pub fn free_on_drop(&mut self, enable: bool) -> &mut Self {
+        self.free_on_drop = enable;
+        self
+    }
+
+    /// What to do with promises which are rejected without a rejection
+    /// handler. See [`PromiseRejectionPolicy`].
+    /// Ignored by default.
+    pub fn promise_rejection_policy(&mut self, policy: PromiseRejectionPolicy) -> &mut Self {
+        self.promise_rejection_policy = policy;
+        self
+    }
+
     /// The stream to use for calls to `console.log`.
     pub fn log_stream(&mut self, stream: Box<dyn Write>) -> &mut Self {
         self.log_stream = stream;
         self
     }
 
-    /// The stream to use for calls to `console.error`.
+    /// The stream to use for calls to `console.error`, and to report
+    /// unhandled promise rejections with [`PromiseRejectionPolicy::Warn`].
     pub fn err_stream(&mut self, stream: Box<dyn Write>) -> &mut Self {
         self.err_stream = stream;
         self
@@ -247,6 +493,111 @@ impl Config {
         self
     }
 
+    /// Whether the `setTimeout`, `setInterval`, `clearTimeout` and
+    /// `clearInterval` globals will be available.
+    /// Disabled by default. Timers are only fired when driving the event
+    /// loop through [`Runtime::run_event_loop`](crate::Runtime::run_event_loop).
+    pub fn timers(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::TIMERS, enable);
+        self
+    }
+
+    /// Whether the `crypto` global will be available, providing
+    /// `crypto.getRandomValues`, `crypto.randomUUID` and a subset of
+    /// `crypto.subtle`: `digest` with SHA-1, SHA-256, SHA-384 and SHA-512, and
+    /// HMAC keys for `sign` and `verify`.
+    /// This setting requires the `crypto` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "crypto")]
+    pub fn crypto(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::CRYPTO, enable);
+        self
+    }
+
+    /// Whether the `URL` and `URLSearchParams` globals will be available.
+    /// URLs are parsed as defined by the [URL
+    /// Standard](https://url.spec.whatwg.org/).
+    /// This setting requires the `url` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "url")]
+    pub fn url(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::URL, enable);
+        self
+    }
+
+    /// Whether the `atob` and `btoa` globals will be available.
+    /// This setting requires the `base64` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "base64")]
+    pub fn base64(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::BASE64, enable);
+        self
+    }
+
+    /// Whether the `structuredClone` global will be available. Values are
+    /// cloned with the [structured clone
+    /// algorithm](https://html.spec.whatwg.org/multipage/structured-data.html#safe-passing-of-structured-data)
+    /// of the HTML Standard, throwing a `DataCloneError` for values that
+    /// can't be cloned.
+    /// Disabled by default.
+    pub fn structured_clone(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::STRUCTURED_CLONE, enable);
+        self
+    }
+
+    /// Whether the `Headers`, `Request`, `Response`, `Blob`, `File` and
+    /// `FormData` globals will be available. Bodies are backed by byte
+    /// buffers, no networking is involved.
+    /// This setting requires the `fetch` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "fetch")]
+    pub fn fetch_types(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::FETCH_TYPES, enable);
+        self
+    }
+
+    /// Whether the `EventTarget`, `Event`, `CustomEvent`, `AbortController`
+    /// and `AbortSignal` globals will be available. `AbortSignal.timeout` is
+    /// only available when [`Self::timers`] is enabled as well.
+    /// Disabled by default.
+    pub fn event_target(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::EVENT_TARGET, enable);
+        self
+    }
+
+    /// Whether the `ReadableStream`, `WritableStream` and `TransformStream`
+    /// globals, along with their readers, writers, controllers and queuing
+    /// strategies, will be available. `TextEncoderStream` and
+    /// `TextDecoderStream` are available when [`Self::text_encoding`] is
+    /// enabled as well, and `Javy.IO.stdin`, `Javy.IO.stdout` and
+    /// `Javy.IO.stderr` when [`Self::javy_stream_io`] is.
+    /// This setting requires the `streams` crate feature to be enabled.
+    /// Disabled by default.
+    #[cfg(feature = "streams")]
+    pub fn streams(&mut self, enable: bool) -> &mut Self {
+        self.intrinsics.set(JSIntrinsics::STREAMS, enable);
+        self
+    }
+
+    /// Registers a module resolver and a module loader, used to resolve and
+    /// load the modules imported by other modules.
+    ///
+    /// This method can be called multiple times, resolvers and loaders are
+    /// tried in the order in which they were registered. Without any module
+    /// loader, imports fail to resolve.
+    ///
+    /// See [`modules`](crate::modules) for the resolvers and loaders provided
+    /// by Javy, any [`rquickjs`] resolver and loader can be used as well.
+    pub fn module_loader<R, L>(&mut self, resolver: R, loader: L) -> &mut Self
+    where
+        R: Resolver + 'static,
+        L: Loader + 'static,
+    {
+        self.module_resolvers.push(Box::new(resolver));
+        self.module_loaders.push(Box::new(loader));
+        self
+    }
+
     pub(crate) fn validate(self) -> Result<Self> {
         if self.simd_json_builtins && !self.intrinsics.contains(JSIntrinsics::JSON) {
             bail!("JSON Intrinsic is required to override JSON.parse and JSON.stringify");
@@ -0,0 +1,570 @@
+use crate::config::{
+    Config, JSIntrinsics, JavyIntrinsics, JsonBigIntPolicy, PromiseRejectionPolicy,
+};
+
+/// The type of the value of a [`ConfigSpec`] property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigPropertyType {
+    /// A boolean, enabling or disabling a feature.
+    Boolean,
+    /// An unsigned integer, for limits in bytes.
+    Integer,
+    /// One of the given names, for policies.
+    Enum(&'static [&'static str]),
+}
+
+/// A property of a [`ConfigSpec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProperty {
+    /// The name of the property, in kebab case (e.g., `text-encoding`).
+    pub name: String,
+    /// The documentation of the property.
+    pub doc: String,
+    /// The type of the value of the property.
+    pub ty: ConfigPropertyType,
+}
+
+/// The type of the value of a [`ConfigSpec`] property, used by
+/// [`config_spec!`](crate::config_spec).
+#[doc(hidden)]
+pub trait ConfigPropertyValue {
+    /// The type of the property.
+    const TYPE: ConfigPropertyType;
+}
+
+impl ConfigPropertyValue for bool {
+    const TYPE: ConfigPropertyType = ConfigPropertyType::Boolean;
+}
+
+impl ConfigPropertyValue for usize {
+    const TYPE: ConfigPropertyType = ConfigPropertyType::Integer;
+}
+
+impl ConfigPropertyValue for PromiseRejectionPolicy {
+    const TYPE: ConfigPropertyType = ConfigPropertyType::Enum(&["ignore", "warn", "fail"]);
+}
+
+impl ConfigPropertyValue for JsonBigIntPolicy {
+    const TYPE: ConfigPropertyType = ConfigPropertyType::Enum(&["number", "big-int", "string"]);
+}
+
+/// Defines a serializable config specification, whose properties are declared
+/// once along with their documentation, and listed by its `properties`
+/// function.
+///
+/// Properties are named in kebab case and unknown properties are rejected. A
+/// specification can extend another one, whose properties are flattened into
+/// it, by starting with a `#[serde(flatten)]` field.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! config_spec {
+    (
+        $(#[$attr:meta])*
+        pub struct $spec:ident {
+            $(
+                #[serde(flatten)]
+                $base:ident: $base_ty:ty,
+            )?
+            $(
+                $(
+                    #[doc = $doc:tt]
+                )*
+                $(#[cfg($cfg:meta)])?
+                $opt:ident: Option<$ty:ty>,
+            )+
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(::serde::Serialize)]
+        #[serde(rename_all = "kebab-case")]
+        pub struct $spec {
+            $(
+                #[doc = concat!("The properties of [`", stringify!($base_ty), "`].")]
+                #[serde(flatten)]
+                pub $base: $base_ty,
+            )?
+            $(
+                $(
+                    #[doc = $doc]
+                )*
+                $(#[cfg($cfg)])?
+                #[serde(skip_serializing_if = "Option::is_none")]
+                pub $opt: Option<$ty>,
+            )+
+        }
+
+        impl $spec {
+            /// The properties of the specification, along with their
+            /// documentation.
+            pub fn properties() -> Vec<$crate::ConfigProperty> {
+                #[allow(unused_mut)]
+                let mut properties = Vec::new();
+                $(
+                    properties = <$base_ty>::properties();
+                )?
+                properties.extend([
+                    $(
+                        $(#[cfg($cfg)])?
+                        $crate::ConfigProperty {
+                            name: stringify!($opt).replace('_', "-"),
+                            doc: concat!($($doc, "\n",)*).into(),
+                            ty: <$ty as $crate::ConfigPropertyValue>::TYPE,
+                        },
+                    )+
+                ]);
+                properties
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $spec {
+            fn deserialize<D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> ::std::result::Result<Self, D::Error> {
+                #[derive(::serde::Deserialize)]
+                #[serde(rename_all = "kebab-case")]
+                struct Properties {
+                    $(
+                        #[serde(flatten)]
+                        $base: $base_ty,
+                    )?
+                    $(
+                        $(#[cfg($cfg)])?
+                        $opt: Option<$ty>,
+                    )+
+                    /// The remaining properties, which includes the ones of
+                    /// the flattened specification.
+                    #[serde(flatten)]
+                    others: ::std::collections::BTreeMap<String, ::serde::de::IgnoredAny>,
+                }
+
+                let properties = Properties::deserialize(deserializer)?;
+                let known = Self::properties();
+                if let Some(name) = properties
+                    .others
+                    .keys()
+                    .find(|name| !known.iter().any(|property| &property.name == *name))
+                {
+                    return Err(::serde::de::Error::custom(format!(
+                        "unknown property `{name}`"
+                    )));
+                }
+                Ok(Self {
+                    $(
+                        $base: properties.$base,
+                    )?
+                    $(
+                        $(#[cfg($cfg)])?
+                        $opt: properties.$opt,
+                    )+
+                })
+            }
+        }
+    };
+}
+
+config_spec! {
+    /// A serializable specification of a [`Config`].
+    ///
+    /// Properties are named in kebab case, e.g. `text-encoding`, and unset
+    /// properties keep the value of the [`Config`] the specification is
+    /// applied to. Only the intrinsics, the Javy intrinsics, the JSON builtins,
+    /// the limits and the promise rejection policy can be specified, streams,
+    /// module loaders and the interrupt policy have to be configured on the
+    /// [`Config`] directly.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ConfigSpec {
+        /// Whether to enable the `Date` intrinsic.
+        date: Option<bool>,
+        /// Whether to enable `eval`.
+        eval: Option<bool>,
+        /// Whether to enable the regular expression compiler.
+        regexp_compiler: Option<bool>,
+        /// Whether to enable the `RegExp` intrinsic.
+        regexp: Option<bool>,
+        /// Whether to enable the `JSON` intrinsic.
+        json: Option<bool>,
+        /// Whether to enable the `Proxy` intrinsic.
+        proxy: Option<bool>,
+        /// Whether to enable the `Map` and `Set` intrinsics.
+        map_set: Option<bool>,
+        /// Whether to enable the `Promise` intrinsic.
+        promise: Option<bool>,
+        /// Whether to enable operator overloading.
+        operator_overloading: Option<bool>,
+        /// Whether to enable the `BigNum` extensions.
+        bignum_extension: Option<bool>,
+        /// Whether to enable the `TextEncoder` and `TextDecoder` APIs.
+        text_encoding: Option<bool>,
+        /// Whether to enable the `WeakRef` intrinsic.
+        weak_ref: Option<bool>,
+        /// Whether to enable the `Performance` intrinsic.
+        performance: Option<bool>,
+        /// Whether to enable `setTimeout`, `setInterval`, `clearTimeout` and
+        /// `clearInterval`.
+        timers: Option<bool>,
+        /// Whether to enable the `crypto` global. Requires the `crypto` feature
+        /// of the `javy` crate.
+        #[cfg(feature = "crypto")]
+        crypto: Option<bool>,
+        /// Whether to enable the `URL` and `URLSearchParams` globals. Requires
+        /// the `url` feature of the `javy` crate.
+        #[cfg(feature = "url")]
+        url: Option<bool>,
+        /// Whether to enable the `atob` and `btoa` globals. Requires the
+        /// `base64` feature of the `javy` crate.
+        #[cfg(feature = "base64")]
+        base64: Option<bool>,
+        /// Whether to enable the `structuredClone` global.
+        structured_clone: Option<bool>,
+        /// Whether to enable the `Headers`, `Request`, `Response`, `Blob`,
+        /// `File` and `FormData` globals. Requires the `fetch` feature of the
+        /// `javy` crate.
+        #[cfg(feature = "fetch")]
+        fetch_types: Option<bool>,
+        /// Whether to enable the `EventTarget`, `Event`, `CustomEvent`,
+        /// `AbortController` and `AbortSignal` globals.
+        event_target: Option<bool>,
+        /// Whether to enable the `ReadableStream`, `WritableStream` and
+        /// `TransformStream` globals. Requires the `streams` feature of the
+        /// `javy` crate.
+        #[cfg(feature = "streams")]
+        streams: Option<bool>,
+        /// Whether to enable the `Javy.IO` builtins.
+        javy_stream_io: Option<bool>,
+        /// Whether to enable the `Javy.Encoding` builtins. Requires the
+        /// `base64` feature of the `javy` crate.
+        #[cfg(feature = "base64")]
+        javy_encoding: Option<bool>,
+        /// Whether to enable the `Javy.FS` builtins.
+        javy_fs: Option<bool>,
+        /// Whether to enable the `Javy.args`, `Javy.env` and `Javy.exit`
+        /// builtins.
+        javy_process: Option<bool>,
+        /// Whether to enable the `Javy.memoryUsage` and `Javy.gc` builtins.
+        javy_memory: Option<bool>,
+        /// Whether to enable the `Javy.MessagePack` builtins. Requires the
+        /// `messagepack` feature of the `javy` crate.
+        #[cfg(feature = "messagepack")]
+        javy_messagepack: Option<bool>,
+        /// Whether to enable the `Javy.CBOR` builtins. Requires the `cbor`
+        /// feature of the `javy` crate.
+        #[cfg(feature = "cbor")]
+        javy_cbor: Option<bool>,
+        /// Whether to override the `JSON.parse` and `JSON.stringify`
+        /// implementations with an alternative, more performant, SIMD based
+        /// implementation. Requires the `json` intrinsic and the `json` feature
+        /// of the `javy` crate.
+        #[cfg(feature = "json")]
+        simd_json_builtins: Option<bool>,
+        /// How the Rust JSON implementation handles integers outside of the
+        /// safe range and `BigInt`s: as `number`s, as `big-int`s or as
+        /// `string`s. Requires the `json` feature of the `javy` crate.
+        #[cfg(feature = "json")]
+        json_big_int_policy: Option<JsonBigIntPolicy>,
+        /// The number of bytes to use to trigger garbage collection.
+        gc_threshold: Option<usize>,
+        /// The limit on the max amount of memory the runtime will use.
+        memory_limit: Option<usize>,
+        /// The limit on the max size of stack the runtime will use.
+        max_stack_size: Option<usize>,
+        /// What to do with promises rejected without a rejection handler:
+        /// `ignore` them, `warn` about them on stderr, or `fail` with the
+        /// reason of the first of them.
+        unhandled_rejection: Option<PromiseRejectionPolicy>,
+    }
+}
+
+impl ConfigSpec {
+    /// Applies the properties which are set to a [`Config`].
+    pub fn apply_to_config(&self, config: &mut Config) {
+        let intrinsics = [
+            (self.date, JSIntrinsics::DATE),
+            (self.eval, JSIntrinsics::EVAL),
+            (self.regexp_compiler, JSIntrinsics::REGEXP_COMPILER),
+            (self.regexp, JSIntrinsics::REGEXP),
+            (self.json, JSIntrinsics::JSON),
+            (self.proxy, JSIntrinsics::PROXY),
+            (self.map_set, JSIntrinsics::MAP_SET),
+            (self.promise, JSIntrinsics::PROMISE),
+            (self.operator_overloading, JSIntrinsics::OPERATORS),
+            (self.bignum_extension, JSIntrinsics::BIGNUM_EXTENSION),
+            (self.text_encoding, JSIntrinsics::TEXT_ENCODING),
+            (self.weak_ref, JSIntrinsics::WEAK_REF),
+            (self.performance, JSIntrinsics::PERFORMANCE),
+            (self.timers, JSIntrinsics::TIMERS),
+            #[cfg(feature = "crypto")]
+            (self.crypto, JSIntrinsics::CRYPTO),
+            #[cfg(feature = "url")]
+            (self.url, JSIntrinsics::URL),
+            #[cfg(feature = "base64")]
+            (self.base64, JSIntrinsics::BASE64),
+            (self.structured_clone, JSIntrinsics::STRUCTURED_CLONE),
+            #[cfg(feature = "fetch")]
+            (self.fetch_types, JSIntrinsics::FETCH_TYPES),
+            (self.event_target, JSIntrinsics::EVENT_TARGET),
+            #[cfg(feature = "streams")]
+            (self.streams, JSIntrinsics::STREAMS),
+        ];
+        for (enable, intrinsic) in intrinsics {
+            if let Some(enable) = enable {
+                config.intrinsics.set(intrinsic, enable);
+            }
+        }
+
+        let javy_intrinsics = [
+            (self.javy_stream_io, JavyIntrinsics::STREAM_IO),
+            #[cfg(feature = "base64")]
+            (self.javy_encoding, JavyIntrinsics::ENCODING),
+            (self.javy_fs, JavyIntrinsics::FS),
+            (self.javy_process, JavyIntrinsics::PROCESS),
+            (self.javy_memory, JavyIntrinsics::MEMORY),
+        ];
+        for (enable, intrinsic) in javy_intrinsics {
+            if let Some(enable) = enable {
+                config.javy_intrinsics.set(intrinsic, enable);
+            }
+        }
+
+        #[cfg(feature = "messagepack")]
+        if let Some(enable) = self.javy_messagepack {
+            config.javy_messagepack(enable);
+        }
+        #[cfg(feature = "cbor")]
+        if let Some(enable) = self.javy_cbor {
+            config.javy_cbor(enable);
+        }
+        #[cfg(feature = "json")]
+        if let Some(enable) = self.simd_json_builtins {
+            config.simd_json_builtins = enable;
+        }
+        #[cfg(feature = "json")]
+        if let Some(policy) = self.json_big_int_policy {
+            config.json_big_int_policy(policy);
+        }
+        if let Some(bytes) = self.gc_threshold {
+            config.gc_threshold(bytes);
+        }
+        if let Some(bytes) = self.memory_limit {
+            config.memory_limit(bytes);
+        }
+        if let Some(bytes) = self.max_stack_size {
+            config.max_stack_size(bytes);
+        }
+        if let Some(policy) = self.unhandled_rejection {
+            config.promise_rejection_policy(policy);
+        }
+    }
+}
+
+impl From<&Config> for ConfigSpec {
+    /// Creates a [`ConfigSpec`] with every property set to the value of the
+    /// [`Config`].
+    fn from(config: &Config) -> Self {
+        let intrinsic = |intrinsic| Some(config.intrinsics.contains(intrinsic));
+        let javy_intrinsic = |intrinsic| Some(config.javy_intrinsics.contains(intrinsic));
+        Self {
+            date: intrinsic(JSIntrinsics::DATE),
+            eval: intrinsic(JSIntrinsics::EVAL),
+            regexp_compiler: intrinsic(JSIntrinsics::REGEXP_COMPILER),
+            regexp: intrinsic(JSIntrinsics::REGEXP),
+            json: intrinsic(JSIntrinsics::JSON),
+            proxy: intrinsic(JSIntrinsics::PROXY),
+            map_set: intrinsic(JSIntrinsics::MAP_SET),
+            promise: intrinsic(JSIntrinsics::PROMISE),
+            operator_overloading: intrinsic(JSIntrinsics::OPERATORS),
+            bignum_extension: intrinsic(JSIntrinsics::BIGNUM_EXTENSION),
+            text_encoding: intrinsic(JSIntrinsics::TEXT_ENCODING),
+            weak_ref: intrinsic(JSIntrinsics::WEAK_REF),
+            performance: intrinsic(JSIntrinsics::PERFORMANCE),
+            timers: intrinsic(JSIntrinsics::TIMERS),
+            #[cfg(feature = "crypto")]
+            crypto: intrinsic(JSIntrinsics::CRYPTO),
+            #[cfg(feature = "url")]
+            url: intrinsic(JSIntrinsics::URL),
+            #[cfg(feature = "base64")]
+            base64: intrinsic(JSIntrinsics::BASE64),
+            structured_clone: intrinsic(JSIntrinsics::STRUCTURED_CLONE),
+            #[cfg(feature = "fetch")]
+            fetch_types: intrinsic(JSIntrinsics::FETCH_TYPES),
+            event_target: intrinsic(JSIntrinsics::EVENT_TARGET),
+            #[cfg(feature = "streams")]
+            streams: intrinsic(JSIntrinsics::STREAMS),
+            javy_stream_io: javy_intrinsic(JavyIntrinsics::STREAM_IO),
+            #[cfg(feature = "base64")]
+            javy_encoding: javy_intrinsic(JavyIntrinsics::ENCODING),
+            javy_fs: javy_intrinsic(JavyIntrinsics::FS),
+            javy_process: javy_intrinsic(JavyIntrinsics::PROCESS),
+            javy_memory: javy_intrinsic(JavyIntrinsics::MEMORY),
+            #[cfg(feature = "messagepack")]
+            javy_messagepack: javy_intrinsic(JavyIntrinsics::MESSAGEPACK),
+            #[cfg(feature = "cbor")]
+            javy_cbor: javy_intrinsic(JavyIntrinsics::CBOR),
+            #[cfg(feature = "json")]
+            simd_json_builtins: Some(config.simd_json_builtins),
+            #[cfg(feature = "json")]
+            json_big_int_policy: Some(config.json_big_int_policy),
+            gc_threshold: Some(config.gc_threshold),
+            memory_limit: Some(config.memory_limit),
+            max_stack_size: Some(config.max_stack_size),
+            unhandled_rejection: Some(config.promise_rejection_policy),
+        }
+    }
+}
+
+impl From<&ConfigSpec> for Config {
+    /// Creates a [`Config`] with the default values, overridden by the
+    /// properties of the [`ConfigSpec`] which are set.
+    fn from(spec: &ConfigSpec) -> Self {
+        let mut config = Config::default();
+        spec.apply_to_config(&mut config);
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigPropertyType, ConfigSpec};
+    use crate::{Config, PromiseRejectionPolicy};
+    use anyhow::Result;
+
+    #[test]
+    fn round_trips_through_config() {
+        let mut config = Config::default();
+        config
+            .text_encoding(true)
+            .eval(false)
+            .javy_fs(true)
+            .memory_limit(1024);
+        let spec = ConfigSpec::from(&config);
+        assert_eq!(Some(true), spec.text_encoding);
+        assert_eq!(Some(false), spec.eval);
+        assert_eq!(Some(true), spec.javy_fs);
+        assert_eq!(Some(false), spec.javy_memory);
+        assert_eq!(Some(1024), spec.memory_limit);
+
+        assert_eq!(spec, ConfigSpec::from(&Config::from(&spec)));
+    }
+
+    #[test]
+    fn unset_properties_are_left_untouched() {
+        let mut config = Config::default();
+        config.timers(true).gc_threshold(10);
+        let spec = ConfigSpec {
+            structured_clone: Some(true),
+            timers: None,
+            ..Default::default()
+        };
+        spec.apply_to_config(&mut config);
+
+        let spec = ConfigSpec::from(&config);
+        assert_eq!(Some(true), spec.structured_clone);
+        assert_eq!(Some(true), spec.timers);
+        assert_eq!(Some(10), spec.gc_threshold);
+    }
+
+    #[test]
+    fn deserializes_kebab_case_properties() -> Result<()> {
+        let spec: ConfigSpec = serde_json::from_str(
+            r#"{ "text-encoding": true, "javy-stream-io": false, "max-stack-size": 2048 }"#,
+        )?;
+        assert_eq!(
+            ConfigSpec {
+                text_encoding: Some(true),
+                javy_stream_io: Some(false),
+                max_stack_size: Some(2048),
+                ..Default::default()
+            },
+            spec
+        );
+        let err = serde_json::from_str::<ConfigSpec>(r#"{ "unknown": true }"#).unwrap_err();
+        assert!(err.to_string().starts_with("unknown property `unknown`"));
+
+        #[cfg(feature = "json")]
+        {
+            let spec: ConfigSpec = serde_json::from_str(r#"{ "json-big-int-policy": "big-int" }"#)?;
+            assert_eq!(
+                Some(crate::JsonBigIntPolicy::BigInt),
+                spec.json_big_int_policy
+            );
+        }
+
+        let json = serde_json::to_string(&ConfigSpec {
+            regexp_compiler: Some(false),
+            ..Default::default()
+        })?;
+        assert!(json.contains(r#""regexp-compiler":false"#));
+        Ok(())
+    }
+
+    #[test]
+    fn feature_gated_properties() -> Result<()> {
+        let properties = ConfigSpec::properties();
+        let names: Vec<_> = properties
+            .iter()
+            .map(|property| property.name.as_str())
+            .collect();
+        assert_eq!(
+            cfg!(feature = "messagepack"),
+            names.contains(&"javy-messagepack")
+        );
+        assert_eq!(cfg!(feature = "cbor"), names.contains(&"javy-cbor"));
+        assert_eq!(
+            cfg!(feature = "json"),
+            names.contains(&"simd-json-builtins")
+        );
+
+        let spec = serde_json::from_str::<ConfigSpec>(r#"{ "javy-cbor": true }"#);
+        assert_eq!(cfg!(feature = "cbor"), spec.is_ok());
+        #[cfg(feature = "cbor")]
+        {
+            let config = Config::from(&spec?);
+            assert_eq!(Some(true), ConfigSpec::from(&config).javy_cbor);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn properties() {
+        let properties = ConfigSpec::properties();
+        let text_encoding = properties
+            .iter()
+            .find(|property| property.name == "text-encoding")
+            .unwrap();
+        assert_eq!(ConfigPropertyType::Boolean, text_encoding.ty);
+        assert_eq!(
+            " Whether to enable the `TextEncoder` and `TextDecoder` APIs.\n",
+            text_encoding.doc
+        );
+
+        let memory_limit = properties
+            .iter()
+            .find(|property| property.name == "memory-limit")
+            .unwrap();
+        assert_eq!(ConfigPropertyType::Integer, memory_limit.ty);
+    }
+
+    #[test]
+    fn enum_properties_list_their_values() -> Result<()> {
+        let properties = ConfigSpec::properties();
+        let unhandled_rejection = properties
+            .iter()
+            .find(|property| property.name == "unhandled-rejection")
+            .unwrap();
+        let ConfigPropertyType::Enum(values) = unhandled_rejection.ty else {
+            panic!("unhandled-rejection should be an enum");
+        };
+        for (value, policy) in values.iter().zip([
+            PromiseRejectionPolicy::Ignore,
+            PromiseRejectionPolicy::Warn,
+            PromiseRejectionPolicy::Fail,
+        ]) {
+            let spec: ConfigSpec =
+                serde_json::from_str(&format!(r#"{{ "unhandled-rejection": "{value}" }}"#))?;
+            assert_eq!(Some(policy), spec.unhandled_rejection);
+        }
+        assert!(
+            serde_json::from_str::<ConfigSpec>(r#"{ "unhandled-rejection": "throw" }"#).is_err()
+        );
+        Ok(())
+    }
+}
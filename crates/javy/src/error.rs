@@ -0,0 +1,401 @@
+//! Errors raised by JavaScript code.
+
+use std::fmt;
+
+use rquickjs::{Array, Ctx, Value};
+use serde::Serialize;
+
+use crate::val_to_string;
+
+/// The maximum depth of the `cause`s and `AggregateError` children
+/// converted, guarding against cycles.
+const MAX_DEPTH: usize = 16;
+
+/// A JavaScript exception, along with its stack, its `cause` and, for an
+/// `AggregateError`, the errors it aggregates.
+///
+/// Returned by [`from_js_error`](crate::from_js_error), wrapped in an
+/// [`anyhow::Error`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct JsError {
+    /// The name of the error, e.g. `TypeError`. `None` when the thrown value
+    /// isn't an `Error`.
+    pub name: Option<String>,
+    /// The message of the error, or the thrown value converted to a string
+    /// when it isn't an `Error`.
+    pub message: String,
+    /// The stack frames, innermost first.
+    pub stack: Vec<StackFrame>,
+    /// The `cause` of the error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cause: Option<Box<JsError>>,
+    /// The errors aggregated by an `AggregateError`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<JsError>,
+}
+
+/// A frame of the stack of a [`JsError`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct StackFrame {
+    /// The name of the function, `<anonymous>` for anonymous functions and
+    /// the top-level code. `None` for the location of a syntax error.
+    pub function: Option<String>,
+    /// The file, i.e. the name of the module or script. `None` for native
+    /// functions.
+    pub file: Option<String>,
+    /// The line, starting at 1.
+    pub line: Option<u32>,
+    /// The column, starting at 1.
+    pub column: Option<u32>,
+}
+
+impl JsError {
+    /// Converts a thrown value to a [`JsError`].
+    pub fn from_value<'js>(cx: &Ctx<'js>, value: Value<'js>) -> Self {
+        Self::from_value_at_depth(cx, value, 0)
+    }
+
+    fn from_value_at_depth<'js>(cx: &Ctx<'js>, value: Value<'js>, depth: usize) -> Self {
+        let Some(exception) = value.clone().into_exception() else {
+            return Self {
+                name: None,
+                message: val_to_string(cx, value).unwrap_or_else(|_| "Internal error".to_string()),
+                stack: vec![],
+                cause: None,
+                errors: vec![],
+            };
+        };
+
+        let own = |key: &str| -> Option<Value<'js>> {
+            exception
+                .contains_key(key)
+                .unwrap_or(false)
+                .then(|| exception.get(key).ok())
+                .flatten()
+        };
+        let nested = depth < MAX_DEPTH;
+        let cause = own("cause")
+            .filter(|_| nested)
+            .map(|cause| Box::new(Self::from_value_at_depth(cx, cause, depth + 1)));
+        let errors = own("errors")
+            .filter(|_| nested)
+            .and_then(|errors| errors.into_array())
+            .map(|errors| Self::from_array(cx, errors, depth + 1))
+            .unwrap_or_default();
+
+        Self {
+            name: Some(
+                exception
+                    .as_object()
+                    .get::<_, String>("name")
+                    .unwrap_or_else(|_| "Error".to_string()),
+            ),
+            message: exception.message().unwrap_or_default(),
+            stack: exception
+                .stack()
+                .map(|stack| stack.lines().filter_map(StackFrame::parse).collect())
+                .unwrap_or_default(),
+            cause,
+            errors,
+        }
+    }
+
+    fn from_array<'js>(cx: &Ctx<'js>, errors: Array<'js>, depth: usize) -> Vec<Self> {
+        errors
+            .iter::<Value<'js>>()
+            .filter_map(|error| error.ok())
+            .map(|error| Self::from_value_at_depth(cx, error, depth))
+            .collect()
+    }
+}
+
+impl StackFrame {
+    /// Parses a line of a QuickJS stack, either `at function (location)` or
+    /// `at location`, where the location is `file:line:column`, `file` or
+    /// `native`.
+    fn parse(line: &str) -> Option<Self> {
+        let frame = line.trim_start().strip_prefix("at ")?;
+        let (function, location) = match frame.strip_suffix(')').and_then(|f| f.split_once(" (")) {
+            Some((function, location)) => (Some(function.to_string()), location),
+            None => (None, frame),
+        };
+
+        if function.is_some() && location == "native" {
+            return Some(Self {
+                function,
+                file: None,
+                line: None,
+                column: None,
+            });
+        }
+
+        let mut parts = location.rsplitn(3, ':');
+        let (column, line, file) = (parts.next(), parts.next(), parts.next());
+        let position = line
+            .and_then(|line| line.parse().ok())
+            .zip(column.and_then(|column| column.parse().ok()));
+        Some(match (position, file) {
+            (Some((line, column)), Some(file)) => Self {
+                function,
+                file: Some(file.to_string()),
+                line: Some(line),
+                column: Some(column),
+            },
+            _ => Self {
+                function,
+                file: Some(location.to_string()),
+                line: None,
+                column: None,
+            },
+        })
+    }
+}
+
+impl fmt::Display for JsError {
+    /// Formats the error like QuickJS does, its name and message followed by
+    /// its stack.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) if self.message.is_empty() => write!(f, "{name}")?,
+            Some(name) => write!(f, "{name}: {}", self.message)?,
+            None => write!(f, "{}", self.message)?,
+        }
+        if !self.stack.is_empty() {
+            writeln!(f)?;
+        }
+        for frame in &self.stack {
+            writeln!(f, "    at {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = match (&self.file, self.line, self.column) {
+            (Some(file), Some(line), Some(column)) => format!("{file}:{line}:{column}"),
+            (Some(file), _, _) => file.clone(),
+            (None, _, _) => "native".to_string(),
+        };
+        match &self.function {
+            Some(function) => write!(f, "{function} ({location})"),
+            None => write!(f, "{location}"),
+        }
+    }
+}
+
+impl std::error::Error for JsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause
+            .as_deref()
+            .map(|cause| cause as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// The error returned when the execution of JavaScript is interrupted by the
+/// [`InterruptPolicy`](crate::InterruptPolicy) of the
+/// [`Runtime`](crate::Runtime).
+#[derive(Debug)]
+pub struct Interrupted {
+    /// The uncatchable `InternalError` thrown by QuickJS.
+    error: JsError,
+}
+
+impl Interrupted {
+    pub(crate) fn new(error: JsError) -> Self {
+        Self { error }
+    }
+
+    /// The uncatchable `InternalError` thrown by QuickJS, with the stack at
+    /// the time of the interruption.
+    pub fn error(&self) -> &JsError {
+        &self.error
+    }
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Execution interrupted by the interrupt policy: {}",
+            self.error
+        )
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// The error returned when a promise is rejected without a rejection handler
+/// and the [`PromiseRejectionPolicy`](crate::PromiseRejectionPolicy) is
+/// [`Fail`](crate::PromiseRejectionPolicy::Fail).
+#[derive(Debug)]
+pub struct UnhandledRejection {
+    /// The reason of the rejection.
+    reason: JsError,
+}
+
+impl UnhandledRejection {
+    pub(crate) fn new(reason: JsError) -> Self {
+        Self { reason }
+    }
+
+    /// The reason the promise was rejected with.
+    pub fn reason(&self) -> &JsError {
+        &self.reason
+    }
+}
+
+impl fmt::Display for UnhandledRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unhandled promise rejection: {}", self.reason)
+    }
+}
+
+impl std::error::Error for UnhandledRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsError, StackFrame};
+    use crate::{Runtime, quickjs::Value};
+    use anyhow::{Error, Result};
+
+    fn frame(
+        function: Option<&str>,
+        file: Option<&str>,
+        position: Option<(u32, u32)>,
+    ) -> StackFrame {
+        StackFrame {
+            function: function.map(String::from),
+            file: file.map(String::from),
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+        }
+    }
+
+    #[test]
+    fn test_parse_stack_frames() {
+        let cases = [
+            (
+                "    at foo (function.mjs:2:13)",
+                frame(Some("foo"), Some("function.mjs"), Some((2, 13))),
+            ),
+            (
+                "    at <anonymous> (a:b.js:5:1)",
+                frame(Some("<anonymous>"), Some("a:b.js"), Some((5, 1))),
+            ),
+            ("    at map (native)", frame(Some("map"), None, None)),
+            (
+                "    at proxy (missing)",
+                frame(Some("proxy"), Some("missing"), None),
+            ),
+            (
+                "    at index.js:3:7",
+                frame(None, Some("index.js"), Some((3, 7))),
+            ),
+            ("    at <input>", frame(None, Some("<input>"), None)),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(Some(expected), StackFrame::parse(line), "{line}");
+        }
+        assert_eq!(None, StackFrame::parse(""));
+    }
+
+    fn error(source: &str) -> Result<JsError> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            let value: Value<'_> = cx.eval(source)?;
+            Ok::<_, Error>(JsError::from_value(&cx, value))
+        })
+    }
+
+    #[test]
+    fn test_from_value() -> Result<()> {
+        let err = error(
+            r#"
+            function fail() {
+                return new TypeError("bad", { cause: new RangeError("worse") });
+            }
+            fail()
+        "#,
+        )?;
+        assert_eq!(Some("TypeError"), err.name.as_deref());
+        assert_eq!("bad", err.message);
+        assert_eq!(
+            vec![
+                frame(Some("fail"), Some("eval_script"), Some((3, 58))),
+                frame(Some("<eval>"), Some("eval_script"), Some((5, 13))),
+            ],
+            err.stack
+        );
+        let cause = err.cause.as_deref().unwrap();
+        assert_eq!(Some("RangeError"), cause.name.as_deref());
+        assert_eq!("worse", cause.message);
+        assert_eq!(
+            "TypeError: bad\n    at fail (eval_script:3:58)\n    at <eval> (eval_script:5:13)\n",
+            err.to_string()
+        );
+        assert_eq!(
+            Some("RangeError: worse"),
+            std::error::Error::source(&err)
+                .map(|source| source.to_string())
+                .as_deref()
+                .map(|source| source.lines().next().unwrap())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_value_of_aggregate_errors_and_values() -> Result<()> {
+        let err = error(
+            r#"
+            const error = new AggregateError([new Error("one"), "two"], "many", { cause: 3 });
+            error.name = "CustomError";
+            error
+        "#,
+        )?;
+        assert_eq!(Some("CustomError"), err.name.as_deref());
+        assert_eq!("many", err.message);
+        assert_eq!(2, err.errors.len());
+        assert_eq!(Some("Error"), err.errors[0].name.as_deref());
+        assert_eq!("one", err.errors[0].message);
+        assert_eq!(None, err.errors[1].name);
+        assert_eq!("two", err.errors[1].message);
+        assert_eq!("3", err.cause.unwrap().message);
+
+        let err = error(r#"({ toString() { return "thrown"; } })"#)?;
+        assert_eq!(None, err.name);
+        assert_eq!("thrown", err.message);
+        assert_eq!("thrown", err.to_string());
+
+        // Cycles are cut off.
+        let err = error("const e = new Error('cycle'); e.cause = e; e")?;
+        let mut depth = 0;
+        let mut cause = err.cause.as_deref();
+        while let Some(next) = cause {
+            depth += 1;
+            cause = next.cause.as_deref();
+        }
+        assert_eq!(16, depth);
+        Ok(())
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serialize() -> Result<()> {
+        let err = error("new Error('message', { cause: new Error('cause') })")?;
+        let json = serde_json::to_string(&err)?;
+        assert_eq!(
+            r#"{"name":"Error","message":"message","stack":[{"function":"<eval>","file":"eval_script","line":1,"column":34}],"cause":{"name":"Error","message":"cause","stack":[{"function":"<eval>","file":"eval_script","line":1,"column":34}]}}"#,
+            json
+        );
+        Ok(())
+    }
+}
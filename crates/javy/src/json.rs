@@ -1,20 +1,376 @@
-use crate::quickjs::{Ctx, Value};
-use anyhow::Result;
-use rquickjs_serde::{de::Deserializer, ser::Serializer};
+use std::{fmt, io};
+
+use crate::{
+    JsonBigIntPolicy, apis,
+    apis::serializer::{MAX_DEPTH, stack_overflow},
+    quickjs::{
+        Array, BigInt, Ctx, Error as JSError, JsLifetime, Object, String as JSString, Value,
+    },
+};
+use anyhow::{Result, anyhow};
+use rquickjs_serde::{MAX_SAFE_INTEGER, MIN_SAFE_INTEGER, de::Deserializer};
+use serde::{
+    Serialize,
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+};
+use serde_json::ser::{CompactFormatter, Formatter, PrettyFormatter};
 
 /// Transcodes a byte slice containing a JSON encoded payload into a [Value].
+///
+/// Integers outside of the safe range are handled according to
+/// [`Config::json_big_int_policy`](crate::Config::json_big_int_policy).
 pub fn parse<'js>(context: Ctx<'js>, bytes: &mut [u8]) -> Result<Value<'js>> {
     let mut deserializer = simd_json::Deserializer::from_slice(bytes)?;
-    let mut serializer = Serializer::from_context(context.clone())?;
-    let value = serde_transcode::transcode(&mut deserializer, &mut serializer)?;
-    Ok(value)
+    let seed = ValueSeed {
+        cx: context.clone(),
+        policy: big_int_policy(&context),
+        depth: 0,
+    };
+    seed.deserialize(&mut deserializer).map_err(|e| {
+        // Keep the exceptions thrown while building the value, e.g. when it's
+        // nested too deeply, rather than reporting them as syntax errors.
+        if context.has_exception() {
+            anyhow!(JSError::Exception)
+        } else {
+            e.into()
+        }
+    })
 }
 
 /// Transcodes a [Value] into a slice of JSON bytes.
+///
+/// `BigInt`s are handled according to
+/// [`Config::json_big_int_policy`](crate::Config::json_big_int_policy).
 pub fn stringify(val: Value<'_>) -> Result<Vec<u8>> {
+    let policy = big_int_policy(val.ctx());
+    if policy != JsonBigIntPolicy::Number {
+        return apis::json::stringify(val, policy);
+    }
+
     let mut output: Vec<u8> = Vec::new();
     let mut deserializer = Deserializer::from(val).with_strict();
-    let mut serializer = serde_json::Serializer::new(&mut output);
+    let mut serializer =
+        serde_json::Serializer::with_formatter(&mut output, NumberFormatter(CompactFormatter));
     serde_transcode::transcode(&mut deserializer, &mut serializer)?;
     Ok(output)
 }
+
+unsafe impl<'js> JsLifetime<'js> for JsonBigIntPolicy {
+    type Changed<'to> = JsonBigIntPolicy;
+}
+
+/// The [`JsonBigIntPolicy`] of the runtime of a context.
+pub(crate) fn big_int_policy(cx: &Ctx<'_>) -> JsonBigIntPolicy {
+    cx.userdata::<JsonBigIntPolicy>()
+        .map(|policy| *policy)
+        .unwrap_or_default()
+}
+
+/// Builds JavaScript values out of a deserializer, keeping integers outside
+/// of the safe range according to a [`JsonBigIntPolicy`].
+#[derive(Clone)]
+struct ValueSeed<'js> {
+    cx: Ctx<'js>,
+    policy: JsonBigIntPolicy,
+    /// The number of arrays and objects the value is nested in.
+    depth: usize,
+}
+
+impl<'js> ValueSeed<'js> {
+    /// The seed of the elements of an array or object, throwing a
+    /// `RangeError` if they're nested too deeply.
+    fn nested<E: de::Error>(&self) -> Result<Self, E> {
+        if self.depth >= MAX_DEPTH {
+            return Err(E::custom(stack_overflow(&self.cx)));
+        }
+        Ok(Self {
+            depth: self.depth + 1,
+            ..self.clone()
+        })
+    }
+
+    /// Converts an integer outside of the safe range.
+    fn out_of_range<E: de::Error>(
+        self,
+        number: f64,
+        digits: &dyn fmt::Display,
+        big_int: impl FnOnce(Ctx<'js>) -> crate::quickjs::Result<BigInt<'js>>,
+    ) -> Result<Value<'js>, E> {
+        let value = match self.policy {
+            JsonBigIntPolicy::Number => Ok(Value::new_number(self.cx, number)),
+            JsonBigIntPolicy::BigInt => big_int(self.cx).map(BigInt::into_value),
+            JsonBigIntPolicy::String => {
+                JSString::from_str(self.cx, &digits.to_string()).map(JSString::into_value)
+            }
+        };
+        value.map_err(E::custom)
+    }
+}
+
+impl<'de, 'js> DeserializeSeed<'de> for ValueSeed<'js> {
+    type Value = Value<'js>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'js> Visitor<'de> for ValueSeed<'js> {
+    type Value = Value<'js>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Value::new_null(self.cx))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Value::new_null(self.cx))
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Value::new_bool(self.cx, v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        if (MIN_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&v) {
+            Ok(Value::new_number(self.cx, v as f64))
+        } else {
+            self.out_of_range(v as f64, &v, |cx| BigInt::from_i64(cx, v))
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        if v <= MAX_SAFE_INTEGER as u64 {
+            Ok(Value::new_number(self.cx, v as f64))
+        } else {
+            self.out_of_range(v as f64, &v, |cx| BigInt::from_u64(cx, v))
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        // Not `Value::new_number`, which turns `-0` into `0`.
+        Ok(Value::new_float(self.cx, v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        JSString::from_str(self.cx, v)
+            .map(JSString::into_value)
+            .map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let seed = self.nested()?;
+        let array = Array::new(self.cx.clone()).map_err(de::Error::custom)?;
+        let mut index = 0;
+        while let Some(element) = seq.next_element_seed(seed.clone())? {
+            array.set(index, element).map_err(de::Error::custom)?;
+            index += 1;
+        }
+        Ok(array.into_value())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let seed = self.nested()?;
+        let object = Object::new(self.cx.clone()).map_err(de::Error::custom)?;
+        while let Some((key, value)) = map.next_entry_seed(seed.clone(), seed.clone())? {
+            object.set(key, value).map_err(de::Error::custom)?;
+        }
+        Ok(object.into_value())
+    }
+}
+
+/// Serializes a value into a slice of JSON bytes, indented with `gap` like
+/// with the `space` argument of `JSON.stringify`, unless it's empty.
+pub(crate) fn to_vec<T: Serialize + ?Sized>(value: &T, gap: &str) -> serde_json::Result<Vec<u8>> {
+    let mut output: Vec<u8> = Vec::new();
+    if gap.is_empty() {
+        let formatter = NumberFormatter(CompactFormatter);
+        value.serialize(&mut serde_json::Serializer::with_formatter(
+            &mut output,
+            formatter,
+        ))?;
+    } else {
+        let formatter = NumberFormatter(PrettyFormatter::with_indent(gap.as_bytes()));
+        value.serialize(&mut serde_json::Serializer::with_formatter(
+            &mut output,
+            formatter,
+        ))?;
+    }
+    Ok(output)
+}
+
+/// A [`Formatter`] writing floating point numbers like JavaScript's
+/// `Number.prototype.toString`, e.g. `1e+21` rather than `1e21`.
+struct NumberFormatter<F>(F);
+
+impl<F: Formatter> Formatter for NumberFormatter<F> {
+    fn write_f64<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        writer.write_all(number_to_string(value).as_bytes())
+    }
+
+    fn begin_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.begin_array(writer)
+    }
+
+    fn end_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.end_array(writer)
+    }
+
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.end_array_value(writer)
+    }
+
+    fn begin_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.begin_object(writer)
+    }
+
+    fn end_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.end_object(writer)
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.begin_object_key(writer, first)
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.begin_object_value(writer)
+    }
+
+    fn end_object_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.0.end_object_value(writer)
+    }
+}
+
+/// Formats a finite number like JavaScript's `Number::toString`, see
+/// <https://tc39.es/ecma262/#sec-numeric-types-number-tostring>.
+fn number_to_string(value: f64) -> String {
+    if value == 0.0 {
+        return "0".into();
+    }
+
+    // Rust formats the shortest representation which round trips, like
+    // JavaScript, only the layout differs.
+    let exponential = format!("{:e}", value.abs());
+    let (mantissa, exponent) = exponential
+        .split_once('e')
+        .expect("exponential notation to have an exponent");
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().expect("exponent to be an integer") + 1;
+
+    let mut result = String::new();
+    if value < 0.0 {
+        result.push('-');
+    }
+    if k <= n && n <= 21 {
+        result.push_str(&digits);
+        result.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        result.push_str(&digits[..n as usize]);
+        result.push('.');
+        result.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        result.push_str("0.");
+        result.extend(std::iter::repeat_n('0', -n as usize));
+        result.push_str(&digits);
+    } else {
+        result.push_str(&digits[..1]);
+        if k > 1 {
+            result.push('.');
+            result.push_str(&digits[1..]);
+        }
+        let sign = if n > 0 { '+' } else { '-' };
+        result.push_str(&format!("e{sign}{}", (n - 1).abs()));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{number_to_string, parse, stringify};
+    use crate::{
+        Config, JsonBigIntPolicy, Runtime,
+        quickjs::{Object, Value},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn formats_numbers_like_javascript() {
+        for (value, expected) in [
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (0.1, "0.1"),
+            (123.456, "123.456"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (1.5e21, "1.5e+21"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (-1.25e-7, "-1.25e-7"),
+            (9007199254740994.0, "9007199254740994"),
+            (f64::MAX, "1.7976931348623157e+308"),
+            (5e-324, "5e-324"),
+        ] {
+            assert_eq!(expected, number_to_string(value));
+        }
+    }
+
+    #[test]
+    fn round_trips_big_integers() -> Result<()> {
+        let mut config = Config::default();
+        config.json_big_int_policy(JsonBigIntPolicy::BigInt);
+        let runtime = Runtime::new(config)?;
+        runtime.context().with(|cx| {
+            let json = r#"{"id":12345678901234567890,"n":[-0,1.5,9007199254740991]}"#;
+            let value = parse(cx.clone(), &mut json.as_bytes().to_vec())?;
+            let object: &Object = value.as_object().unwrap();
+            assert!(object.get::<_, Value>("id")?.is_big_int());
+            assert_eq!(
+                json.replace("-0", "0"),
+                String::from_utf8(stringify(value)?)?
+            );
+            Ok(())
+        })
+    }
+}
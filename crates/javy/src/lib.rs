@@ -39,22 +39,31 @@
 //! ## Core concepts
 //! * [`Runtime`] - The entrypoint for using the JavaScript runtime. Use a
 //!   [`Config`] to configure behavior.
+//! * [`modules`] - Resolution and loading of ES modules imported by other
+//!   modules.
+//! * [`serde`] - Conversions between Rust types and JavaScript values.
 //!
 //! ## Features
 //! * `json` - functions for converting between [`quickjs::JSValueRef`] and JSON
 //!   byte slices
 //! * `messagepack` - functions for converting between [`quickjs::JSValueRef`]
 //!   and MessagePack byte slices
+//! * `cbor` - functions for converting between [`quickjs::JSValueRef`] and CBOR
+//!   byte slices
 
 pub use config::*;
+pub use config_spec::{ConfigProperty, ConfigPropertyType, ConfigPropertyValue, ConfigSpec};
+pub use error::{Interrupted, JsError, StackFrame, UnhandledRejection};
 pub use rquickjs as quickjs;
-pub use runtime::Runtime;
+pub use runtime::{MemoryUsage, Realm, Runtime};
 use std::str;
 
 mod config;
+mod config_spec;
+mod error;
 mod runtime;
 
-use anyhow::{Error, Result, anyhow};
+use anyhow::{Error, Result};
 use rquickjs::{
     Ctx, Error as JSError, Exception, FromJs, String as JSString, Value, convert, prelude::Rest,
     qjs,
@@ -63,9 +72,16 @@ use rquickjs::{
 #[cfg(feature = "messagepack")]
 pub mod messagepack;
 
+#[cfg(feature = "cbor")]
+pub mod cbor;
+
 #[cfg(feature = "json")]
 pub mod json;
 
+pub mod modules;
+
+pub mod serde;
+
 mod apis;
 
 /// A struct to hold the current [`Ctx`] and [`Value`]s passed as arguments to Rust
@@ -104,15 +120,32 @@ macro_rules! hold {
     };
 }
 
+/// Fails if a native function didn't receive exactly `expected` arguments.
+#[cfg(any(feature = "crypto", feature = "fetch", feature = "url"))]
+pub(crate) fn check_arg_count(args: &[Value<'_>], expected: usize) -> Result<()> {
+    if args.len() != expected {
+        anyhow::bail!(
+            "Wrong number of arguments. Expected {expected} arguments. Got: {}",
+            args.len()
+        );
+    }
+    Ok(())
+}
+
 /// Handles a JavaScript error or exception and converts to [anyhow::Error].
+///
+/// Exceptions are converted to a [`JsError`], except the ones raised by the
+/// [`InterruptPolicy`], which are converted to an [`Interrupted`] error.
 pub fn from_js_error(ctx: Ctx<'_>, e: JSError) -> Error {
     if e.is_exception() {
         let val = ctx.catch();
+        let uncatchable = unsafe { qjs::JS_IsUncatchableError(val.as_raw()) };
+        let error = JsError::from_value(&ctx, val);
 
-        if let Some(exception) = val.clone().into_exception() {
-            anyhow!("{exception}")
+        if uncatchable {
+            Error::new(Interrupted::new(error))
         } else {
-            anyhow!(val_to_string(&ctx, val).unwrap_or_else(|_| "Internal error".to_string()))
+            Error::new(error)
         }
     } else {
         Into::into(e)
@@ -122,10 +155,12 @@ pub fn from_js_error(ctx: Ctx<'_>, e: JSError) -> Error {
 /// Converts an [`anyhow::Error`]  to a [`JSError`].
 ///
 /// If the error is an [`anyhow::Error`] this function will construct and throw
-/// a JS [`Exception`] in order to construct the [`JSError`].
+/// a JS [`Exception`] in order to construct the [`JSError`]. A
+/// [`serde::Error`] is thrown as a `TypeError`.
 pub fn to_js_error(cx: Ctx, e: Error) -> JSError {
     match e.downcast::<JSError>() {
         Ok(e) => e,
+        Err(e) if e.is::<serde::Error>() => Exception::throw_type(&cx, &e.to_string()),
         Err(e) => {
             // In some cases the original error context is lost i.e. we can't
             // retain the original JSError when invoking serde_transcode,
@@ -0,0 +1,397 @@
+//! Resolution and loading of ES modules.
+//!
+//! By default, a [`Runtime`](crate::Runtime) can't import other modules.
+//! Module resolvers and loaders, built on top of rquickjs' [`Resolver`] and
+//! [`Loader`] traits, can be registered through
+//! [`Config::module_loader`](crate::Config::module_loader) to allow modules to
+//! import each other.
+//!
+//! This module provides:
+//! * [`BytecodeModules`] - A resolver and loader for modules which have been
+//!   compiled ahead of time through
+//!   [`Runtime::compile_to_bytecode`](crate::Runtime::compile_to_bytecode).
+//! * [`ModuleBundle`] - A binary container to ship a graph of modules, either
+//!   as source code or bytecode, as a single byte slice.
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::quickjs::{
+    Ctx, Error as JSError, Module, Result as JSResult,
+    loader::{ImportAttributes, Loader, Resolver},
+    module::Declared,
+};
+
+/// A collection of modules compiled to QuickJS bytecode.
+///
+/// [`BytecodeModules`] acts both as a [`Resolver`] and as a [`Loader`].
+/// Relative specifiers (i.e., starting with `./` or `../`) are resolved
+/// against the name of the importing module, all other specifiers are
+/// looked up as is.
+///
+/// Cloning a [`BytecodeModules`] is cheap, all clones share the same
+/// collection, which allows adding modules after the collection has been
+/// registered in a [`Config`](crate::Config).
+#[derive(Clone, Default)]
+pub struct BytecodeModules {
+    modules: Rc<RefCell<HashMap<String, Vec<u8>>>>,
+}
+
+impl BytecodeModules {
+    /// Adds a module under the given name.
+    ///
+    /// The bytecode is expected to have been produced by
+    /// [`Runtime::compile_to_bytecode`](crate::Runtime::compile_to_bytecode),
+    /// using the same name.
+    pub fn add(&self, name: impl Into<String>, bytecode: impl Into<Vec<u8>>) -> &Self {
+        self.modules
+            .borrow_mut()
+            .insert(name.into(), bytecode.into());
+        self
+    }
+
+    /// Returns true if a module with the given name is available.
+    pub fn contains(&self, name: &str) -> bool {
+        self.modules.borrow().contains_key(name)
+    }
+}
+
+impl Resolver for BytecodeModules {
+    fn resolve<'js>(
+        &mut self,
+        _ctx: &Ctx<'js>,
+        base: &str,
+        name: &str,
+        _attributes: Option<ImportAttributes<'js>>,
+    ) -> JSResult<String> {
+        let path = resolve_specifier(base, name);
+        if self.contains(&path) {
+            Ok(path)
+        } else {
+            Err(JSError::new_resolving(base, name))
+        }
+    }
+}
+
+impl Loader for BytecodeModules {
+    fn load<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        name: &str,
+        _attributes: Option<ImportAttributes<'js>>,
+    ) -> JSResult<Module<'js, Declared>> {
+        let modules = self.modules.borrow();
+        let bytecode = modules
+            .get(name)
+            .ok_or_else(|| JSError::new_loading(name))?;
+        // Safety: the bytecode is expected to be produced by
+        // `Runtime::compile_to_bytecode`.
+        unsafe { Module::load(ctx.clone(), bytecode) }
+    }
+}
+
+/// Resolves a module specifier against the name of the importing module.
+///
+/// Relative specifiers are joined with the directory of `base` and
+/// normalized, other specifiers are returned unchanged.
+pub(crate) fn resolve_specifier(base: &str, name: &str) -> String {
+    if !name.starts_with("./") && !name.starts_with("../") {
+        return name.to_string();
+    }
+
+    let mut components: Vec<&str> = base.split('/').collect();
+    // Drop the file name of the importing module.
+    components.pop();
+    for component in name.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if matches!(components.last(), None | Some(&"..")) {
+                    components.push("..");
+                } else {
+                    components.pop();
+                }
+            }
+            c => components.push(c),
+        }
+    }
+    components.join("/")
+}
+
+/// The resolvers registered in a [`Config`](crate::Config), tried in order.
+#[derive(Default)]
+pub(crate) struct Resolvers(pub(crate) Vec<Box<dyn Resolver>>);
+
+impl Resolver for Resolvers {
+    fn resolve<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        base: &str,
+        name: &str,
+        attributes: Option<ImportAttributes<'js>>,
+    ) -> JSResult<String> {
+        for resolver in &mut self.0 {
+            match resolver.resolve(ctx, base, name, attributes.clone()) {
+                // The next resolver might be able to resolve the module.
+                Err(JSError::Resolving { .. }) => continue,
+                result => return result,
+            }
+        }
+        Err(JSError::new_resolving(base, name))
+    }
+}
+
+/// The loaders registered in a [`Config`](crate::Config), tried in order.
+#[derive(Default)]
+pub(crate) struct Loaders(pub(crate) Vec<Box<dyn Loader>>);
+
+impl Loader for Loaders {
+    fn load<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        name: &str,
+        attributes: Option<ImportAttributes<'js>>,
+    ) -> JSResult<Module<'js, Declared>> {
+        for loader in &mut self.0 {
+            match loader.load(ctx, name, attributes.clone()) {
+                // The next loader might be able to load the module.
+                Err(JSError::Loading { .. }) => continue,
+                result => return result,
+            }
+        }
+        Err(JSError::new_loading(name))
+    }
+}
+
+/// A graph of named modules, stored either as source code or as bytecode.
+///
+/// The first module of the bundle is the entry point of the graph.
+///
+/// The binary representation of a bundle is:
+/// * The magic bytes `\0javy-modules\0`.
+/// * The number of modules, as a little-endian `u32`.
+/// * For each module, the length of its name as a little-endian `u32`
+///   followed by the UTF-8 encoded name, then the length of its contents
+///   as a little-endian `u32` followed by the contents.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleBundle {
+    modules: Vec<(String, Vec<u8>)>,
+}
+
+impl ModuleBundle {
+    const MAGIC: &[u8] = b"\0javy-modules\0";
+
+    /// Creates an empty [`ModuleBundle`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a module to the bundle. The first module added is the entry
+    /// point.
+    pub fn push(&mut self, name: impl Into<String>, contents: impl Into<Vec<u8>>) -> &mut Self {
+        self.modules.push((name.into(), contents.into()));
+        self
+    }
+
+    /// The entry point of the bundle.
+    pub fn entry(&self) -> Option<(&str, &[u8])> {
+        self.modules().next()
+    }
+
+    /// Iterates over the name and the contents of each module, starting
+    /// with the entry point.
+    pub fn modules(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.modules
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.as_slice()))
+    }
+
+    /// Returns true if the given bytes hold a serialized [`ModuleBundle`].
+    pub fn is_bundle(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::MAGIC)
+    }
+
+    /// Deserializes a [`ModuleBundle`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let Some(mut bytes) = bytes.strip_prefix(Self::MAGIC) else {
+            bail!("Invalid module bundle");
+        };
+
+        let count = read_u32(&mut bytes)?;
+        let mut bundle = Self::new();
+        for _ in 0..count {
+            let name = read_slice(&mut bytes)?;
+            let name = std::str::from_utf8(name).map_err(|_| anyhow!("Invalid module name"))?;
+            let contents = read_slice(&mut bytes)?;
+            bundle.push(name, contents);
+        }
+
+        if !bytes.is_empty() {
+            bail!("Invalid module bundle");
+        }
+        Ok(bundle)
+    }
+
+    /// Serializes the [`ModuleBundle`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Self::MAGIC.to_vec();
+        bytes.extend((self.modules.len() as u32).to_le_bytes());
+        for (name, contents) in &self.modules {
+            bytes.extend((name.len() as u32).to_le_bytes());
+            bytes.extend(name.as_bytes());
+            bytes.extend((contents.len() as u32).to_le_bytes());
+            bytes.extend(contents);
+        }
+        bytes
+    }
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Result<u32> {
+    let Some((len, rest)) = bytes.split_first_chunk::<4>() else {
+        bail!("Invalid module bundle");
+    };
+    *bytes = rest;
+    Ok(u32::from_le_bytes(*len))
+}
+
+fn read_slice<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = read_u32(bytes)? as usize;
+    if bytes.len() < len {
+        bail!("Invalid module bundle");
+    }
+    let (slice, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BytecodeModules, ModuleBundle, resolve_specifier};
+    use crate::{
+        Config, Runtime, from_js_error,
+        quickjs::{
+            Module,
+            loader::{BuiltinLoader, BuiltinResolver},
+        },
+    };
+    use anyhow::{Error, Result};
+
+    #[test]
+    fn test_resolve_specifier() {
+        assert_eq!("util.js", resolve_specifier("index.js", "./util.js"));
+        assert_eq!("lib/a.js", resolve_specifier("index.js", "./lib/a.js"));
+        assert_eq!("lib/b.js", resolve_specifier("lib/a.js", "./b.js"));
+        assert_eq!("c.js", resolve_specifier("lib/a.js", "../c.js"));
+        assert_eq!("../c.js", resolve_specifier("a.js", "../c.js"));
+        assert_eq!("lib/c.js", resolve_specifier("lib/a.js", "./x/.././c.js"));
+        assert_eq!("javy:util", resolve_specifier("lib/a.js", "javy:util"));
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() -> Result<()> {
+        let mut bundle = ModuleBundle::new();
+        bundle.push("index.js", "import './a.js';").push("a.js", "");
+
+        let bytes = bundle.to_bytes();
+        let mut expected = b"\0javy-modules\0\x02\0\0\0".to_vec();
+        expected.extend(b"\x08\0\0\0index.js\x10\0\0\0import './a.js';");
+        expected.extend(b"\x04\0\0\0a.js\0\0\0\0");
+        assert_eq!(expected, bytes);
+        assert!(ModuleBundle::is_bundle(&bytes));
+        assert!(!ModuleBundle::is_bundle(b"import './a.js';"));
+
+        let parsed = ModuleBundle::from_bytes(&bytes)?;
+        assert_eq!(bundle, parsed);
+        assert_eq!(
+            Some(("index.js", "import './a.js';".as_bytes())),
+            parsed.entry()
+        );
+
+        assert!(ModuleBundle::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytecode_modules() -> Result<()> {
+        let modules = BytecodeModules::default();
+        let mut config = Config::default();
+        config.module_loader(modules.clone(), modules.clone());
+        let runtime = Runtime::new(config)?;
+
+        modules.add(
+            "lib/math.js",
+            runtime.compile_to_bytecode("lib/math.js", "export const double = (x) => x * 2;")?,
+        );
+        modules.add(
+            "lib/index.js",
+            runtime.compile_to_bytecode(
+                "lib/index.js",
+                "export { double } from './math.js'; export const ten = 10;",
+            )?,
+        );
+        let entry = runtime.compile_to_bytecode(
+            "index.js",
+            "import { double, ten } from './lib/index.js'; globalThis.result = double(ten);",
+        )?;
+
+        runtime.context().with(|cx| {
+            let module = unsafe { Module::load(cx.clone(), &entry)? };
+            let (_, promise) = module.eval()?;
+            promise.finish::<()>()?;
+            assert_eq!(20, cx.globals().get::<_, i32>("result")?);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_module() -> Result<()> {
+        let modules = BytecodeModules::default();
+        let mut config = Config::default();
+        config.module_loader(modules.clone(), modules);
+        let runtime = Runtime::new(config)?;
+
+        let err = runtime
+            .context()
+            .with(|cx| {
+                Module::evaluate(cx.clone(), "index.js", "import './missing.js';")
+                    .and_then(|promise| promise.finish::<()>())
+                    .map_err(|e| from_js_error(cx, e))
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("missing.js"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_loaders_are_tried_in_order() -> Result<()> {
+        let modules = BytecodeModules::default();
+        let mut config = Config::default();
+        config
+            .module_loader(modules.clone(), modules.clone())
+            .module_loader(
+                BuiltinResolver::default().with_module("javy:answer"),
+                BuiltinLoader::default().with_module("javy:answer", "export default 42;"),
+            );
+        let runtime = Runtime::new(config)?;
+        modules.add(
+            "answer.js",
+            runtime.compile_to_bytecode(
+                "answer.js",
+                "import answer from 'javy:answer'; export default answer;",
+            )?,
+        );
+
+        runtime.context().with(|cx| {
+            Module::evaluate(
+                cx.clone(),
+                "index.js",
+                "import answer from './answer.js'; globalThis.answer = answer;",
+            )?
+            .finish::<()>()?;
+            assert_eq!(42, cx.globals().get::<_, i32>("answer")?);
+            Ok::<_, Error>(())
+        })?;
+        Ok(())
+    }
+}
@@ -1,19 +1,47 @@
 // use crate::quickjs::JSContextRef;
 use super::from_js_error;
+#[cfg(feature = "cbor")]
+use crate::apis::cbor;
+#[cfg(feature = "crypto")]
+use crate::apis::crypto;
+#[cfg(feature = "base64")]
+use crate::apis::encoding;
+#[cfg(feature = "fetch")]
+use crate::apis::fetch;
 #[cfg(feature = "json")]
 use crate::apis::json;
+#[cfg(feature = "messagepack")]
+use crate::apis::messagepack;
+#[cfg(feature = "streams")]
+use crate::apis::streams;
+#[cfg(feature = "url")]
+use crate::apis::url;
 use crate::{
-    Config,
-    apis::{console, random, stream_io, text_encoding},
+    Config, JsError, PromiseRejectionPolicy, UnhandledRejection,
+    apis::{
+        console, event_target,
+        fs::{self, OpenFiles},
+        memory, process, random, stream_io, structured_clone, text_encoding,
+        timers::{self, TimerQueue},
+    },
     config::{JSIntrinsics, JavyIntrinsics},
+    modules::{Loaders, Resolvers},
 };
 
-use anyhow::{Result, bail};
+use anyhow::{Error, Result};
 use rquickjs::{
-    Context, Module, Runtime as QRuntime, WriteOptions,
+    Context, Error as JSError, Module, Persistent, Runtime as QRuntime, Value, WriteOptions,
     context::{Intrinsic, intrinsic},
+    qjs,
+    runtime::RejectionTracker,
+};
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    mem::{self, ManuallyDrop},
+    rc::Rc,
+    time::Instant,
 };
-use std::mem::ManuallyDrop;
 
 /// A JavaScript Runtime.
 ///
@@ -30,32 +58,188 @@ pub struct Runtime {
     // will collect the instance's memory when execution ends, making these
     // drops unnecessary.
     //
-    // This might not be suitable for all use-cases, so we'll make this
-    // behaviour configurable.
+    // Long-lived embeddings can opt into dropping them through
+    // `Config::free_on_drop`.
     context: ManuallyDrop<Context>,
     /// The inner QuickJS runtime representation.
     // Read above on the usage of `ManuallyDrop`.
     inner: ManuallyDrop<QRuntime>,
+    /// The timers scheduled through `setTimeout` and `setInterval`.
+    timers: Rc<RefCell<TimerQueue>>,
+    /// The files opened through `Javy.FS.openSync`.
+    files: Rc<RefCell<OpenFiles>>,
+    /// Whether to drop the context and the runtime when dropped.
+    free_on_drop: bool,
+    /// What to do with unhandled promise rejections.
+    promise_rejection_policy: PromiseRejectionPolicy,
+    /// The stream `console.error` writes to, where unhandled rejections are
+    /// reported with [`PromiseRejectionPolicy::Warn`].
+    err_stream: SharedStream,
+    /// The promises rejected without a rejection handler since the last
+    /// check, along with their reason.
+    rejections: Rc<RefCell<Vec<Rejection>>>,
+}
+
+/// A rejected promise and its reason.
+type Rejection = (Persistent<Value<'static>>, Persistent<Value<'static>>);
+
+/// A stream shared between `console` and the [`Runtime`].
+#[derive(Clone)]
+struct SharedStream(Rc<RefCell<Box<dyn Write>>>);
+
+impl Write for SharedStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// A context created with [`Runtime::new_context`].
+///
+/// A realm has its own global object and intrinsics, while sharing the
+/// garbage collected heap, the pending jobs, the timers, the open files and
+/// the module loader of the [`Runtime`] which created it.
+pub struct Realm {
+    /// The QuickJS context.
+    // Read the comments of `Runtime` on the usage of `ManuallyDrop`.
+    context: ManuallyDrop<Context>,
+    /// Whether to drop the context when dropped.
+    free_on_drop: bool,
+}
+
+impl Realm {
+    /// A reference to the inner [Context].
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl Drop for Realm {
+    fn drop(&mut self) {
+        if self.free_on_drop {
+            unsafe { ManuallyDrop::drop(&mut self.context) }
+        }
+    }
 }
 
 impl Runtime {
     /// Creates a new [Runtime].
     pub fn new(config: Config) -> Result<Self> {
+        let mut cfg = config.validate()?;
         let rt = ManuallyDrop::new(QRuntime::new()?);
+        let timers = Rc::new(RefCell::new(TimerQueue::default()));
+        let files = Rc::new(RefCell::new(OpenFiles::default()));
+
+        rt.set_gc_threshold(cfg.gc_threshold);
+        rt.set_memory_limit(cfg.memory_limit);
+        rt.set_max_stack_size(cfg.max_stack_size);
+        if !cfg.module_resolvers.is_empty() {
+            rt.set_loader(
+                Resolvers(mem::take(&mut cfg.module_resolvers)),
+                Loaders(mem::take(&mut cfg.module_loaders)),
+            );
+        }
+        let interrupt_policy = cfg.interrupt_policy.take();
+        let free_on_drop = cfg.free_on_drop;
+        let promise_rejection_policy = cfg.promise_rejection_policy;
+        #[cfg(feature = "json")]
+        let json_big_int_policy = cfg.json_big_int_policy;
+        let rejections = Rc::new(RefCell::new(vec![]));
+        let err_stream = SharedStream(Rc::new(RefCell::new(mem::replace(
+            &mut cfg.err_stream,
+            Box::new(io::sink()),
+        ))));
+        cfg.err_stream = Box::new(err_stream.clone());
+        if promise_rejection_policy != PromiseRejectionPolicy::Ignore {
+            rt.set_host_promise_rejection_tracker(Some(Self::track_rejections(rejections.clone())));
+        }
 
-        let context = Self::build_from_config(&rt, config)?;
-        Ok(Self { inner: rt, context })
+        let context = Self::build_context(&rt, cfg, timers.clone(), files.clone())?;
+
+        // Stored on the runtime so `json::parse` and `json::stringify` can
+        // look it up from any context.
+        #[cfg(feature = "json")]
+        context.with(|cx| {
+            cx.store_userdata(json_big_int_policy)
+                .expect("storing the JSON BigInt policy to succeed");
+        });
+
+        // Installed once the APIs are registered, so that evaluating their
+        // JavaScript doesn't count towards the policy.
+        if let Some(policy) = interrupt_policy {
+            rt.set_interrupt_handler(Some(policy.into_handler()));
+        }
+
+        Ok(Self {
+            inner: rt,
+            context: ManuallyDrop::new(context),
+            timers,
+            files,
+            free_on_drop,
+            promise_rejection_policy,
+            err_stream,
+            rejections,
+        })
+    }
+
+    /// The promise rejection tracker, keeping track of the promises rejected
+    /// without a handler, until a handler is attached to them.
+    fn track_rejections(rejections: Rc<RefCell<Vec<Rejection>>>) -> RejectionTracker {
+        Box::new(move |cx, promise, reason, is_handled| {
+            let mut rejections = rejections.borrow_mut();
+            if is_handled {
+                rejections.retain(|(rejected, _)| {
+                    rejected
+                        .clone()
+                        .restore(&cx)
+                        .map_or(true, |rejected| rejected != promise)
+                });
+            } else {
+                rejections.push((
+                    Persistent::save(&cx, promise),
+                    Persistent::save(&cx, reason),
+                ));
+            }
+        })
     }
 
-    fn build_from_config(rt: &QRuntime, cfg: Config) -> Result<ManuallyDrop<Context>> {
-        let cfg = cfg.validate()?;
+    /// Creates a new [`Realm`], a context with its own global object, sharing
+    /// the heap of this runtime.
+    ///
+    /// The intrinsics and APIs of the realm are the ones enabled in `config`.
+    /// The settings which apply to the whole runtime, namely
+    /// [`Config::gc_threshold`], [`Config::memory_limit`],
+    /// [`Config::max_stack_size`], the module loaders,
+    /// [`Config::interrupt_policy`], [`Config::free_on_drop`],
+    /// [`Config::promise_rejection_policy`] and `Config::json_big_int_policy`,
+    /// are the ones this runtime was
+    /// created with, and are ignored.
+    pub fn new_context(&self, config: Config) -> Result<Realm> {
+        let context = Self::build_context(
+            &self.inner,
+            config.validate()?,
+            self.timers.clone(),
+            self.files.clone(),
+        )?;
+        Ok(Realm {
+            context: ManuallyDrop::new(context),
+            free_on_drop: self.free_on_drop,
+        })
+    }
+
+    /// Creates a context with the intrinsics and APIs enabled in the config.
+    fn build_context(
+        rt: &QRuntime,
+        cfg: Config,
+        timers: Rc<RefCell<TimerQueue>>,
+        files: Rc<RefCell<OpenFiles>>,
+    ) -> Result<Context> {
         let intrinsics = &cfg.intrinsics;
         let javy_intrinsics = &cfg.javy_intrinsics;
 
-        rt.set_gc_threshold(cfg.gc_threshold);
-        rt.set_memory_limit(cfg.memory_limit);
-        rt.set_max_stack_size(cfg.max_stack_size);
-
         let context = Context::base(rt)?;
 
         // We use `Context::with` to ensure that there's a proper lock on the
@@ -115,6 +299,27 @@ impl Runtime {
                     .expect("registering TextEncoding APIs to succeed");
             }
 
+            #[cfg(feature = "crypto")]
+            if intrinsics.contains(JSIntrinsics::CRYPTO) {
+                crypto::register(ctx.clone()).expect("registering crypto APIs to succeed");
+            }
+
+            #[cfg(feature = "url")]
+            if intrinsics.contains(JSIntrinsics::URL) {
+                url::register(ctx.clone()).expect("registering URL APIs to succeed");
+            }
+
+            #[cfg(feature = "base64")]
+            if intrinsics.contains(JSIntrinsics::BASE64) {
+                encoding::register_globals(ctx.clone())
+                    .expect("registering atob and btoa to succeed");
+            }
+
+            if intrinsics.contains(JSIntrinsics::STRUCTURED_CLONE) {
+                structured_clone::register(ctx.clone())
+                    .expect("registering structuredClone to succeed");
+            }
+
             if intrinsics.contains(JSIntrinsics::WEAK_REF) {
                 unsafe { intrinsic::WeakRef::add_intrinsic(ctx.as_raw()) };
             }
@@ -123,16 +328,77 @@ impl Runtime {
                 unsafe { intrinsic::Performance::add_intrinsic(ctx.as_raw()) };
             }
 
+            if intrinsics.contains(JSIntrinsics::TIMERS) {
+                timers::register(ctx.clone(), timers.clone())
+                    .expect("registering timers to succeed");
+            }
+
             console::register(ctx.clone(), cfg.log_stream, cfg.err_stream)
                 .expect("registering console to succeed");
 
+            // Registered after timers and `console`, which are used by
+            // `AbortSignal.timeout` and to report exceptions thrown by
+            // listeners.
+            if intrinsics.contains(JSIntrinsics::EVENT_TARGET) {
+                let timers = intrinsics.contains(JSIntrinsics::TIMERS).then_some(timers);
+                event_target::register(ctx.clone(), timers)
+                    .expect("registering EventTarget APIs to succeed");
+            }
+
+            // Registered after `EventTarget` APIs, which back the `signal`s of
+            // `pipeTo` and `WritableStreamDefaultController`.
+            #[cfg(feature = "streams")]
+            if intrinsics.contains(JSIntrinsics::STREAMS) {
+                streams::register(ctx.clone()).expect("registering streams to succeed");
+            }
+
+            // Registered after `EventTarget` APIs, which back `Request.signal`.
+            #[cfg(feature = "fetch")]
+            if intrinsics.contains(JSIntrinsics::FETCH_TYPES) {
+                fetch::register(ctx.clone()).expect("registering fetch types to succeed");
+            }
+
+            // Registered after streams, which back `Javy.IO.stdin` and
+            // `Javy.IO.stdout`.
             if javy_intrinsics.contains(JavyIntrinsics::STREAM_IO) {
-                stream_io::register(ctx.clone())
+                stream_io::register(ctx.clone(), files.clone())
                     .expect("registering StreamIO functions to succeed");
             }
+
+            #[cfg(feature = "base64")]
+            if javy_intrinsics.contains(JavyIntrinsics::ENCODING) {
+                encoding::register_javy(ctx.clone())
+                    .expect("registering Javy.Encoding functions to succeed");
+            }
+
+            if javy_intrinsics.contains(JavyIntrinsics::FS) {
+                fs::register(ctx.clone(), files.clone())
+                    .expect("registering Javy.FS functions to succeed");
+            }
+
+            if javy_intrinsics.contains(JavyIntrinsics::PROCESS) {
+                process::register(ctx.clone())
+                    .expect("registering Javy.args, Javy.env and Javy.exit to succeed");
+            }
+
+            if javy_intrinsics.contains(JavyIntrinsics::MEMORY) {
+                memory::register(ctx.clone())
+                    .expect("registering Javy.memoryUsage and Javy.gc to succeed");
+            }
+
+            #[cfg(feature = "messagepack")]
+            if javy_intrinsics.contains(JavyIntrinsics::MESSAGEPACK) {
+                messagepack::register(ctx.clone())
+                    .expect("registering Javy.MessagePack functions to succeed");
+            }
+
+            #[cfg(feature = "cbor")]
+            if javy_intrinsics.contains(JavyIntrinsics::CBOR) {
+                cbor::register(ctx.clone()).expect("registering Javy.CBOR functions to succeed");
+            }
         });
 
-        Ok(ManuallyDrop::new(context))
+        Ok(context)
     }
 
     /// A reference to the inner [Context].
@@ -140,7 +406,8 @@ impl Runtime {
         &self.context
     }
 
-    /// Resolves all the pending jobs in the queue.
+    /// Resolves all the pending jobs in the queue, then checks for unhandled
+    /// promise rejections, see [`Runtime::check_unhandled_rejections`].
     pub fn resolve_pending_jobs(&self) -> Result<()> {
         if self.inner.is_job_pending() {
             loop {
@@ -150,12 +417,52 @@ impl Runtime {
                 }
 
                 if let Err(e) = result {
-                    bail!("{e}")
+                    // The exception is pending in the context of the job.
+                    return Err(e.0.with(|cx| from_js_error(cx, JSError::Exception)));
                 }
             }
         }
 
-        Ok(())
+        self.check_unhandled_rejections()
+    }
+
+    /// Handles the promises rejected without a rejection handler since the
+    /// last check, according to the [`Config::promise_rejection_policy`].
+    ///
+    /// With [`PromiseRejectionPolicy::Fail`], returns an
+    /// [`UnhandledRejection`] error for the first of them.
+    pub fn check_unhandled_rejections(&self) -> Result<()> {
+        let rejections = mem::take(&mut *self.rejections.borrow_mut());
+        if rejections.is_empty() {
+            return Ok(());
+        }
+
+        self.context().with(|cx| {
+            let mut reasons = rejections.into_iter().map(|(_, reason)| {
+                reason
+                    .restore(&cx)
+                    .map(|reason| JsError::from_value(&cx, reason))
+            });
+            match self.promise_rejection_policy {
+                PromiseRejectionPolicy::Ignore => Ok(()),
+                PromiseRejectionPolicy::Warn => {
+                    let mut err_stream = self.err_stream.clone();
+                    for reason in reasons {
+                        let reason = reason?.to_string();
+                        writeln!(
+                            err_stream,
+                            "Unhandled promise rejection: {}",
+                            reason.trim_end()
+                        )?;
+                    }
+                    Ok(())
+                }
+                PromiseRejectionPolicy::Fail => match reasons.next() {
+                    Some(reason) => Err(Error::new(UnhandledRejection::new(reason?))),
+                    None => Ok(()),
+                },
+            }
+        })
     }
 
     /// Returns true if there are pending jobs in the queue.
@@ -163,6 +470,74 @@ impl Runtime {
         self.inner.is_job_pending()
     }
 
+    /// Returns true if there are timers waiting to fire which keep the event
+    /// loop running.
+    ///
+    /// Timers scheduled by the runtime itself, like the one behind
+    /// `AbortSignal.timeout`, are not taken into account, they only fire if
+    /// the event loop is kept running by something else.
+    pub fn has_pending_timers(&self) -> bool {
+        !self.timers.borrow().is_idle()
+    }
+
+    /// Waits for the next timer to be due and fires it.
+    ///
+    /// Returns `false` if there are no pending timers.
+    ///
+    /// The deadline of each timer is tracked with the monotonic clock, when
+    /// targeting WASI, waiting for a timer blocks the calling thread through
+    /// the host's `poll_oneoff` implementation.
+    pub fn run_next_timer(&self) -> Result<bool> {
+        let Some(deadline) = self.timers.borrow().next_deadline() else {
+            return Ok(false);
+        };
+
+        let now = Instant::now();
+        if deadline > now {
+            std::thread::sleep(deadline - now);
+        }
+
+        self.context()
+            .with(|cx| timers::fire_next(&cx, &self.timers).map_err(|e| from_js_error(cx, e)))
+    }
+
+    /// Runs the event loop until there is no work left.
+    ///
+    /// All the pending jobs are resolved first, then timers are fired in the
+    /// order of their deadlines, resolving any jobs queued by each timer
+    /// before moving on to the next one.
+    ///
+    /// Note that the event loop only ends once all the timers have fired,
+    /// which means that an interval which is never cleared will keep the event
+    /// loop running indefinitely.
+    pub fn run_event_loop(&self) -> Result<()> {
+        loop {
+            self.resolve_pending_jobs()?;
+            if !self.has_pending_timers() {
+                break;
+            }
+            self.run_next_timer()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the memory usage statistics of the runtime.
+    ///
+    /// Computing the statistics walks all the objects, strings and functions
+    /// allocated by the runtime, so it's best suited for tuning the
+    /// [`Config::gc_threshold`] and [`Config::memory_limit`] rather than to be
+    /// called repeatedly.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.inner.memory_usage().into()
+    }
+
+    /// Runs the garbage collector, collecting objects which are only kept
+    /// alive by cyclic references.
+    pub fn run_gc(&self) {
+        self.inner.run_gc()
+    }
+
     /// Compiles the given module to bytecode.
     pub fn compile_to_bytecode(&self, name: &str, contents: &str) -> Result<Vec<u8>> {
         self.context()
@@ -173,6 +548,111 @@ impl Runtime {
     }
 }
 
+/// Memory usage statistics of a [`Runtime`], as reported by QuickJS.
+///
+/// Sizes are in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MemoryUsage {
+    /// The number of bytes allocated by the runtime.
+    pub allocated_bytes: u64,
+    /// The number of allocations made by the runtime.
+    pub allocation_count: u64,
+    /// The number of bytes used by the runtime, including the overhead of the
+    /// allocator.
+    pub used_bytes: u64,
+    /// The number of memory blocks used by the runtime.
+    pub used_count: u64,
+    /// The number of atoms, the interned strings used for property names.
+    pub atom_count: u64,
+    /// The number of bytes used by the atoms.
+    pub atom_bytes: u64,
+    /// The number of strings.
+    pub string_count: u64,
+    /// The number of bytes used by the strings.
+    pub string_bytes: u64,
+    /// The number of objects.
+    pub object_count: u64,
+    /// The number of bytes used by the objects.
+    pub object_bytes: u64,
+    /// The number of object properties.
+    pub property_count: u64,
+    /// The number of bytes used by the object properties.
+    pub property_bytes: u64,
+    /// The number of object shapes.
+    pub shape_count: u64,
+    /// The number of bytes used by the object shapes.
+    pub shape_bytes: u64,
+    /// The number of JavaScript functions.
+    pub function_count: u64,
+    /// The number of bytes used by the JavaScript functions, excluding their
+    /// bytecode.
+    pub function_bytes: u64,
+    /// The size of the bytecode of the JavaScript functions.
+    pub function_code_bytes: u64,
+    /// The number of functions implemented in Rust or C.
+    pub native_function_count: u64,
+    /// The number of arrays.
+    pub array_count: u64,
+    /// The number of arrays with contiguous elements.
+    pub fast_array_count: u64,
+    /// The number of elements of the arrays with contiguous elements.
+    pub fast_array_elements: u64,
+    /// The number of `ArrayBuffer`s and `SharedArrayBuffer`s.
+    pub binary_object_count: u64,
+    /// The number of bytes used by the `ArrayBuffer`s and `SharedArrayBuffer`s.
+    pub binary_object_bytes: u64,
+}
+
+impl From<qjs::JSMemoryUsage> for MemoryUsage {
+    fn from(usage: qjs::JSMemoryUsage) -> Self {
+        // QuickJS reports the statistics as signed integers, none of them
+        // can be negative.
+        let n = |value: i64| value.max(0) as u64;
+        Self {
+            allocated_bytes: n(usage.malloc_size),
+            allocation_count: n(usage.malloc_count),
+            used_bytes: n(usage.memory_used_size),
+            used_count: n(usage.memory_used_count),
+            atom_count: n(usage.atom_count),
+            atom_bytes: n(usage.atom_size),
+            string_count: n(usage.str_count),
+            string_bytes: n(usage.str_size),
+            object_count: n(usage.obj_count),
+            object_bytes: n(usage.obj_size),
+            property_count: n(usage.prop_count),
+            property_bytes: n(usage.prop_size),
+            shape_count: n(usage.shape_count),
+            shape_bytes: n(usage.shape_size),
+            function_count: n(usage.js_func_count),
+            function_bytes: n(usage.js_func_size),
+            function_code_bytes: n(usage.js_func_code_size),
+            native_function_count: n(usage.c_func_count),
+            array_count: n(usage.array_count),
+            fast_array_count: n(usage.fast_array_count),
+            fast_array_elements: n(usage.fast_array_elements),
+            binary_object_count: n(usage.binary_object_count),
+            binary_object_bytes: n(usage.binary_object_size),
+        }
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        if self.free_on_drop {
+            // The pending timers and the unhandled rejections hold on to
+            // values of the context, which need to be released before the
+            // runtime is freed.
+            self.timers.borrow_mut().clear();
+            self.rejections.borrow_mut().clear();
+            unsafe {
+                ManuallyDrop::drop(&mut self.context);
+                ManuallyDrop::drop(&mut self.inner);
+            }
+        }
+    }
+}
+
 impl Default for Runtime {
     /// Returns a [`Runtime`] with a default configuration.
     ///
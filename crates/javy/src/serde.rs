@@ -0,0 +1,235 @@
+//! Conversions between Rust types and JavaScript values, built on serde.
+//!
+//! Useful to pass configuration or results between Rust functions and
+//! JavaScript, e.g. in the functions a plugin registers on its runtime:
+//!
+//! ```
+//! use anyhow::Result;
+//! use javy::quickjs::{self, Ctx, Function, Value};
+//! use javy::{Runtime, to_js_error};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize)]
+//! struct Point {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Distance {
+//!     distance: f64,
+//! }
+//!
+//! fn distance<'js>(cx: Ctx<'js>, point: Value<'js>) -> quickjs::Result<Value<'js>> {
+//!     let point: Point = javy::serde::from_js(point).map_err(|e| to_js_error(cx.clone(), e))?;
+//!     let distance = point.x.hypot(point.y);
+//!     javy::serde::to_js(cx.clone(), &Distance { distance }).map_err(|e| to_js_error(cx, e))
+//! }
+//!
+//! fn main() -> Result<()> {
+//!     let runtime = Runtime::default();
+//!     runtime.context().with(|cx| {
+//!         cx.globals()
+//!             .set("distance", Function::new(cx.clone(), distance)?)?;
+//!
+//!         let distance: f64 = cx.eval("distance({ x: 3, y: 4 }).distance")?;
+//!         assert_eq!(5.0, distance);
+//!         Ok(())
+//!     })
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::quickjs::{Ctx, Value};
+use anyhow::{Result, anyhow};
+use rquickjs_serde::{Deserializer, Serializer, err::CaughtError};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Converts a `T` into a JavaScript [`Value`].
+///
+/// Fails with an [`Error`] naming the path of the field which couldn't be
+/// converted.
+pub fn to_js<'js, T: Serialize + ?Sized>(cx: Ctx<'js>, value: &T) -> Result<Value<'js>> {
+    let mut serializer = Serializer::from_context(cx.clone())?;
+    serde_path_to_error::serialize(value, &mut serializer).map_err(|e| Error::from_path(&cx, e))
+}
+
+/// Converts a JavaScript [`Value`] into a `T`.
+///
+/// Fails with an [`Error`] naming the path of the property which couldn't be
+/// converted, and with the exception when accessing a property throws.
+pub fn from_js<T: DeserializeOwned>(value: Value<'_>) -> Result<T> {
+    let cx = value.ctx().clone();
+    let mut deserializer = Deserializer::from(value);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|e| Error::from_path(&cx, e))
+}
+
+/// The error returned when a value can't be converted by [`to_js`] or
+/// [`from_js`].
+///
+/// [`to_js_error`](crate::to_js_error) converts it to a `TypeError`.
+#[derive(Debug)]
+pub struct Error {
+    /// The path of the offending field, e.g. `users[0].name`, `.` for the
+    /// value itself.
+    path: String,
+    /// Why the field couldn't be converted.
+    message: String,
+}
+
+impl Error {
+    /// Converts the error of a conversion, keeping the exceptions thrown by
+    /// JavaScript as is.
+    fn from_path(
+        cx: &Ctx<'_>,
+        error: serde_path_to_error::Error<rquickjs_serde::Error>,
+    ) -> anyhow::Error {
+        let path = error.path().to_string();
+        let message = match error.into_inner().catch(cx) {
+            CaughtError::Exception(exception) => return anyhow!(cx.throw(exception.into_value())),
+            CaughtError::Value(value) => return anyhow!(cx.throw(value)),
+            CaughtError::Error(e) => e.to_string(),
+            CaughtError::Message(message) => message,
+        };
+        anyhow!(Self { path, message })
+    }
+
+    /// The path of the offending field, e.g. `users[0].name`, `.` for the
+    /// value itself.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Why the field couldn't be converted.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path == "." {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, from_js, to_js};
+    use crate::{
+        Runtime,
+        quickjs::{Ctx, Function, Value},
+        to_js_error,
+    };
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        age: u8,
+        tags: Vec<String>,
+        #[serde(default)]
+        nickname: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Team {
+        users: Vec<User>,
+        scores: BTreeMap<String, f64>,
+    }
+
+    fn fail<S: Serializer>(_: &u8, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("unsupported"))
+    }
+
+    #[derive(Serialize)]
+    struct Unserializable {
+        #[serde(serialize_with = "fail")]
+        field: u8,
+    }
+
+    #[test]
+    fn round_trips_values() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            let team = Team {
+                users: vec![User {
+                    name: "Ada".into(),
+                    age: 36,
+                    tags: vec!["math".into()],
+                    nickname: None,
+                }],
+                scores: BTreeMap::from([("Ada".into(), 1.5)]),
+            };
+            let value = to_js(cx.clone(), &team)?;
+            cx.globals().set("team", value.clone())?;
+            let json: String = cx.eval("JSON.stringify(team)")?;
+            assert_eq!(
+                r#"{"users":[{"name":"Ada","age":36,"tags":["math"],"nickname":null}],"scores":{"Ada":1.5}}"#,
+                json
+            );
+            assert_eq!(team, from_js::<Team>(value)?);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn names_the_offending_field() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            let value: Value<'_> =
+                cx.eval(r#"({ users: [{ name: "Ada", age: 36, tags: [1] }], scores: {} })"#)?;
+            let error = from_js::<Team>(value).unwrap_err();
+            let error = error.downcast_ref::<Error>().unwrap();
+            assert_eq!("users[0].tags[0]", error.path());
+
+            let value: Value<'_> = cx.eval(r#"({ users: [{ name: "Ada" }], scores: {} })"#)?;
+            let error = from_js::<Team>(value).unwrap_err();
+            assert_eq!("users[0]: missing field `age`", error.to_string());
+
+            let error = to_js(cx.clone(), &Unserializable { field: 1 }).unwrap_err();
+            assert_eq!("field: unsupported", error.to_string());
+
+            let error = from_js::<u8>(Value::new_bool(cx.clone(), true)).unwrap_err();
+            assert_eq!(".", error.downcast_ref::<Error>().unwrap().path());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn throws_type_errors() -> Result<()> {
+        let runtime = Runtime::default();
+        runtime.context().with(|cx| {
+            cx.globals().set(
+                "greet",
+                Function::new(cx.clone(), |cx: Ctx<'_>, user: Value<'_>| {
+                    let user: User = from_js(user).map_err(|e| to_js_error(cx.clone(), e))?;
+                    Ok::<_, crate::quickjs::Error>(format!("Hello, {}!", user.name))
+                })?,
+            )?;
+            let result: String = cx.eval(
+                r#"
+                const error = (f) => { try { return f(); } catch (e) { return `${e.name}: ${e.message}`; } };
+                [
+                    greet({ name: "Ada", age: 36, tags: [] }),
+                    error(() => greet({ name: "Ada", age: "old", tags: [] })),
+                    error(() => greet({ get name() { throw new RangeError("nope"); } })),
+                ].join("\n")
+            "#,
+            )?;
+            let lines: Vec<&str> = result.lines().collect();
+            assert_eq!("Hello, Ada!", lines[0]);
+            assert!(lines[1].starts_with("TypeError: age: "), "{}", lines[1]);
+            assert_eq!("RangeError: nope", lines[2]);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use anyhow::{Error, Result};
+use javy::{Config, InterruptPolicy, Interrupted, Runtime, from_js_error, quickjs::Value};
+
+const LOOP: &str = r#"
+    globalThis.caught = false;
+    try {
+        for (;;) {}
+    } catch {
+        globalThis.caught = true;
+    }
+"#;
+
+fn runtime(policy: InterruptPolicy) -> Result<Runtime> {
+    let mut config = Config::default();
+    config.interrupt_policy(policy);
+    Runtime::new(config)
+}
+
+/// Evaluates the source, returning the error it fails with.
+fn eval_err(runtime: &Runtime, source: &str) -> Error {
+    runtime.context().with(|cx| {
+        cx.eval::<Value<'_>, _>(source)
+            .map(|_| ())
+            .map_err(|e| from_js_error(cx.clone(), e))
+            .expect_err("evaluation to fail")
+    })
+}
+
+#[test]
+fn budget_interrupts_uncatchably() -> Result<()> {
+    let runtime = runtime(InterruptPolicy::Budget(1_000_000))?;
+    let err = eval_err(&runtime, LOOP);
+
+    let interrupted = err.downcast_ref::<Interrupted>().unwrap();
+    assert!(
+        interrupted.to_string().starts_with(
+            "Execution interrupted by the interrupt policy: InternalError: interrupted"
+        )
+    );
+    assert_eq!(Some("InternalError"), interrupted.error().name.as_deref());
+    assert_eq!("interrupted", interrupted.error().message);
+    runtime.context().with(|cx| {
+        let caught: bool = cx.globals().get("caught")?;
+        assert!(!caught);
+        Ok::<_, Error>(())
+    })?;
+
+    // The budget stays exhausted.
+    let err = eval_err(&runtime, "for (let i = 0; i < 100000; i++) {}");
+    assert!(err.is::<Interrupted>());
+    Ok(())
+}
+
+#[test]
+fn budget_allows_scripts_within_it() -> Result<()> {
+    let runtime = runtime(InterruptPolicy::Budget(1_000_000))?;
+    runtime.context().with(|cx| {
+        let sum: i32 = cx.eval("let sum = 0; for (let i = 0; i < 1000; i++) { sum += i; } sum")?;
+        assert_eq!(499500, sum);
+        Ok::<_, Error>(())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn timeout_interrupts() -> Result<()> {
+    let runtime = runtime(InterruptPolicy::Timeout(Duration::from_millis(50)))?;
+    let err = eval_err(&runtime, LOOP);
+    assert!(err.is::<Interrupted>());
+    Ok(())
+}
+
+#[test]
+fn callback_interrupts_and_can_resume() -> Result<()> {
+    let interrupt = Rc::new(Cell::new(true));
+    let runtime = runtime(InterruptPolicy::Callback(Box::new({
+        let interrupt = interrupt.clone();
+        move || interrupt.get()
+    })))?;
+
+    let err = eval_err(&runtime, LOOP);
+    assert!(err.is::<Interrupted>());
+
+    interrupt.set(false);
+    runtime.context().with(|cx| {
+        let result: i32 = cx.eval("let n = 0; for (let i = 0; i < 100000; i++) { n++; } n")?;
+        assert_eq!(100000, result);
+        Ok::<_, Error>(())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn interrupted_jobs_report_interrupted() -> Result<()> {
+    let runtime = runtime(InterruptPolicy::Budget(1_000_000))?;
+    runtime.context().with(|cx| {
+        cx.eval::<(), _>(
+            r#"
+            (async () => {
+                await null;
+                try {
+                    for (;;) {}
+                } catch {}
+            })();
+        "#,
+        )?;
+        Ok::<_, Error>(())
+    })?;
+
+    let err = runtime.resolve_pending_jobs().unwrap_err();
+    assert!(err.is::<Interrupted>());
+    Ok(())
+}
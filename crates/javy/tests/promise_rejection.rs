@@ -0,0 +1,146 @@
+use anyhow::{Error, Result};
+use javy::{Config, PromiseRejectionPolicy, Runtime, UnhandledRejection};
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    rc::Rc,
+};
+
+fn runtime(policy: PromiseRejectionPolicy) -> Result<Runtime> {
+    let mut config = Config::default();
+    config.promise_rejection_policy(policy);
+    Runtime::new(config)
+}
+
+/// A stream writing to a buffer shared with the test.
+#[derive(Clone, Default)]
+struct Stream(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn eval(runtime: &Runtime, source: &str) -> Result<()> {
+    runtime
+        .context()
+        .with(|cx| cx.eval::<(), _>(source).map_err(Error::from))
+}
+
+#[test]
+fn unhandled_rejections_are_ignored_by_default() -> Result<()> {
+    let runtime = Runtime::default();
+    eval(&runtime, "Promise.reject(new Error('ignored'));")?;
+    runtime.resolve_pending_jobs()?;
+    runtime.check_unhandled_rejections()?;
+    Ok(())
+}
+
+#[test]
+fn fail_returns_the_rejection_reason() -> Result<()> {
+    let runtime = runtime(PromiseRejectionPolicy::Fail)?;
+    eval(
+        &runtime,
+        r#"
+        (async function fail() {
+            await null;
+            throw new TypeError("boom");
+        })();
+    "#,
+    )?;
+
+    let err = runtime.resolve_pending_jobs().unwrap_err();
+    let rejection = err.downcast_ref::<UnhandledRejection>().unwrap();
+    assert_eq!(Some("TypeError"), rejection.reason().name.as_deref());
+    assert_eq!("boom", rejection.reason().message);
+    assert!(
+        rejection
+            .to_string()
+            .starts_with("Unhandled promise rejection: TypeError: boom\n    at fail")
+    );
+
+    // Rejections are only reported once.
+    runtime.resolve_pending_jobs()?;
+    Ok(())
+}
+
+#[test]
+fn fail_without_pending_jobs() -> Result<()> {
+    let runtime = runtime(PromiseRejectionPolicy::Fail)?;
+    eval(&runtime, "Promise.reject(42);")?;
+    assert!(!runtime.has_pending_jobs());
+
+    let err = runtime.check_unhandled_rejections().unwrap_err();
+    let rejection = err.downcast_ref::<UnhandledRejection>().unwrap();
+    assert_eq!(None, rejection.reason().name);
+    assert_eq!("42", rejection.reason().message);
+    Ok(())
+}
+
+#[test]
+fn rejections_handled_by_later_jobs_are_not_reported() -> Result<()> {
+    let runtime = runtime(PromiseRejectionPolicy::Fail)?;
+    eval(
+        &runtime,
+        r#"
+        globalThis.caught = "";
+        const rejected = Promise.reject(new Error("late"));
+        (async () => {
+            await null;
+            await null;
+            rejected.catch((e) => { caught = e.message; });
+        })();
+        Promise.reject(new Error("handled")).catch(() => {});
+    "#,
+    )?;
+
+    runtime.resolve_pending_jobs()?;
+    runtime.context().with(|cx| {
+        let caught: String = cx.globals().get("caught")?;
+        assert_eq!("late", caught);
+        Ok::<_, Error>(())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn warn_writes_to_the_err_stream() -> Result<()> {
+    let stream = Stream::default();
+    let mut config = Config::default();
+    config
+        .promise_rejection_policy(PromiseRejectionPolicy::Warn)
+        .err_stream(Box::new(stream.clone()));
+    let runtime = Runtime::new(config)?;
+    eval(
+        &runtime,
+        "console.error('before'); Promise.reject(new Error('warned'));",
+    )?;
+    runtime.resolve_pending_jobs()?;
+    runtime.check_unhandled_rejections()?;
+
+    let output = String::from_utf8(stream.0.borrow().clone())?;
+    assert!(
+        output.starts_with("before\nUnhandled promise rejection: Error: warned\n"),
+        "{output}"
+    );
+    Ok(())
+}
+
+#[test]
+fn realms_share_the_policy() -> Result<()> {
+    let runtime = runtime(PromiseRejectionPolicy::Fail)?;
+    let realm = runtime.new_context(Config::default())?;
+    realm
+        .context()
+        .with(|cx| cx.eval::<(), _>("Promise.reject(new RangeError('realm'));"))?;
+
+    let err = runtime.check_unhandled_rejections().unwrap_err();
+    let rejection = err.downcast_ref::<UnhandledRejection>().unwrap();
+    assert_eq!(Some("RangeError"), rejection.reason().name.as_deref());
+    Ok(())
+}
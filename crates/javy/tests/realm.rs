@@ -0,0 +1,130 @@
+use anyhow::{Error, Result};
+use javy::{
+    Config, Runtime,
+    quickjs::{Persistent, Value},
+};
+
+#[test]
+fn realms_have_their_own_globals_and_intrinsics() -> Result<()> {
+    let runtime = Runtime::default();
+    let mut config = Config::default();
+    config.text_encoding(true).json(false);
+    let realm = runtime.new_context(config)?;
+
+    runtime.context().with(|cx| {
+        cx.eval::<(), _>("globalThis.shared = 1;")?;
+        let result: String = cx.eval("[typeof TextEncoder, typeof JSON].join()")?;
+        assert_eq!("undefined,object", result);
+        Ok::<_, Error>(())
+    })?;
+
+    realm.context().with(|cx| {
+        let result: String = cx.eval("[typeof shared, typeof TextEncoder, typeof JSON].join()")?;
+        assert_eq!("undefined,function,undefined", result);
+        Ok::<_, Error>(())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn realms_share_the_heap_and_jobs() -> Result<()> {
+    let runtime = Runtime::default();
+    let realm = runtime.new_context(Config::default())?;
+
+    let (array, promise) = realm.context().with(|cx| {
+        let array: Value<'_> = cx.eval("[1, 2, 3]")?;
+        let promise: Value<'_> = cx.eval(
+            r#"
+            globalThis.settled = false;
+            Promise.resolve().then(() => { globalThis.settled = true; })
+        "#,
+        )?;
+        Ok::<_, Error>((Persistent::save(&cx, array), Persistent::save(&cx, promise)))
+    })?;
+
+    // Values of a realm can be used from other contexts of the runtime, they
+    // keep the intrinsics of their own realm.
+    runtime.context().with(|cx| {
+        cx.globals().set("array", array.restore(&cx)?)?;
+        let result: String =
+            cx.eval("[array.length, array instanceof Array, Array.isArray(array)].join()")?;
+        assert_eq!("3,false,true", result);
+        Ok::<_, Error>(())
+    })?;
+
+    runtime.resolve_pending_jobs()?;
+    realm.context().with(|cx| {
+        let settled: bool = cx.globals().get("settled")?;
+        assert!(settled);
+        drop(promise.restore(&cx)?);
+        Ok::<_, Error>(())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn free_on_drop_frees_realms() -> Result<()> {
+    let mut config = Config::default();
+    config.free_on_drop(true);
+    let runtime = Runtime::new(config)?;
+
+    let create_realms = |runtime: &Runtime| -> Result<()> {
+        for _ in 0..20 {
+            let realm = runtime.new_context(Config::default())?;
+            realm.context().with(|cx| {
+                cx.eval::<(), _>(
+                    "globalThis.values = Array.from({ length: 100 }, (_, i) => ({ i }));",
+                )
+            })?;
+        }
+        runtime.run_gc();
+        Ok(())
+    };
+
+    create_realms(&runtime)?;
+    let before = runtime.memory_usage();
+    create_realms(&runtime)?;
+    let after = runtime.memory_usage();
+    assert_eq!(before.object_count, after.object_count);
+
+    // Without it, the realms are leaked.
+    let runtime = Runtime::default();
+    create_realms(&runtime)?;
+    let before = runtime.memory_usage();
+    create_realms(&runtime)?;
+    let after = runtime.memory_usage();
+    assert!(after.object_count >= before.object_count + 20 * 100);
+    Ok(())
+}
+
+#[test]
+fn free_on_drop_frees_runtimes_with_pending_work() -> Result<()> {
+    let mut config = Config::default();
+    config.free_on_drop(true).timers(true);
+    let runtime = Runtime::new(config)?;
+    let mut config = Config::default();
+    config.timers(true);
+    let realm = runtime.new_context(config)?;
+
+    for context in [runtime.context(), realm.context()] {
+        context.with(|cx| {
+            cx.eval::<(), _>(
+                r#"
+                setTimeout(() => {}, 60000);
+                setInterval(() => {}, 1000);
+                Promise.resolve().then(() => {});
+            "#,
+            )
+        })?;
+    }
+
+    // The realm keeps the runtime alive until it's dropped.
+    drop(runtime);
+    realm.context().with(|cx| {
+        let result: i32 = cx.eval("1 + 1")?;
+        assert_eq!(2, result);
+        Ok::<_, Error>(())
+    })?;
+    drop(realm);
+    Ok(())
+}
@@ -7,12 +7,32 @@ pub struct Config {
     pub(crate) runtime_config: javy::Config,
     /// Whether to enable the event loop.
     pub(crate) event_loop: bool,
+    /// Whether to write errors to stderr as JSON.
+    pub(crate) json_errors: bool,
 }
 
 impl Config {
     /// Whether to enable the event loop.
+    ///
+    /// Enabling the event loop also makes the timer APIs (`setTimeout`,
+    /// `setInterval`, `clearTimeout` and `clearInterval`) available.
     pub fn event_loop(&mut self, enabled: bool) -> &mut Self {
         self.event_loop = enabled;
+        self.runtime_config.timers(enabled);
+        self
+    }
+
+    /// Whether to write the errors of invocations to stderr as single line
+    /// JSON objects instead of text.
+    ///
+    /// JavaScript exceptions, including the reasons of unhandled promise
+    /// rejections, are written as a serialized [`javy::JsError`], with their
+    /// `name`, `message`, `stack` and, when present, `cause` and `errors`.
+    /// Other errors are written as an object with a `message`.
+    ///
+    /// Disabled by default.
+    pub fn json_errors(&mut self, enabled: bool) -> &mut Self {
+        self.json_errors = enabled;
         self
     }
 }
@@ -30,3 +50,126 @@ impl DerefMut for Config {
         &mut self.runtime_config
     }
 }
+
+javy::config_spec! {
+    /// A serializable specification of a [`Config`].
+    ///
+    /// Extends [`javy::ConfigSpec`], whose properties are flattened, with the
+    /// properties of the plugin API. Unset properties keep the value of the
+    /// [`Config`] the specification is applied to.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ConfigSpec {
+        #[serde(flatten)]
+        runtime: javy::ConfigSpec,
+        /// Whether to enable the event loop. Also enables `setTimeout`,
+        /// `setInterval`, `clearTimeout` and `clearInterval`.
+        event_loop: Option<bool>,
+        /// Whether to write uncaught errors to stderr as JSON.
+        json_errors: Option<bool>,
+    }
+}
+
+impl ConfigSpec {
+    /// Applies the properties which are set to a [`Config`].
+    ///
+    /// The event loop is applied after the runtime properties, so enabling it
+    /// enables timers regardless of the `timers` property.
+    pub fn apply_to_config(&self, config: &mut Config) {
+        self.runtime.apply_to_config(&mut config.runtime_config);
+        if let Some(enable) = self.event_loop {
+            config.event_loop(enable);
+        }
+        if let Some(enable) = self.json_errors {
+            config.json_errors(enable);
+        }
+    }
+}
+
+impl From<&Config> for ConfigSpec {
+    /// Creates a [`ConfigSpec`] with every property set to the value of the
+    /// [`Config`].
+    fn from(config: &Config) -> Self {
+        Self {
+            runtime: (&config.runtime_config).into(),
+            event_loop: Some(config.event_loop),
+            json_errors: Some(config.json_errors),
+        }
+    }
+}
+
+impl From<&ConfigSpec> for Config {
+    /// Creates a [`Config`] with the default values, overridden by the
+    /// properties of the [`ConfigSpec`] which are set.
+    fn from(spec: &ConfigSpec) -> Self {
+        let mut config = Config::default();
+        spec.apply_to_config(&mut config);
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, ConfigSpec};
+    use anyhow::Result;
+
+    #[test]
+    fn round_trips_through_config() {
+        let mut config = Config::default();
+        config
+            .event_loop(true)
+            .json_errors(true)
+            .structured_clone(true);
+        let spec = ConfigSpec::from(&config);
+        assert_eq!(Some(true), spec.event_loop);
+        assert_eq!(Some(true), spec.json_errors);
+        assert_eq!(Some(true), spec.runtime.timers);
+        assert_eq!(Some(true), spec.runtime.structured_clone);
+
+        assert_eq!(spec, ConfigSpec::from(&Config::from(&spec)));
+    }
+
+    #[test]
+    fn flattens_the_runtime_properties() -> Result<()> {
+        let spec: ConfigSpec =
+            serde_json::from_str(r#"{ "event-loop": true, "text-encoding": false }"#)?;
+        assert_eq!(Some(true), spec.event_loop);
+        assert_eq!(None, spec.json_errors);
+        assert_eq!(Some(false), spec.runtime.text_encoding);
+
+        assert_eq!(
+            r#"{"text-encoding":false,"event-loop":true}"#,
+            serde_json::to_string(&spec)?
+        );
+
+        let properties = ConfigSpec::properties();
+        assert!(
+            properties
+                .iter()
+                .any(|property| property.name == "text-encoding")
+        );
+        let event_loop = properties
+            .iter()
+            .find(|property| property.name == "event-loop")
+            .unwrap();
+        assert_eq!(
+            " Whether to enable the event loop. Also enables `setTimeout`,\n `setInterval`, `clearTimeout` and `clearInterval`.\n",
+            event_loop.doc
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_properties() {
+        for json in [
+            r#"{ "unknown": true }"#,
+            r#"{ "event-loop": true, "unknown": true }"#,
+            r#"{ "text-encoding": true, "unknown": true }"#,
+        ] {
+            let err = serde_json::from_str::<ConfigSpec>(json).unwrap_err();
+            assert!(
+                err.to_string().starts_with("unknown property `unknown`"),
+                "{err}"
+            );
+        }
+    }
+}
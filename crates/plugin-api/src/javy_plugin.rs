@@ -43,7 +43,7 @@ macro_rules! javy_plugin {
 
             fn invoke(bytecode: Vec<u8>, function: Option<String>) -> () {
                 javy_plugin_api::invoke(&bytecode, function.as_deref()).unwrap_or_else(|e| {
-                    eprintln!("{e}");
+                    javy_plugin_api::report_error(&e);
                     std::process::abort();
                 });
             }
@@ -89,15 +89,17 @@
 //! # Features
 //! * `json` - enables the `json` feature in the `javy` crate.
 //! * `messagepack` - enables the `messagepack` feature in the `javy` crate.
+//! * `cbor` - enables the `cbor` feature in the `javy` crate.
 
 // Allow these in this file because we only run this program single threaded
 // and we can safely reason about the accesses to the Javy Runtime. We also
 // don't want to introduce overhead from taking unnecessary mutex locks.
 #![allow(static_mut_refs)]
 use anyhow::{Result, anyhow, bail};
-pub use config::Config;
-use javy::quickjs::{self, Ctx, Error as JSError, Function, Module, Value};
-use javy::{Runtime, from_js_error};
+pub use config::{Config, ConfigSpec};
+use javy::modules::{BytecodeModules, ModuleBundle};
+use javy::quickjs::{Error as JSError, Function, Module, Persistent, Value};
+use javy::{Interrupted, JsError, Runtime, UnhandledRejection, from_js_error};
 use std::cell::OnceCell;
 use std::str;
 
@@ -116,7 +118,9 @@ thread_local! {
 }
 
 static mut RUNTIME: OnceCell<Runtime> = OnceCell::new();
+static mut MODULES: OnceCell<BytecodeModules> = OnceCell::new();
 static mut EVENT_LOOP_ENABLED: bool = false;
+static mut JSON_ERRORS_ENABLED: bool = false;
 
 static EVENT_LOOP_ERR: &str = r#"
                 Pending jobs in the event queue.
@@ -130,11 +134,22 @@ where
     F: FnOnce() -> Config,
     G: FnOnce(Runtime) -> Runtime,
 {
-    let config = config();
+    let mut config = config();
+    // The modules imported by the entry module of a module bundle, registered
+    // when invoking the bundle.
+    let modules = BytecodeModules::default();
+    config
+        .runtime_config
+        .module_loader(modules.clone(), modules.clone());
     let runtime = Runtime::new(config.runtime_config)?;
     let runtime = modify_runtime(runtime);
     unsafe {
         RUNTIME.take(); // Allow re-initializing.
+        MODULES.take();
+        MODULES
+            .set(modules)
+            .map_err(|_| anyhow!("Could not pre-initialize module loader"))
+            .unwrap();
         RUNTIME
             .set(runtime)
             // `unwrap` requires error `T` to implement `Debug` but `set`
@@ -143,6 +158,7 @@ where
             .map_err(|_| anyhow!("Could not pre-initialize javy::Runtime"))
             .unwrap();
         EVENT_LOOP_ENABLED = config.event_loop;
+        JSON_ERRORS_ENABLED = config.json_errors;
     };
     Ok(())
 }
@@ -152,6 +168,10 @@ where
 /// Returns result with the success value being a vector of the bytecode and
 /// failure being the error message.
 ///
+/// If `js_src` is a serialized [`ModuleBundle`] of source code, each module of
+/// the bundle is compiled under its own name, and the result is a serialized
+/// [`ModuleBundle`] of bytecode, which can be passed as is to [`invoke`].
+///
 /// # Arguments
 ///
 /// * `config` - A function that returns a config for Javy
@@ -174,73 +194,166 @@ pub fn compile_src(js_src: &[u8]) -> Result<Vec<u8>> {
     let runtime = unsafe { RUNTIME.get() }.ok_or_else(|| {
         anyhow!("Javy runtime not initialized. Ensure `javy init-plugin` has been invoked.")
     })?;
+    if ModuleBundle::is_bundle(js_src) {
+        let mut bytecode = ModuleBundle::new();
+        for (name, src) in ModuleBundle::from_bytes(js_src)?.modules() {
+            bytecode.push(
+                name,
+                runtime.compile_to_bytecode(name, &String::from_utf8_lossy(src))?,
+            );
+        }
+        return Ok(bytecode.to_bytes());
+    }
+
     runtime.compile_to_bytecode(FUNCTION_MODULE_NAME, &String::from_utf8_lossy(js_src))
 }
 
 /// Evaluates QuickJS bytecode and optionally invokes exported JS function with
 /// name.
 ///
+/// When the event loop is enabled, the event loop is driven after evaluating
+/// the module and after invoking the exported function, until there are no
+/// pending jobs and no pending timers left. Timers which are still pending
+/// when the exported function returns are therefore always fired before
+/// `invoke` returns, an interval which is never cleared will prevent `invoke`
+/// from returning.
+///
 /// # Arguments
 ///
-/// * `bytecode` - The QuickJS bytecode
+/// * `bytecode` - The QuickJS bytecode, or a serialized [`ModuleBundle`] of
+///   bytecode as returned by [`compile_src`]
 /// * `fn_name` - The JS function name
 pub fn invoke(bytecode: &[u8], fn_name: Option<&str>) -> Result<()> {
     let runtime = unsafe { RUNTIME.get() }.ok_or_else(|| {
         anyhow!("Javy runtime not initialized. Ensure `javy init-plugin` has been invoked.")
     })?;
-    runtime
+    let bundle;
+    let bytecode = if ModuleBundle::is_bundle(bytecode) {
+        bundle = ModuleBundle::from_bytes(bytecode)?;
+        register_modules(&bundle)?
+    } else {
+        bytecode
+    };
+    let (module, promise) = runtime
         .context()
         .with(|this| {
             let module = unsafe { Module::load(this.clone(), bytecode)? };
             let (module, promise) = module.eval()?;
+            Ok((
+                Persistent::save(&this, module.namespace()?),
+                Persistent::save(&this, promise.into_value()),
+            ))
+        })
+        .map_err(|e| runtime.context().with(|cx| from_js_error(cx.clone(), e)))?;
 
-            handle_maybe_promise(this.clone(), promise.into())?;
+    handle_maybe_promise(runtime, promise)?;
 
-            if let Some(fn_name) = fn_name {
-                let fun: Function = module.get(fn_name)?;
+    if let Some(fn_name) = fn_name {
+        let value = runtime
+            .context()
+            .with(|this| {
+                let namespace = module.restore(&this)?;
+                let fun: Function = namespace.get(fn_name)?;
                 // Exported functions are guaranteed not to have arguments so
                 // we can safely pass an empty tuple for arguments.
-                let value = fun.call(())?;
-                handle_maybe_promise(this.clone(), value)?
-            }
-            Ok(())
-        })
-        .map_err(|e| runtime.context().with(|cx| from_js_error(cx.clone(), e)))
-        .and_then(|_: ()| ensure_pending_jobs(runtime))
+                let value: Value = fun.call(())?;
+                Ok(Persistent::save(&this, value))
+            })
+            .map_err(|e| runtime.context().with(|cx| from_js_error(cx.clone(), e)))?;
+        handle_maybe_promise(runtime, value)?;
+    }
+
+    ensure_pending_jobs(runtime)
+}
+
+/// Makes the modules of the bundle available for import, returning the
+/// bytecode of the entry module.
+fn register_modules(bundle: &ModuleBundle) -> Result<&[u8]> {
+    let modules =
+        unsafe { MODULES.get() }.ok_or_else(|| anyhow!("Javy module loader not initialized."))?;
+    let mut bundled = bundle.modules();
+    let (_, entry) = bundled
+        .next()
+        .ok_or_else(|| anyhow!("Module bundle has no entry module"))?;
+    for (name, bytecode) in bundled {
+        modules.add(name, bytecode);
+    }
+    Ok(entry)
+}
+
+/// Writes an error returned by [`invoke`] to stderr, as JSON if
+/// [`Config::json_errors`] is enabled.
+#[doc(hidden)]
+pub fn report_error(e: &anyhow::Error) {
+    if !unsafe { JSON_ERRORS_ENABLED } {
+        eprintln!("{e}");
+        return;
+    }
+
+    let error = e
+        .downcast_ref::<JsError>()
+        .or_else(|| e.downcast_ref::<Interrupted>().map(Interrupted::error))
+        .or_else(|| {
+            e.downcast_ref::<UnhandledRejection>()
+                .map(UnhandledRejection::reason)
+        });
+    let json = match error {
+        Some(error) => serde_json::to_string(error),
+        None => serde_json::to_string(&serde_json::json!({ "message": e.to_string() })),
+    };
+    match json {
+        Ok(json) => eprintln!("{json}"),
+        Err(_) => eprintln!("{e}"),
+    }
 }
 
 /// Handles the promise returned by evaluating the JS bytecode.
-fn handle_maybe_promise(this: Ctx, value: Value) -> quickjs::Result<()> {
-    match value.as_promise() {
-        Some(promise) => {
-            if unsafe { EVENT_LOOP_ENABLED } {
-                // If the event loop is enabled, trigger it.
-                let resolved = promise.finish::<Value>();
-                // `Promise::finish` returns Err(Wouldblock) when the all
-                // pending jobs have been handled.
-                if let Err(JSError::WouldBlock) = resolved {
-                    Ok(())
-                } else {
-                    resolved.map(|_| ())
+fn handle_maybe_promise(runtime: &Runtime, value: Persistent<Value<'static>>) -> Result<()> {
+    loop {
+        let settled = runtime
+            .context()
+            .with(|this| {
+                let value = value.clone().restore(&this)?;
+                match value.as_promise() {
+                    Some(promise) => {
+                        if unsafe { EVENT_LOOP_ENABLED } {
+                            // If the event loop is enabled, trigger it.
+                            let resolved = promise.finish::<Value>();
+                            // `Promise::finish` returns Err(Wouldblock) when
+                            // all pending jobs have been handled.
+                            if let Err(JSError::WouldBlock) = resolved {
+                                Ok(false)
+                            } else {
+                                resolved.map(|_| true)
+                            }
+                        } else {
+                            // Else we simply expect the promise to resolve
+                            // immediately.
+                            match promise.result() {
+                                None => Err(javy::to_js_error(this, anyhow!(EVENT_LOOP_ERR))),
+                                Some(r) => r.map(|_: Value| true),
+                            }
+                        }
+                    }
+                    None => Ok(true),
                 }
-            } else {
-                // Else we simply expect the promise to resolve immediately.
-                match promise.result() {
-                    None => Err(javy::to_js_error(this, anyhow!(EVENT_LOOP_ERR))),
-                    Some(r) => r,
-                }
-            }
+            })
+            .map_err(|e| runtime.context().with(|cx| from_js_error(cx.clone(), e)))?;
+
+        // The promise may be waiting on a timer, fire the next one and check
+        // again.
+        if settled || !runtime.run_next_timer()? {
+            return Ok(());
         }
-        None => Ok(()),
     }
 }
 
 fn ensure_pending_jobs(rt: &Runtime) -> Result<()> {
     if unsafe { EVENT_LOOP_ENABLED } {
-        rt.resolve_pending_jobs()
-    } else if rt.has_pending_jobs() {
+        rt.run_event_loop()
+    } else if rt.has_pending_jobs() || rt.has_pending_timers() {
         bail!(EVENT_LOOP_ERR);
     } else {
-        Ok(())
+        rt.check_unhandled_rejections()
     }
 }
@@ -93,7 +93,7 @@ extern "C" fn invoke(
         fn_name = Some(fn_name_string);
     }
     crate::invoke(bytecode, fn_name.as_deref()).unwrap_or_else(|e| {
-        eprintln!("{e}");
+        crate::report_error(&e);
         process::abort();
     });
 }
@@ -3,8 +3,7 @@
 use std::cell::OnceCell;
 
 use anyhow::Result;
-use javy_plugin_api::Config;
-use serde::Deserialize;
+use javy_plugin_api::{Config, ConfigSpec};
 
 mod runtime_config;
 
@@ -16,41 +15,34 @@ thread_local! {
 }
 
 runtime_config! {
-    #[derive(Debug, Default, Deserialize)]
-    #[serde(deny_unknown_fields, rename_all = "kebab-case")]
-    pub struct SharedConfig {
-        /// Whether to enable the `Javy.readSync` and `Javy.writeSync` builtins.
-        javy_stream_io: Option<bool>,
-        /// Whether to override the `JSON.parse` and `JSON.stringify`
-        /// implementations with an alternative, more performant, SIMD based
-        /// implemetation.
-        simd_json_builtins: Option<bool>,
-        /// Whether to enable support for the `TextEncoder` and `TextDecoder`
-        /// APIs.
-        text_encoding: Option<bool>,
-        /// Whether to enable the event loop.
-        event_loop: Option<bool>,
+    /// The properties of the [`ConfigSpec`] which the CLI accepts with `-J`.
+    /// The JSON config may set any of its properties.
+    #[derive(Debug, Default)]
+    pub struct SharedConfig(ConfigSpec) {
+        javy_stream_io,
+        simd_json_builtins,
+        text_encoding,
+        event_loop,
+        url,
+        fetch_types,
+        event_target,
+        streams,
+        javy_fs,
+        javy_process,
+        javy_memory,
+        javy_messagepack,
+        json_errors,
+        unhandled_rejection,
     }
 }
 
 impl SharedConfig {
     pub fn parse_from_json(config: &[u8]) -> Result<Self> {
-        Ok(serde_json::from_slice::<Self>(config)?)
+        Ok(Self(serde_json::from_slice::<ConfigSpec>(config)?))
     }
 
     pub fn apply_to_config(&self, config: &mut Config) {
-        if let Some(enable) = self.javy_stream_io {
-            config.javy_stream_io(enable);
-        }
-        if let Some(enable) = self.simd_json_builtins {
-            config.simd_json_builtins(enable);
-        }
-        if let Some(enable) = self.text_encoding {
-            config.text_encoding(enable);
-        }
-        if let Some(enable) = self.event_loop {
-            config.event_loop(enable);
-        }
+        self.0.apply_to_config(config);
     }
 }
 
@@ -71,3 +63,33 @@ fn config_schema() -> *const u32 {
         key.get().unwrap().as_ptr()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SharedConfig;
+
+    #[test]
+    fn config_schema_lists_the_cli_properties() {
+        let properties = SharedConfig::config_schema().supported_properties;
+        // Every property listed in `SharedConfig` is part of the spec.
+        assert_eq!(14, properties.len());
+        let unhandled_rejection = properties
+            .iter()
+            .find(|property| property.name == "unhandled-rejection")
+            .unwrap();
+        assert_eq!(
+            Some(vec!["ignore".into(), "warn".into(), "fail".into()]),
+            unhandled_rejection.values
+        );
+        for name in ["eval", "promise", "json", "proxy", "memory-limit"] {
+            assert!(properties.iter().all(|property| property.name != name));
+        }
+    }
+
+    #[test]
+    fn parse_from_json() {
+        assert!(SharedConfig::parse_from_json(br#"{ "event-loop": true, "eval": false }"#).is_ok());
+        let err = SharedConfig::parse_from_json(br#"{ "unknown": true }"#).unwrap_err();
+        assert!(err.to_string().starts_with("unknown property `unknown`"));
+    }
+}
@@ -11,44 +11,47 @@ pub(super) struct ConfigSchema {
 pub(super) struct ConfigProperty {
     pub(super) name: String,
     pub(super) doc: String,
+    /// The values the property accepts, for properties which aren't
+    /// booleans.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) values: Option<Vec<String>>,
 }
 
 #[macro_export]
 macro_rules! runtime_config {
     (
         $(#[$attr:meta])*
-        pub struct $opts:ident {
-            $(
-                $(
-                    #[doc = $doc:tt]
-                )*
-                $opt:ident: Option<bool>,
-            )+
+        pub struct $opts:ident($spec_ty:ident) {
+            $($opt:ident,)+
         }
     ) => {
         $(#[$attr])*
-        pub struct $opts {
-            $(
-                $(
-                    #[doc = $doc]
-                )*
-                $opt: Option<bool>,
-            )+
-        }
+        pub struct $opts($spec_ty);
 
         impl $opts {
+            /// The properties of the spec which can be set with `-J`, along
+            /// with their documentation.
             fn config_schema() -> $crate::shared_config::runtime_config::ConfigSchema {
+                let names = [$(stringify!($opt).replace('_', "-"),)+];
+                let supported_properties = $spec_ty::properties()
+                    .into_iter()
+                    .filter(|property| names.contains(&property.name))
+                    .map(|property| {
+                        let values = match property.ty {
+                            javy_plugin_api::javy::ConfigPropertyType::Enum(values) => {
+                                Some(values.iter().map(|value| value.to_string()).collect())
+                            }
+                            _ => None,
+                        };
+                        $crate::shared_config::runtime_config::ConfigProperty {
+                            name: property.name,
+                            doc: property.doc,
+                            values,
+                        }
+                    })
+                    .collect();
                 $crate::shared_config::runtime_config::ConfigSchema {
-                    supported_properties: vec![
-                        $(
-                            {
-                                $crate::shared_config::runtime_config::ConfigProperty {
-                                    name: stringify!($opt).replace('_', "-").to_string(),
-                                    doc: concat!($($doc, "\n",)*).into(),
-                                }
-                            },
-                        )+
-                    ]
+                    supported_properties,
                 }
             }
         }
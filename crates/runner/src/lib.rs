@@ -88,6 +88,31 @@ pub struct Builder {
     text_encoding: Option<bool>,
     /// Whether to enable the event loop.
     event_loop: Option<bool>,
+    /// Whether to enable the `URL` and `URLSearchParams` globals.
+    url: Option<bool>,
+    /// Whether to enable the `Headers`, `Request`, `Response`, `Blob`, `File`
+    /// and `FormData` globals.
+    fetch_types: Option<bool>,
+    /// Whether to enable the `EventTarget`, `Event`, `CustomEvent`,
+    /// `AbortController` and `AbortSignal` globals.
+    event_target: Option<bool>,
+    /// Whether to enable the `ReadableStream`, `WritableStream` and
+    /// `TransformStream` globals.
+    streams: Option<bool>,
+    /// Whether to enable the `Javy.FS` builtins.
+    javy_fs: Option<bool>,
+    /// Whether to enable the `Javy.args`, `Javy.env` and `Javy.exit`
+    /// builtins.
+    javy_process: Option<bool>,
+    /// Whether to enable the `Javy.memoryUsage` and `Javy.gc` builtins.
+    javy_memory: Option<bool>,
+    /// Whether to enable the `Javy.MessagePack` builtins.
+    messagepack: Option<bool>,
+    /// Whether to write uncaught errors to stderr as JSON.
+    json_errors: Option<bool>,
+    /// What to do with unhandled promise rejections: `ignore`, `warn` or
+    /// `fail`.
+    unhandled_rejection: Option<String>,
     built: bool,
     /// Preload the module at path, using the given instance name.
     preload: Option<(String, PathBuf)>,
@@ -113,6 +138,16 @@ impl Default for Builder {
             simd_json_builtins: None,
             text_encoding: None,
             event_loop: None,
+            url: None,
+            fetch_types: None,
+            event_target: None,
+            streams: None,
+            javy_fs: None,
+            javy_process: None,
+            javy_memory: None,
+            messagepack: None,
+            json_errors: None,
+            unhandled_rejection: None,
             plugin: Plugin::Default,
             source_code: None,
             deterministic: None,
@@ -171,6 +206,56 @@ impl Builder {
         self
     }
 
+    pub fn url(&mut self, enabled: bool) -> &mut Self {
+        self.url = Some(enabled);
+        self
+    }
+
+    pub fn fetch_types(&mut self, enabled: bool) -> &mut Self {
+        self.fetch_types = Some(enabled);
+        self
+    }
+
+    pub fn event_target(&mut self, enabled: bool) -> &mut Self {
+        self.event_target = Some(enabled);
+        self
+    }
+
+    pub fn streams(&mut self, enabled: bool) -> &mut Self {
+        self.streams = Some(enabled);
+        self
+    }
+
+    pub fn javy_fs(&mut self, enabled: bool) -> &mut Self {
+        self.javy_fs = Some(enabled);
+        self
+    }
+
+    pub fn javy_process(&mut self, enabled: bool) -> &mut Self {
+        self.javy_process = Some(enabled);
+        self
+    }
+
+    pub fn javy_memory(&mut self, enabled: bool) -> &mut Self {
+        self.javy_memory = Some(enabled);
+        self
+    }
+
+    pub fn messagepack(&mut self, enabled: bool) -> &mut Self {
+        self.messagepack = Some(enabled);
+        self
+    }
+
+    pub fn json_errors(&mut self, enabled: bool) -> &mut Self {
+        self.json_errors = Some(enabled);
+        self
+    }
+
+    pub fn unhandled_rejection(&mut self, policy: &str) -> &mut Self {
+        self.unhandled_rejection = Some(policy.to_string());
+        self
+    }
+
     pub fn plugin(&mut self, plugin: Plugin) -> &mut Self {
         self.plugin = plugin;
         self
@@ -207,6 +292,16 @@ impl Builder {
             simd_json_builtins,
             text_encoding,
             event_loop,
+            url,
+            fetch_types,
+            event_target,
+            streams,
+            javy_fs,
+            javy_process,
+            javy_memory,
+            messagepack,
+            json_errors,
+            unhandled_rejection,
             built: _,
             preload,
             plugin,
@@ -226,6 +321,16 @@ impl Builder {
             simd_json_builtins,
             text_encoding,
             event_loop,
+            url,
+            fetch_types,
+            event_target,
+            streams,
+            javy_fs,
+            javy_process,
+            javy_memory,
+            messagepack,
+            json_errors,
+            unhandled_rejection,
             preload,
             plugin,
             source_code,
@@ -296,6 +401,16 @@ impl Runner {
         override_json_parse_and_stringify: Option<bool>,
         text_encoding: Option<bool>,
         event_loop: Option<bool>,
+        url: Option<bool>,
+        fetch_types: Option<bool>,
+        event_target: Option<bool>,
+        streams: Option<bool>,
+        javy_fs: Option<bool>,
+        javy_process: Option<bool>,
+        javy_memory: Option<bool>,
+        messagepack: Option<bool>,
+        json_errors: Option<bool>,
+        unhandled_rejection: Option<String>,
         preload: Option<(String, PathBuf)>,
         plugin: Plugin,
         source_code: Option<Source>,
@@ -318,6 +433,16 @@ impl Runner {
             &override_json_parse_and_stringify,
             &text_encoding,
             &event_loop,
+            &url,
+            &fetch_types,
+            &event_target,
+            &streams,
+            &javy_fs,
+            &javy_process,
+            &javy_memory,
+            &messagepack,
+            &json_errors,
+            &unhandled_rejection,
             &plugin,
             &source_code,
             &deterministic,
@@ -425,6 +550,16 @@ impl Runner {
         simd_json_builtins: &Option<bool>,
         text_encoding: &Option<bool>,
         event_loop: &Option<bool>,
+        url: &Option<bool>,
+        fetch_types: &Option<bool>,
+        event_target: &Option<bool>,
+        streams: &Option<bool>,
+        javy_fs: &Option<bool>,
+        javy_process: &Option<bool>,
+        javy_memory: &Option<bool>,
+        messagepack: &Option<bool>,
+        json_errors: &Option<bool>,
+        unhandled_rejection: &Option<String>,
         plugin: &Plugin,
         source_code: &Option<Source>,
         deterministic: &Option<bool>,
@@ -474,6 +609,59 @@ impl Runner {
             args.push(format!("event-loop={}", if enabled { "y" } else { "n" }));
         }
 
+        if let Some(enabled) = *url {
+            args.push("-J".to_string());
+            args.push(format!("url={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(enabled) = *fetch_types {
+            args.push("-J".to_string());
+            args.push(format!("fetch-types={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(enabled) = *event_target {
+            args.push("-J".to_string());
+            args.push(format!("event-target={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(enabled) = *streams {
+            args.push("-J".to_string());
+            args.push(format!("streams={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(enabled) = *javy_fs {
+            args.push("-J".to_string());
+            args.push(format!("javy-fs={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(enabled) = *javy_process {
+            args.push("-J".to_string());
+            args.push(format!("javy-process={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(enabled) = *javy_memory {
+            args.push("-J".to_string());
+            args.push(format!("javy-memory={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(enabled) = *messagepack {
+            args.push("-J".to_string());
+            args.push(format!(
+                "javy-messagepack={}",
+                if enabled { "y" } else { "n" }
+            ));
+        }
+
+        if let Some(enabled) = *json_errors {
+            args.push("-J".to_string());
+            args.push(format!("json-errors={}", if enabled { "y" } else { "n" }));
+        }
+
+        if let Some(policy) = unhandled_rejection {
+            args.push("-J".to_string());
+            args.push(format!("unhandled-rejection={policy}"));
+        }
+
         if plugin.needs_plugin_arg() {
             args.push("-C".to_string());
             args.push(format!("plugin={}", plugin.path().to_str().unwrap()));
@@ -0,0 +1,42 @@
+// Copy of url-constructor.any.js importing the test data, which is loaded with
+// `fetch` upstream.
+
+import urlConstructorTestData from "wpt:url/resources/urltestdata.json";
+import urlConstructorJavascriptOnlyTestData from "wpt:url/resources/urltestdata-javascript-only.json";
+
+function runURLTests(urlTests) {
+  for (const expected of urlTests) {
+    // Skip comments
+    if (typeof expected === "string")
+      continue;
+
+    const base = expected.base !== null ? expected.base : undefined;
+
+    test(function() {
+      if (expected.failure) {
+        assert_throws_js(TypeError, function() {
+          new URL(expected.input, base);
+        });
+        return;
+      }
+
+      const url = new URL(expected.input, base);
+      assert_equals(url.href, expected.href, "href");
+      assert_equals(url.protocol, expected.protocol, "protocol");
+      assert_equals(url.username, expected.username, "username");
+      assert_equals(url.password, expected.password, "password");
+      assert_equals(url.host, expected.host, "host");
+      assert_equals(url.hostname, expected.hostname, "hostname");
+      assert_equals(url.port, expected.port, "port");
+      assert_equals(url.pathname, expected.pathname, "pathname");
+      assert_equals(url.search, expected.search, "search");
+      if ("searchParams" in expected) {
+        assert_true("searchParams" in url);
+        assert_equals(url.searchParams.toString(), expected.searchParams, "searchParams");
+      }
+      assert_equals(url.hash, expected.hash, "hash");
+    }, `Parsing: <${expected.input}> ${base ? "against <" + base + ">" : "without base"}`);
+  }
+}
+
+runURLTests([...urlConstructorTestData, ...urlConstructorJavascriptOnlyTestData]);
@@ -0,0 +1,29 @@
+// Copy of url-setters.any.js importing the test data, which is loaded with
+// `fetch` upstream.
+
+import urlSettersTestData from "wpt:url/resources/setters_tests.json";
+
+function runURLSettersTests(json) {
+  for (const attribute_to_be_set in json) {
+    if (attribute_to_be_set == "comment") {
+      continue;
+    }
+    const tests = json[attribute_to_be_set];
+    for (const test_case of tests) {
+      let name = "Setting <" + test_case.href + ">." + attribute_to_be_set +
+                 " = '" + test_case.new_value + "'";
+      if ("comment" in test_case) {
+        name += " " + test_case.comment;
+      }
+      test(function() {
+        const url = new URL(test_case.href);
+        url[attribute_to_be_set] = test_case.new_value;
+        for (const attribute in test_case.expected) {
+          assert_equals(url[attribute], test_case.expected[attribute]);
+        }
+      }, "URL: " + name);
+    }
+  }
+}
+
+runURLSettersTests(urlSettersTestData);
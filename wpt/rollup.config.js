@@ -50,6 +50,21 @@ export default {
         return transformedCode;
       },
     },
+    // This plugin allows custom tests to import the JSON resources of the
+    // upstream tests with `wpt:` imports, e.g.
+    // `import data from "wpt:url/resources/urltestdata.json"`, since upstream
+    // tests load them with `fetch`.
+    {
+      name: "wpt-resources",
+      resolveId(id) {
+        if (!id.startsWith("wpt:")) return;
+        return path.join(projectRoot, id.slice("wpt:".length));
+      },
+      transform(code, id) {
+        if (!id.endsWith(".json")) return;
+        return `export default ${code};`;
+      },
+    },
     // This plugin handles the special import in `runner.js`.
     // It parses `test_spec.js` and concatenates all
     // the specified tests.